@@ -5,6 +5,8 @@ use std::time::Duration;
 
 use bonsaidb_core::api;
 use bonsaidb_core::api::ApiName;
+#[cfg(feature = "websockets")]
+use bonsaidb_core::networking::WebSocketFormat;
 use bonsaidb_core::networking::CURRENT_PROTOCOL_VERSION;
 #[cfg(not(target_arch = "wasm32"))]
 use fabruic::Certificate;
@@ -31,6 +33,8 @@ pub struct Blocking;
 pub struct Builder<AsyncMode> {
     url: Url,
     protocol_version: &'static str,
+    #[cfg(feature = "websockets")]
+    websocket_format: WebSocketFormat,
     custom_apis: HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>,
     connect_timeout: Option<Duration>,
     request_timeout: Option<Duration>,
@@ -47,6 +51,8 @@ impl<AsyncMode> Builder<AsyncMode> {
         Self {
             url,
             protocol_version: CURRENT_PROTOCOL_VERSION,
+            #[cfg(feature = "websockets")]
+            websocket_format: WebSocketFormat::default(),
             custom_apis: HashMap::new(),
             request_timeout: None,
             connect_timeout: None,
@@ -100,6 +106,18 @@ impl<AsyncMode> Builder<AsyncMode> {
         self
     }
 
+    /// Sets the format used to encode payloads when connecting over
+    /// `WebSockets`. The format is negotiated with the server during the
+    /// handshake.
+    ///
+    /// If not specified, [`WebSocketFormat::Bincode`] is used.
+    #[cfg(feature = "websockets")]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_websocket_format(mut self, format: WebSocketFormat) -> Self {
+        self.websocket_format = format;
+        self
+    }
+
     /// Sets the request timeout for the client.
     ///
     /// If not specified, requests will time out after 60 seconds.
@@ -121,6 +139,8 @@ impl<AsyncMode> Builder<AsyncMode> {
         AsyncClient::new_from_parts(
             self.url,
             self.protocol_version,
+            #[cfg(feature = "websockets")]
+            self.websocket_format,
             self.custom_apis,
             self.connect_timeout,
             self.request_timeout,
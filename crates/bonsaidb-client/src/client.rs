@@ -14,10 +14,12 @@ use bonsaidb_core::arc_bytes::OwnedBytes;
 use bonsaidb_core::connection::{
     AsyncStorageConnection, Database, HasSession, IdentityReference, Session,
 };
+#[cfg(feature = "websockets")]
+use bonsaidb_core::networking::WebSocketFormat;
 use bonsaidb_core::networking::{
     AlterUserPermissionGroupMembership, AlterUserRoleMembership, AssumeIdentity, CreateDatabase,
     CreateUser, DeleteDatabase, DeleteUser, ListAvailableSchemas, ListDatabases, LogOutSession,
-    MessageReceived, Payload, UnregisterSubscriber, CURRENT_PROTOCOL_VERSION,
+    MessageReceived, Payload, UnregisterSubscriber, ValueEncoding, CURRENT_PROTOCOL_VERSION,
 };
 use bonsaidb_core::permissions::Permissions;
 use bonsaidb_core::schema::{Nameable, Schema, SchemaName, SchemaSummary, Schematic};
@@ -256,6 +258,7 @@ pub struct Data {
     connection_counter: Arc<AtomicU32>,
     request_id: AtomicU32,
     subscribers: SubscriberMap,
+    value_encoding: ValueEncoding,
 }
 
 impl AsyncClient {
@@ -283,6 +286,8 @@ impl AsyncClient {
         Self::new_from_parts(
             url,
             CURRENT_PROTOCOL_VERSION,
+            #[cfg(feature = "websockets")]
+            WebSocketFormat::default(),
             HashMap::default(),
             None,
             None,
@@ -311,6 +316,7 @@ impl AsyncClient {
     pub(crate) fn new_from_parts(
         url: Url,
         protocol_version: &'static str,
+        #[cfg(feature = "websockets")] websocket_format: WebSocketFormat,
         mut custom_apis: HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>,
         connect_timeout: Option<Duration>,
         request_timeout: Option<Duration>,
@@ -361,6 +367,7 @@ impl AsyncClient {
             "wss" | "ws" => Ok(Self::new_websocket_client(
                 connection,
                 protocol_version,
+                websocket_format,
                 custom_apis,
                 #[cfg(not(target_arch = "wasm32"))]
                 tokio,
@@ -402,6 +409,7 @@ impl AsyncClient {
                 request_id: AtomicU32::default(),
                 effective_permissions: Mutex::default(),
                 subscribers,
+                value_encoding: ValueEncoding::Pot,
             }),
             session: ClientSession::default(),
             request_timeout,
@@ -412,6 +420,7 @@ impl AsyncClient {
     fn new_websocket_client(
         server: ConnectionInfo,
         protocol_version: &'static str,
+        format: WebSocketFormat,
         custom_apis: HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>,
         tokio: Option<Handle>,
     ) -> Self {
@@ -424,6 +433,7 @@ impl AsyncClient {
             tungstenite_worker::reconnecting_client_loop(
                 server,
                 protocol_version,
+                format,
                 request_receiver,
                 Arc::new(custom_apis),
                 connection_counter.clone(),
@@ -439,6 +449,7 @@ impl AsyncClient {
                 connection_counter,
                 effective_permissions: Mutex::default(),
                 subscribers,
+                value_encoding: format.value_encoding(),
             }),
             session: ClientSession::default(),
             request_timeout,
@@ -449,6 +460,7 @@ impl AsyncClient {
     fn new_websocket_client(
         server: ConnectionInfo,
         protocol_version: &'static str,
+        format: WebSocketFormat,
         custom_apis: HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>,
     ) -> Self {
         let (request_sender, request_receiver) = flume::unbounded();
@@ -457,6 +469,7 @@ impl AsyncClient {
         wasm_websocket_worker::spawn_client(
             Arc::new(server.url),
             protocol_version,
+            format,
             request_receiver,
             Arc::new(custom_apis),
             server.subscribers.clone(),
@@ -476,6 +489,7 @@ impl AsyncClient {
                 connection_counter,
                 effective_permissions: Mutex::default(),
                 subscribers: server.subscribers,
+                value_encoding: format.value_encoding(),
                 #[cfg(feature = "test-util")]
                 background_task_running,
             }),
@@ -553,10 +567,12 @@ impl AsyncClient {
         &self,
         request: &Api,
     ) -> Result<Api::Response, ApiError<Api::Error>> {
-        let request = Bytes::from(pot::to_vec(request).map_err(Error::from)?);
+        let encoding = self.data.value_encoding;
+        let request = encoding.serialize(request).map_err(Error::from)?;
         let response = self.send_request_async(Api::name(), request).await?;
-        let response =
-            pot::from_slice::<Result<Api::Response, Api::Error>>(&response).map_err(Error::from)?;
+        let response = encoding
+            .deserialize::<Result<Api::Response, Api::Error>>(&response)
+            .map_err(Error::from)?;
         response.map_err(ApiError::Api)
     }
 
@@ -565,16 +581,18 @@ impl AsyncClient {
         &self,
         request: &Api,
     ) -> Result<Api::Response, ApiError<Api::Error>> {
-        let request = Bytes::from(pot::to_vec(request).map_err(Error::from)?);
+        let encoding = self.data.value_encoding;
+        let request = encoding.serialize(request).map_err(Error::from)?;
         let response = self.send_request(Api::name(), request)?;
 
-        let response =
-            pot::from_slice::<Result<Api::Response, Api::Error>>(&response).map_err(Error::from)?;
+        let response = encoding
+            .deserialize::<Result<Api::Response, Api::Error>>(&response)
+            .map_err(Error::from)?;
         response.map_err(ApiError::Api)
     }
 
     fn invoke_blocking_api_request<Api: api::Api>(&self, request: &Api) -> Result<(), Error> {
-        let request = Bytes::from(pot::to_vec(request).map_err(Error::from)?);
+        let request = self.data.value_encoding.serialize(request)?;
         self.send_request_without_confirmation(Api::name(), request)
             .map(|_| ())
     }
@@ -862,6 +880,7 @@ async fn process_response_payload(
     payload: Payload,
     outstanding_requests: &OutstandingRequestMapHandle,
     custom_apis: &HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>,
+    encoding: ValueEncoding,
 ) {
     if let Some(payload_id) = payload.id {
         if let Some(outstanding_request) = {
@@ -878,7 +897,7 @@ async fn process_response_payload(
         custom_apis.get(&payload.name).and_then(Option::as_ref),
         payload.value,
     ) {
-        custom_api_callback.response_received(value).await;
+        custom_api_callback.response_received(value, encoding).await;
     } else {
         log::warn!("unexpected api response received ({})", payload.name);
     }
@@ -951,14 +970,15 @@ impl<Api: api::Api> ApiCallback<Api> {
 #[async_trait]
 pub trait AnyApiCallback: Send + Sync + 'static {
     /// An out-of-band `response` was received. This happens when the server
-    /// sends a response that isn't in response to a request.
-    async fn response_received(&self, response: Bytes);
+    /// sends a response that isn't in response to a request. The response is
+    /// encoded using `encoding`.
+    async fn response_received(&self, response: Bytes, encoding: ValueEncoding);
 }
 
 #[async_trait]
 impl<Api: api::Api> AnyApiCallback for ApiCallback<Api> {
-    async fn response_received(&self, response: Bytes) {
-        match pot::from_slice::<Result<Api::Response, Api::Error>>(&response) {
+    async fn response_received(&self, response: Bytes, encoding: ValueEncoding) {
+        match encoding.deserialize::<Result<Api::Response, Api::Error>>(&response) {
             Ok(response) => self.generator.invoke(response.unwrap()).await,
             Err(err) => {
                 log::error!("error deserializing api: {err}");
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use bonsaidb_core::api::ApiName;
-use bonsaidb_core::networking::Payload;
+use bonsaidb_core::networking::{Payload, ValueEncoding};
 use bonsaidb_utils::fast_async_lock;
 use fabruic::{self, Certificate, Endpoint};
 use flume::Receiver;
@@ -146,7 +146,13 @@ pub async fn process(
 ) -> Result<(), Error> {
     while let Some(payload) = payload_receiver.next().await {
         let payload = payload?;
-        super::process_response_payload(payload, &outstanding_requests, &custom_apis).await;
+        super::process_response_payload(
+            payload,
+            &outstanding_requests,
+            &custom_apis,
+            ValueEncoding::Pot,
+        )
+        .await;
     }
 
     Err(Error::disconnected())
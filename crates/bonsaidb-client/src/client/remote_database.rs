@@ -4,15 +4,18 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use bonsaidb_core::connection::{
     AccessPolicy, AsyncConnection, AsyncLowLevelConnection, HasSchema, HasSession, Range,
-    SerializedQueryKey, Session, Sort,
+    SerializedDocumentFilter, SerializedQueryKey, Session, Sort,
 };
 use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
 use bonsaidb_core::networking::{
-    ApplyTransaction, Compact, CompactCollection, CompactKeyValueStore, Count, DeleteDocs, Get,
-    GetMultiple, LastTransactionId, List, ListExecutedTransactions, ListHeaders, Query,
+    ApplyTransaction, Compact, CompactCollection, CompactKeyValueStore, Count,
+    DefineDynamicCollection, DeleteDocs, FilterDocuments, FilterHeaders, Get, GetMultiple,
+    GetViewStatus, LastTransactionId, List, ListExecutedTransactions, ListHeaders, Query,
     QueryWithDocs, Reduce, ReduceGrouped,
 };
+use bonsaidb_core::schema::dynamic::DynamicCollection;
 use bonsaidb_core::schema::view::map::MappedSerializedValue;
+use bonsaidb_core::schema::view::ViewStatus;
 use bonsaidb_core::schema::{self, CollectionName, Schematic, ViewName};
 use bonsaidb_core::transaction::{Executed, OperationResult, Transaction};
 
@@ -285,16 +288,20 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
         &self,
         view: &ViewName,
         key: Option<SerializedQueryKey>,
+        group_level: Option<usize>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<MappedSerializedValue>, bonsaidb_core::Error> {
         Ok(self
             .client
-            .send_api_request(&ReduceGrouped(Reduce {
-                database: self.name.to_string(),
-                view: view.clone(),
-                key,
-                access_policy,
-            }))
+            .send_api_request(&ReduceGrouped {
+                reduce: Reduce {
+                    database: self.name.to_string(),
+                    view: view.clone(),
+                    key,
+                    access_policy,
+                },
+                group_level,
+            })
             .await?)
     }
 
@@ -314,6 +321,74 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
             })
             .await?)
     }
+
+    async fn filter_documents_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        Ok(self
+            .client
+            .send_api_request(&FilterDocuments {
+                database: self.name.to_string(),
+                collection: collection.clone(),
+                filter,
+                order,
+                limit,
+                access_policy,
+            })
+            .await?)
+    }
+
+    async fn filter_headers_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, bonsaidb_core::Error> {
+        Ok(self
+            .client
+            .send_api_request(&FilterHeaders(FilterDocuments {
+                database: self.name.to_string(),
+                collection: collection.clone(),
+                filter,
+                order,
+                limit,
+                access_policy,
+            }))
+            .await?)
+    }
+
+    async fn view_status_by_name(
+        &self,
+        view: &ViewName,
+    ) -> Result<ViewStatus, bonsaidb_core::Error> {
+        Ok(self
+            .client
+            .send_api_request(&GetViewStatus {
+                database: self.name.to_string(),
+                view: view.clone(),
+            })
+            .await?)
+    }
+
+    async fn define_dynamic_collection(
+        &self,
+        collection: DynamicCollection,
+    ) -> Result<(), bonsaidb_core::Error> {
+        Ok(self
+            .client
+            .send_api_request(&DefineDynamicCollection {
+                database: self.name.to_string(),
+                collection,
+            })
+            .await?)
+    }
 }
 
 impl HasSchema for AsyncRemoteDatabase {
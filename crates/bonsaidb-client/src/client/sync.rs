@@ -8,20 +8,26 @@ use bonsaidb_core::api;
 use bonsaidb_core::arc_bytes::serde::Bytes;
 use bonsaidb_core::connection::{
     AccessPolicy, Connection, Database, HasSchema, HasSession, IdentityReference,
-    LowLevelConnection, Range, SerializedQueryKey, Sort, StorageConnection,
+    LowLevelConnection, Range, SerializedDocumentFilter, SerializedQueryKey, Sort,
+    StorageConnection,
 };
 use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
 use bonsaidb_core::keyvalue::KeyValue;
+#[cfg(feature = "websockets")]
+use bonsaidb_core::networking::WebSocketFormat;
 use bonsaidb_core::networking::{
     AlterUserPermissionGroupMembership, AlterUserRoleMembership, ApplyTransaction, AssumeIdentity,
     Compact, CompactCollection, CompactKeyValueStore, Count, CreateDatabase, CreateSubscriber,
-    CreateUser, DeleteDatabase, DeleteDocs, DeleteUser, ExecuteKeyOperation, Get, GetMultiple,
+    CreateUser, DefineDynamicCollection, DeleteDatabase, DeleteDocs, DeleteUser,
+    ExecuteKeyOperation, FilterDocuments, FilterHeaders, Get, GetMultiple, GetViewStatus,
     LastTransactionId, List, ListAvailableSchemas, ListDatabases, ListExecutedTransactions,
     ListHeaders, Publish, PublishToAll, Query, QueryWithDocs, Reduce, ReduceGrouped, SubscribeTo,
     UnsubscribeFrom, CURRENT_PROTOCOL_VERSION,
 };
 use bonsaidb_core::pubsub::{AsyncSubscriber, PubSub, Receiver, Subscriber};
+use bonsaidb_core::schema::dynamic::DynamicCollection;
 use bonsaidb_core::schema::view::map;
+use bonsaidb_core::schema::view::ViewStatus;
 use bonsaidb_core::schema::{CollectionName, ViewName};
 use futures::Future;
 use tokio::runtime::{Handle, Runtime};
@@ -62,6 +68,8 @@ impl BlockingClient {
         AsyncClient::new_from_parts(
             url,
             CURRENT_PROTOCOL_VERSION,
+            #[cfg(feature = "websockets")]
+            WebSocketFormat::default(),
             HashMap::default(),
             None,
             None,
@@ -84,7 +92,7 @@ impl BlockingClient {
     /// Sends an api `request` without waiting for a result. The response from
     /// the server will be ignored.
     pub fn invoke_api_request<Api: api::Api>(&self, request: &Api) -> Result<(), Error> {
-        let request = Bytes::from(pot::to_vec(request).map_err(Error::from)?);
+        let request = self.0.data.value_encoding.serialize(request)?;
         self.0
             .send_request_without_confirmation(Api::name(), request)
             .map(|_| ())
@@ -516,18 +524,19 @@ impl LowLevelConnection for BlockingRemoteDatabase {
         &self,
         view: &bonsaidb_core::schema::ViewName,
         key: Option<SerializedQueryKey>,
+        group_level: Option<usize>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<bonsaidb_core::schema::view::map::MappedSerializedValue>, bonsaidb_core::Error>
     {
-        Ok(self
-            .0
-            .client
-            .send_blocking_api_request(&ReduceGrouped(Reduce {
+        Ok(self.0.client.send_blocking_api_request(&ReduceGrouped {
+            reduce: Reduce {
                 database: self.0.name.to_string(),
                 view: view.clone(),
                 key,
                 access_policy,
-            }))?)
+            },
+            group_level,
+        })?)
     }
 
     fn delete_docs_by_name(
@@ -543,6 +552,65 @@ impl LowLevelConnection for BlockingRemoteDatabase {
             access_policy,
         })?)
     }
+
+    fn filter_documents_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        Ok(self.0.client.send_blocking_api_request(&FilterDocuments {
+            database: self.0.name.to_string(),
+            collection: collection.clone(),
+            filter,
+            order,
+            limit,
+            access_policy,
+        })?)
+    }
+
+    fn filter_headers_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, bonsaidb_core::Error> {
+        Ok(self
+            .0
+            .client
+            .send_blocking_api_request(&FilterHeaders(FilterDocuments {
+                database: self.0.name.to_string(),
+                collection: collection.clone(),
+                filter,
+                order,
+                limit,
+                access_policy,
+            }))?)
+    }
+
+    fn view_status_by_name(&self, view: &ViewName) -> Result<ViewStatus, bonsaidb_core::Error> {
+        Ok(self.0.client.send_blocking_api_request(&GetViewStatus {
+            database: self.0.name.to_string(),
+            view: view.clone(),
+        })?)
+    }
+
+    fn define_dynamic_collection(
+        &self,
+        collection: DynamicCollection,
+    ) -> Result<(), bonsaidb_core::Error> {
+        Ok(self
+            .0
+            .client
+            .send_blocking_api_request(&DefineDynamicCollection {
+                database: self.0.name.to_string(),
+                collection,
+            })?)
+    }
 }
 
 impl HasSession for BlockingRemoteDatabase {
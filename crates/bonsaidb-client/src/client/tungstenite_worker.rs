@@ -3,7 +3,7 @@ use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use bonsaidb_core::api::ApiName;
-use bonsaidb_core::networking::Payload;
+use bonsaidb_core::networking::{Payload, WebSocketFormat};
 use bonsaidb_utils::fast_async_lock;
 use flume::Receiver;
 use futures::stream::{SplitSink, SplitStream};
@@ -22,10 +22,12 @@ use crate::Error;
 pub(super) async fn reconnecting_client_loop(
     server: ConnectionInfo,
     protocol_version: &str,
+    format: WebSocketFormat,
     request_receiver: Receiver<PendingRequest>,
     custom_apis: Arc<HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>>,
     connection_counter: Arc<AtomicU32>,
 ) -> Result<(), Error> {
+    let subprotocol = format.subprotocol(protocol_version);
     let mut pending_error = None;
     while let Ok(request) = {
         server.subscribers.clear();
@@ -43,7 +45,7 @@ pub(super) async fn reconnecting_client_loop(
                 tokio_tungstenite::tungstenite::handshake::client::Request::get(
                     server.url.as_str(),
                 )
-                .header("Sec-WebSocket-Protocol", protocol_version)
+                .header("Sec-WebSocket-Protocol", subprotocol.as_str())
                 .header("Sec-WebSocket-Version", "13")
                 .header("Sec-WebSocket-Key", generate_key())
                 .header("Host", server.url.host_str().expect("no host"))
@@ -72,7 +74,7 @@ pub(super) async fn reconnecting_client_loop(
         {
             let mut outstanding_requests = fast_async_lock!(outstanding_requests);
             if let Err(err) = sender
-                .send(Message::Binary(bincode::serialize(&request.request)?))
+                .send(payload_message(format, &request.request)?)
                 .await
             {
                 drop(request.responder.send(Err(Error::from(err))));
@@ -85,8 +87,13 @@ pub(super) async fn reconnecting_client_loop(
         }
 
         if let Err(err) = tokio::try_join!(
-            request_sender(&request_receiver, sender, outstanding_requests.clone()),
-            response_processor(receiver, outstanding_requests.clone(), &custom_apis,)
+            request_sender(
+                &request_receiver,
+                sender,
+                outstanding_requests.clone(),
+                format
+            ),
+            response_processor(receiver, outstanding_requests.clone(), &custom_apis, format)
         ) {
             // Our socket was disconnected, clear the outstanding requests before returning.
             log::error!("Error on socket {:?}", err);
@@ -102,11 +109,12 @@ async fn request_sender(
     request_receiver: &Receiver<PendingRequest>,
     mut sender: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
     outstanding_requests: OutstandingRequestMapHandle,
+    format: WebSocketFormat,
 ) -> Result<(), Error> {
     while let Ok(pending) = request_receiver.recv_async().await {
         let mut outstanding_requests = fast_async_lock!(outstanding_requests);
         sender
-            .send(Message::Binary(bincode::serialize(&pending.request)?))
+            .send(payload_message(format, &pending.request)?)
             .await?;
 
         outstanding_requests.insert(
@@ -123,20 +131,38 @@ async fn response_processor(
     mut receiver: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
     outstanding_requests: OutstandingRequestMapHandle,
     custom_apis: &HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>,
+    format: WebSocketFormat,
 ) -> Result<(), Error> {
     while let Some(message) = receiver.next().await {
-        let message = message?;
-        match message {
-            Message::Binary(response) => {
-                let payload = bincode::deserialize::<Payload>(&response)?;
-
-                super::process_response_payload(payload, &outstanding_requests, custom_apis).await;
+        let payload = match (format, message?) {
+            (WebSocketFormat::Bincode, Message::Binary(response)) => {
+                bincode::deserialize(&response)?
             }
-            other => {
+            (WebSocketFormat::Pot, Message::Binary(response)) => pot::from_slice(&response)?,
+            (WebSocketFormat::Json, Message::Text(response)) => Payload::from_json(&response)?,
+            (_, other) => {
                 log::error!("Unexpected websocket message: {:?}", other);
+                continue;
             }
-        }
+        };
+
+        super::process_response_payload(
+            payload,
+            &outstanding_requests,
+            custom_apis,
+            format.value_encoding(),
+        )
+        .await;
     }
 
     Ok(())
 }
+
+/// Encodes `payload` into a message using `format`.
+fn payload_message(format: WebSocketFormat, payload: &Payload) -> Result<Message, Error> {
+    match format {
+        WebSocketFormat::Bincode => Ok(Message::Binary(bincode::serialize(payload)?)),
+        WebSocketFormat::Pot => Ok(Message::Binary(pot::to_vec(payload)?)),
+        WebSocketFormat::Json => Ok(Message::Text(payload.to_json()?)),
+    }
+}
@@ -4,7 +4,7 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use bonsaidb_core::api::ApiName;
-use bonsaidb_core::networking::Payload;
+use bonsaidb_core::networking::{Payload, WebSocketFormat};
 use bonsaidb_utils::fast_async_lock;
 use flume::Receiver;
 use url::Url;
@@ -22,6 +22,7 @@ use crate::Error;
 pub fn spawn_client(
     url: Arc<Url>,
     protocol_version: &'static str,
+    format: WebSocketFormat,
     request_receiver: Receiver<PendingRequest>,
     custom_apis: Arc<HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>>,
     subscribers: SubscriberMap,
@@ -32,6 +33,7 @@ pub fn spawn_client(
     wasm_bindgen_futures::spawn_local(create_websocket(
         url,
         protocol_version,
+        format,
         request_receiver,
         custom_apis,
         subscribers,
@@ -45,6 +47,7 @@ pub fn spawn_client(
 async fn create_websocket(
     url: Arc<Url>,
     protocol_version: &'static str,
+    format: WebSocketFormat,
     request_receiver: Receiver<PendingRequest>,
     custom_apis: Arc<HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>>,
     subscribers: SubscriberMap,
@@ -70,7 +73,8 @@ async fn create_websocket(
     // In wasm we're not going to have a real loop. We're going create a
     // websocket and store it in JS. This will allow us to get around Send/Sync
     // issues since each access of the websocket can pull it from js.
-    let ws = match WebSocket::new_with_str(&url.to_string(), protocol_version) {
+    let ws = match WebSocket::new_with_str(&url.to_string(), &format.subprotocol(protocol_version))
+    {
         Ok(ws) => ws,
         Err(err) => {
             drop(
@@ -81,6 +85,7 @@ async fn create_websocket(
             spawn_client(
                 url,
                 protocol_version,
+                format,
                 request_receiver,
                 custom_apis.clone(),
                 subscribers,
@@ -109,10 +114,12 @@ async fn create_websocket(
         initial_request.clone(),
         outstanding_requests.clone(),
         ws.clone(),
+        format,
     );
     ws.set_onopen(Some(onopen_callback.as_ref().unchecked_ref()));
 
-    let onmessage_callback = on_message_callback(outstanding_requests.clone(), custom_apis.clone());
+    let onmessage_callback =
+        on_message_callback(outstanding_requests.clone(), custom_apis.clone(), format);
     ws.set_onmessage(Some(onmessage_callback.as_ref().unchecked_ref()));
 
     let onerror_callback =
@@ -131,6 +138,7 @@ async fn create_websocket(
     let onclose_callback = on_close_callback(
         url.clone(),
         protocol_version,
+        format,
         request_receiver.clone(),
         shutdown_sender,
         ws.clone(),
@@ -174,13 +182,14 @@ fn on_open_callback(
     initial_request: Arc<Mutex<Option<PendingRequest>>>,
     requests: OutstandingRequestMapHandle,
     ws: WebSocket,
+    format: WebSocketFormat,
 ) -> JsValue {
     Closure::once_into_js(move || {
         wasm_bindgen_futures::spawn_local(async move {
             if let Some(initial_request) = take_initial_request(&initial_request) {
-                if send_request(&ws, initial_request, &requests).await {
+                if send_request(&ws, initial_request, &requests, format).await {
                     while let Ok(pending) = request_receiver.recv_async().await {
-                        if !send_request(&ws, pending, &requests).await {
+                        if !send_request(&ws, pending, &requests, format).await {
                             break;
                         }
                     }
@@ -198,18 +207,32 @@ async fn send_request(
     ws: &WebSocket,
     pending: PendingRequest,
     requests: &OutstandingRequestMapHandle,
+    format: WebSocketFormat,
 ) -> bool {
     let mut outstanding_requests = fast_async_lock!(requests);
-    let bytes = match bincode::serialize(&pending.request) {
-        Ok(bytes) => bytes,
+    let sent = match format {
+        WebSocketFormat::Bincode => bincode::serialize(&pending.request)
+            .map_err(Error::from)
+            .map(|bytes| ws.send_with_u8_array(&bytes)),
+        WebSocketFormat::Pot => pot::to_vec(&pending.request)
+            .map_err(Error::from)
+            .map(|bytes| ws.send_with_u8_array(&bytes)),
+        WebSocketFormat::Json => pending
+            .request
+            .to_json()
+            .map_err(Error::from)
+            .map(|text| ws.send_with_str(&text)),
+    };
+    let result = match sent {
+        Ok(result) => result,
         Err(err) => {
-            drop(pending.responder.send(Err(Error::from(err))));
+            drop(pending.responder.send(Err(err)));
             // Despite not sending, this error was handled, so we report
             // success.
             return true;
         }
     };
-    match ws.send_with_u8_array(&bytes) {
+    match result {
         Ok(()) => {
             outstanding_requests.insert(
                 pending.request.id.expect("all requests must have ids"),
@@ -231,12 +254,12 @@ async fn send_request(
 fn on_message_callback(
     outstanding_requests: OutstandingRequestMapHandle,
     custom_apis: Arc<HashMap<ApiName, Option<Arc<dyn AnyApiCallback>>>>,
+    format: WebSocketFormat,
 ) -> JsValue {
     Closure::wrap(Box::new(move |e: MessageEvent| {
         // Handle difference Text/Binary,...
-        if let Ok(abuf) = e.data().dyn_into::<js_sys::ArrayBuffer>() {
-            let array = js_sys::Uint8Array::new(&abuf);
-            let payload = match bincode::deserialize::<Payload>(&array.to_vec()) {
+        if let Some(payload) = decode_payload(format, e.data()) {
+            let payload = match payload {
                 Ok(payload) => payload,
                 Err(err) => {
                     log::error!("error deserializing response: {:?}", err);
@@ -247,7 +270,13 @@ fn on_message_callback(
             let outstanding_requests = outstanding_requests.clone();
             let custom_apis = custom_apis.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                super::process_response_payload(payload, &outstanding_requests, &custom_apis).await;
+                super::process_response_payload(
+                    payload,
+                    &outstanding_requests,
+                    &custom_apis,
+                    format.value_encoding(),
+                )
+                .await;
             });
         } else {
             log::warn!("Unexpected WebSocket message received: {:?}", e.data());
@@ -256,6 +285,27 @@ fn on_message_callback(
     .into_js_value()
 }
 
+/// Decodes a payload from the data of a message event, returning `None` if the
+/// data is not the kind of message `format` is sent as.
+fn decode_payload(format: WebSocketFormat, data: JsValue) -> Option<Result<Payload, Error>> {
+    let binary = |data: JsValue| {
+        data.dyn_into::<js_sys::ArrayBuffer>()
+            .ok()
+            .map(|abuf| js_sys::Uint8Array::new(&abuf).to_vec())
+    };
+    match format {
+        WebSocketFormat::Bincode => {
+            binary(data).map(|bytes| bincode::deserialize(&bytes).map_err(Error::from))
+        }
+        WebSocketFormat::Pot => {
+            binary(data).map(|bytes| pot::from_slice(&bytes).map_err(Error::from))
+        }
+        WebSocketFormat::Json => data
+            .as_string()
+            .map(|text| Payload::from_json(&text).map_err(Error::from)),
+    }
+}
+
 fn connect_timeout_callback(
     ws: WebSocket,
     initial_request: Arc<Mutex<Option<PendingRequest>>>,
@@ -313,6 +363,7 @@ fn take_initial_request(initial_request: &Mutex<Option<PendingRequest>>) -> Opti
 fn on_close_callback(
     url: Arc<Url>,
     protocol_version: &'static str,
+    format: WebSocketFormat,
     request_receiver: Receiver<PendingRequest>,
     shutdown: flume::Sender<()>,
     ws: WebSocket,
@@ -347,6 +398,7 @@ fn on_close_callback(
             spawn_client(
                 url,
                 protocol_version,
+                format,
                 request_receiver,
                 custom_apis.clone(),
                 subscribers,
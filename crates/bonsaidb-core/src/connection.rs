@@ -15,9 +15,12 @@ use zeroize::Zeroize;
 
 use crate::admin::{Role, User};
 use crate::document::{
-    CollectionDocument, CollectionHeader, Document, HasHeader, Header, OwnedDocument,
+    CollectionDocument, CollectionHeader, Document, DocumentId, HasHeader, Header, OwnedDocument,
+};
+use crate::key::{
+    AsyncGeoQuery, ByteSource, GeoArea, GeoPoint, GeoQuery, IntoPrefixRange, Key, KeyEncoding,
+    KeyKind, KeyVisitor,
 };
-use crate::key::{ByteSource, IntoPrefixRange, Key, KeyEncoding, KeyKind, KeyVisitor};
 use crate::permissions::Permissions;
 use crate::schema::view::map::{MappedDocuments, ViewMappings as ViewMappingsCurrent};
 use crate::schema::{
@@ -26,11 +29,20 @@ use crate::schema::{
 };
 use crate::{transaction, Error};
 
+mod filter;
 mod has_session;
 mod lowlevel;
+mod stream;
 
+pub use self::filter::{
+    AsyncFilter, DocumentFilter, Filter, SerializedDocumentFilter, MAX_FILTER_DEPTH,
+};
 pub use self::has_session::HasSession;
 pub use self::lowlevel::{AsyncLowLevelConnection, HasSchema, LowLevelConnection};
+pub use self::stream::{
+    DocumentIter, DocumentStream, MappedDocumentIter, MappedDocumentStream, OwnedMappedDocument,
+    DEFAULT_BATCH_SIZE,
+};
 
 /// A connection to a database's [`Schema`](schema::Schema), giving access to
 /// [`Collection`s](crate::schema::Collection) and
@@ -445,6 +457,29 @@ where
         )
     }
 
+    /// Retrieves the documents matching `filter`, which combines the entries of
+    /// one or more views of this collection.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::{Connection, DocumentFilter};
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// let top_ranked = DocumentFilter::key_range(&ScoresByRank, 1..=10)?
+    ///     .or(DocumentFilter::key(&MyCollectionByName, "ecton")?);
+    /// for header in db
+    ///     .collection::<MyCollection>()
+    ///     .filter(top_ranked)
+    ///     .headers()?
+    /// {
+    ///     println!("Matched #{}", header.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn filter(&self, filter: DocumentFilter<Cl>) -> Filter<'a, Cn, Cl> {
+        Filter::new(self.connection, filter)
+    }
+
     /// Removes a `Document` from the database.
     ///
     /// ```rust
@@ -595,6 +630,47 @@ where
         } = self;
         collection.connection.list::<Cl, _, _>(range, sort, limit)
     }
+
+    /// Returns an iterator over the matching documents. Rather than retrieving
+    /// all of the documents at once, the iterator retrieves
+    /// [`DEFAULT_BATCH_SIZE`] documents at a time. This can be changed using
+    /// [`DocumentIter::batch_size()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// for doc in db.collection::<MyCollection>().all().iter()?.batch_size(100) {
+    ///     let doc = doc?;
+    ///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter(self) -> Result<DocumentIter<'a, Cn>, Error> {
+        self.iter_converting(Ok)
+    }
+
+    pub(crate) fn iter_converting<D>(
+        self,
+        convert: fn(OwnedDocument) -> Result<D, Error>,
+    ) -> Result<DocumentIter<'a, Cn, D>, Error> {
+        let Self {
+            collection,
+            range,
+            sort,
+            limit,
+        } = self;
+        let ids = range.map_result(|id| DocumentId::new(id))?;
+        Ok(DocumentIter::new(
+            collection.connection,
+            Cl::collection_name(),
+            ids,
+            sort,
+            limit,
+            convert,
+        ))
+    }
 }
 
 /// Parameters to query a [`schema::View`].
@@ -810,6 +886,43 @@ where
         }
     }
 
+    /// Returns a query for the entries in the view with keys within `area`.
+    /// The query uses this builder's access policy and ignores its key
+    /// filter, sort order, and limit.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// use bonsaidb_core::key::{GeoArea, GeoPoint};
+    ///
+    /// #[derive(View, Debug, Clone)]
+    /// #[view(name = "by-location", key = GeoPoint, collection = MyCollection)]
+    /// # #[view(core = bonsaidb_core)]
+    /// struct ByLocation;
+    ///
+    /// let paris = GeoPoint::new(48.8566, 2.3522);
+    /// for mapping in ByLocation::entries(&db)
+    ///     .within(GeoArea::radius(paris, 10_000.))
+    ///     .sorted_by_distance()
+    ///     .query()?
+    /// {
+    ///     println!(
+    ///         "{:?} is {}m away",
+    ///         mapping.source,
+    ///         paris.distance_to(&mapping.key)
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn within(self, area: impl Into<GeoArea>) -> GeoQuery<'a, Cn, V>
+    where
+        V: schema::SerializedView<Key = GeoPoint>,
+    {
+        GeoQuery::new(self.connection, area.into(), self.access_policy)
+    }
+
     /// Sets the access policy for queries.
     ///
     /// ```rust
@@ -940,6 +1053,44 @@ where
         )
     }
 
+    /// Returns an iterator over the results of the query with the associated
+    /// [`Document`s](crate::document::OwnedDocument). Rather than retrieving
+    /// all of the results at once, the iterator retrieves the entries for
+    /// [`DEFAULT_BATCH_SIZE`] keys at a time. This can be changed using
+    /// [`MappedDocumentIter::batch_size()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// for mapped in ScoresByRank::entries(&db)
+    ///     .with_key_range(42..)
+    ///     .iter_with_docs()?
+    /// {
+    ///     let mapped = mapped?;
+    ///     println!(
+    ///         "Mapping from #{} with rank: {} and score: {}. Document bytes: {:?}",
+    ///         mapped.document.header.id,
+    ///         mapped.mapping.key,
+    ///         mapped.mapping.value,
+    ///         mapped.document.contents
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_with_docs(self) -> Result<MappedDocumentIter<'a, Cn, V>, Error> {
+        let view = self.connection.schematic().view::<V>()?.view_name();
+        Ok(MappedDocumentIter::new(
+            self.connection,
+            view,
+            self.key.map(|key| key.serialized()).transpose()?,
+            self.sort,
+            self.limit,
+            self.access_policy,
+        ))
+    }
+
     /// Executes the query and retrieves the results with the associated [`CollectionDocument`s](crate::document::CollectionDocument).
     ///
     /// ```rust
@@ -1011,6 +1162,29 @@ where
             .reduce_grouped::<V, Key>(self.key, self.access_policy)
     }
 
+    /// Executes a reduce over the results of the query, grouping by the first
+    /// `group_level` fields of the view's composite key. `Prefix` is the type
+    /// the grouped keys are decoded as, and should be a tuple containing the
+    /// first `group_level` fields of the view's key.
+    ///
+    /// For example, a view with a key of `(u16, u8, u8)` representing a year,
+    /// month, and day can be reduced by year using
+    /// `reduce_grouped_by_level::<(u16,)>(1)` or by month using
+    /// `reduce_grouped_by_level::<(u16, u8)>(2)`.
+    pub fn reduce_grouped_by_level<Prefix>(
+        self,
+        group_level: usize,
+    ) -> Result<Vec<MappedValue<Prefix, V::Value>>, Error>
+    where
+        Prefix: for<'k> crate::key::Key<'k>,
+    {
+        self.connection.reduce_grouped_by_level::<V, Key, Prefix>(
+            self.key,
+            group_level,
+            self.access_policy,
+        )
+    }
+
     /// Deletes all of the associated documents that match this view query.
     ///
     /// ```rust
@@ -1474,6 +1648,32 @@ where
         AsyncList::new(MaybeOwned::Borrowed(self), RangeRef::from(..))
     }
 
+    /// Retrieves the documents matching `filter`, which combines the entries of
+    /// one or more views of this collection.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::{AsyncConnection, DocumentFilter};
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let top_ranked = DocumentFilter::key_range(&ScoresByRank, 1..=10)?
+    ///     .or(DocumentFilter::key(&MyCollectionByName, "ecton")?);
+    /// for header in db
+    ///     .collection::<MyCollection>()
+    ///     .filter(top_ranked)
+    ///     .headers()
+    ///     .await?
+    /// {
+    ///     println!("Matched #{}", header.id);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn filter(&self, filter: DocumentFilter<Cl>) -> AsyncFilter<'a, Cn, Cl> {
+        AsyncFilter::new(self.connection, filter)
+    }
+
     /// Removes a `Document` from the database.
     ///
     /// ```rust
@@ -1728,6 +1928,55 @@ where
             _ => unreachable!("Attempted to use after retrieving the result"),
         }
     }
+
+    /// Returns a stream of the matching documents. Rather than retrieving all
+    /// of the documents at once, the stream retrieves [`DEFAULT_BATCH_SIZE`]
+    /// documents at a time. This can be changed using
+    /// [`DocumentStream::batch_size()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use futures::StreamExt;
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut docs = db.collection::<MyCollection>().all().stream()?;
+    /// while let Some(doc) = docs.next().await {
+    ///     let doc = doc?;
+    ///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn stream(self) -> Result<DocumentStream<'a, Cn>, Error> {
+        self.stream_converting(Ok)
+    }
+
+    pub(crate) fn stream_converting<D>(
+        self,
+        convert: fn(OwnedDocument) -> Result<D, Error>,
+    ) -> Result<DocumentStream<'a, Cn, D>, Error> {
+        match self.state {
+            ListState::Pending(Some(AsyncListBuilder {
+                collection,
+                range,
+                sort,
+                limit,
+            })) => {
+                let ids = range.map_result(|id| DocumentId::new(id))?;
+                Ok(DocumentStream::new(
+                    collection.connection,
+                    Cl::collection_name(),
+                    ids,
+                    sort,
+                    limit,
+                    convert,
+                ))
+            }
+            _ => unreachable!("Attempted to use after retrieving the result"),
+        }
+    }
 }
 
 #[allow(clippy::type_repetition_in_bounds)]
@@ -2008,6 +2257,46 @@ where
         }
     }
 
+    /// Returns a query for the entries in the view with keys within `area`.
+    /// The query uses this builder's access policy and ignores its key
+    /// filter, sort order, and limit.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use bonsaidb_core::key::{GeoArea, GeoPoint};
+    ///
+    /// #[derive(View, Debug, Clone)]
+    /// #[view(name = "by-location", key = GeoPoint, collection = MyCollection)]
+    /// # #[view(core = bonsaidb_core)]
+    /// struct ByLocation;
+    ///
+    /// let paris = GeoPoint::new(48.8566, 2.3522);
+    /// for mapping in ByLocation::entries_async(&db)
+    ///     .within(GeoArea::radius(paris, 10_000.))
+    ///     .sorted_by_distance()
+    ///     .query()
+    ///     .await?
+    /// {
+    ///     println!(
+    ///         "{:?} is {}m away",
+    ///         mapping.source,
+    ///         paris.distance_to(&mapping.key)
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn within(self, area: impl Into<GeoArea>) -> AsyncGeoQuery<'a, Cn, V>
+    where
+        V: schema::SerializedView<Key = GeoPoint>,
+    {
+        AsyncGeoQuery::new(self.connection, area.into(), self.access_policy)
+    }
+
     /// Sets the access policy for queries.
     ///
     /// ```rust
@@ -2154,6 +2443,47 @@ where
             .await
     }
 
+    /// Returns a stream of the results of the query with the associated
+    /// [`Document`s](crate::document::OwnedDocument). Rather than retrieving
+    /// all of the results at once, the stream retrieves the entries for
+    /// [`DEFAULT_BATCH_SIZE`] keys at a time. This can be changed using
+    /// [`MappedDocumentStream::batch_size()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use futures::StreamExt;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut results = ScoresByRank::entries_async(&db)
+    ///     .with_key_range(42..)
+    ///     .stream_with_docs()?;
+    /// while let Some(mapped) = results.next().await {
+    ///     let mapped = mapped?;
+    ///     println!(
+    ///         "Mapping from #{} with rank: {} and score: {}. Document bytes: {:?}",
+    ///         mapped.document.header.id,
+    ///         mapped.mapping.key,
+    ///         mapped.mapping.value,
+    ///         mapped.document.contents
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn stream_with_docs(self) -> Result<MappedDocumentStream<'a, Cn, V>, Error> {
+        let view = self.connection.schematic().view::<V>()?.view_name();
+        Ok(MappedDocumentStream::new(
+            self.connection,
+            view,
+            self.key.map(|key| key.serialized()).transpose()?,
+            self.sort,
+            self.limit,
+            self.access_policy,
+        ))
+    }
+
     /// Executes the query and retrieves the results with the associated [`CollectionDocument`s](crate::document::CollectionDocument).
     ///
     /// ```rust
@@ -2231,6 +2561,27 @@ where
             .await
     }
 
+    /// Executes a reduce over the results of the query, grouping by the first
+    /// `group_level` fields of the view's composite key. `Prefix` is the type
+    /// the grouped keys are decoded as, and should be a tuple containing the
+    /// first `group_level` fields of the view's key.
+    ///
+    /// For example, a view with a key of `(u16, u8, u8)` representing a year,
+    /// month, and day can be reduced by year using
+    /// `reduce_grouped_by_level::<(u16,)>(1)` or by month using
+    /// `reduce_grouped_by_level::<(u16, u8)>(2)`.
+    pub async fn reduce_grouped_by_level<Prefix>(
+        self,
+        group_level: usize,
+    ) -> Result<Vec<MappedValue<Prefix, V::Value>>, Error>
+    where
+        Prefix: for<'k> crate::key::Key<'k>,
+    {
+        self.connection
+            .reduce_grouped_by_level::<V, _, Prefix>(self.key, group_level, self.access_policy)
+            .await
+    }
+
     /// Deletes all of the associated documents that match this view query.
     ///
     /// ```rust
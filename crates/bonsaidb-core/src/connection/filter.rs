@@ -0,0 +1,364 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use derive_where::derive_where;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{
+    AccessPolicy, AsyncConnection, Connection, MaybeOwned, QueryKey, RangeRef, SerializedQueryKey,
+    Sort,
+};
+use crate::document::{Header, OwnedDocument};
+use crate::key::KeyEncoding;
+use crate::schema::{self, ViewName};
+use crate::Error;
+
+/// Matches documents in a collection using the entries of one or more of the
+/// collection's views.
+///
+/// Filters are combined using [`DocumentFilter::and()`] and
+/// [`DocumentFilter::or()`]. The database evaluates the filter by intersecting
+/// and unioning the ids of the documents matched by each view, which means
+/// only the documents that match the entire filter are loaded and returned.
+///
+/// ```rust
+/// # bonsaidb_core::__doctest_prelude!();
+/// # use bonsaidb_core::connection::{Connection, DocumentFilter};
+/// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+/// let ranked_ecton = DocumentFilter::key(&ScoresByRank, &1)?
+///     .and(DocumentFilter::key(&MyCollectionByName, "ecton")?);
+/// for doc in db.collection::<MyCollection>().filter(ranked_ecton).query()? {
+///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive_where(Clone, Debug)]
+#[must_use]
+pub struct DocumentFilter<C> {
+    serialized: SerializedDocumentFilter,
+    _collection: PhantomData<C>,
+}
+
+impl<C> DocumentFilter<C>
+where
+    C: schema::Collection,
+{
+    const fn new(serialized: SerializedDocumentFilter) -> Self {
+        Self {
+            serialized,
+            _collection: PhantomData,
+        }
+    }
+
+    /// Matches all documents that have at least one entry in `view`.
+    pub fn view<V>(view: &V) -> Self
+    where
+        V: schema::SerializedView<Collection = C>,
+    {
+        Self::new(SerializedDocumentFilter::View {
+            view: view.view_name(),
+            key: None,
+        })
+    }
+
+    /// Matches all documents that have an entry in `view` with `key`.
+    pub fn key<V, K>(view: &V, key: &K) -> Result<Self, Error>
+    where
+        V: schema::SerializedView<Collection = C>,
+        K: KeyEncoding<V::Key> + PartialEq + ?Sized,
+        V::Key: Borrow<K> + PartialEq<K>,
+    {
+        Self::with_query_key(view, &QueryKey::Matches(MaybeOwned::Borrowed(key)))
+    }
+
+    /// Matches all documents that have an entry in `view` with any of `keys`.
+    pub fn keys<'k, V, K, IntoIter>(view: &V, keys: IntoIter) -> Result<Self, Error>
+    where
+        V: schema::SerializedView<Collection = C>,
+        K: KeyEncoding<V::Key> + PartialEq + ?Sized + 'k,
+        V::Key: Borrow<K> + PartialEq<K>,
+        IntoIter: IntoIterator<Item = &'k K>,
+    {
+        Self::with_query_key(
+            view,
+            &QueryKey::Multiple(keys.into_iter().map(MaybeOwned::Borrowed).collect()),
+        )
+    }
+
+    /// Matches all documents that have an entry in `view` with a key
+    /// contained in `range`.
+    pub fn key_range<'k, V, K, R>(view: &V, range: R) -> Result<Self, Error>
+    where
+        V: schema::SerializedView<Collection = C>,
+        K: KeyEncoding<V::Key> + PartialEq + ?Sized + 'k,
+        V::Key: Borrow<K> + PartialEq<K>,
+        R: Into<RangeRef<'k, V::Key, K>>,
+    {
+        Self::with_query_key(view, &QueryKey::Range(range.into()))
+    }
+
+    fn with_query_key<V, K>(view: &V, key: &QueryKey<'_, V::Key, K>) -> Result<Self, Error>
+    where
+        V: schema::SerializedView<Collection = C>,
+        K: KeyEncoding<V::Key> + PartialEq + ?Sized,
+        V::Key: Borrow<K> + PartialEq<K>,
+    {
+        Ok(Self::new(SerializedDocumentFilter::View {
+            view: view.view_name(),
+            key: Some(key.serialized()?),
+        }))
+    }
+
+    /// Returns a filter that matches documents that are matched by both this
+    /// filter and `other`.
+    pub fn and(self, other: Self) -> Self {
+        Self::new(self.serialized.and(other.serialized))
+    }
+
+    /// Returns a filter that matches documents that are matched by either this
+    /// filter or `other`.
+    pub fn or(self, other: Self) -> Self {
+        Self::new(self.serialized.or(other.serialized))
+    }
+
+    /// Returns the serialized representation of this filter.
+    #[must_use]
+    pub fn into_serialized(self) -> SerializedDocumentFilter {
+        self.serialized
+    }
+}
+
+/// The maximum number of [`SerializedDocumentFilter::All`] and
+/// [`SerializedDocumentFilter::Any`] filters that can be nested inside of each
+/// other. Filters nested more deeply are rejected by the database.
+pub const MAX_FILTER_DEPTH: usize = 32;
+
+/// A [`DocumentFilter`] that has had its keys serialized.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum SerializedDocumentFilter {
+    /// Matches documents that have an entry in `view`. If `key` is provided,
+    /// only entries matching `key` are considered.
+    View {
+        /// The view to query.
+        view: ViewName,
+        /// The key filter for the view.
+        key: Option<SerializedQueryKey>,
+    },
+    /// Matches documents that are matched by every filter. An empty list
+    /// matches every document in the collection.
+    All(Vec<SerializedDocumentFilter>),
+    /// Matches documents that are matched by any filter. An empty list
+    /// matches no documents.
+    Any(Vec<SerializedDocumentFilter>),
+}
+
+impl SerializedDocumentFilter {
+    /// Returns a filter that matches documents that are matched by both this
+    /// filter and `other`.
+    #[must_use]
+    pub fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::All(mut filters), Self::All(others)) => {
+                filters.extend(others);
+                Self::All(filters)
+            }
+            (Self::All(mut filters), other) => {
+                filters.push(other);
+                Self::All(filters)
+            }
+            (filter, Self::All(mut others)) => {
+                others.insert(0, filter);
+                Self::All(others)
+            }
+            (filter, other) => Self::All(vec![filter, other]),
+        }
+    }
+
+    /// Returns a filter that matches documents that are matched by either this
+    /// filter or `other`.
+    #[must_use]
+    pub fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Any(mut filters), Self::Any(others)) => {
+                filters.extend(others);
+                Self::Any(filters)
+            }
+            (Self::Any(mut filters), other) => {
+                filters.push(other);
+                Self::Any(filters)
+            }
+            (filter, Self::Any(mut others)) => {
+                others.insert(0, filter);
+                Self::Any(others)
+            }
+            (filter, other) => Self::Any(vec![filter, other]),
+        }
+    }
+}
+
+/// Retrieves the documents matching a [`DocumentFilter`]. Returned from
+/// [`Collection::filter()`](super::Collection::filter).
+#[must_use]
+pub struct Filter<'a, Cn, Cl> {
+    connection: &'a Cn,
+    filter: DocumentFilter<Cl>,
+
+    /// The access policy used when querying the filter's views. The default
+    /// value is [`AccessPolicy::UpdateBefore`].
+    pub access_policy: AccessPolicy,
+
+    /// The order the documents are returned in, by id.
+    pub sort: Sort,
+
+    /// The maximum number of results to return.
+    pub limit: Option<u32>,
+}
+
+impl<'a, Cn, Cl> Filter<'a, Cn, Cl>
+where
+    Cn: Connection,
+    Cl: schema::Collection,
+{
+    pub(crate) const fn new(connection: &'a Cn, filter: DocumentFilter<Cl>) -> Self {
+        Self {
+            connection,
+            filter,
+            access_policy: AccessPolicy::UpdateBefore,
+            sort: Sort::Ascending,
+            limit: None,
+        }
+    }
+
+    /// Returns the documents in ascending order of their ids. This is the
+    /// default behavior.
+    pub const fn ascending(mut self) -> Self {
+        self.sort = Sort::Ascending;
+        self
+    }
+
+    /// Returns the documents in descending order of their ids.
+    pub const fn descending(mut self) -> Self {
+        self.sort = Sort::Descending;
+        self
+    }
+
+    /// Sets the maximum number of results to return.
+    pub const fn limit(mut self, maximum_results: u32) -> Self {
+        self.limit = Some(maximum_results);
+        self
+    }
+
+    /// Sets the access policy used when querying the filter's views.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Returns the headers of the matching documents.
+    pub fn headers(self) -> Result<Vec<Header>, Error> {
+        self.connection.filter_headers_by_name(
+            &Cl::collection_name(),
+            self.filter.into_serialized(),
+            self.sort,
+            self.limit,
+            self.access_policy,
+        )
+    }
+
+    /// Retrieves the matching documents.
+    pub fn query(self) -> Result<Vec<OwnedDocument>, Error> {
+        self.connection.filter_documents_by_name(
+            &Cl::collection_name(),
+            self.filter.into_serialized(),
+            self.sort,
+            self.limit,
+            self.access_policy,
+        )
+    }
+}
+
+/// Retrieves the documents matching a [`DocumentFilter`]. Returned from
+/// [`AsyncCollection::filter()`](super::AsyncCollection::filter).
+#[must_use]
+pub struct AsyncFilter<'a, Cn, Cl> {
+    connection: &'a Cn,
+    filter: DocumentFilter<Cl>,
+
+    /// The access policy used when querying the filter's views. The default
+    /// value is [`AccessPolicy::UpdateBefore`].
+    pub access_policy: AccessPolicy,
+
+    /// The order the documents are returned in, by id.
+    pub sort: Sort,
+
+    /// The maximum number of results to return.
+    pub limit: Option<u32>,
+}
+
+impl<'a, Cn, Cl> AsyncFilter<'a, Cn, Cl>
+where
+    Cn: AsyncConnection,
+    Cl: schema::Collection,
+{
+    pub(crate) const fn new(connection: &'a Cn, filter: DocumentFilter<Cl>) -> Self {
+        Self {
+            connection,
+            filter,
+            access_policy: AccessPolicy::UpdateBefore,
+            sort: Sort::Ascending,
+            limit: None,
+        }
+    }
+
+    /// Returns the documents in ascending order of their ids. This is the
+    /// default behavior.
+    pub const fn ascending(mut self) -> Self {
+        self.sort = Sort::Ascending;
+        self
+    }
+
+    /// Returns the documents in descending order of their ids.
+    pub const fn descending(mut self) -> Self {
+        self.sort = Sort::Descending;
+        self
+    }
+
+    /// Sets the maximum number of results to return.
+    pub const fn limit(mut self, maximum_results: u32) -> Self {
+        self.limit = Some(maximum_results);
+        self
+    }
+
+    /// Sets the access policy used when querying the filter's views.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Returns the headers of the matching documents.
+    pub async fn headers(self) -> Result<Vec<Header>, Error> {
+        self.connection
+            .filter_headers_by_name(
+                &Cl::collection_name(),
+                self.filter.into_serialized(),
+                self.sort,
+                self.limit,
+                self.access_policy,
+            )
+            .await
+    }
+
+    /// Retrieves the matching documents.
+    pub async fn query(self) -> Result<Vec<OwnedDocument>, Error> {
+        self.connection
+            .filter_documents_by_name(
+                &Cl::collection_name(),
+                self.filter.into_serialized(),
+                self.sort,
+                self.limit,
+                self.access_policy,
+            )
+            .await
+    }
+}
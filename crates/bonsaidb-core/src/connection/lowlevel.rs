@@ -6,16 +6,18 @@ use async_trait::async_trait;
 
 use super::GroupedReductions;
 use crate::connection::{
-    AccessPolicy, HasSession, QueryKey, Range, RangeRef, SerializedQueryKey, Sort,
+    AccessPolicy, HasSession, QueryKey, Range, RangeRef, SerializedDocumentFilter,
+    SerializedQueryKey, Sort,
 };
 use crate::document::{
     CollectionDocument, CollectionHeader, Document, DocumentId, HasHeader, Header, OwnedDocument,
 };
 use crate::key::{self, ByteSource, Key, KeyEncoding};
+use crate::schema::dynamic::DynamicCollection;
 use crate::schema::view::map::{
     CollectionMap, MappedDocuments, MappedSerializedValue, ViewMappings,
 };
-use crate::schema::view::{self};
+use crate::schema::view::{self, ViewStatus};
 use crate::schema::{self, CollectionName, MappedValue, Schematic, SerializedCollection, ViewName};
 use crate::transaction::{OperationResult, Transaction};
 use crate::Error;
@@ -293,7 +295,10 @@ pub trait LowLevelConnection: HasSchema + HasSession {
                         .map_err(view::Error::key_serialization)
                         .map_err(Error::from)?,
                     value: V::deserialize(&mapping.value)?,
+                    // Linked collections have the same primary key type as
+                    // the view's collection.
                     source: mapping.source.try_into()?,
+                    collection: mapping.collection,
                 })
             })
             .collect::<Result<Vec<_>, Error>>()
@@ -321,23 +326,17 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         Key: KeyEncoding<V::Key> + PartialEq + ?Sized,
         V::Key: Borrow<Key> + PartialEq<Key>,
     {
-        // Query permission is checked by the query call
-        let results = self.query::<V, Key>(key, order, limit, access_policy)?;
-
-        // Verify that there is permission to fetch each document
-        let documents = self
-            .get_multiple::<V::Collection, _, _, _>(results.iter().map(|m| &m.source.id))?
-            .into_iter()
-            .map(|doc| {
-                let id = doc.header.id.deserialize()?;
-                Ok((id, doc))
-            })
-            .collect::<Result<BTreeMap<_, _>, Error>>()?;
-
-        Ok(MappedDocuments {
-            mappings: results,
-            documents,
-        })
+        // Permissions are checked by the query call. Views that store
+        // projections return their documents without additional lookups.
+        let view = self.schematic().view::<V>()?;
+        self.query_by_name_with_docs(
+            &view.view_name(),
+            key.map(|key| key.serialized()).transpose()?,
+            order,
+            limit,
+            access_policy,
+        )?
+        .deserialized::<V>()
     }
 
     /// Queries for view entries matching [`View`](schema::View) with their
@@ -373,6 +372,7 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         Ok(MappedDocuments {
             mappings: mapped_docs.mappings,
             documents: collection_docs,
+            linked_documents: mapped_docs.linked_documents,
         })
     }
 
@@ -427,6 +427,7 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         self.reduce_grouped_by_name(
             &view.view_name(),
             key.map(|key| key.serialized()).transpose()?,
+            None,
             access_policy,
         )?
         .into_iter()
@@ -440,6 +441,48 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         .collect::<Result<Vec<_>, Error>>()
     }
 
+    /// Reduces the view entries matching [`View`](schema::View), reducing the
+    /// values by the first `group_level` fields of each entry's composite key.
+    /// The grouped keys are decoded as `Prefix`, which should be a tuple
+    /// containing the first `group_level` fields of the view's key.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider reducing the
+    /// view using
+    /// [`View::entries(self).reduce_grouped_by_level()`](super::View::reduce_grouped_by_level)
+    /// instead. The parameters for the query can be customized on the builder
+    /// returned from
+    /// [`SerializedView::entries()`](schema::SerializedView::entries),
+    /// [`SerializedView::entries_async()`](schema::SerializedView::entries_async),
+    /// or [`Connection::view()`](super::Connection::view).
+    fn reduce_grouped_by_level<V: schema::SerializedView, Key, Prefix>(
+        &self,
+        key: Option<QueryKey<'_, V::Key, Key>>,
+        group_level: usize,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<MappedValue<Prefix, V::Value>>, Error>
+    where
+        Key: KeyEncoding<V::Key> + PartialEq + ?Sized,
+        V::Key: Borrow<Key> + PartialEq<Key>,
+        Prefix: for<'k> key::Key<'k>,
+    {
+        let view = self.schematic().view::<V>()?;
+        self.reduce_grouped_by_name(
+            &view.view_name(),
+            key.map(|key| key.serialized()).transpose()?,
+            Some(group_level),
+            access_policy,
+        )?
+        .into_iter()
+        .map(|map| {
+            Ok(MappedValue::new(
+                Prefix::from_ord_bytes(ByteSource::Borrowed(&map.key))
+                    .map_err(view::Error::key_serialization)?,
+                V::deserialize(&map.value)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, Error>>()
+    }
+
     /// Deletes all of the documents associated with this view.
     ///
     /// This is a lower-level API. For better ergonomics, consider querying the
@@ -467,6 +510,12 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         )
     }
 
+    /// Returns the indexing status of [`View`](schema::View).
+    fn view_status<V: schema::SerializedView>(&self) -> Result<ViewStatus, Error> {
+        let view = self.schematic().view::<V>()?;
+        self.view_status_by_name(&view.view_name())
+    }
+
     /// Applies a [`Transaction`] to the [`schema::Schema`]. If any operation in the
     /// [`Transaction`] fails, none of the operations will be applied to the
     /// [`schema::Schema`].
@@ -607,7 +656,9 @@ pub trait LowLevelConnection: HasSchema + HasSession {
     ) -> Result<Vec<u8>, Error>;
 
     /// Reduces the view entries from the named `view`, reducing the values by each
-    /// unique key.
+    /// unique key. If `group_level` is provided, the values are instead reduced
+    /// by each unique prefix of the first `group_level` fields of the view's
+    /// composite key.
     ///
     /// This is a lower-level API. For better ergonomics, consider reducing
     /// the view using
@@ -618,6 +669,7 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         &self,
         view: &ViewName,
         key: Option<SerializedQueryKey>,
+        group_level: Option<usize>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<MappedSerializedValue>, Error>;
 
@@ -635,6 +687,53 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         key: Option<SerializedQueryKey>,
         access_policy: AccessPolicy,
     ) -> Result<u64, Error>;
+
+    /// Retrieves the documents in `collection` matching `filter`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider filtering
+    /// the collection using
+    /// [`Collection::filter()`](super::Collection::filter) instead.
+    fn filter_documents_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, Error>;
+
+    /// Retrieves the headers of the documents in `collection` matching
+    /// `filter`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider filtering
+    /// the collection using
+    /// [`Collection::filter()`](super::Collection::filter) instead.
+    fn filter_headers_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, Error>;
+
+    /// Returns the indexing status of the named `view`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider using
+    /// [`view_status()`](Self::view_status) instead.
+    fn view_status_by_name(&self, view: &ViewName) -> Result<ViewStatus, Error>;
+
+    /// Defines `collection` in this database, or replaces its definition if it
+    /// was previously defined using this function. The definition is
+    /// persisted, and the collection's indexes are maintained as views named
+    /// using [`DynamicIndex::view_name()`](schema::dynamic::DynamicIndex::view_name).
+    ///
+    /// The collection is accessible using the `*_by_name` functions of
+    /// database connections opened after this function returns. Existing
+    /// connections to the database are not updated. Returns
+    /// [`Error::CollectionAlreadyDefined`] if the database's schema contains a
+    /// collection with the same name.
+    fn define_dynamic_collection(&self, collection: DynamicCollection) -> Result<(), Error>;
 }
 
 /// The low-level interface to a database's [`schema::Schema`], giving access to
@@ -923,7 +1022,10 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
                         .map_err(view::Error::key_serialization)
                         .map_err(Error::from)?,
                     value: V::deserialize(&mapping.value)?,
+                    // Linked collections have the same primary key type as
+                    // the view's collection.
                     source: mapping.source.try_into()?,
+                    collection: mapping.collection,
                 })
             })
             .collect::<Result<Vec<_>, Error>>()
@@ -947,26 +1049,18 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         Key: KeyEncoding<V::Key> + PartialEq + ?Sized,
         V::Key: Borrow<Key> + PartialEq<Key>,
     {
-        // Query permission is checked by the query call
-        let results = self
-            .query::<V, Key>(key, order, limit, access_policy)
-            .await?;
-
-        // Verify that there is permission to fetch each document
-        let documents = self
-            .get_multiple::<V::Collection, _, _, _>(results.iter().map(|m| &m.source.id))
-            .await?
-            .into_iter()
-            .map(|doc| {
-                let id = doc.header.id.deserialize()?;
-                Ok((id, doc))
-            })
-            .collect::<Result<BTreeMap<_, _>, Error>>()?;
-
-        Ok(MappedDocuments {
-            mappings: results,
-            documents,
-        })
+        // Permissions are checked by the query call. Views that store
+        // projections return their documents without additional lookups.
+        let view = self.schematic().view::<V>()?;
+        self.query_by_name_with_docs(
+            &view.view_name(),
+            key.map(|key| key.serialized()).transpose()?,
+            order,
+            limit,
+            access_policy,
+        )
+        .await?
+        .deserialized::<V>()
     }
 
     /// Queries for view entries matching [`View`](schema::View) with their source documents,
@@ -1002,6 +1096,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         Ok(MappedDocuments {
             mappings: mapped_docs.mappings,
             documents: collection_docs,
+            linked_documents: mapped_docs.linked_documents,
         })
     }
 
@@ -1054,6 +1149,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         self.reduce_grouped_by_name(
             &view.view_name(),
             key.map(|key| key.serialized()).transpose()?,
+            None,
             access_policy,
         )
         .await?
@@ -1068,6 +1164,47 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         .collect::<Result<Vec<_>, Error>>()
     }
 
+    /// Reduces the view entries matching [`View`](schema::View), reducing the
+    /// values by the first `group_level` fields of each entry's composite key.
+    /// The grouped keys are decoded as `Prefix`, which should be a tuple
+    /// containing the first `group_level` fields of the view's key.
+    ///
+    /// This is the lower-level API. For better ergonomics, consider reducing
+    /// the view using
+    /// [`View::entries(self).reduce_grouped_by_level()`](super::AsyncView::reduce_grouped_by_level)
+    /// instead. The parameters for the query can be customized on the builder
+    /// returned from [`AsyncConnection::view()`](super::AsyncConnection::view).
+    #[must_use]
+    async fn reduce_grouped_by_level<V: schema::SerializedView, Key, Prefix>(
+        &self,
+        key: Option<QueryKey<'_, V::Key, Key>>,
+        group_level: usize,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<MappedValue<Prefix, V::Value>>, Error>
+    where
+        Key: KeyEncoding<V::Key> + PartialEq + ?Sized,
+        V::Key: Borrow<Key> + PartialEq<Key>,
+        Prefix: for<'k> key::Key<'k>,
+    {
+        let view = self.schematic().view::<V>()?;
+        self.reduce_grouped_by_name(
+            &view.view_name(),
+            key.map(|key| key.serialized()).transpose()?,
+            Some(group_level),
+            access_policy,
+        )
+        .await?
+        .into_iter()
+        .map(|map| {
+            Ok(MappedValue::new(
+                Prefix::from_ord_bytes(ByteSource::Borrowed(&map.key))
+                    .map_err(view::Error::key_serialization)?,
+                V::deserialize(&map.value)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, Error>>()
+    }
+
     /// Deletes all of the documents associated with this view.
     ///
     /// This is the lower-level API. For better ergonomics, consider querying
@@ -1094,6 +1231,12 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         .await
     }
 
+    /// Returns the indexing status of [`View`](schema::View).
+    async fn view_status<V: schema::SerializedView>(&self) -> Result<ViewStatus, Error> {
+        let view = self.schematic().view::<V>()?;
+        self.view_status_by_name(&view.view_name()).await
+    }
+
     /// Applies a [`Transaction`] to the [`Schema`](schema::Schema). If any
     /// operation in the [`Transaction`] fails, none of the operations will be
     /// applied to the [`Schema`](schema::Schema).
@@ -1236,7 +1379,9 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
     ) -> Result<Vec<u8>, Error>;
 
     /// Reduces the view entries from the named `view`, reducing the values by each
-    /// unique key.
+    /// unique key. If `group_level` is provided, the values are instead reduced
+    /// by each unique prefix of the first `group_level` fields of the view's
+    /// composite key.
     ///
     /// This is the lower-level API. For better ergonomics, consider querying
     /// the view using
@@ -1247,6 +1392,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         &self,
         view: &ViewName,
         key: Option<SerializedQueryKey>,
+        group_level: Option<usize>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<MappedSerializedValue>, Error>;
 
@@ -1264,6 +1410,53 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         key: Option<SerializedQueryKey>,
         access_policy: AccessPolicy,
     ) -> Result<u64, Error>;
+
+    /// Retrieves the documents in `collection` matching `filter`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider filtering
+    /// the collection using
+    /// [`AsyncCollection::filter()`](super::AsyncCollection::filter) instead.
+    async fn filter_documents_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, Error>;
+
+    /// Retrieves the headers of the documents in `collection` matching
+    /// `filter`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider filtering
+    /// the collection using
+    /// [`AsyncCollection::filter()`](super::AsyncCollection::filter) instead.
+    async fn filter_headers_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, Error>;
+
+    /// Returns the indexing status of the named `view`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider using
+    /// [`view_status()`](Self::view_status) instead.
+    async fn view_status_by_name(&self, view: &ViewName) -> Result<ViewStatus, Error>;
+
+    /// Defines `collection` in this database, or replaces its definition if it
+    /// was previously defined using this function. The definition is
+    /// persisted, and the collection's indexes are maintained as views named
+    /// using [`DynamicIndex::view_name()`](schema::dynamic::DynamicIndex::view_name).
+    ///
+    /// The collection is accessible using the `*_by_name` functions of
+    /// database connections opened after this function returns. Existing
+    /// connections to the database are not updated. Returns
+    /// [`Error::CollectionAlreadyDefined`] if the database's schema contains a
+    /// collection with the same name.
+    async fn define_dynamic_collection(&self, collection: DynamicCollection) -> Result<(), Error>;
 }
 
 /// Access to a connection's schema.
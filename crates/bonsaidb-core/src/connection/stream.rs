@@ -0,0 +1,551 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use arc_bytes::serde::Bytes;
+use futures::future::BoxFuture;
+use futures::{FutureExt, Stream};
+
+use crate::connection::{
+    AccessPolicy, AsyncConnection, AsyncLowLevelConnection, Bound, Connection, LowLevelConnection,
+    Range, SerializedQueryKey, Sort,
+};
+use crate::document::{DocumentId, OwnedDocument};
+use crate::schema::view::map::{self, CollectionMap, MappedSerializedDocuments};
+use crate::schema::{Collection, CollectionName, SerializedView, View, ViewName};
+use crate::Error;
+
+/// The number of results retrieved per request by [`DocumentIter`],
+/// [`DocumentStream`], [`MappedDocumentIter`], and [`MappedDocumentStream`]
+/// unless changed using `batch_size()`.
+pub const DEFAULT_BATCH_SIZE: u32 = 1_000;
+
+/// A mapping from a view and the document that emitted it, returned when
+/// iterating over view results.
+pub struct OwnedMappedDocument<V: View> {
+    /// The mapping.
+    pub mapping: CollectionMap<<V::Collection as Collection>::PrimaryKey, V::Key, V::Value>,
+    /// The source document of the mapping. For mappings emitted by documents
+    /// from the view's [linked
+    /// collections](crate::schema::ViewSchema::linked_collections), this is
+    /// the document from the linked collection.
+    pub document: OwnedDocument,
+}
+
+/// Tracks the progress of listing a range of documents one batch at a time.
+struct DocumentPages {
+    collection: CollectionName,
+    ids: Range<DocumentId>,
+    order: Sort,
+    remaining: Option<u32>,
+    batch_size: u32,
+    finished: bool,
+}
+
+impl DocumentPages {
+    fn new(
+        collection: CollectionName,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+    ) -> Self {
+        Self {
+            collection,
+            ids,
+            order,
+            remaining: limit,
+            batch_size: DEFAULT_BATCH_SIZE,
+            finished: false,
+        }
+    }
+
+    /// Returns the range and limit of the next batch, if there are more
+    /// documents to retrieve.
+    fn next_request(&self) -> Option<(Range<DocumentId>, u32)> {
+        let limit = self
+            .remaining
+            .map_or(self.batch_size, |remaining| remaining.min(self.batch_size));
+        (!self.finished && limit > 0).then(|| (self.ids.clone(), limit))
+    }
+
+    /// Advances past `documents`, which were returned from a request limited
+    /// to `limit` documents.
+    fn received(&mut self, limit: u32, documents: &[OwnedDocument]) {
+        let received = u32::try_from(documents.len()).unwrap_or(u32::MAX);
+        if let Some(remaining) = &mut self.remaining {
+            *remaining = remaining.saturating_sub(received);
+        }
+        match documents.last() {
+            Some(last) if received >= limit => {
+                let after = Bound::Excluded(last.header.id.clone());
+                match self.order {
+                    Sort::Ascending => self.ids.start = after,
+                    Sort::Descending => self.ids.end = after,
+                }
+            }
+            _ => self.finished = true,
+        }
+    }
+}
+
+/// A blocking iterator over a range of documents that retrieves the documents
+/// in batches.
+///
+/// Each batch is a separate request, which means that documents changed while
+/// iterating may or may not be returned.
+#[must_use]
+pub struct DocumentIter<'a, Cn, D = OwnedDocument> {
+    connection: &'a Cn,
+    pages: DocumentPages,
+    buffer: VecDeque<OwnedDocument>,
+    convert: fn(OwnedDocument) -> Result<D, Error>,
+}
+
+impl<'a, Cn, D> DocumentIter<'a, Cn, D> {
+    pub(crate) fn new(
+        connection: &'a Cn,
+        collection: CollectionName,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        convert: fn(OwnedDocument) -> Result<D, Error>,
+    ) -> Self {
+        Self {
+            connection,
+            pages: DocumentPages::new(collection, ids, order, limit),
+            buffer: VecDeque::new(),
+            convert,
+        }
+    }
+
+    /// Sets the maximum number of documents retrieved per request. The
+    /// default is [`DEFAULT_BATCH_SIZE`].
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.pages.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl<'a, Cn, D> Iterator for DocumentIter<'a, Cn, D>
+where
+    Cn: Connection,
+{
+    type Item = Result<D, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(document) = self.buffer.pop_front() {
+                return Some((self.convert)(document));
+            }
+
+            let (ids, limit) = self.pages.next_request()?;
+            match self.connection.list_from_collection(
+                ids,
+                self.pages.order,
+                Some(limit),
+                &self.pages.collection,
+            ) {
+                Ok(documents) => {
+                    self.pages.received(limit, &documents);
+                    self.buffer.extend(documents);
+                }
+                Err(err) => {
+                    self.pages.finished = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+/// An asynchronous stream of a range of documents that retrieves the
+/// documents in batches.
+///
+/// Each batch is a separate request, which means that documents changed while
+/// iterating may or may not be returned.
+#[must_use]
+pub struct DocumentStream<'a, Cn, D = OwnedDocument> {
+    connection: &'a Cn,
+    pages: DocumentPages,
+    buffer: VecDeque<OwnedDocument>,
+    pending: Option<(u32, BoxFuture<'a, Result<Vec<OwnedDocument>, Error>>)>,
+    convert: fn(OwnedDocument) -> Result<D, Error>,
+}
+
+impl<'a, Cn, D> DocumentStream<'a, Cn, D> {
+    pub(crate) fn new(
+        connection: &'a Cn,
+        collection: CollectionName,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        convert: fn(OwnedDocument) -> Result<D, Error>,
+    ) -> Self {
+        Self {
+            connection,
+            pages: DocumentPages::new(collection, ids, order, limit),
+            buffer: VecDeque::new(),
+            pending: None,
+            convert,
+        }
+    }
+
+    /// Sets the maximum number of documents retrieved per request. The
+    /// default is [`DEFAULT_BATCH_SIZE`].
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.pages.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl<'a, Cn, D> Unpin for DocumentStream<'a, Cn, D> {}
+
+impl<'a, Cn, D> Stream for DocumentStream<'a, Cn, D>
+where
+    Cn: AsyncConnection,
+{
+    type Item = Result<D, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(document) = self.buffer.pop_front() {
+                return Poll::Ready(Some((self.convert)(document)));
+            }
+
+            if let Some((limit, future)) = &mut self.pending {
+                let limit = *limit;
+                let result = ready!(future.poll_unpin(cx));
+                self.pending = None;
+                match result {
+                    Ok(documents) => {
+                        self.pages.received(limit, &documents);
+                        self.buffer.extend(documents);
+                    }
+                    Err(err) => {
+                        self.pages.finished = true;
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                }
+            } else if let Some((ids, limit)) = self.pages.next_request() {
+                let connection = self.connection;
+                let order = self.pages.order;
+                let collection = self.pages.collection.clone();
+                let future = async move {
+                    connection
+                        .list_from_collection(ids, order, Some(limit), &collection)
+                        .await
+                }
+                .boxed();
+                self.pending = Some((limit, future));
+            } else {
+                return Poll::Ready(None);
+            }
+        }
+    }
+}
+
+/// Tracks the progress of querying a view one batch of keys at a time.
+struct MappingPages {
+    view: ViewName,
+    key: Option<SerializedQueryKey>,
+    order: Sort,
+    access_policy: AccessPolicy,
+    remaining: Option<u32>,
+    batch_size: u32,
+    finished: bool,
+}
+
+impl MappingPages {
+    fn new(
+        view: ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Self {
+        Self {
+            view,
+            key,
+            order,
+            access_policy,
+            remaining: limit,
+            batch_size: DEFAULT_BATCH_SIZE,
+            finished: false,
+        }
+    }
+
+    /// Returns true if the query can be split into ranges of keys.
+    const fn is_ranged(&self) -> bool {
+        matches!(self.key, None | Some(SerializedQueryKey::Range(_)))
+    }
+
+    /// Returns the key, limit, and access policy of the next batch, if there
+    /// are more entries to retrieve.
+    fn next_request(&self) -> Option<(Option<SerializedQueryKey>, Option<u32>, AccessPolicy)> {
+        if self.finished {
+            return None;
+        }
+
+        let limit = if self.is_ranged() {
+            let limit = self
+                .remaining
+                .map_or(self.batch_size, |remaining| remaining.min(self.batch_size));
+            if limit == 0 {
+                return None;
+            }
+            Some(limit)
+        } else {
+            // Queries for specific keys are retrieved with a single request.
+            self.remaining
+        };
+        Some((self.key.clone(), limit, self.access_policy))
+    }
+
+    /// Advances past `mappings`, which were returned from a request limited
+    /// to `limit` keys.
+    fn received(&mut self, limit: Option<u32>, mappings: &[map::Serialized]) {
+        // The view only needs to be updated before the first batch.
+        self.access_policy = AccessPolicy::NoUpdate;
+
+        let (Some(limit), true) = (limit, self.is_ranged()) else {
+            self.finished = true;
+            return;
+        };
+
+        let mut keys = 0_u32;
+        let mut last_key = None;
+        for mapping in mappings {
+            if last_key != Some(&mapping.key) {
+                keys = keys.saturating_add(1);
+                last_key = Some(&mapping.key);
+            }
+        }
+        if let Some(remaining) = &mut self.remaining {
+            *remaining = remaining.saturating_sub(keys);
+        }
+
+        match last_key {
+            Some(last_key) if keys >= limit => {
+                let after = Bound::Excluded(last_key.clone());
+                let mut range = match self.key.take() {
+                    Some(SerializedQueryKey::Range(range)) => range,
+                    _ => Range::<Bytes>::default(),
+                };
+                match self.order {
+                    Sort::Ascending => range.start = after,
+                    Sort::Descending => range.end = after,
+                }
+                self.key = Some(SerializedQueryKey::Range(range));
+            }
+            _ => self.finished = true,
+        }
+    }
+}
+
+/// Pairs each mapping in `results` with its source document. Mappings whose
+/// source document could not be retrieved are skipped.
+fn owned_mapped_documents<V: SerializedView>(
+    results: MappedSerializedDocuments,
+) -> Result<Vec<OwnedMappedDocument<V>>, Error> {
+    let MappedSerializedDocuments {
+        mappings,
+        documents,
+        linked_documents,
+    } = results;
+    let mut mapped_documents = Vec::with_capacity(mappings.len());
+    for mapping in mappings {
+        let document = if let Some(collection) = &mapping.collection {
+            linked_documents
+                .get(collection)
+                .and_then(|documents| documents.get(&mapping.source.id))
+        } else {
+            documents.get(&mapping.source.id)
+        };
+        let Some(document) = document else {
+            continue;
+        };
+        let deserialized = mapping.deserialized::<V>()?;
+        mapped_documents.push(OwnedMappedDocument {
+            mapping: CollectionMap {
+                source: deserialized.source.try_into()?,
+                collection: mapping.collection,
+                key: deserialized.key,
+                value: deserialized.value,
+            },
+            document: document.clone(),
+        });
+    }
+    Ok(mapped_documents)
+}
+
+/// A blocking iterator over the results of a view query, including each
+/// mapping's source document, that retrieves the results in batches.
+///
+/// When the query is not for a specific key or set of keys, each batch is a
+/// separate request for a range of keys, which means that entries changed
+/// while iterating may or may not be returned. The view is only updated
+/// before the first batch is retrieved, according to the query's
+/// [`AccessPolicy`].
+#[must_use]
+pub struct MappedDocumentIter<'a, Cn, V: View> {
+    connection: &'a Cn,
+    pages: MappingPages,
+    buffer: VecDeque<OwnedMappedDocument<V>>,
+    _view: PhantomData<fn() -> V>,
+}
+
+impl<'a, Cn, V: View> MappedDocumentIter<'a, Cn, V> {
+    pub(crate) fn new(
+        connection: &'a Cn,
+        view: ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Self {
+        Self {
+            connection,
+            pages: MappingPages::new(view, key, order, limit, access_policy),
+            buffer: VecDeque::new(),
+            _view: PhantomData,
+        }
+    }
+
+    /// Sets the maximum number of keys retrieved per request. The default is
+    /// [`DEFAULT_BATCH_SIZE`].
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.pages.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl<'a, Cn, V> Iterator for MappedDocumentIter<'a, Cn, V>
+where
+    Cn: Connection,
+    V: SerializedView,
+{
+    type Item = Result<OwnedMappedDocument<V>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(mapped) = self.buffer.pop_front() {
+                return Some(Ok(mapped));
+            }
+
+            let (key, limit, access_policy) = self.pages.next_request()?;
+            let result = self
+                .connection
+                .query_by_name_with_docs(
+                    &self.pages.view,
+                    key,
+                    self.pages.order,
+                    limit,
+                    access_policy,
+                )
+                .and_then(|results| {
+                    self.pages.received(limit, &results.mappings);
+                    owned_mapped_documents::<V>(results)
+                });
+            match result {
+                Ok(mapped) => self.buffer.extend(mapped),
+                Err(err) => {
+                    self.pages.finished = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+/// An asynchronous stream of the results of a view query, including each
+/// mapping's source document, that retrieves the results in batches.
+///
+/// When the query is not for a specific key or set of keys, each batch is a
+/// separate request for a range of keys, which means that entries changed
+/// while iterating may or may not be returned. The view is only updated
+/// before the first batch is retrieved, according to the query's
+/// [`AccessPolicy`].
+#[must_use]
+pub struct MappedDocumentStream<'a, Cn, V: View> {
+    connection: &'a Cn,
+    pages: MappingPages,
+    buffer: VecDeque<OwnedMappedDocument<V>>,
+    pending: Option<(
+        Option<u32>,
+        BoxFuture<'a, Result<MappedSerializedDocuments, Error>>,
+    )>,
+}
+
+impl<'a, Cn, V: View> MappedDocumentStream<'a, Cn, V> {
+    pub(crate) fn new(
+        connection: &'a Cn,
+        view: ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Self {
+        Self {
+            connection,
+            pages: MappingPages::new(view, key, order, limit, access_policy),
+            buffer: VecDeque::new(),
+            pending: None,
+        }
+    }
+
+    /// Sets the maximum number of keys retrieved per request. The default is
+    /// [`DEFAULT_BATCH_SIZE`].
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.pages.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl<'a, Cn, V: View> Unpin for MappedDocumentStream<'a, Cn, V> {}
+
+impl<'a, Cn, V> Stream for MappedDocumentStream<'a, Cn, V>
+where
+    Cn: AsyncConnection,
+    V: SerializedView,
+{
+    type Item = Result<OwnedMappedDocument<V>, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(mapped) = self.buffer.pop_front() {
+                return Poll::Ready(Some(Ok(mapped)));
+            }
+
+            if let Some((limit, future)) = &mut self.pending {
+                let limit = *limit;
+                let result = ready!(future.poll_unpin(cx));
+                self.pending = None;
+                let result = result.and_then(|results| {
+                    self.pages.received(limit, &results.mappings);
+                    owned_mapped_documents::<V>(results)
+                });
+                match result {
+                    Ok(mapped) => self.buffer.extend(mapped),
+                    Err(err) => {
+                        self.pages.finished = true;
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                }
+            } else if let Some((key, limit, access_policy)) = self.pages.next_request() {
+                let connection = self.connection;
+                let view = self.pages.view.clone();
+                let order = self.pages.order;
+                let future = async move {
+                    connection
+                        .query_by_name_with_docs(&view, key, order, limit, access_policy)
+                        .await
+                }
+                .boxed();
+                self.pending = Some((limit, future));
+            } else {
+                return Poll::Ready(None);
+            }
+        }
+    }
+}
@@ -6,7 +6,7 @@ use std::ops::Deref;
 use std::str::FromStr;
 
 use actionable::Identifier;
-use serde::de::Visitor;
+use serde::de::{SeqAccess, Visitor};
 use serde::{Deserialize, Serialize};
 use tinyvec::{Array, TinyVec};
 
@@ -298,6 +298,19 @@ impl<'de> Visitor<'de> for DocumentIdVisitor {
     {
         Ok(DocumentId(TinyVec::from(v)))
     }
+
+    // Self-describing formats without a bytes type, like JSON, encode ids as a
+    // sequence of bytes.
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut id = TinyVec::new();
+        while let Some(byte) = seq.next_element()? {
+            id.push(byte);
+        }
+        Ok(DocumentId(id))
+    }
 }
 
 impl<'k> Key<'k> for DocumentId {
@@ -1,3 +1,11 @@
+mod collation;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod datetime;
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+mod descending;
+mod geo;
+mod net;
 /// [`Key`] implementations for time types.
 pub mod time;
 mod varint;
@@ -19,7 +27,16 @@ use std::string::FromUtf8Error;
 use arc_bytes::serde::{Bytes, CowBytes};
 use arc_bytes::ArcBytes;
 pub use bonsaidb_macros::Key;
+pub use collation::{
+    CaseAndAccentInsensitive, CaseInsensitive, Collated, CollatedWithOriginal, Collation,
+    Normalized,
+};
 pub use deprecated::*;
+pub use descending::{Descending, DescendingKeyError};
+pub use geo::{
+    AsyncGeoQuery, GeoArea, GeoBoundingBox, GeoPoint, GeoQuery, DEFAULT_MAX_KEY_RANGES,
+    EARTH_RADIUS_METERS,
+};
 use num_traits::{FromPrimitive, ToPrimitive};
 use ordered_varint::{Signed, Unsigned, Variable};
 use serde::{Deserialize, Serialize};
@@ -146,6 +163,31 @@ pub trait KeyEncoding<K = Self>: Send + Sync {
 /// assert_eq!(encoded.len(), 4);
 /// ```
 ///
+/// ## Enums with fields
+///
+/// Variants may contain named or unnamed fields. These enums are encoded using
+/// [`CompositeKeyEncoder`]: the variant's discriminant is encoded first,
+/// followed by each of the variant's fields. Because the discriminant is
+/// encoded first, keys are sorted by variant in the order the variants are
+/// declared (or by their explicit discriminants), and then by the variant's
+/// fields.
+///
+/// ```rust
+/// use bonsaidb_core::key::{Key, KeyEncoding};
+///
+/// #[derive(Key, Clone, Debug)]
+/// # #[key(core = bonsaidb_core)]
+/// enum Event {
+///     Login { user: u64 },
+///     Message(u64, String),
+///     Shutdown,
+/// }
+///
+/// let login = Event::Login { user: 2 }.as_ord_bytes().unwrap();
+/// let message = Event::Message(1, String::from("hi")).as_ord_bytes().unwrap();
+/// assert!(login < message);
+/// ```
+///
 /// ## `null_handling`
 ///
 /// The derive macro offers an argument `null_handling`, which defaults to
@@ -206,6 +248,25 @@ pub trait Key<'k>: KeyEncoding<Self> + Clone + Send + Sync {
     fn next_value(&self) -> Result<Self, NextValueError> {
         Err(NextValueError::Unsupported)
     }
+
+    /// Decodes the composite key contained in `bytes` and returns the encoded
+    /// bytes of its first `fields` fields. The returned bytes are equal to the
+    /// encoding of a composite key made up of only those fields. If `fields`
+    /// is greater than or equal to the number of fields in this key, the
+    /// entire key is re-encoded.
+    ///
+    /// This is used to group view entries by a prefix of their keys. Tuples
+    /// implement this function. All other types return an error.
+    fn encode_composite_prefix<'e>(
+        bytes: ByteSource<'k, 'e>,
+        fields: usize,
+    ) -> Result<Vec<u8>, CompositeKeyError> {
+        let _ = (bytes, fields);
+        Err(CompositeKeyError::new(io::Error::new(
+            ErrorKind::Unsupported,
+            "key is not a composite key",
+        )))
+    }
 }
 
 impl<'a, 'k, K, KE> KeyEncoding<K> for &'a KE
@@ -310,6 +371,12 @@ pub enum KeyKind {
     String,
     /// A byte array encoded using BonsaiDb's built-in `KeyEncoding`.
     Bytes,
+    /// An `f32` encoded in big-endian encoding after being transformed to
+    /// preserve its total ordering.
+    F32,
+    /// An `f64` encoded in big-endian encoding after being transformed to
+    /// preserve its total ordering.
+    F64,
 }
 
 /// A value used as part of [`KeyVisitor::visit_composite_attribute`].
@@ -395,6 +462,18 @@ pub enum CompositeKind {
     /// [`SystemTime`](std::time::SystemTime)'s `KeyEncoding` implementation
     /// reports.
     Struct(Cow<'static, str>),
+    /// An enum containing variants with fields, identified by the fully
+    /// qualified name. The first field describes the type used to encode the
+    /// variant's discriminant, and each remaining field is a
+    /// [`CompositeKind::Variant`].
+    Enum(Cow<'static, str>),
+    /// A variant of a [`CompositeKind::Enum`], identified by the variant's
+    /// name. The `discriminant` attribute contains the value that identifies
+    /// this variant. Unit variants report a single [`KeyKind::Unit`] field.
+    Variant(Cow<'static, str>),
+    /// A [`Descending`] key, which always contains a single field whose
+    /// encoded bytes are inverted.
+    Descending,
 }
 
 /// A description of an encoded [`Key`].
@@ -1251,6 +1330,23 @@ macro_rules! impl_key_for_tuple {
 
                 Ok(($($varname),+,))
             }
+
+            fn encode_composite_prefix<'e>(
+                bytes: ByteSource<'k, 'e>,
+                fields: usize,
+            ) -> Result<Vec<u8>, CompositeKeyError> {
+                let mut decoder = CompositeKeyDecoder::default_for(bytes);
+                let mut encoder = CompositeKeyEncoder::default();
+                $(
+                    let $varname = decoder.decode::<$generic>()?;
+                    if $index < fields {
+                        encoder.encode(&$varname)?;
+                    }
+                )+
+                decoder.finish()?;
+
+                Ok(encoder.finish())
+            }
         }
 
         impl<$($generic),+> KeyEncoding<Self> for ($($generic),+,)
@@ -1866,6 +1962,33 @@ fn composite_key_tests() {
     recursive_test_enum_variations!(t1, t2, t3, t4, t5, t6, t7, t8);
 }
 
+#[test]
+fn composite_prefix_tests() {
+    let key = (2023_u16, String::from("march"), 14_u8);
+    let encoded = key.as_ord_bytes().unwrap();
+
+    let year =
+        <(u16, String, u8)>::encode_composite_prefix(ByteSource::Borrowed(&encoded), 1).unwrap();
+    assert_eq!(year, &(2023_u16,).as_ord_bytes().unwrap()[..]);
+    assert_eq!(
+        <(u16,)>::from_ord_bytes(ByteSource::Borrowed(&year)).unwrap(),
+        (2023,)
+    );
+
+    let month =
+        <(u16, String, u8)>::encode_composite_prefix(ByteSource::Borrowed(&encoded), 2).unwrap();
+    assert_eq!(
+        <(u16, String)>::from_ord_bytes(ByteSource::Borrowed(&month)).unwrap(),
+        (2023, String::from("march"))
+    );
+
+    let everything =
+        <(u16, String, u8)>::encode_composite_prefix(ByteSource::Borrowed(&encoded), 5).unwrap();
+    assert_eq!(everything, &encoded[..]);
+
+    assert!(u64::encode_composite_prefix(ByteSource::Borrowed(&[0; 8]), 1).is_err());
+}
+
 /// An error occurred inside of one of the composite key fields.
 #[derive(thiserror::Error, Debug)]
 #[error("key error: {0}")]
@@ -2128,6 +2251,34 @@ impl KeyEncoding<Self> for uuid::Uuid {
     }
 }
 
+#[cfg(feature = "ulid")]
+impl<'k> Key<'k> for ulid::Ulid {
+    const CAN_OWN_BYTES: bool = false;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        Ok(Self::from_bytes(bytes.as_ref().try_into()?))
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl KeyEncoding<Self> for ulid::Ulid {
+    type Error = std::array::TryFromSliceError;
+
+    const LENGTH: Option<usize> = Some(16);
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(CompositeKind::Struct(Cow::Borrowed("ulid::Ulid")), 1);
+        visitor.visit_type(KeyKind::Bytes);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        Ok(Cow::Owned(self.to_bytes().to_vec()))
+    }
+}
+
 fn decode_skipping_first_byte<'k, 'e, T>(bytes: ByteSource<'k, 'e>) -> Result<T, T::Error>
 where
     T: Key<'k>,
@@ -2413,6 +2564,61 @@ impl_key_for_primitive!(u64, KeyKind::U64);
 impl_key_for_primitive!(i128, KeyKind::I128);
 impl_key_for_primitive!(u128, KeyKind::U128);
 
+macro_rules! impl_key_for_float {
+    ($type:ident, $bits:ident, $keykind:expr) => {
+        /// Encodes the float such that the encoded bytes sort in the same order
+        /// as the float's `total_cmp()` function.
+        ///
+        /// Positive numbers have their sign bit set, and negative numbers have
+        /// all of their bits inverted. This results in the following order:
+        /// negative NaN, negative infinity, negative numbers, `-0.0`, `0.0`,
+        /// positive numbers, positive infinity, positive NaN. NaN values are
+        /// stored without being normalized, which means NaNs with different
+        /// payloads are distinct keys.
+        impl<'k> Key<'k> for $type {
+            const CAN_OWN_BYTES: bool = false;
+
+            fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+                const SIGN_BIT: $bits = 1 << ($bits::BITS - 1);
+                let encoded = $bits::from_be_bytes(bytes.as_ref().try_into()?);
+                let bits = if encoded & SIGN_BIT == 0 {
+                    !encoded
+                } else {
+                    encoded ^ SIGN_BIT
+                };
+                Ok($type::from_bits(bits))
+            }
+        }
+
+        impl KeyEncoding<Self> for $type {
+            type Error = IncorrectByteLength;
+
+            const LENGTH: Option<usize> = Some(std::mem::size_of::<$type>());
+
+            fn describe<Visitor>(visitor: &mut Visitor)
+            where
+                Visitor: KeyVisitor,
+            {
+                visitor.visit_type($keykind);
+            }
+
+            fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+                const SIGN_BIT: $bits = 1 << ($bits::BITS - 1);
+                let bits = self.to_bits();
+                let encoded = if bits & SIGN_BIT == 0 {
+                    bits ^ SIGN_BIT
+                } else {
+                    !bits
+                };
+                Ok(Cow::from(encoded.to_be_bytes().to_vec()))
+            }
+        }
+    };
+}
+
+impl_key_for_float!(f32, u32, KeyKind::F32);
+impl_key_for_float!(f64, u64, KeyKind::F64);
+
 macro_rules! impl_key_for_nonzero_primitive {
     ($nonzero:ident, $type:ident) => {
         impl<'k> Key<'k> for $nonzero {
@@ -2540,6 +2746,53 @@ fn primitive_key_encoding_tests() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn float_key_encoding_tests() -> anyhow::Result<()> {
+    macro_rules! test_float_ordering {
+        ($type:ident) => {
+            let ordered = [
+                -$type::NAN,
+                $type::NEG_INFINITY,
+                $type::MIN,
+                -1.,
+                -$type::MIN_POSITIVE,
+                -0.,
+                0.,
+                $type::MIN_POSITIVE,
+                1.,
+                $type::MAX,
+                $type::INFINITY,
+                $type::NAN,
+            ];
+            let encoded = ordered
+                .iter()
+                .map(|value| value.as_ord_bytes().map(|bytes| bytes.to_vec()))
+                .collect::<Result<Vec<_>, _>>()?;
+            for (value, encoded) in ordered.iter().zip(&encoded) {
+                let decoded = $type::from_ord_bytes(ByteSource::Borrowed(encoded))?;
+                assert_eq!(value.to_bits(), decoded.to_bits());
+            }
+            for pair in encoded.windows(2) {
+                assert!(pair[0] < pair[1]);
+            }
+        };
+    }
+
+    test_float_ordering!(f32);
+    test_float_ordering!(f64);
+
+    let mut encoder = CompositeKeyEncoder::default();
+    encoder.encode(&-1.5_f64)?;
+    encoder.encode(&2.5_f32)?;
+    let encoded = encoder.finish();
+    let mut decoder = CompositeKeyDecoder::default_for(ByteSource::Borrowed(&encoded));
+    assert_eq!(decoder.decode::<f64>()?.to_bits(), (-1.5_f64).to_bits());
+    assert_eq!(decoder.decode::<f32>()?.to_bits(), 2.5_f32.to_bits());
+    decoder.finish()?;
+
+    Ok(())
+}
+
 #[test]
 fn nonzero_key_encoding_tests() -> anyhow::Result<()> {
     macro_rules! test_nonzero {
@@ -2810,6 +3063,18 @@ fn key_descriptions() {
         KeyDescription::for_key::<Vec<u8>>(),
         KeyDescription::Basic(KeyKind::Bytes)
     );
+    assert_eq!(
+        KeyDescription::for_key::<f64>(),
+        KeyDescription::Basic(KeyKind::F64)
+    );
+    assert_eq!(
+        KeyDescription::for_key::<Descending<u32>>(),
+        KeyDescription::Composite(CompositeKeyDescription {
+            kind: CompositeKind::Descending,
+            fields: vec![KeyDescription::Basic(KeyKind::U32)],
+            attributes: HashMap::new(),
+        })
+    );
     assert_eq!(
         dbg!(KeyDescription::for_key::<time::TimestampAsNanoseconds>()),
         KeyDescription::Composite(CompositeKeyDescription {
@@ -0,0 +1,391 @@
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::string::FromUtf8Error;
+
+use derive_where::derive_where;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::connection::{Bound, BoundRef, MaybeOwned, RangeRef};
+use crate::key::{
+    ByteSource, CompositeKeyDecoder, CompositeKeyEncoder, CompositeKeyError, CompositeKind,
+    IntoPrefixRange, Key, KeyEncoding, KeyKind, KeyVisitor,
+};
+use crate::schema::NameKey;
+
+/// A method of converting a string into a sort key. Strings that produce the
+/// same sort key are considered equal by [`Collated`].
+///
+/// These collations are not locale-aware. They are intended to provide an
+/// ordering that is more natural than comparing the raw UTF-8 bytes of a
+/// string, while remaining stable across releases.
+pub trait Collation: Debug + Send + Sync + 'static {
+    /// The name reported in the [`KeyDescription`](crate::key::KeyDescription)
+    /// of keys using this collation.
+    const NAME: &'static str;
+
+    /// Returns the sort key for `value`.
+    fn sort_key(value: &str) -> String;
+}
+
+/// Compares strings after normalizing them using [Unicode Normalization Form
+/// C](https://unicode.org/reports/tr15/). This ensures that canonically
+/// equivalent strings, such as `"é"` written as a single character or as `"e"`
+/// followed by a combining accent, are equal.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Normalized;
+
+impl Collation for Normalized {
+    const NAME: &'static str = "bonsaidb::core::key::Normalized";
+
+    fn sort_key(value: &str) -> String {
+        value.nfc().collect()
+    }
+}
+
+/// Compares strings without regard to case after normalizing them using
+/// [Unicode Normalization Form KC](https://unicode.org/reports/tr15/). With
+/// this collation, `"apple"` sorts before `"Zebra"`, and `"APPLE"` is equal to
+/// `"apple"`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct CaseInsensitive;
+
+impl Collation for CaseInsensitive {
+    const NAME: &'static str = "bonsaidb::core::key::CaseInsensitive";
+
+    fn sort_key(value: &str) -> String {
+        value
+            .nfkc()
+            .collect::<String>()
+            .to_lowercase()
+            .nfc()
+            .collect()
+    }
+}
+
+/// Compares strings without regard to case or accents. Strings are decomposed
+/// using [Unicode Normalization Form KD](https://unicode.org/reports/tr15/),
+/// combining marks are removed, and the result is converted to lowercase. With
+/// this collation, `"Émile"` is equal to `"emile"` and sorts before `"Eve"`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct CaseAndAccentInsensitive;
+
+impl Collation for CaseAndAccentInsensitive {
+    const NAME: &'static str = "bonsaidb::core::key::CaseAndAccentInsensitive";
+
+    fn sort_key(value: &str) -> String {
+        value
+            .nfkd()
+            .filter(|ch| !is_combining_mark(*ch))
+            .collect::<String>()
+            .to_lowercase()
+    }
+}
+
+/// A string [`Key`] that is encoded using the sort key produced by a
+/// [`Collation`]. The original string is not stored, which means decoding
+/// this key returns the sort key.
+///
+/// Because strings that collate equally produce the same key, this type is
+/// useful for lookups. For example, using `Collated<CaseInsensitive>` as the
+/// key of a [`NamedCollection::ByNameView`](crate::schema::NamedCollection::ByNameView)
+/// allows loading documents by name without regard to case. To sort by a
+/// collation while retaining the original string, use
+/// [`CollatedWithOriginal`].
+///
+/// ```rust
+/// use bonsaidb_core::key::{CaseInsensitive, Collated, KeyEncoding};
+///
+/// let apple = Collated::<CaseInsensitive>::new("Apple");
+/// let zebra = Collated::<CaseInsensitive>::new("zebra");
+/// assert!(apple.as_ord_bytes().unwrap() < zebra.as_ord_bytes().unwrap());
+/// assert_eq!(apple, Collated::new("APPLE"));
+/// ```
+#[derive_where(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Collated<C: Collation> {
+    sort_key: String,
+    _collation: PhantomData<C>,
+}
+
+impl<C> Collated<C>
+where
+    C: Collation,
+{
+    /// Returns a new key for `value`.
+    #[must_use]
+    pub fn new(value: &str) -> Self {
+        Self::from_sort_key(C::sort_key(value))
+    }
+
+    const fn from_sort_key(sort_key: String) -> Self {
+        Self {
+            sort_key,
+            _collation: PhantomData,
+        }
+    }
+
+    /// Returns the sort key of this value.
+    #[must_use]
+    pub fn sort_key(&self) -> &str {
+        &self.sort_key
+    }
+
+    /// Consumes this value and returns its sort key.
+    #[must_use]
+    pub fn into_sort_key(self) -> String {
+        self.sort_key
+    }
+}
+
+impl<'a, C> From<&'a str> for Collated<C>
+where
+    C: Collation,
+{
+    fn from(value: &'a str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<C> From<String> for Collated<C>
+where
+    C: Collation,
+{
+    fn from(value: String) -> Self {
+        Self::new(&value)
+    }
+}
+
+impl<'k, C> Key<'k> for Collated<C>
+where
+    C: Collation,
+{
+    const CAN_OWN_BYTES: bool = true;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        String::from_utf8(bytes.into_owned()).map(Self::from_sort_key)
+    }
+}
+
+impl<C> KeyEncoding<Self> for Collated<C>
+where
+    C: Collation,
+{
+    type Error = FromUtf8Error;
+
+    const LENGTH: Option<usize> = None;
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(CompositeKind::Struct(Cow::Borrowed(C::NAME)), 1);
+        visitor.visit_type(KeyKind::String);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        Ok(Cow::Borrowed(self.sort_key.as_bytes()))
+    }
+}
+
+impl<'a, C> IntoPrefixRange<'a, Self> for Collated<C>
+where
+    C: Collation,
+{
+    fn to_prefix_range(&'a self) -> RangeRef<'a, Self> {
+        let end = match self.sort_key.to_prefix_range().end {
+            BoundRef::Unbounded => BoundRef::Unbounded,
+            BoundRef::Included(end) => {
+                BoundRef::Included(MaybeOwned::Owned(Self::from_sort_key((*end).clone())))
+            }
+            BoundRef::Excluded(end) => {
+                BoundRef::Excluded(MaybeOwned::Owned(Self::from_sort_key((*end).clone())))
+            }
+        };
+        RangeRef {
+            start: BoundRef::borrowed(Bound::Included(self)),
+            end,
+        }
+    }
+}
+
+impl<C> NameKey for Collated<C>
+where
+    C: Collation,
+{
+    fn from_name(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+/// A string [`Key`] that sorts using the sort key produced by a [`Collation`]
+/// while preserving the original string.
+///
+/// Values are ordered by their sort key first, and strings with equal sort
+/// keys are ordered by their original UTF-8 bytes. Because the original string
+/// is part of the key, querying for a specific key requires the original
+/// string. To look up values without regard to the original string, use
+/// [`Collated`].
+///
+/// ```rust
+/// use bonsaidb_core::key::{CaseInsensitive, CollatedWithOriginal, KeyEncoding};
+///
+/// let apple = CollatedWithOriginal::<CaseInsensitive>::new("apple");
+/// let zebra = CollatedWithOriginal::<CaseInsensitive>::new("Zebra");
+/// assert!(apple.as_ord_bytes().unwrap() < zebra.as_ord_bytes().unwrap());
+/// assert_eq!(zebra.original(), "Zebra");
+/// ```
+#[derive_where(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct CollatedWithOriginal<C: Collation> {
+    sort_key: String,
+    original: String,
+    _collation: PhantomData<C>,
+}
+
+impl<C> CollatedWithOriginal<C>
+where
+    C: Collation,
+{
+    /// Returns a new key for `value`.
+    #[must_use]
+    pub fn new(value: impl Into<String>) -> Self {
+        let original = value.into();
+        Self {
+            sort_key: C::sort_key(&original),
+            original,
+            _collation: PhantomData,
+        }
+    }
+
+    /// Returns the sort key of this value.
+    #[must_use]
+    pub fn sort_key(&self) -> &str {
+        &self.sort_key
+    }
+
+    /// Returns the original string.
+    #[must_use]
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// Consumes this value and returns the original string.
+    #[must_use]
+    pub fn into_original(self) -> String {
+        self.original
+    }
+}
+
+impl<'a, C> From<&'a str> for CollatedWithOriginal<C>
+where
+    C: Collation,
+{
+    fn from(value: &'a str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<C> From<String> for CollatedWithOriginal<C>
+where
+    C: Collation,
+{
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<'k, C> Key<'k> for CollatedWithOriginal<C>
+where
+    C: Collation,
+{
+    const CAN_OWN_BYTES: bool = false;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        let mut decoder = CompositeKeyDecoder::default_for(bytes);
+        let sort_key = decoder.decode::<String>()?;
+        let original = decoder.decode::<String>()?;
+        decoder.finish()?;
+
+        Ok(Self {
+            sort_key,
+            original,
+            _collation: PhantomData,
+        })
+    }
+}
+
+impl<C> KeyEncoding<Self> for CollatedWithOriginal<C>
+where
+    C: Collation,
+{
+    type Error = CompositeKeyError;
+
+    const LENGTH: Option<usize> = None;
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(CompositeKind::Struct(Cow::Borrowed(C::NAME)), 2);
+        visitor.visit_type(KeyKind::String);
+        visitor.visit_type(KeyKind::String);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        let mut encoder = CompositeKeyEncoder::default();
+        encoder.encode(&self.sort_key)?;
+        encoder.encode(&self.original)?;
+        Ok(Cow::Owned(encoder.finish()))
+    }
+}
+
+#[test]
+fn collation_tests() {
+    assert_eq!(Normalized::sort_key("e\u{301}"), "\u{e9}");
+    assert_eq!(CaseInsensitive::sort_key("ÉMILE"), "émile");
+    assert_eq!(CaseAndAccentInsensitive::sort_key("Émile"), "emile");
+    assert_eq!(
+        Collated::<CaseInsensitive>::new("e\u{301}"),
+        Collated::new("\u{c9}")
+    );
+}
+
+#[test]
+fn collated_ordering_tests() -> anyhow::Result<()> {
+    let names = ["apple", "Banana", "cherry", "Émile", "Zebra"];
+    let mut sorted = names
+        .iter()
+        .map(|name| {
+            Collated::<CaseAndAccentInsensitive>::new(name)
+                .as_ord_bytes()
+                .map(|bytes| bytes.to_vec())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let encoded = sorted.clone();
+    sorted.sort();
+    assert_eq!(encoded, sorted);
+
+    let original = CollatedWithOriginal::<CaseInsensitive>::new("Zebra");
+    let decoded = CollatedWithOriginal::<CaseInsensitive>::from_ord_bytes(ByteSource::Borrowed(
+        &original.as_ord_bytes()?,
+    ))?;
+    assert_eq!(decoded.original(), "Zebra");
+    assert_eq!(decoded.sort_key(), "zebra");
+    assert!(
+        original.as_ord_bytes()?
+            < CollatedWithOriginal::<CaseInsensitive>::new("zebra").as_ord_bytes()?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn collated_prefix_range_tests() {
+    use std::ops::RangeBounds;
+
+    let prefix = Collated::<CaseInsensitive>::new("AP");
+    let range = prefix.to_prefix_range();
+    assert!(range.contains(&Collated::new("apple")));
+    assert!(range.contains(&Collated::new("Apricot")));
+    assert!(!range.contains(&Collated::new("banana")));
+}
@@ -0,0 +1,268 @@
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime};
+
+use ordered_varint::Variable;
+
+use crate::key::time::limited::{
+    AnyTimestamp, LimitedResolutionTimestamp, TimeEpoch, TimeResolution,
+};
+use crate::key::time::TimeError;
+use crate::key::{ByteSource, CompositeKind, Key, KeyEncoding, KeyKind, KeyVisitor};
+
+/// Encodes a timestamp as the number of seconds relative to the unix epoch
+/// using [`ordered_varint`], followed by the subsecond nanoseconds as a
+/// big-endian `u32`.
+fn encode_timestamp(seconds: i64, nanos: u32) -> Result<Vec<u8>, TimeError> {
+    let mut bytes = seconds.to_variable_vec()?;
+    bytes.extend_from_slice(&nanos.to_be_bytes());
+    Ok(bytes)
+}
+
+fn decode_timestamp(mut bytes: &[u8]) -> Result<(i64, u32), TimeError> {
+    let seconds = i64::decode_variable(&mut bytes)?;
+    let nanos = <[u8; 4]>::try_from(bytes).map_err(|_| TimeError::InvalidValue)?;
+    Ok((seconds, u32::from_be_bytes(nanos)))
+}
+
+fn describe_timestamp<Visitor>(name: &'static str, visitor: &mut Visitor)
+where
+    Visitor: KeyVisitor,
+{
+    visitor.visit_composite(CompositeKind::Struct(Cow::Borrowed(name)), 2);
+    visitor.visit_type(KeyKind::Signed);
+    visitor.visit_type(KeyKind::U32);
+}
+
+fn duration_since_unix_epoch(seconds: i64, nanos: u32) -> Result<Duration, TimeError> {
+    let seconds = u64::try_from(seconds).map_err(|_| TimeError::DeltaNotRepresentable)?;
+    Ok(Duration::new(seconds, nanos))
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use super::*;
+
+    /// Encodes the timestamp as the number of seconds since the unix epoch,
+    /// followed by the subsecond nanoseconds. The encoding is shared with
+    /// `time::OffsetDateTime`.
+    impl<'k> Key<'k> for DateTime<Utc> {
+        const CAN_OWN_BYTES: bool = false;
+
+        fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+            let (seconds, nanos) = decode_timestamp(bytes.as_ref())?;
+            Utc.timestamp_opt(seconds, nanos)
+                .single()
+                .ok_or(TimeError::DeltaNotRepresentable)
+        }
+    }
+
+    impl KeyEncoding<Self> for DateTime<Utc> {
+        type Error = TimeError;
+
+        const LENGTH: Option<usize> = None;
+
+        fn describe<Visitor>(visitor: &mut Visitor)
+        where
+            Visitor: KeyVisitor,
+        {
+            describe_timestamp("chrono::DateTime<chrono::Utc>", visitor);
+        }
+
+        fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+            encode_timestamp(self.timestamp(), self.timestamp_subsec_nanos()).map(Cow::Owned)
+        }
+    }
+
+    impl AnyTimestamp for DateTime<Utc> {
+        fn duration_since_unix_epoch(&self) -> Result<Duration, TimeError> {
+            duration_since_unix_epoch(self.timestamp(), self.timestamp_subsec_nanos())
+        }
+    }
+
+    impl<Resolution, Epoch> TryFrom<DateTime<Utc>> for LimitedResolutionTimestamp<Resolution, Epoch>
+    where
+        Resolution: TimeResolution,
+        Epoch: TimeEpoch,
+    {
+        type Error = TimeError;
+
+        fn try_from(time: DateTime<Utc>) -> Result<Self, TimeError> {
+            Self::try_from(SystemTime::from(time))
+        }
+    }
+
+    impl<Resolution, Epoch> TryFrom<LimitedResolutionTimestamp<Resolution, Epoch>> for DateTime<Utc>
+    where
+        Resolution: TimeResolution,
+        Epoch: TimeEpoch,
+    {
+        type Error = TimeError;
+
+        fn try_from(
+            time: LimitedResolutionTimestamp<Resolution, Epoch>,
+        ) -> Result<Self, TimeError> {
+            SystemTime::try_from(time).map(Self::from)
+        }
+    }
+
+    #[test]
+    fn chrono_tests() -> anyhow::Result<()> {
+        use crate::key::time::TimestampAsMilliseconds;
+
+        let times = [
+            Utc.timestamp_opt(-86_400, 1).unwrap(),
+            Utc.timestamp_opt(-1, 999_999_999).unwrap(),
+            Utc.timestamp_opt(0, 0).unwrap(),
+            Utc.timestamp_opt(0, 1).unwrap(),
+            Utc.timestamp_opt(1_700_000_000, 500).unwrap(),
+        ];
+        let encoded = times
+            .iter()
+            .map(|time| time.as_ord_bytes().map(|bytes| bytes.to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (time, encoded) in times.iter().zip(&encoded) {
+            assert_eq!(
+                &DateTime::<Utc>::from_ord_bytes(ByteSource::Borrowed(encoded))?,
+                time
+            );
+        }
+        for pair in encoded.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+
+        let time = Utc.timestamp_opt(1_700_000_000, 123_000_000).unwrap();
+        let timestamp = TimestampAsMilliseconds::try_from(time)?;
+        assert_eq!(DateTime::<Utc>::try_from(timestamp)?, time);
+        assert_eq!(
+            time.duration_since_unix_epoch()?,
+            timestamp.duration_since_unix_epoch()?
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    /// Encodes the timestamp as the number of seconds since the unix epoch,
+    /// followed by the subsecond nanoseconds. The encoding is shared with
+    /// `chrono::DateTime<Utc>`.
+    ///
+    /// The UTC offset is not encoded. Decoded values are always in UTC, and
+    /// compare equal to the original values.
+    impl<'k> Key<'k> for OffsetDateTime {
+        const CAN_OWN_BYTES: bool = false;
+
+        fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+            let (seconds, nanos) = decode_timestamp(bytes.as_ref())?;
+            OffsetDateTime::from_unix_timestamp(seconds)
+                .and_then(|time| time.replace_nanosecond(nanos))
+                .map_err(|_| TimeError::DeltaNotRepresentable)
+        }
+    }
+
+    impl KeyEncoding<Self> for OffsetDateTime {
+        type Error = TimeError;
+
+        const LENGTH: Option<usize> = None;
+
+        fn describe<Visitor>(visitor: &mut Visitor)
+        where
+            Visitor: KeyVisitor,
+        {
+            describe_timestamp("time::OffsetDateTime", visitor);
+        }
+
+        fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+            encode_timestamp(self.unix_timestamp(), self.nanosecond()).map(Cow::Owned)
+        }
+    }
+
+    impl AnyTimestamp for OffsetDateTime {
+        fn duration_since_unix_epoch(&self) -> Result<Duration, TimeError> {
+            duration_since_unix_epoch(self.unix_timestamp(), self.nanosecond())
+        }
+    }
+
+    impl<Resolution, Epoch> TryFrom<OffsetDateTime> for LimitedResolutionTimestamp<Resolution, Epoch>
+    where
+        Resolution: TimeResolution,
+        Epoch: TimeEpoch,
+    {
+        type Error = TimeError;
+
+        fn try_from(time: OffsetDateTime) -> Result<Self, TimeError> {
+            Self::try_from(SystemTime::from(time))
+        }
+    }
+
+    impl<Resolution, Epoch> TryFrom<LimitedResolutionTimestamp<Resolution, Epoch>> for OffsetDateTime
+    where
+        Resolution: TimeResolution,
+        Epoch: TimeEpoch,
+    {
+        type Error = TimeError;
+
+        fn try_from(
+            time: LimitedResolutionTimestamp<Resolution, Epoch>,
+        ) -> Result<Self, TimeError> {
+            SystemTime::try_from(time).map(Self::from)
+        }
+    }
+
+    #[test]
+    fn offset_date_time_tests() -> anyhow::Result<()> {
+        use time::UtcOffset;
+
+        use crate::key::time::TimestampAsMilliseconds;
+
+        let times = [
+            OffsetDateTime::from_unix_timestamp_nanos(-86_399_999_999_999)?,
+            OffsetDateTime::from_unix_timestamp_nanos(-1)?,
+            OffsetDateTime::UNIX_EPOCH,
+            OffsetDateTime::from_unix_timestamp_nanos(1)?,
+            OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_000_000_500)?,
+        ];
+        let encoded = times
+            .iter()
+            .map(|time| time.as_ord_bytes().map(|bytes| bytes.to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (time, encoded) in times.iter().zip(&encoded) {
+            assert_eq!(
+                &OffsetDateTime::from_ord_bytes(ByteSource::Borrowed(encoded))?,
+                time
+            );
+        }
+        for pair in encoded.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+
+        // The offset does not affect the encoding.
+        let utc = OffsetDateTime::from_unix_timestamp(1_700_000_000)?;
+        let offset = utc.to_offset(UtcOffset::from_hms(-5, 0, 0)?);
+        assert_eq!(utc.as_ord_bytes()?, offset.as_ord_bytes()?);
+
+        // The encoding is shared with chrono.
+        #[cfg(feature = "chrono")]
+        {
+            use chrono::{DateTime, TimeZone, Utc};
+            let chrono = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+            assert_eq!(utc.as_ord_bytes()?, chrono.as_ord_bytes()?);
+            assert_eq!(
+                DateTime::<Utc>::from_ord_bytes(ByteSource::Borrowed(&utc.as_ord_bytes()?))?,
+                chrono
+            );
+        }
+
+        let timestamp = TimestampAsMilliseconds::try_from(utc)?;
+        assert_eq!(OffsetDateTime::try_from(timestamp)?, utc);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use std::borrow::Cow;
+use std::io::{self, ErrorKind};
+
+use rust_decimal::Decimal;
+
+use crate::key::{ByteSource, CompositeKind, Key, KeyEncoding, KeyKind, KeyVisitor};
+
+/// The maximum number of digits in a [`Decimal`]'s 96-bit mantissa.
+const MAX_DIGITS: u32 = 29;
+const NEGATIVE: u8 = 0;
+const ZERO: u8 = 1;
+const POSITIVE: u8 = 2;
+
+/// Decimals are encoded similarly to scientific notation: a byte for the sign,
+/// a byte for the exponent of the most significant digit, and the digits of the
+/// mantissa aligned to 29 digits as a big-endian `u128`. Negative values have
+/// their exponent and mantissa bytes inverted.
+///
+/// Values that compare equal encode identically, regardless of their scale.
+/// For example, `1.0` and `1.00` both decode as `1`.
+impl<'k> Key<'k> for Decimal {
+    const CAN_OWN_BYTES: bool = false;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        let bytes = bytes.as_ref();
+        if bytes.len() != 18 {
+            return Err(invalid_data());
+        }
+        let negative = match bytes[0] {
+            NEGATIVE => true,
+            ZERO => return Ok(Decimal::ZERO),
+            POSITIVE => false,
+            _ => return Err(invalid_data()),
+        };
+        let mut exponent_and_mantissa = [0; 17];
+        exponent_and_mantissa.copy_from_slice(&bytes[1..]);
+        if negative {
+            for byte in &mut exponent_and_mantissa {
+                *byte = !*byte;
+            }
+        }
+
+        let exponent = i32::from(exponent_and_mantissa[0]) - 128;
+        let mut mantissa = u128::from_be_bytes(
+            exponent_and_mantissa[1..]
+                .try_into()
+                .expect("slice is 16 bytes"),
+        );
+        if mantissa == 0 {
+            return Err(invalid_data());
+        }
+        let mut digits = MAX_DIGITS;
+        while mantissa % 10 == 0 {
+            mantissa /= 10;
+            digits -= 1;
+        }
+        let mut scale = i32::try_from(digits).expect("digits fit in i32") - exponent;
+        while scale < 0 {
+            mantissa = mantissa.checked_mul(10).ok_or_else(invalid_data)?;
+            scale += 1;
+        }
+
+        let mantissa = i128::try_from(mantissa).map_err(|_| invalid_data())?;
+        let scale = u32::try_from(scale).map_err(|_| invalid_data())?;
+        Decimal::try_from_i128_with_scale(if negative { -mantissa } else { mantissa }, scale)
+            .map_err(|_| invalid_data())
+    }
+}
+
+impl KeyEncoding<Self> for Decimal {
+    type Error = io::Error;
+
+    const LENGTH: Option<usize> = Some(18);
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(
+            CompositeKind::Struct(Cow::Borrowed("rust_decimal::Decimal")),
+            1,
+        );
+        visitor.visit_type(KeyKind::Bytes);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        let mut encoded = vec![ZERO; 18];
+        let value = self.normalize();
+        if value.is_zero() {
+            return Ok(Cow::Owned(encoded));
+        }
+
+        let mut mantissa = value.mantissa().unsigned_abs();
+        let digits = mantissa.ilog10() + 1;
+        mantissa *= 10_u128.pow(MAX_DIGITS - digits);
+        // The mantissa has between 1 and 29 digits and the scale is between 0
+        // and 28, which means the exponent is always between -27 and 29.
+        let exponent = i32::try_from(digits).expect("digits fit in i32")
+            - i32::try_from(value.scale()).expect("scale fits in i32");
+        let exponent = u8::try_from(exponent + 128).expect("exponent is always in range");
+
+        encoded[1] = exponent;
+        encoded[2..].copy_from_slice(&mantissa.to_be_bytes());
+        if value.is_sign_negative() {
+            encoded[0] = NEGATIVE;
+            for byte in &mut encoded[1..] {
+                *byte = !*byte;
+            }
+        } else {
+            encoded[0] = POSITIVE;
+        }
+
+        Ok(Cow::Owned(encoded))
+    }
+}
+
+fn invalid_data() -> io::Error {
+    io::Error::from(ErrorKind::InvalidData)
+}
+
+#[test]
+fn decimal_key_tests() -> anyhow::Result<()> {
+    let values = [
+        Decimal::MIN,
+        Decimal::new(-1000, 0),
+        Decimal::new(-15, 1),
+        Decimal::new(-1, 0),
+        Decimal::new(-1, 28),
+        Decimal::ZERO,
+        Decimal::new(1, 28),
+        Decimal::new(1, 3),
+        Decimal::new(999, 3),
+        Decimal::new(1, 0),
+        Decimal::new(10_001, 4),
+        Decimal::new(2, 0),
+        Decimal::new(100, 0),
+        Decimal::MAX,
+    ];
+    let encoded = values
+        .iter()
+        .map(|value| value.as_ord_bytes().map(|bytes| bytes.to_vec()))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (value, encoded) in values.iter().zip(&encoded) {
+        assert_eq!(
+            &Decimal::from_ord_bytes(ByteSource::Borrowed(encoded))?,
+            value
+        );
+    }
+    for pair in encoded.windows(2) {
+        assert!(pair[0] < pair[1]);
+    }
+
+    // Equal values with different scales have the same encoding.
+    assert_eq!(
+        Decimal::new(1, 0).as_ord_bytes()?,
+        Decimal::new(1000, 3).as_ord_bytes()?
+    );
+    assert_eq!(
+        Decimal::ZERO.as_ord_bytes()?,
+        Decimal::new(0, 5).as_ord_bytes()?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{BoundRef, MaybeOwned, RangeRef};
+use crate::key::{ByteSource, CompositeKind, IntoPrefixRange, Key, KeyEncoding, KeyVisitor};
+use crate::AnyError;
+
+/// A [`Key`] wrapper that sorts in the opposite order of `K`.
+///
+/// The bytes produced by `K`'s encoding are inverted. This allows mixing sort
+/// orders within a composite key, such as sorting by category ascending, and
+/// then by score descending:
+///
+/// ```rust
+/// use bonsaidb_core::key::{Descending, KeyEncoding};
+///
+/// let high_score = (String::from("a"), Descending(100_u32));
+/// let low_score = (String::from("a"), Descending(10_u32));
+/// assert!(high_score.as_ord_bytes().unwrap() < low_score.as_ord_bytes().unwrap());
+/// ```
+///
+/// When `K` is a variable-length type, the inverted bytes are terminated by
+/// `[0xFF, 0xFF]` and any `0xFF` bytes produced by the inversion are escaped as
+/// `[0xFF, 0x00]`. This ensures that longer values sort before values they
+/// begin with, e.g., `Descending("ab")` sorts before `Descending("a")`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Descending<K>(pub K);
+
+impl<K> Ord for Descending<K>
+where
+    K: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl<K> PartialOrd for Descending<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        other.0.partial_cmp(&self.0)
+    }
+}
+
+impl<K> From<K> for Descending<K> {
+    fn from(value: K) -> Self {
+        Self(value)
+    }
+}
+
+impl<'k, K> Key<'k> for Descending<K>
+where
+    K: Key<'k>,
+{
+    const CAN_OWN_BYTES: bool = false;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        let bytes = bytes.as_ref();
+        let decoded = if K::LENGTH.is_some() {
+            bytes.iter().map(|byte| !byte).collect::<Vec<_>>()
+        } else {
+            let escaped = bytes
+                .strip_suffix(&[0xFF, 0xFF])
+                .ok_or(DescendingKeyError::InvalidEncoding)?;
+            let mut decoded = Vec::with_capacity(escaped.len());
+            let mut bytes = escaped.iter();
+            while let Some(&byte) = bytes.next() {
+                if byte == 0xFF && bytes.next() != Some(&0) {
+                    return Err(DescendingKeyError::InvalidEncoding);
+                }
+                decoded.push(!byte);
+            }
+            decoded
+        };
+
+        K::from_ord_bytes(ByteSource::Owned(decoded))
+            .map(Self)
+            .map_err(DescendingKeyError::Key)
+    }
+}
+
+impl<K, T> KeyEncoding<Descending<K>> for Descending<T>
+where
+    T: KeyEncoding<K>,
+{
+    type Error = DescendingKeyError<T::Error>;
+
+    const LENGTH: Option<usize> = T::LENGTH;
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(CompositeKind::Descending, 1);
+        T::describe(visitor);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        let bytes = self.0.as_ord_bytes().map_err(DescendingKeyError::Key)?;
+        if T::LENGTH.is_some() {
+            Ok(Cow::Owned(bytes.iter().map(|byte| !byte).collect()))
+        } else {
+            let mut encoded = Vec::with_capacity(bytes.len() + 2);
+            for byte in bytes.iter().map(|byte| !byte) {
+                encoded.push(byte);
+                if byte == 0xFF {
+                    encoded.push(0);
+                }
+            }
+            encoded.extend_from_slice(&[0xFF, 0xFF]);
+            Ok(Cow::Owned(encoded))
+        }
+    }
+}
+
+impl<'a, T> IntoPrefixRange<'a, Self> for Descending<T>
+where
+    T: IntoPrefixRange<'a, T> + Clone,
+{
+    /// Returns the range of values whose inner values begin with `self.0`.
+    /// Because the order is inverted, the inner range's end becomes the start
+    /// of this range, and the inner range's start becomes the end.
+    fn to_prefix_range(&'a self) -> RangeRef<'a, Self> {
+        let inner = self.0.to_prefix_range();
+        RangeRef {
+            start: invert_bound(inner.end),
+            end: invert_bound(inner.start),
+        }
+    }
+}
+
+fn invert_bound<'a, T>(bound: BoundRef<'a, T>) -> BoundRef<'a, Descending<T>>
+where
+    T: PartialEq + Clone,
+{
+    let descending = |value: MaybeOwned<'a, T>| MaybeOwned::Owned(Descending((*value).clone()));
+    match bound {
+        BoundRef::Unbounded => BoundRef::Unbounded,
+        BoundRef::Included(value) => BoundRef::Included(descending(value)),
+        BoundRef::Excluded(value) => BoundRef::Excluded(descending(value)),
+    }
+}
+
+/// An error encoding or decoding a [`Descending`] key.
+#[derive(thiserror::Error, Debug)]
+pub enum DescendingKeyError<E: AnyError> {
+    /// An error from the wrapped key.
+    #[error("{0}")]
+    Key(E),
+    /// The encoded bytes were not produced by [`Descending`].
+    #[error("invalid descending key encoding")]
+    InvalidEncoding,
+}
+
+#[test]
+fn descending_ordering_tests() -> anyhow::Result<()> {
+    fn assert_descending<K>(ascending: &[K]) -> anyhow::Result<()>
+    where
+        K: for<'k> Key<'k> + PartialEq + std::fmt::Debug,
+    {
+        let encoded = ascending
+            .iter()
+            .map(|value| {
+                Descending(value.clone())
+                    .as_ord_bytes()
+                    .map(|bytes| bytes.to_vec())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for (value, encoded) in ascending.iter().zip(&encoded) {
+            let decoded = Descending::<K>::from_ord_bytes(ByteSource::Borrowed(encoded))?;
+            assert_eq!(&decoded.0, value);
+        }
+        for pair in encoded.windows(2) {
+            assert!(pair[0] > pair[1]);
+        }
+        Ok(())
+    }
+
+    assert_descending(&[0_u32, 1, 255, 256, u32::MAX])?;
+    assert_descending(&[
+        String::new(),
+        String::from("\0"),
+        String::from("a"),
+        String::from("a\0"),
+        String::from("a\u{1}"),
+        String::from("aa"),
+        String::from("b"),
+    ])?;
+
+    // Mixed orderings within a composite key.
+    let keys = [
+        (String::from("a"), Descending(String::from("b"))),
+        (String::from("a"), Descending(String::from("a"))),
+        (String::from("b"), Descending(String::from("z"))),
+        (String::from("b"), Descending(String::new())),
+    ];
+    let encoded = keys
+        .iter()
+        .map(|key| key.as_ord_bytes().map(|bytes| bytes.to_vec()))
+        .collect::<Result<Vec<_>, _>>()?;
+    for pair in encoded.windows(2) {
+        assert!(pair[0] < pair[1]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn descending_prefix_range_tests() {
+    use std::ops::RangeBounds;
+
+    let prefix = Descending(String::from("ab"));
+    let range = prefix.to_prefix_range();
+    assert!(!range.contains(&Descending(String::from("a"))));
+    assert!(range.contains(&Descending(String::from("ab"))));
+    assert!(range.contains(&Descending(String::from("abc"))));
+    assert!(!range.contains(&Descending(String::from("ac"))));
+}
@@ -0,0 +1,774 @@
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{AccessPolicy, AsyncConnection, Connection};
+use crate::key::{
+    ByteSource, CompositeKind, IncorrectByteLength, Key, KeyEncoding, KeyKind, KeyVisitor,
+};
+use crate::schema::view::map::ViewMappings;
+use crate::schema::SerializedView;
+use crate::Error;
+
+/// The mean radius of the Earth, in meters.
+pub const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// The default maximum number of key ranges queried by a [`GeoQuery`].
+pub const DEFAULT_MAX_KEY_RANGES: usize = 16;
+
+/// 2^32, the number of quantized steps along each axis.
+const STEPS: f64 = 4_294_967_296.0;
+
+/// A location on Earth, measured in degrees.
+///
+/// As a [`Key`], a point is encoded as 8 bytes containing the Z-order curve
+/// (Morton code) of its quantized latitude and longitude. Points that are near
+/// each other usually have keys that are near each other, which allows
+/// [`View::within()`](crate::connection::View::within) to find the entries in
+/// an area by querying a small number of key ranges.
+///
+/// Each coordinate is quantized to 32 bits, which is a precision of about
+/// a centimeter. Decoding a key produces the south-west corner of the
+/// quantized cell containing the original point.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    /// The latitude, between -90 (south) and 90 (north) degrees.
+    pub latitude: f64,
+    /// The longitude, between -180 (west) and 180 (east) degrees.
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    /// Returns a new point. `latitude` is clamped between -90 and 90 degrees,
+    /// and `longitude` is wrapped to be between -180 and 180 degrees.
+    #[must_use]
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        let longitude = if (-180.0..180.0).contains(&longitude) {
+            longitude
+        } else {
+            (longitude + 180.).rem_euclid(360.) - 180.
+        };
+        Self {
+            latitude: latitude.clamp(-90., 90.),
+            longitude,
+        }
+    }
+
+    /// Returns the great-circle distance between this point and `other`, in
+    /// meters.
+    ///
+    /// ```rust
+    /// use bonsaidb_core::key::GeoPoint;
+    ///
+    /// let london = GeoPoint::new(51.5074, -0.1278);
+    /// let paris = GeoPoint::new(48.8566, 2.3522);
+    /// let distance = london.distance_to(&paris);
+    /// assert!((343_000. ..344_000.).contains(&distance));
+    /// ```
+    #[must_use]
+    pub fn distance_to(&self, other: &Self) -> f64 {
+        let latitude_delta = (other.latitude - self.latitude).to_radians();
+        let longitude_delta = (other.longitude - self.longitude).to_radians();
+        let a = (latitude_delta / 2.).sin().powi(2)
+            + self.latitude.to_radians().cos()
+                * other.latitude.to_radians().cos()
+                * (longitude_delta / 2.).sin().powi(2);
+        2. * EARTH_RADIUS_METERS * a.sqrt().min(1.).asin()
+    }
+
+    fn quantized(&self) -> (u32, u32) {
+        (
+            quantize(self.latitude, -90., 180.),
+            quantize(self.longitude, -180., 360.),
+        )
+    }
+
+    fn from_quantized(latitude: u32, longitude: u32) -> Self {
+        Self {
+            latitude: dequantize(latitude, -90., 180.),
+            longitude: dequantize(longitude, -180., 360.),
+        }
+    }
+
+    fn z_order(&self) -> u64 {
+        let (latitude, longitude) = self.quantized();
+        interleave(latitude, longitude)
+    }
+
+    fn from_z_order(z_order: u64) -> Self {
+        Self::from_quantized(compact(z_order), compact(z_order >> 1))
+    }
+}
+
+impl<'k> Key<'k> for GeoPoint {
+    const CAN_OWN_BYTES: bool = false;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        Ok(Self::from_z_order(u64::from_be_bytes(<[u8; 8]>::try_from(
+            bytes.as_ref(),
+        )?)))
+    }
+}
+
+impl KeyEncoding<Self> for GeoPoint {
+    type Error = IncorrectByteLength;
+
+    const LENGTH: Option<usize> = Some(8);
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(
+            CompositeKind::Struct(Cow::Borrowed("bonsaidb::core::key::GeoPoint")),
+            1,
+        );
+        visitor.visit_type(KeyKind::U64);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        Ok(Cow::Owned(self.z_order().to_be_bytes().to_vec()))
+    }
+}
+
+/// Converts `value` to one of 2^32 steps between `minimum` and `minimum +
+/// span`. For values produced by [`dequantize()`], each operation is exact,
+/// which ensures decoded keys encode to the same bytes.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn quantize(value: f64, minimum: f64, span: f64) -> u32 {
+    // Float to integer casts saturate, clamping out-of-range values.
+    ((value - minimum) * STEPS / span).floor() as u32
+}
+
+fn dequantize(value: u32, minimum: f64, span: f64) -> f64 {
+    (f64::from(value) * span + minimum * STEPS) / STEPS
+}
+
+/// Interleaves the bits of `latitude` and `longitude`, with each longitude
+/// bit preceding the corresponding latitude bit.
+fn interleave(latitude: u32, longitude: u32) -> u64 {
+    spread(longitude) << 1 | spread(latitude)
+}
+
+fn spread(value: u32) -> u64 {
+    let mut value = u64::from(value);
+    value = (value | value << 16) & 0x0000_FFFF_0000_FFFF;
+    value = (value | value << 8) & 0x00FF_00FF_00FF_00FF;
+    value = (value | value << 4) & 0x0F0F_0F0F_0F0F_0F0F;
+    value = (value | value << 2) & 0x3333_3333_3333_3333;
+    (value | value << 1) & 0x5555_5555_5555_5555
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn compact(value: u64) -> u32 {
+    let mut value = value & 0x5555_5555_5555_5555;
+    value = (value | value >> 1) & 0x3333_3333_3333_3333;
+    value = (value | value >> 2) & 0x0F0F_0F0F_0F0F_0F0F;
+    value = (value | value >> 4) & 0x00FF_00FF_00FF_00FF;
+    value = (value | value >> 8) & 0x0000_FFFF_0000_FFFF;
+    (value | value >> 16) as u32
+}
+
+/// A rectangular area bounded by lines of latitude and longitude.
+///
+/// If `south_west.longitude` is greater than `north_east.longitude`, the box
+/// crosses the antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoBoundingBox {
+    /// The south-west corner of the box.
+    pub south_west: GeoPoint,
+    /// The north-east corner of the box.
+    pub north_east: GeoPoint,
+}
+
+impl GeoBoundingBox {
+    /// Returns a box with the corners `south_west` and `north_east`.
+    #[must_use]
+    pub const fn new(south_west: GeoPoint, north_east: GeoPoint) -> Self {
+        Self {
+            south_west,
+            north_east,
+        }
+    }
+
+    /// Returns true if `point` is within this box. Points are compared using
+    /// the precision of their [`Key`] encoding.
+    #[must_use]
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        let (latitude, longitude) = point.quantized();
+        let (south, west) = self.south_west.quantized();
+        let (north, east) = self.north_east.quantized();
+        (south..=north).contains(&latitude)
+            && if west <= east {
+                (west..=east).contains(&longitude)
+            } else {
+                longitude >= west || longitude <= east
+            }
+    }
+
+    /// Returns the point in the middle of this box.
+    #[must_use]
+    pub fn center(&self) -> GeoPoint {
+        let mut east = self.north_east.longitude;
+        if east < self.south_west.longitude {
+            east += 360.;
+        }
+        GeoPoint::new(
+            (self.south_west.latitude + self.north_east.latitude) / 2.,
+            (self.south_west.longitude + east) / 2.,
+        )
+    }
+
+    fn quantized_boxes(&self) -> Vec<QuantizedBox> {
+        let (south, west) = self.south_west.quantized();
+        let (north, east) = self.north_east.quantized();
+        if south > north {
+            Vec::new()
+        } else if west <= east {
+            vec![QuantizedBox {
+                latitude: south..=north,
+                longitude: west..=east,
+            }]
+        } else {
+            vec![
+                QuantizedBox {
+                    latitude: south..=north,
+                    longitude: west..=u32::MAX,
+                },
+                QuantizedBox {
+                    latitude: south..=north,
+                    longitude: 0..=east,
+                },
+            ]
+        }
+    }
+}
+
+/// An area that a [`GeoQuery`] searches within.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GeoArea {
+    /// The points within a bounding box.
+    BoundingBox(GeoBoundingBox),
+    /// The points within `meters` of `center`.
+    Radius {
+        /// The center of the circle.
+        center: GeoPoint,
+        /// The radius of the circle, in meters.
+        meters: f64,
+    },
+}
+
+impl GeoArea {
+    /// Returns an area containing the points within `meters` of `center`.
+    #[must_use]
+    pub const fn radius(center: GeoPoint, meters: f64) -> Self {
+        Self::Radius { center, meters }
+    }
+
+    /// Returns true if `point` is within this area.
+    #[must_use]
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        match self {
+            Self::BoundingBox(bounds) => bounds.contains(point),
+            Self::Radius { center, meters } => center.distance_to(point) <= *meters,
+        }
+    }
+
+    /// Returns the center of this area.
+    #[must_use]
+    pub fn center(&self) -> GeoPoint {
+        match self {
+            Self::BoundingBox(bounds) => bounds.center(),
+            Self::Radius { center, .. } => *center,
+        }
+    }
+
+    /// Returns the smallest bounding box that contains this area.
+    #[must_use]
+    pub fn bounding_box(&self) -> GeoBoundingBox {
+        match self {
+            Self::BoundingBox(bounds) => *bounds,
+            Self::Radius { center, meters } => {
+                let angle = (meters / EARTH_RADIUS_METERS).max(0.);
+                let latitude_delta = angle.to_degrees();
+                let south = center.latitude - latitude_delta;
+                let north = center.latitude + latitude_delta;
+                if south <= -90. || north >= 90. || angle >= std::f64::consts::FRAC_PI_2 {
+                    // The circle contains a pole, which means it crosses
+                    // every line of longitude.
+                    return GeoBoundingBox::new(
+                        GeoPoint::new(south, -180.),
+                        GeoPoint {
+                            latitude: north.min(90.),
+                            longitude: 180.,
+                        },
+                    );
+                }
+                let longitude_delta = (angle.sin() / center.latitude.to_radians().cos())
+                    .min(1.)
+                    .asin()
+                    .to_degrees();
+                GeoBoundingBox::new(
+                    GeoPoint::new(south, center.longitude - longitude_delta),
+                    GeoPoint::new(north, center.longitude + longitude_delta),
+                )
+            }
+        }
+    }
+
+    /// Returns ranges of keys that contain every [`GeoPoint`] in this area,
+    /// in ascending order. No more than `maximum_ranges` ranges are returned,
+    /// unless `maximum_ranges` is 0, in which case one range covering every
+    /// key is returned.
+    ///
+    /// The ranges may also contain points outside of this area. Allowing more
+    /// ranges reduces the number of these false positives.
+    #[must_use]
+    pub fn key_ranges(&self, maximum_ranges: usize) -> Vec<RangeInclusive<GeoPoint>> {
+        let boxes = self.bounding_box().quantized_boxes();
+        let mut cells = boxes
+            .iter()
+            .map(|quantized| (Cell::WORLD, quantized))
+            .collect::<Vec<_>>();
+        let mut contained = Vec::new();
+        let mut covering = Vec::new();
+        // Cells that are partially within a box are divided into quadrants
+        // until either every cell is fully contained or covering the cells
+        // would require more than `maximum_ranges` ranges.
+        loop {
+            let mut partial = Vec::new();
+            for (cell, quantized) in cells {
+                match quantized.overlap(&cell) {
+                    Overlap::None => {}
+                    Overlap::Full => contained.push(cell.z_order_range()),
+                    Overlap::Partial => partial.push((cell, quantized)),
+                }
+            }
+
+            let candidate = merge_ranges(
+                contained
+                    .iter()
+                    .copied()
+                    .chain(partial.iter().map(|(cell, _)| cell.z_order_range())),
+            );
+            if candidate.len() > maximum_ranges && !covering.is_empty() {
+                break;
+            }
+            covering = candidate;
+            // Limit the number of cells examined when most partial cells
+            // are adjacent to each other.
+            if partial.is_empty() || partial.len() > maximum_ranges.saturating_mul(16) {
+                break;
+            }
+
+            cells = partial
+                .iter()
+                .flat_map(|(cell, quantized)| {
+                    cell.quadrants().map(|quadrant| (quadrant, *quantized))
+                })
+                .collect();
+        }
+
+        covering
+            .into_iter()
+            .map(|(start, end)| GeoPoint::from_z_order(start)..=GeoPoint::from_z_order(end))
+            .collect()
+    }
+}
+
+impl From<GeoBoundingBox> for GeoArea {
+    fn from(bounds: GeoBoundingBox) -> Self {
+        Self::BoundingBox(bounds)
+    }
+}
+
+/// Sorts `ranges` and combines ranges that overlap or are adjacent.
+fn merge_ranges(ranges: impl Iterator<Item = (u64, u64)>) -> Vec<(u64, u64)> {
+    let mut ranges = ranges.collect::<Vec<_>>();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => {
+                last.1 = last.1.max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// A bounding box of quantized coordinates.
+struct QuantizedBox {
+    latitude: RangeInclusive<u32>,
+    longitude: RangeInclusive<u32>,
+}
+
+impl QuantizedBox {
+    fn overlap(&self, cell: &Cell) -> Overlap {
+        let latitude = cell.latitude..=cell.latitude + cell.extent();
+        let longitude = cell.longitude..=cell.longitude + cell.extent();
+        if latitude.end() < self.latitude.start()
+            || latitude.start() > self.latitude.end()
+            || longitude.end() < self.longitude.start()
+            || longitude.start() > self.longitude.end()
+        {
+            Overlap::None
+        } else if self.latitude.contains(latitude.start())
+            && self.latitude.contains(latitude.end())
+            && self.longitude.contains(longitude.start())
+            && self.longitude.contains(longitude.end())
+        {
+            Overlap::Full
+        } else {
+            Overlap::Partial
+        }
+    }
+}
+
+enum Overlap {
+    None,
+    Partial,
+    Full,
+}
+
+/// A square of quantized coordinates whose Z-order values are contiguous.
+#[derive(Clone, Copy)]
+struct Cell {
+    latitude: u32,
+    longitude: u32,
+    level: u32,
+}
+
+impl Cell {
+    const WORLD: Self = Self {
+        latitude: 0,
+        longitude: 0,
+        level: 0,
+    };
+
+    /// Returns the difference between the first and last coordinate along
+    /// each axis of this cell.
+    fn extent(&self) -> u32 {
+        u32::MAX.checked_shr(self.level).unwrap_or(0)
+    }
+
+    fn z_order_range(&self) -> (u64, u64) {
+        (
+            interleave(self.latitude, self.longitude),
+            interleave(
+                self.latitude + self.extent(),
+                self.longitude + self.extent(),
+            ),
+        )
+    }
+
+    fn quadrants(&self) -> [Self; 4] {
+        let half = self.extent() / 2 + 1;
+        let level = self.level + 1;
+        [
+            Self {
+                latitude: self.latitude,
+                longitude: self.longitude,
+                level,
+            },
+            Self {
+                latitude: self.latitude + half,
+                longitude: self.longitude,
+                level,
+            },
+            Self {
+                latitude: self.latitude,
+                longitude: self.longitude + half,
+                level,
+            },
+            Self {
+                latitude: self.latitude + half,
+                longitude: self.longitude + half,
+                level,
+            },
+        ]
+    }
+}
+
+/// A query for the entries of a view keyed by [`GeoPoint`] that are within a
+/// [`GeoArea`].
+///
+/// The area is decomposed into ranges of keys, each of which is queried
+/// separately. Entries in those ranges that are outside of the area are
+/// removed from the results.
+#[must_use]
+pub struct GeoQuery<'a, Cn, V> {
+    connection: &'a Cn,
+    area: GeoArea,
+    sorted_by_distance: bool,
+
+    /// The maximum number of results to return.
+    pub limit: Option<usize>,
+
+    /// The maximum number of key ranges to query.
+    pub max_key_ranges: usize,
+
+    /// The view's data access policy.
+    pub access_policy: AccessPolicy,
+
+    _view: std::marker::PhantomData<V>,
+}
+
+impl<'a, Cn, V> GeoQuery<'a, Cn, V>
+where
+    Cn: Connection,
+    V: SerializedView<Key = GeoPoint>,
+{
+    pub(crate) const fn new(
+        connection: &'a Cn,
+        area: GeoArea,
+        access_policy: AccessPolicy,
+    ) -> Self {
+        Self {
+            connection,
+            area,
+            sorted_by_distance: false,
+            limit: None,
+            max_key_ranges: DEFAULT_MAX_KEY_RANGES,
+            access_policy,
+            _view: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the results ordered from nearest to farthest from the center
+    /// of the area. By default, results are returned in key order.
+    pub const fn sorted_by_distance(mut self) -> Self {
+        self.sorted_by_distance = true;
+        self
+    }
+
+    /// Limits the number of results returned. The limit is applied after
+    /// sorting.
+    pub const fn limit(mut self, maximum_results: usize) -> Self {
+        self.limit = Some(maximum_results);
+        self
+    }
+
+    /// Sets the maximum number of key ranges to query. The default is
+    /// [`DEFAULT_MAX_KEY_RANGES`].
+    pub const fn with_max_key_ranges(mut self, max_key_ranges: usize) -> Self {
+        self.max_key_ranges = max_key_ranges;
+        self
+    }
+
+    /// Sets the access policy for the query.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Executes the query and returns the mappings within the area.
+    pub fn query(self) -> Result<ViewMappings<V>, Error> {
+        let mut mappings = Vec::new();
+        for range in self.area.key_ranges(self.max_key_ranges) {
+            mappings.extend(
+                self.connection
+                    .view::<V>()
+                    .with_access_policy(self.access_policy)
+                    .with_key_range::<GeoPoint, _>(range)
+                    .query()?,
+            );
+        }
+        Ok(finish_geo_query(
+            &self.area,
+            mappings,
+            self.sorted_by_distance,
+            self.limit,
+        ))
+    }
+}
+
+/// A query for the entries of a view keyed by [`GeoPoint`] that are within a
+/// [`GeoArea`].
+///
+/// The area is decomposed into ranges of keys, each of which is queried
+/// separately. Entries in those ranges that are outside of the area are
+/// removed from the results.
+#[must_use]
+pub struct AsyncGeoQuery<'a, Cn, V> {
+    connection: &'a Cn,
+    area: GeoArea,
+    sorted_by_distance: bool,
+
+    /// The maximum number of results to return.
+    pub limit: Option<usize>,
+
+    /// The maximum number of key ranges to query.
+    pub max_key_ranges: usize,
+
+    /// The view's data access policy.
+    pub access_policy: AccessPolicy,
+
+    _view: std::marker::PhantomData<V>,
+}
+
+impl<'a, Cn, V> AsyncGeoQuery<'a, Cn, V>
+where
+    Cn: AsyncConnection,
+    V: SerializedView<Key = GeoPoint>,
+{
+    pub(crate) const fn new(
+        connection: &'a Cn,
+        area: GeoArea,
+        access_policy: AccessPolicy,
+    ) -> Self {
+        Self {
+            connection,
+            area,
+            sorted_by_distance: false,
+            limit: None,
+            max_key_ranges: DEFAULT_MAX_KEY_RANGES,
+            access_policy,
+            _view: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the results ordered from nearest to farthest from the center
+    /// of the area. By default, results are returned in key order.
+    pub const fn sorted_by_distance(mut self) -> Self {
+        self.sorted_by_distance = true;
+        self
+    }
+
+    /// Limits the number of results returned. The limit is applied after
+    /// sorting.
+    pub const fn limit(mut self, maximum_results: usize) -> Self {
+        self.limit = Some(maximum_results);
+        self
+    }
+
+    /// Sets the maximum number of key ranges to query. The default is
+    /// [`DEFAULT_MAX_KEY_RANGES`].
+    pub const fn with_max_key_ranges(mut self, max_key_ranges: usize) -> Self {
+        self.max_key_ranges = max_key_ranges;
+        self
+    }
+
+    /// Sets the access policy for the query.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Executes the query and returns the mappings within the area.
+    pub async fn query(self) -> Result<ViewMappings<V>, Error> {
+        let mut mappings = Vec::new();
+        for range in self.area.key_ranges(self.max_key_ranges) {
+            mappings.extend(
+                self.connection
+                    .view::<V>()
+                    .with_access_policy(self.access_policy)
+                    .with_key_range::<GeoPoint, _>(range)
+                    .query()
+                    .await?,
+            );
+        }
+        Ok(finish_geo_query(
+            &self.area,
+            mappings,
+            self.sorted_by_distance,
+            self.limit,
+        ))
+    }
+}
+
+fn finish_geo_query<V: SerializedView<Key = GeoPoint>>(
+    area: &GeoArea,
+    mut mappings: ViewMappings<V>,
+    sorted_by_distance: bool,
+    limit: Option<usize>,
+) -> ViewMappings<V> {
+    mappings.retain(|mapping| area.contains(&mapping.key));
+    if sorted_by_distance {
+        let center = area.center();
+        let mut by_distance = mappings
+            .into_iter()
+            .map(|mapping| (center.distance_to(&mapping.key), mapping))
+            .collect::<Vec<_>>();
+        by_distance.sort_by(|(left, _), (right, _)| left.total_cmp(right));
+        mappings = by_distance
+            .into_iter()
+            .map(|(_, mapping)| mapping)
+            .collect();
+    }
+    if let Some(limit) = limit {
+        mappings.truncate(limit);
+    }
+    mappings
+}
+
+#[test]
+fn geo_point_encoding_tests() {
+    for point in [
+        GeoPoint::new(0., 0.),
+        GeoPoint::new(-90., -180.),
+        GeoPoint::new(90., 179.999_999),
+        GeoPoint::new(51.5074, -0.1278),
+        GeoPoint::new(-33.8688, 151.2093),
+    ] {
+        let decoded =
+            GeoPoint::from_ord_bytes(ByteSource::Borrowed(&point.as_ord_bytes().unwrap())).unwrap();
+        assert!(
+            decoded.distance_to(&point) < 0.02,
+            "{point:?} != {decoded:?}"
+        );
+        assert_eq!(
+            decoded.as_ord_bytes().unwrap(),
+            point.as_ord_bytes().unwrap()
+        );
+    }
+
+    assert_eq!(GeoPoint::new(100., 190.), GeoPoint::new(90., -170.));
+    assert_eq!(GeoPoint::new(0., -540.), GeoPoint::new(0., -180.));
+}
+
+#[test]
+fn geo_key_range_tests() {
+    let points = (0..=256)
+        .flat_map(|latitude| {
+            (0..512).map(move |longitude| {
+                GeoPoint::new(
+                    f64::from(latitude) * 0.703 - 90.,
+                    f64::from(longitude) * 0.703 - 180.,
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    let areas = [
+        GeoArea::from(GeoBoundingBox::new(
+            GeoPoint::new(-10., -20.),
+            GeoPoint::new(30., 15.),
+        )),
+        // Crosses the antimeridian.
+        GeoArea::from(GeoBoundingBox::new(
+            GeoPoint::new(40., 170.),
+            GeoPoint::new(60., -160.),
+        )),
+        GeoArea::radius(GeoPoint::new(48.8566, 2.3522), 1_000_000.),
+        // Contains the north pole.
+        GeoArea::radius(GeoPoint::new(85., 0.), 1_000_000.),
+        GeoArea::radius(GeoPoint::new(0., 179.), 500_000.),
+    ];
+    for area in areas {
+        let ranges = area.key_ranges(DEFAULT_MAX_KEY_RANGES);
+        assert!(ranges.len() <= DEFAULT_MAX_KEY_RANGES);
+        for point in &points {
+            let z_order = point.z_order();
+            let in_ranges = ranges
+                .iter()
+                .any(|range| (range.start().z_order()..=range.end().z_order()).contains(&z_order));
+            assert!(
+                !area.contains(point) || in_ranges,
+                "{point:?} in {area:?} not covered"
+            );
+        }
+    }
+}
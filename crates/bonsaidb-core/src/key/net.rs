@@ -0,0 +1,147 @@
+use std::borrow::Cow;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::key::{
+    ByteSource, CompositeKind, IncorrectByteLength, Key, KeyEncoding, KeyKind, KeyVisitor,
+};
+
+impl<'k> Key<'k> for Ipv4Addr {
+    const CAN_OWN_BYTES: bool = false;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        Ok(Self::from(<[u8; 4]>::try_from(bytes.as_ref())?))
+    }
+}
+
+impl KeyEncoding<Self> for Ipv4Addr {
+    type Error = IncorrectByteLength;
+
+    const LENGTH: Option<usize> = Some(4);
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(
+            CompositeKind::Struct(Cow::Borrowed("std::net::Ipv4Addr")),
+            1,
+        );
+        visitor.visit_type(KeyKind::U32);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        Ok(Cow::Owned(self.octets().to_vec()))
+    }
+}
+
+impl<'k> Key<'k> for Ipv6Addr {
+    const CAN_OWN_BYTES: bool = false;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        Ok(Self::from(<[u8; 16]>::try_from(bytes.as_ref())?))
+    }
+}
+
+impl KeyEncoding<Self> for Ipv6Addr {
+    type Error = IncorrectByteLength;
+
+    const LENGTH: Option<usize> = Some(16);
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(
+            CompositeKind::Struct(Cow::Borrowed("std::net::Ipv6Addr")),
+            1,
+        );
+        visitor.visit_type(KeyKind::U128);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        Ok(Cow::Owned(self.octets().to_vec()))
+    }
+}
+
+/// Encodes the address as a byte containing the IP version, followed by the
+/// address's octets. All IPv4 addresses sort before IPv6 addresses, matching
+/// `IpAddr`'s `Ord` implementation.
+impl<'k> Key<'k> for IpAddr {
+    const CAN_OWN_BYTES: bool = false;
+
+    fn from_ord_bytes<'e>(bytes: ByteSource<'k, 'e>) -> Result<Self, Self::Error> {
+        match bytes.as_ref() {
+            [4, octets @ ..] => Ok(Self::V4(Ipv4Addr::from(<[u8; 4]>::try_from(octets)?))),
+            [6, octets @ ..] => Ok(Self::V6(Ipv6Addr::from(<[u8; 16]>::try_from(octets)?))),
+            _ => Err(IncorrectByteLength),
+        }
+    }
+}
+
+impl KeyEncoding<Self> for IpAddr {
+    type Error = IncorrectByteLength;
+
+    const LENGTH: Option<usize> = None;
+
+    fn describe<Visitor>(visitor: &mut Visitor)
+    where
+        Visitor: KeyVisitor,
+    {
+        visitor.visit_composite(CompositeKind::Struct(Cow::Borrowed("std::net::IpAddr")), 1);
+        visitor.visit_type(KeyKind::Bytes);
+    }
+
+    fn as_ord_bytes(&self) -> Result<Cow<'_, [u8]>, Self::Error> {
+        let mut bytes = Vec::with_capacity(17);
+        match self {
+            IpAddr::V4(address) => {
+                bytes.push(4);
+                bytes.extend_from_slice(&address.octets());
+            }
+            IpAddr::V6(address) => {
+                bytes.push(6);
+                bytes.extend_from_slice(&address.octets());
+            }
+        }
+        Ok(Cow::Owned(bytes))
+    }
+}
+
+#[test]
+fn ip_addr_tests() -> anyhow::Result<()> {
+    let addresses = [
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+        IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+        IpAddr::V4(Ipv4Addr::BROADCAST),
+        IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        IpAddr::V6(Ipv6Addr::LOCALHOST),
+        IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)),
+    ];
+    let encoded = addresses
+        .iter()
+        .map(|address| address.as_ord_bytes().map(|bytes| bytes.to_vec()))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (address, encoded) in addresses.iter().zip(&encoded) {
+        assert_eq!(
+            &IpAddr::from_ord_bytes(ByteSource::Borrowed(encoded))?,
+            address
+        );
+    }
+    for pair in encoded.windows(2) {
+        assert!(pair[0] < pair[1]);
+    }
+
+    let v4 = Ipv4Addr::new(192, 168, 1, 1);
+    assert_eq!(
+        Ipv4Addr::from_ord_bytes(ByteSource::Borrowed(&v4.as_ord_bytes()?))?,
+        v4
+    );
+    let v6 = Ipv6Addr::LOCALHOST;
+    assert_eq!(
+        Ipv6Addr::from_ord_bytes(ByteSource::Borrowed(&v6.as_ord_bytes()?))?,
+        v6
+    );
+
+    Ok(())
+}
@@ -93,6 +93,35 @@ pub enum Error {
     #[error("view '{0}' was already registered")]
     ViewAlreadyRegistered(ViewName),
 
+    /// The view has an eager update policy and
+    /// [links other collections](schema::ViewSchema::linked_collections),
+    /// which is not supported.
+    #[error("view '{0}' is eagerly updated and can't link other collections")]
+    EagerViewLinksCollections(ViewName),
+
+    /// The view [links a collection](schema::ViewSchema::linked_collections)
+    /// whose primary key type is different than the primary key type of the
+    /// view's collection.
+    #[error(
+        "view '{view}' links collection '{collection}', which has a different primary key type"
+    )]
+    LinkedCollectionKeyMismatch {
+        /// The name of the view.
+        view: ViewName,
+        /// The name of the linked collection.
+        collection: CollectionName,
+    },
+
+    /// The view [links a collection](schema::ViewSchema::linked_collections)
+    /// that isn't part of the schema.
+    #[error("view '{view}' links collection '{collection}', which was not found")]
+    LinkedCollectionNotFound {
+        /// The name of the view.
+        view: ViewName,
+        /// The name of the linked collection.
+        collection: CollectionName,
+    },
+
     /// An invalid database name was specified. See
     /// [`StorageConnection::create_database()`](connection::StorageConnection::create_database)
     /// for database name requirements.
@@ -156,10 +185,51 @@ pub enum Error {
         existing_document: Box<Header>,
     },
 
+    /// A document refers to a document through a
+    /// [`Reference`](schema::Reference) that does not exist.
+    #[error(
+        "document {document} refers to {parent_id} from collection {parent}, which was not found"
+    )]
+    ReferencedDocumentNotFound {
+        /// The document that contains the reference.
+        document: Box<Header>,
+        /// The collection of the document being referred to.
+        parent: CollectionName,
+        /// The id of the document being referred to.
+        parent_id: Box<DocumentId>,
+    },
+
+    /// A document could not be deleted because it is still referred to by
+    /// another document through a [`Reference`](schema::Reference) using
+    /// [`OnDelete::Restrict`](schema::OnDelete::Restrict).
+    #[error("document {document} is still referred to by {referencing_document} through {view}")]
+    DocumentStillReferenced {
+        /// The name of the view indexing the reference.
+        view: ViewName,
+        /// The document being deleted.
+        document: Box<Header>,
+        /// The document that refers to the document being deleted.
+        referencing_document: Box<Header>,
+    },
+
     /// When pushing a document, an error occurred while generating the next unique id.
     #[error("an error occurred generating a new unique id for {0}: {1}")]
     DocumentPush(CollectionName, NextValueError),
 
+    /// A full-text search query could not be parsed.
+    #[error("invalid text query: {0}")]
+    InvalidTextQuery(String),
+
+    /// A vector did not have the number of dimensions required by its
+    /// [`VectorIndex`](schema::vector::VectorIndex).
+    #[error("expected a vector with {expected} dimensions, but it had {actual}")]
+    InvalidVectorDimensions {
+        /// The number of dimensions the index requires.
+        expected: usize,
+        /// The number of dimensions the vector had.
+        actual: usize,
+    },
+
     /// An invalid name was specified during schema creation.
     #[error("an invalid name was used in a schema: {0}")]
     InvalidName(#[from] schema::InvalidNameError),
@@ -190,6 +260,16 @@ pub enum Error {
     #[error("reduce is unimplemented")]
     ReduceUnimplemented,
 
+    /// A [`Reducer`](schema::view::reducers::Reducer) that requires at least
+    /// one value was asked to reduce no values.
+    #[error("no values to reduce")]
+    NothingToReduce,
+
+    /// A [`Reducer`](schema::view::reducers::Reducer) produced a value that
+    /// overflowed its type.
+    #[error("reduced value overflowed")]
+    ReduceOverflow,
+
     /// A floating point operation yielded Not a Number.
     #[error("floating point operation yielded NaN")]
     NotANumber,
@@ -1,13 +1,17 @@
 use arc_bytes::serde::Bytes;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use crate::api::{Api, ApiName};
 use crate::connection::{
-    AccessPolicy, Database, IdentityReference, Range, SerializedQueryKey, Session, SessionId, Sort,
+    AccessPolicy, Database, IdentityReference, Range, SerializedDocumentFilter, SerializedQueryKey,
+    Session, SessionId, Sort,
 };
 use crate::document::{DocumentId, Header, OwnedDocument};
 use crate::keyvalue::{KeyOperation, Output};
+use crate::schema::dynamic::DynamicCollection;
 use crate::schema::view::map::{self, MappedSerializedDocuments};
+use crate::schema::view::ViewStatus;
 use crate::schema::{CollectionName, NamedReference, Qualified, SchemaSummary, ViewName};
 use crate::transaction::{Executed, OperationResult, Transaction};
 
@@ -27,6 +31,178 @@ pub struct Payload {
     pub value: Result<Bytes, crate::Error>,
 }
 
+/// The encoding used for the [`Payload::value`] of requests and responses.
+///
+/// Connections using [`WebSocketFormat::Json`] encode values as JSON, allowing
+/// clients that do not implement `Pot` to produce and consume them. All other
+/// connections encode values using `Pot`.
+#[derive(Default, Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum ValueEncoding {
+    /// Values are encoded using `Pot`.
+    #[default]
+    Pot,
+    /// Values are encoded as JSON.
+    #[cfg(feature = "websockets")]
+    Json,
+}
+
+impl ValueEncoding {
+    /// Encodes `value` using this encoding.
+    pub fn serialize<T: Serialize>(self, value: &T) -> Result<Bytes, crate::Error> {
+        match self {
+            Self::Pot => Ok(Bytes::from(pot::to_vec(value)?)),
+            #[cfg(feature = "websockets")]
+            Self::Json => serde_json::to_vec(value)
+                .map(Bytes::from)
+                .map_err(|err| crate::Error::other("json", err)),
+        }
+    }
+
+    /// Decodes a value encoded using this encoding.
+    pub fn deserialize<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, crate::Error> {
+        match self {
+            Self::Pot => Ok(pot::from_slice(bytes)?),
+            #[cfg(feature = "websockets")]
+            Self::Json => {
+                serde_json::from_slice(bytes).map_err(|err| crate::Error::other("json", err))
+            }
+        }
+    }
+}
+
+/// The encoding used for [`Payload`]s sent over a `WebSocket` connection.
+///
+/// The format is negotiated using the `Sec-WebSocket-Protocol` header. Each
+/// subprotocol is the protocol version optionally followed by `+` and the
+/// format's suffix, e.g., `bonsai-pre-1+json`. A subprotocol without a suffix
+/// uses [`WebSocketFormat::Bincode`].
+#[cfg(feature = "websockets")]
+#[derive(Default, Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum WebSocketFormat {
+    /// Payloads are encoded using `bincode` and sent as binary messages.
+    #[default]
+    Bincode,
+    /// Payloads are encoded using the self-describing `Pot` format and sent as
+    /// binary messages.
+    Pot,
+    /// Payloads are encoded as JSON and sent as text messages. The payload's
+    /// value contains the JSON representation of the [`Api`] request or
+    /// response. See [`Payload::to_json()`] for the structure of each message.
+    Json,
+}
+
+#[cfg(feature = "websockets")]
+impl WebSocketFormat {
+    /// Returns the suffix appended to the protocol version to request this
+    /// format, if any.
+    #[must_use]
+    pub const fn suffix(self) -> Option<&'static str> {
+        match self {
+            Self::Bincode => None,
+            Self::Pot => Some("pot"),
+            Self::Json => Some("json"),
+        }
+    }
+
+    /// Returns the `WebSocket` subprotocol requesting this format for
+    /// `protocol_version`.
+    #[must_use]
+    pub fn subprotocol(self, protocol_version: &str) -> String {
+        match self.suffix() {
+            Some(suffix) => format!("{protocol_version}+{suffix}"),
+            None => protocol_version.to_string(),
+        }
+    }
+
+    /// Parses `subprotocol` into its protocol version and format. Returns
+    /// `None` if the format suffix is not recognized.
+    #[must_use]
+    pub fn parse_subprotocol(subprotocol: &str) -> Option<(&str, Self)> {
+        match subprotocol.split_once('+') {
+            Some((version, "pot")) => Some((version, Self::Pot)),
+            Some((version, "json")) => Some((version, Self::Json)),
+            Some(_) => None,
+            None => Some((subprotocol, Self::Bincode)),
+        }
+    }
+
+    /// Returns the first subprotocol in the comma-separated list of
+    /// `subprotocols` that requests [`CURRENT_PROTOCOL_VERSION`], along with
+    /// the format it requests.
+    #[must_use]
+    pub fn negotiate(subprotocols: &str) -> Option<(&str, Self)> {
+        subprotocols
+            .split(',')
+            .map(str::trim)
+            .find_map(|subprotocol| {
+                Self::parse_subprotocol(subprotocol)
+                    .filter(|(version, _)| *version == CURRENT_PROTOCOL_VERSION)
+                    .map(|(_, format)| (subprotocol, format))
+            })
+    }
+
+    /// Returns the [`ValueEncoding`] used for payload values sent in this
+    /// format.
+    #[must_use]
+    pub const fn value_encoding(self) -> ValueEncoding {
+        match self {
+            Self::Bincode | Self::Pot => ValueEncoding::Pot,
+            Self::Json => ValueEncoding::Json,
+        }
+    }
+}
+
+#[cfg(feature = "websockets")]
+impl Payload {
+    /// Returns the JSON representation of this payload. The payload's value
+    /// must have been encoded using [`ValueEncoding::Json`].
+    ///
+    /// The payload is represented as an object containing `session_id`, `id`,
+    /// `name`, and `value` fields. The value is either `{"Ok": value}`,
+    /// containing the JSON-encoded request or response, or `{"Err": error}`.
+    pub fn to_json(&self) -> Result<String, crate::Error> {
+        let value = match &self.value {
+            Ok(value) => Ok(serde_json::from_slice::<serde_json::Value>(value)
+                .map_err(|err| crate::Error::other("json", err))?),
+            Err(err) => Err(err.clone()),
+        };
+        serde_json::to_string(&JsonPayload {
+            session_id: self.session_id,
+            id: self.id,
+            name: self.name.clone(),
+            value,
+        })
+        .map_err(|err| crate::Error::other("json", err))
+    }
+
+    /// Parses a payload from its JSON representation. The returned payload's
+    /// value is encoded using [`ValueEncoding::Json`].
+    pub fn from_json(json: &str) -> Result<Self, crate::Error> {
+        let payload = serde_json::from_str::<JsonPayload>(json)
+            .map_err(|err| crate::Error::other("json", err))?;
+        let value = match payload.value {
+            Ok(value) => Ok(ValueEncoding::Json.serialize(&value)?),
+            Err(err) => Err(err),
+        };
+        Ok(Self {
+            session_id: payload.session_id,
+            id: payload.id,
+            name: payload.name,
+            value,
+        })
+    }
+}
+
+/// The JSON representation of a [`Payload`].
+#[cfg(feature = "websockets")]
+#[derive(Deserialize, Serialize)]
+struct JsonPayload {
+    session_id: Option<SessionId>,
+    id: Option<u32>,
+    name: ApiName,
+    value: Result<serde_json::Value, crate::Error>,
+}
+
 /// Creates a database.
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct CreateDatabase {
@@ -387,7 +563,13 @@ impl Api for Reduce {
 
 /// Reduces a view, grouping the reduced values by key.
 #[derive(Clone, Deserialize, Serialize, Debug)]
-pub struct ReduceGrouped(pub Reduce);
+pub struct ReduceGrouped {
+    /// The reduce query.
+    pub reduce: Reduce,
+    /// If provided, the reduced values are grouped by the first `group_level`
+    /// fields of the view's composite key instead of by the entire key.
+    pub group_level: Option<usize>,
+}
 
 impl Api for ReduceGrouped {
     type Error = crate::Error;
@@ -420,6 +602,82 @@ impl Api for DeleteDocs {
     }
 }
 
+/// Retrieves the documents matching a filter of one or more views.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct FilterDocuments {
+    /// The name of the database.
+    pub database: String,
+    /// The collection of the documents.
+    pub collection: CollectionName,
+    /// The filter to match documents with.
+    pub filter: SerializedDocumentFilter,
+    /// The order for the documents, by id.
+    pub order: Sort,
+    /// The maximum number of results to return.
+    pub limit: Option<u32>,
+    /// The access policy for the view queries.
+    pub access_policy: AccessPolicy,
+}
+
+impl Api for FilterDocuments {
+    type Error = crate::Error;
+    type Response = Vec<OwnedDocument>;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "FilterDocuments")
+    }
+}
+
+/// Retrieves the headers of the documents matching a filter of one or more
+/// views.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct FilterHeaders(pub FilterDocuments);
+
+impl Api for FilterHeaders {
+    type Error = crate::Error;
+    type Response = Vec<Header>;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "FilterHeaders")
+    }
+}
+
+/// Retrieves the indexing status of a view.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct GetViewStatus {
+    /// The name of the database.
+    pub database: String,
+    /// The name of the view.
+    pub view: ViewName,
+}
+
+impl Api for GetViewStatus {
+    type Error = crate::Error;
+    type Response = ViewStatus;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "GetViewStatus")
+    }
+}
+
+/// Defines a collection at runtime.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct DefineDynamicCollection {
+    /// The name of the database.
+    pub database: String,
+    /// The definition of the collection.
+    pub collection: DynamicCollection,
+}
+
+impl Api for DefineDynamicCollection {
+    type Error = crate::Error;
+    type Response = ();
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "DefineDynamicCollection")
+    }
+}
+
 /// Applies a transaction.
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct ApplyTransaction {
@@ -696,3 +954,29 @@ pub enum Error {
     #[error("unexpected disconnection")]
     Disconnected,
 }
+
+#[test]
+#[cfg(feature = "websockets")]
+fn json_payloads() {
+    let request = Get {
+        database: String::from("db"),
+        collection: CollectionName::new("khonsulabs", "basic"),
+        id: DocumentId::from_u64(1),
+    };
+    let payload = Payload {
+        session_id: None,
+        id: Some(1),
+        name: Get::name(),
+        value: Ok(ValueEncoding::Json.serialize(&request).unwrap()),
+    };
+    let json = payload.to_json().unwrap();
+    let parsed = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert_eq!(parsed["value"]["Ok"]["database"], "db");
+
+    let decoded = Payload::from_json(&json).unwrap();
+    let decoded = ValueEncoding::Json
+        .deserialize::<Get>(decoded.value.as_ref().unwrap())
+        .unwrap();
+    assert_eq!(decoded.collection, request.collection);
+    assert_eq!(decoded.id, request.id);
+}
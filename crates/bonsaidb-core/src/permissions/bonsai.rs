@@ -153,6 +153,11 @@ pub enum ServerAction {
 pub enum DatabaseAction {
     /// The ability to compact data to reclaim space.
     Compact,
+    /// Allows defining collections at runtime through
+    /// [`Connection::define_dynamic_collection()`](crate::connection::LowLevelConnection::define_dynamic_collection).
+    /// See [`collection_resource_name()`] for the format of collection
+    /// resource names.
+    DefineCollection,
     /// Actions that operate on a document.
     Document(DocumentAction),
     /// Actions that operate on a view.
@@ -226,6 +231,10 @@ pub enum ViewAction {
     /// [`Connection::delete_docs()`](crate::connection::LowLevelConnection::delete_docs).
     /// See [`view_resource_name`] for the format of view resource names.
     DeleteDocs,
+    /// Allows retrieving the indexing status of a view with
+    /// [`Connection::view_status()`](crate::connection::LowLevelConnection::view_status).
+    /// See [`view_resource_name`] for the format of view resource names.
+    Status,
 }
 
 /// Actions that operate on transactions.
@@ -1,7 +1,13 @@
 mod collection;
+pub mod dynamic;
+mod id_generator;
+mod map_pairs;
 mod names;
+mod reference;
 mod schematic;
 mod summary;
+pub mod text;
+pub mod vector;
 /// Types for defining map/reduce-powered `View`s.
 pub mod view;
 
@@ -9,12 +15,21 @@ pub use bonsaidb_macros::{Collection, Schema, View, ViewSchema};
 
 pub use self::collection::{
     AsyncEntry, AsyncList, Collection, DefaultSerialization, Entry, EntryInsert, EntryUpdate,
-    InsertError, List, Nameable, NamedCollection, NamedReference, SerializedCollection,
+    InsertError, List, NameKey, NameView, Nameable, NamedCollection, NamedReference,
+    SerializedCollection,
+};
+#[cfg(feature = "ulid")]
+pub use self::id_generator::UlidGenerator;
+#[cfg(feature = "uuid")]
+pub use self::id_generator::UuidV7Generator;
+pub use self::id_generator::{
+    IdGenerator, IdGeneratorContext, IdGeneratorFor, KeyIdGenerator, SnowflakeGenerator,
 };
 pub use self::names::{
     Authority, CollectionName, InvalidNameError, Name, Qualified, QualifiedName, SchemaName,
     ViewName,
 };
+pub use self::reference::{OnDelete, Reference, ReferenceView, SerializedReference};
 pub use self::schematic::Schematic;
 pub use self::summary::{CollectionSummary, SchemaSummary, ViewSummary};
 pub use self::view::map::{Map, MappedValue, ViewMappedValue};
@@ -11,13 +11,15 @@ use serde::{Deserialize, Serialize};
 use transmog::{Format, OwnedDeserializer};
 use transmog_pot::Pot;
 
-use crate::connection::{self, AsyncConnection, Connection, RangeRef};
+use crate::connection::{
+    self, AsyncConnection, Connection, DocumentIter, DocumentStream, RangeRef,
+};
 use crate::document::{
     BorrowedDocument, CollectionDocument, CollectionHeader, Document, DocumentId, Header, KeyId,
     OwnedDocument, OwnedDocuments, Revision,
 };
 use crate::key::{IntoPrefixRange, Key, KeyEncoding};
-use crate::schema::{CollectionName, Schematic};
+use crate::schema::{CollectionName, IdGenerator, KeyIdGenerator, Schematic, SerializedView};
 use crate::transaction::{Operation, OperationResult, Transaction};
 use crate::Error;
 
@@ -93,6 +95,10 @@ use crate::Error;
 /// # }
 /// ```
 ///
+/// Similarly, [`Reference`](crate::schema::Reference)s to other collections
+/// can be specified using the `references` parameter, e.g.,
+/// `#[collection(name = "line-items", references = [LineItemOrder])]`.
+///
 /// ### Selecting a Primary Key type
 ///
 /// By default, the `#[collection]` macro will use `u64` for the
@@ -145,6 +151,25 @@ use crate::Error;
 /// the contents must be inserted at the new id and deleted from the previous
 /// id.
 ///
+/// When a document without a natural id is pushed, its id is created by the
+/// collection's [`IdGenerator`]. By default, ids are assigned sequentially
+/// using [`Key::next_value()`]. The `id_generator` parameter can be used to
+/// select a different generator, such as
+/// [`SnowflakeGenerator`](crate::schema::SnowflakeGenerator), which creates
+/// time-ordered ids that are unique across storage instances. The generator
+/// must implement [`IdGeneratorFor`](crate::schema::IdGeneratorFor) for the
+/// collection's primary key:
+///
+/// ```rust
+/// use bonsaidb_core::schema::{Collection, SnowflakeGenerator};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Default, Collection)]
+/// #[collection(name = "MyCollection", id_generator = SnowflakeGenerator)]
+/// # #[collection(core = bonsaidb_core)]
+/// pub struct MyCollection;
+/// ```
+///
 /// [natural-key]: https://en.wikipedia.org/wiki/Natural_key
 ///
 ///
@@ -252,6 +277,18 @@ pub trait Collection: Send + Sync {
     fn encryption_key() -> Option<KeyId> {
         None
     }
+
+    /// Returns the [`IdGenerator`] used to create ids for documents that are
+    /// pushed without an id. The default implementation returns a
+    /// [`KeyIdGenerator`], which uses [`Key::next_value()`] to assign
+    /// sequential ids.
+    #[must_use]
+    fn id_generator() -> Box<dyn IdGenerator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::<KeyIdGenerator<Self>>::default()
+    }
 }
 
 /// A collection that knows how to serialize and deserialize documents to an associated type.
@@ -1349,7 +1386,7 @@ pub struct InsertError<T> {
 #[async_trait]
 pub trait NamedCollection: Collection + Unpin {
     /// The name view defined for the collection.
-    type ByNameView: crate::schema::SerializedView<Key = String, Collection = Self>;
+    type ByNameView: NameView<Collection = Self>;
 
     /// Gets a [`CollectionDocument`] with `id` from `connection`.
     fn load<'name, N: Nameable<'name, Self::PrimaryKey> + Send + Sync, C: Connection>(
@@ -1449,7 +1486,7 @@ pub trait NamedCollection: Collection + Unpin {
             NamedReference::Key(id) => connection.collection::<Self>().get(&id),
             NamedReference::Name(name) => Ok(connection
                 .view::<Self::ByNameView>()
-                .with_key(name.as_ref())
+                .with_key(&Self::ByNameView::name_key(&name))
                 .query_with_docs()?
                 .documents
                 .into_iter()
@@ -1476,7 +1513,7 @@ pub trait NamedCollection: Collection + Unpin {
             NamedReference::Key(id) => connection.collection::<Self>().get(&id).await,
             NamedReference::Name(name) => Ok(connection
                 .view::<Self::ByNameView>()
-                .with_key(name.as_ref())
+                .with_key(&Self::ByNameView::name_key(&name))
                 .query_with_docs()
                 .await?
                 .documents
@@ -1493,7 +1530,7 @@ pub trait NamedCollection: Collection + Unpin {
     {
         Ok(connection
             .view::<Self::ByNameView>()
-            .with_key(name)
+            .with_key(&Self::ByNameView::name_key(name))
             .delete_docs()?
             > 0)
     }
@@ -1508,13 +1545,48 @@ pub trait NamedCollection: Collection + Unpin {
     {
         Ok(connection
             .view::<Self::ByNameView>()
-            .with_key(name)
+            .with_key(&Self::ByNameView::name_key(name))
             .delete_docs()
             .await?
             > 0)
     }
 }
 
+/// A [`View`](crate::schema::View) that can be used as a
+/// [`NamedCollection::ByNameView`]. This trait is automatically implemented for
+/// all views whose key implements [`NameKey`].
+pub trait NameView: SerializedView {
+    /// Returns the key to query this view with to find `name`.
+    fn name_key(name: &str) -> Self::Key;
+}
+
+impl<V> NameView for V
+where
+    V: SerializedView,
+    V::Key: NameKey,
+{
+    fn name_key(name: &str) -> Self::Key {
+        V::Key::from_name(name)
+    }
+}
+
+/// A [`Key`] type that can be used as the key of a
+/// [`NamedCollection::ByNameView`].
+///
+/// This is implemented for [`String`] for exact matching, and for
+/// [`Collated`](crate::key::Collated) for matching names using a
+/// [`Collation`](crate::key::Collation), such as case-insensitive names.
+pub trait NameKey: for<'k> Key<'k> {
+    /// Returns the key for `name`.
+    fn from_name(name: &str) -> Self;
+}
+
+impl NameKey for String {
+    fn from_name(name: &str) -> Self {
+        name.to_string()
+    }
+}
+
 /// A reference to a collection that has a unique name view.
 #[derive(Clone, Eq, PartialEq, Deserialize, Serialize, Debug)]
 #[must_use]
@@ -1664,7 +1736,7 @@ where
         match self {
             Self::Name(name) => connection
                 .view::<Col::ByNameView>()
-                .with_key(name.as_ref())
+                .with_key(&Col::ByNameView::name_key(name))
                 .query()?
                 .into_iter()
                 .next()
@@ -1684,7 +1756,7 @@ where
         match self {
             Self::Name(name) => connection
                 .view::<Col::ByNameView>()
-                .with_key(name.as_ref())
+                .with_key(&Col::ByNameView::name_key(name))
                 .query()
                 .await?
                 .into_iter()
@@ -2151,6 +2223,28 @@ where
     pub fn query(self) -> Result<Vec<CollectionDocument<Cl>>, Error> {
         self.0.query().and_then(|docs| docs.collection_documents())
     }
+
+    /// Returns an iterator over the documents, using the configured options.
+    /// Rather than retrieving all of the documents at once, the iterator
+    /// retrieves [`DEFAULT_BATCH_SIZE`](connection::DEFAULT_BATCH_SIZE)
+    /// documents at a time. This can be changed using
+    /// [`DocumentIter::batch_size()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// for doc in MyCollection::all(db).iter()?.batch_size(100) {
+    ///     let doc = doc?;
+    ///     println!("Retrieved #{} with contents {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter(self) -> Result<DocumentIter<'a, Cn, CollectionDocument<Cl>>, Error> {
+        self.0
+            .iter_converting(|doc| CollectionDocument::try_from(&doc))
+    }
 }
 
 /// Retrieves a list of documents from a collection, when awaited. This
@@ -2234,6 +2328,35 @@ where
     pub async fn headers(self) -> Result<Vec<Header>, Error> {
         self.0.headers().await
     }
+
+    /// Returns a stream of the documents, using the configured options.
+    /// Rather than retrieving all of the documents at once, the stream
+    /// retrieves [`DEFAULT_BATCH_SIZE`](connection::DEFAULT_BATCH_SIZE)
+    /// documents at a time. This can be changed using
+    /// [`DocumentStream::batch_size()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use futures::StreamExt;
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut docs = MyCollection::all_async(db).stream()?;
+    /// while let Some(doc) = docs.next().await {
+    ///     let doc = doc?;
+    ///     println!("Retrieved #{} with contents {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn stream(self) -> Result<DocumentStream<'a, Cn, CollectionDocument<Cl>>, Error>
+    where
+        Cl: SerializedCollection,
+    {
+        self.0
+            .stream_converting(|doc| CollectionDocument::try_from(&doc))
+    }
 }
 
 #[allow(clippy::type_repetition_in_bounds)]
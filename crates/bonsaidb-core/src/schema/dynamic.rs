@@ -0,0 +1,738 @@
+//! Collections and indexes defined at runtime.
+//!
+//! A [`DynamicCollection`] is a collection identified only by its
+//! [`CollectionName`]. Its documents are self-describing values stored using a
+//! [`DocumentFormat`], such as JSON. Because no Rust type describes the
+//! documents, the collection's indexes are defined declaratively: each
+//! [`DynamicIndex`] lists the paths of the fields that make up its key.
+//!
+//! Each index is stored as a view named using [`DynamicIndex::view_name()`],
+//! which means it is updated and persisted the same way any other view is.
+//! Documents and indexes are accessed using the `*_by_name` functions of
+//! [`LowLevelConnection`](crate::connection::LowLevelConnection) and
+//! [`AsyncLowLevelConnection`](crate::connection::AsyncLowLevelConnection).
+//!
+//! ```rust
+//! use bonsaidb_core::schema::dynamic::{DocumentFormat, DynamicCollection, DynamicIndex, FieldKind};
+//! use bonsaidb_core::schema::{CollectionName, Qualified};
+//!
+//! let users = DynamicCollection::new(CollectionName::private("users"), DocumentFormat::Json)
+//!     .with_index(
+//!         DynamicIndex::new("by-email")
+//!             .field("contact.email", FieldKind::String)
+//!             .unique(),
+//!     );
+//! let by_email = users.index_view_name("by-email").unwrap();
+//! assert_eq!(by_email.to_string(), "private.users.by-email");
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::{self, Formatter};
+
+use arc_bytes::serde::Bytes;
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::document::{BorrowedDocument, DocumentId};
+use crate::key::{
+    ByteSource, CompositeKeyDecoder, CompositeKeyDescription, CompositeKeyEncoder,
+    CompositeKeyError, CompositeKind, Key, KeyDescription, KeyEncoding,
+};
+use crate::schema::view::map::{self, MappedValue};
+use crate::schema::view::reducers::{Count, Reducer};
+use crate::schema::view::{self, Serialized, ViewProjection, ViewUpdatePolicy};
+use crate::schema::{CollectionName, IdGenerator, IdGeneratorContext, Name, ViewName};
+use crate::Error;
+
+/// A collection that is defined at runtime.
+///
+/// Dynamic collections use `u64` primary keys. Documents pushed without an id
+/// are assigned the next id after the largest id stored in the collection.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DynamicCollection {
+    /// The name of the collection.
+    pub name: CollectionName,
+    /// The format the collection's documents are stored in.
+    pub format: DocumentFormat,
+    /// The indexes of the collection's documents.
+    pub indexes: Vec<DynamicIndex>,
+}
+
+impl DynamicCollection {
+    /// Returns a new collection named `name` without any indexes.
+    #[must_use]
+    pub const fn new(name: CollectionName, format: DocumentFormat) -> Self {
+        Self {
+            name,
+            format,
+            indexes: Vec::new(),
+        }
+    }
+
+    /// Adds `index` to this collection and returns self.
+    #[must_use]
+    pub fn with_index(mut self, index: DynamicIndex) -> Self {
+        self.indexes.push(index);
+        self
+    }
+
+    /// Returns the name of the view that stores the index named `index`, or
+    /// `None` if this collection has no index with that name.
+    #[must_use]
+    pub fn index_view_name(&self, index: &str) -> Option<ViewName> {
+        self.indexes
+            .iter()
+            .find(|candidate| AsRef::<str>::as_ref(&candidate.name) == index)
+            .map(|index| index.view_name(&self.name))
+    }
+
+    /// Validates this definition, returning an error if an index has no fields
+    /// or if multiple indexes share the same name. Collections using
+    /// [`DocumentFormat::Json`] require the `json` feature.
+    pub fn validate(&self) -> Result<(), Error> {
+        #[cfg(not(feature = "json"))]
+        if self.format == DocumentFormat::Json {
+            return Err(DocumentFormat::json_disabled());
+        }
+
+        let mut names = HashSet::new();
+        for index in &self.indexes {
+            if !names.insert(&index.name) {
+                return Err(Error::ViewAlreadyRegistered(index.view_name(&self.name)));
+            }
+            if index.fields.is_empty() {
+                return Err(Error::other(
+                    "dynamic-collection",
+                    format!("index {} does not contain any fields", index.name),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an error if `contents` can't be deserialized using this
+    /// collection's [`DocumentFormat`].
+    pub fn validate_contents(&self, contents: &[u8]) -> Result<(), Error> {
+        self.format.deserialize::<DocumentValue>(contents)?;
+        Ok(())
+    }
+
+    pub(crate) fn views(&self) -> impl Iterator<Item = DynamicIndexView> + '_ {
+        self.indexes
+            .iter()
+            .map(|index| DynamicIndexView::new(&self.name, self.format, index.clone()))
+    }
+}
+
+/// The format documents in a [`DynamicCollection`] are stored in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DocumentFormat {
+    /// Documents are stored as UTF-8 encoded JSON. Serializing JSON requires
+    /// the `json` feature.
+    Json,
+    /// Documents are stored using [Pot](https://github.com/khonsulabs/pot).
+    Pot,
+}
+
+impl DocumentFormat {
+    /// Serializes `value` using this format.
+    pub fn serialize<T: Serialize>(self, value: &T) -> Result<Vec<u8>, Error> {
+        match self {
+            #[cfg(feature = "json")]
+            Self::Json => {
+                serde_json::to_vec(value).map_err(|err| Error::other("serialization", err))
+            }
+            #[cfg(not(feature = "json"))]
+            Self::Json => Err(Self::json_disabled()),
+            Self::Pot => Ok(pot::to_vec(value)?),
+        }
+    }
+
+    /// Deserializes `bytes` using this format.
+    pub fn deserialize<'de, T: Deserialize<'de>>(self, bytes: &'de [u8]) -> Result<T, Error> {
+        match self {
+            #[cfg(feature = "json")]
+            Self::Json => {
+                serde_json::from_slice(bytes).map_err(|err| Error::other("serialization", err))
+            }
+            #[cfg(not(feature = "json"))]
+            Self::Json => Err(Self::json_disabled()),
+            Self::Pot => Ok(pot::from_slice(bytes)?),
+        }
+    }
+
+    #[cfg(not(feature = "json"))]
+    fn json_disabled() -> Error {
+        Error::other(
+            "serialization",
+            "the json feature of bonsaidb-core is required for JSON documents",
+        )
+    }
+}
+
+/// An index of a [`DynamicCollection`].
+///
+/// Each document emits a single entry whose key is made up of the values of
+/// the index's fields. Indexes with a single field use the field's key type as
+/// their key, and indexes with multiple fields use a tuple of each field's key
+/// type. Documents missing any of the fields, or containing a value that can't
+/// be converted to the field's [`FieldKind`], are not included in the index.
+///
+/// The value of each entry is a `u64` serialized using Pot, and reducing an
+/// index returns the number of matching entries.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DynamicIndex {
+    /// The name of the index, which is used as the name of its view.
+    pub name: Name,
+    /// The fields that make up the key of the index.
+    pub fields: Vec<IndexedField>,
+    /// If true, no two documents may emit the same key.
+    pub unique: bool,
+}
+
+impl DynamicIndex {
+    /// Returns a new index named `name` without any fields.
+    #[must_use]
+    pub fn new(name: impl Into<Name>) -> Self {
+        Self {
+            name: name.into(),
+            fields: Vec::new(),
+            unique: false,
+        }
+    }
+
+    /// Appends the field at `path` to this index's key and returns self.
+    /// Paths are a sequence of map keys or sequence indexes separated by
+    /// `.`, e.g., `"address.lines.0"`.
+    #[must_use]
+    pub fn field(mut self, path: impl Into<String>, kind: FieldKind) -> Self {
+        self.fields.push(IndexedField {
+            path: path.into(),
+            kind,
+        });
+        self
+    }
+
+    /// Requires that no two documents emit the same key and returns self.
+    /// Unique indexes are updated as documents are saved.
+    #[must_use]
+    pub const fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    /// Returns the name of the view that stores this index.
+    #[must_use]
+    pub fn view_name(&self, collection: &CollectionName) -> ViewName {
+        ViewName {
+            collection: collection.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+/// A field indexed by a [`DynamicIndex`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedField {
+    /// The `.`-separated path to the field.
+    pub path: String,
+    /// The type of key the field's value is encoded as.
+    pub kind: FieldKind,
+}
+
+/// The type of key an [`IndexedField`] is encoded as.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FieldKind {
+    /// A `String`. Only string values are indexed.
+    String,
+    /// An `i64`. Integer values that fit in an `i64` are indexed.
+    Integer,
+    /// An `u64`. Integer values that fit in an `u64` are indexed.
+    Unsigned,
+    /// An `f64`. All numeric values are indexed.
+    Float,
+    /// A `bool`. Only boolean values are indexed.
+    Bool,
+    /// A `Vec<u8>`. Only byte values are indexed.
+    Bytes,
+}
+
+impl FieldKind {
+    fn key_description(self) -> KeyDescription {
+        match self {
+            Self::String => KeyDescription::for_key::<String>(),
+            Self::Integer => KeyDescription::for_key::<i64>(),
+            Self::Unsigned => KeyDescription::for_key::<u64>(),
+            Self::Float => KeyDescription::for_key::<f64>(),
+            Self::Bool => KeyDescription::for_key::<bool>(),
+            Self::Bytes => KeyDescription::for_key::<Vec<u8>>(),
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn convert(self, value: &DocumentValue) -> Option<FieldValue<'_>> {
+        match (self, value) {
+            (Self::String, DocumentValue::String(value)) => {
+                Some(FieldValue::String(Cow::Borrowed(value)))
+            }
+            (Self::Integer, DocumentValue::Signed(value)) => Some(FieldValue::Integer(*value)),
+            (Self::Integer, DocumentValue::Unsigned(value)) => {
+                i64::try_from(*value).ok().map(FieldValue::Integer)
+            }
+            (Self::Unsigned, DocumentValue::Unsigned(value)) => Some(FieldValue::Unsigned(*value)),
+            (Self::Unsigned, DocumentValue::Signed(value)) => {
+                u64::try_from(*value).ok().map(FieldValue::Unsigned)
+            }
+            (Self::Float, DocumentValue::Float(value)) => Some(FieldValue::Float(*value)),
+            (Self::Float, DocumentValue::Signed(value)) => Some(FieldValue::Float(*value as f64)),
+            (Self::Float, DocumentValue::Unsigned(value)) => Some(FieldValue::Float(*value as f64)),
+            (Self::Bool, DocumentValue::Bool(value)) => Some(FieldValue::Bool(*value)),
+            (Self::Bytes, DocumentValue::Bytes(value)) => {
+                Some(FieldValue::Bytes(Cow::Borrowed(value)))
+            }
+            _ => None,
+        }
+    }
+
+    fn decode<'k>(
+        self,
+        decoder: &mut CompositeKeyDecoder<'k, '_>,
+    ) -> Result<FieldValue<'k>, CompositeKeyError> {
+        Ok(match self {
+            Self::String => FieldValue::String(Cow::Owned(decoder.decode()?)),
+            Self::Integer => FieldValue::Integer(decoder.decode()?),
+            Self::Unsigned => FieldValue::Unsigned(decoder.decode()?),
+            Self::Float => FieldValue::Float(decoder.decode()?),
+            Self::Bool => FieldValue::Bool(decoder.decode()?),
+            Self::Bytes => FieldValue::Bytes(Cow::Owned(decoder.decode()?)),
+        })
+    }
+}
+
+/// A value of an [`IndexedField`] that has been converted to its key type.
+enum FieldValue<'a> {
+    String(Cow<'a, str>),
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    Bool(bool),
+    Bytes(Cow<'a, [u8]>),
+}
+
+impl FieldValue<'_> {
+    fn to_ord_bytes(&self) -> Result<Vec<u8>, view::Error> {
+        fn encode<K: for<'k> Key<'k>, T: KeyEncoding<K> + ?Sized>(
+            value: &T,
+        ) -> Result<Vec<u8>, view::Error> {
+            value
+                .as_ord_bytes()
+                .map(Cow::into_owned)
+                .map_err(view::Error::key_serialization)
+        }
+
+        match self {
+            Self::String(value) => encode::<String, str>(value),
+            Self::Integer(value) => encode::<i64, i64>(value),
+            Self::Unsigned(value) => encode::<u64, u64>(value),
+            Self::Float(value) => encode::<f64, f64>(value),
+            Self::Bool(value) => encode::<bool, bool>(value),
+            Self::Bytes(value) => encode::<Vec<u8>, &[u8]>(&&**value),
+        }
+    }
+
+    fn encode_into(&self, encoder: &mut CompositeKeyEncoder) -> Result<(), CompositeKeyError> {
+        match self {
+            Self::String(value) => encoder.encode::<String, str>(value),
+            Self::Integer(value) => encoder.encode::<i64, i64>(value),
+            Self::Unsigned(value) => encoder.encode::<u64, u64>(value),
+            Self::Float(value) => encoder.encode::<f64, f64>(value),
+            Self::Bool(value) => encoder.encode::<bool, bool>(value),
+            Self::Bytes(value) => encoder.encode::<Vec<u8>, &[u8]>(&&**value),
+        }
+    }
+}
+
+/// The view that stores a [`DynamicIndex`].
+#[derive(Debug)]
+pub(crate) struct DynamicIndexView {
+    collection: CollectionName,
+    format: DocumentFormat,
+    index: DynamicIndex,
+    version: u64,
+}
+
+impl DynamicIndexView {
+    fn new(collection: &CollectionName, format: DocumentFormat, index: DynamicIndex) -> Self {
+        // Any change to how keys are produced requires rebuilding the index.
+        let mut hasher = Sha256::new();
+        hasher.update([format as u8, u8::from(index.unique)]);
+        for field in &index.fields {
+            hasher.update(field.path.as_bytes());
+            hasher.update([0, field.kind as u8]);
+        }
+        let digest = hasher.finalize();
+        let mut version = [0; 8];
+        version.copy_from_slice(&digest[..8]);
+
+        Self {
+            collection: collection.clone(),
+            format,
+            index,
+            version: u64::from_be_bytes(version),
+        }
+    }
+
+    fn key(&self, document: &DocumentValue) -> Result<Option<Vec<u8>>, view::Error> {
+        let mut values = Vec::with_capacity(self.index.fields.len());
+        for field in &self.index.fields {
+            match document
+                .lookup(&field.path)
+                .and_then(|value| field.kind.convert(value))
+            {
+                Some(value) => values.push(value),
+                None => return Ok(None),
+            }
+        }
+
+        if let [value] = values.as_slice() {
+            value.to_ord_bytes().map(Some)
+        } else {
+            let mut encoder = CompositeKeyEncoder::default();
+            for value in &values {
+                value
+                    .encode_into(&mut encoder)
+                    .map_err(view::Error::key_serialization)?;
+            }
+            Ok(Some(encoder.finish()))
+        }
+    }
+}
+
+impl Serialized for DynamicIndexView {
+    fn collection(&self) -> CollectionName {
+        self.collection.clone()
+    }
+
+    fn key_description(&self) -> KeyDescription {
+        if let [field] = self.index.fields.as_slice() {
+            field.kind.key_description()
+        } else {
+            KeyDescription::Composite(CompositeKeyDescription {
+                kind: CompositeKind::Tuple,
+                fields: self
+                    .index
+                    .fields
+                    .iter()
+                    .map(|field| field.kind.key_description())
+                    .collect(),
+                attributes: std::collections::HashMap::new(),
+            })
+        }
+    }
+
+    fn update_policy(&self) -> ViewUpdatePolicy {
+        if self.index.unique {
+            ViewUpdatePolicy::Unique
+        } else {
+            ViewUpdatePolicy::Lazy
+        }
+    }
+
+    fn version(&self) -> u64 {
+        self.version
+    }
+
+    fn compatible_with_version(&self, version: u64) -> bool {
+        version == self.version
+    }
+
+    fn view_name(&self) -> ViewName {
+        self.index.view_name(&self.collection)
+    }
+
+    fn linked_collections(&self) -> Vec<CollectionName> {
+        Vec::new()
+    }
+
+    fn map(&self, document: &BorrowedDocument<'_>) -> Result<Vec<map::Serialized>, view::Error> {
+        let value = self
+            .format
+            .deserialize::<DocumentValue>(&document.contents)?;
+        let Some(key) = self.key(&value)? else {
+            return Ok(Vec::new());
+        };
+
+        Ok(vec![map::Serialized {
+            source: document.header.clone(),
+            collection: None,
+            key: Bytes::from(key),
+            value: Bytes::from(pot::to_vec(&1_u64)?),
+        }])
+    }
+
+    fn map_linked(
+        &self,
+        _collection: &CollectionName,
+        _document: &BorrowedDocument<'_>,
+    ) -> Result<Vec<map::Serialized>, view::Error> {
+        Ok(Vec::new())
+    }
+
+    fn projection(&self) -> ViewProjection {
+        ViewProjection::None
+    }
+
+    fn project(&self, document: &BorrowedDocument<'_>) -> Result<Vec<u8>, view::Error> {
+        Ok(document.contents.to_vec())
+    }
+
+    fn implements_reduce(&self) -> bool {
+        true
+    }
+
+    fn reduce(&self, mappings: &[(&[u8], &[u8])], rereduce: bool) -> Result<Vec<u8>, view::Error> {
+        let mappings = mappings
+            .iter()
+            .map(|(_, value)| Ok(MappedValue::new((), pot::from_slice::<u64>(value)?)))
+            .collect::<Result<Vec<_>, view::Error>>()?;
+        let count = <Count as Reducer<u64>>::reduce(&mappings, rereduce)?;
+        Ok(pot::to_vec(&count)?)
+    }
+
+    fn group_key(&self, key: &[u8], group_level: usize) -> Result<Vec<u8>, view::Error> {
+        if self.index.fields.len() < 2 {
+            return Err(view::Error::key_serialization(CompositeKeyError::new(
+                std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "key is not a composite key",
+                ),
+            )));
+        }
+
+        let mut decoder = CompositeKeyDecoder::default_for(ByteSource::Borrowed(key));
+        let mut encoder = CompositeKeyEncoder::default();
+        for (index, field) in self.index.fields.iter().enumerate() {
+            let value = field
+                .kind
+                .decode(&mut decoder)
+                .map_err(view::Error::key_serialization)?;
+            if index < group_level {
+                value
+                    .encode_into(&mut encoder)
+                    .map_err(view::Error::key_serialization)?;
+            }
+        }
+        decoder.finish().map_err(view::Error::key_serialization)?;
+        Ok(encoder.finish())
+    }
+}
+
+/// Assigns `u64` ids to documents in a [`DynamicCollection`].
+#[derive(Debug)]
+pub(crate) struct DynamicIdGenerator;
+
+impl IdGenerator for DynamicIdGenerator {
+    fn next_id(&self, context: IdGeneratorContext<'_>) -> Result<DocumentId, Error> {
+        let last_id = context
+            .last_id
+            .map(DocumentId::deserialize::<u64>)
+            .transpose()?
+            .unwrap_or_default();
+        let next_id = last_id.checked_add(1).ok_or_else(|| {
+            Error::DocumentPush(
+                context.collection.clone(),
+                crate::key::NextValueError::WouldWrap,
+            )
+        })?;
+        DocumentId::new(&next_id)
+    }
+}
+
+/// A self-describing value parsed from a document in a [`DynamicCollection`].
+#[derive(Debug, PartialEq)]
+enum DocumentValue {
+    Null,
+    Bool(bool),
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Sequence(Vec<DocumentValue>),
+    Map(Vec<(DocumentValue, DocumentValue)>),
+}
+
+impl DocumentValue {
+    fn lookup(&self, path: &str) -> Option<&Self> {
+        let mut value = self;
+        for segment in path.split('.') {
+            value = match value {
+                Self::Map(entries) => entries.iter().find_map(|(key, value)| {
+                    matches!(key, Self::String(key) if key == segment).then_some(value)
+                })?,
+                Self::Sequence(values) => values.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+}
+
+impl<'de> Deserialize<'de> for DocumentValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DocumentValueVisitor)
+    }
+}
+
+struct DocumentValueVisitor;
+
+impl<'de> Visitor<'de> for DocumentValueVisitor {
+    type Value = DocumentValue;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a self-describing value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(DocumentValue::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(DocumentValue::Signed(v))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(i64::try_from(v).map_or(DocumentValue::Float(v as f64), DocumentValue::Signed))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(DocumentValue::Unsigned(v))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(u64::try_from(v).map_or(DocumentValue::Float(v as f64), DocumentValue::Unsigned))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(DocumentValue::Float(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(DocumentValue::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(DocumentValue::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(DocumentValue::Bytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(DocumentValue::Bytes(v))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(DocumentValue::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        DocumentValue::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(DocumentValue::Null)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        DocumentValue::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(DocumentValue::Sequence(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(DocumentValue::Map(entries))
+    }
+}
+
+#[test]
+fn dynamic_index_keys() {
+    use crate::schema::Qualified;
+
+    let collection =
+        DynamicCollection::new(CollectionName::private("people"), DocumentFormat::Json)
+            .with_index(DynamicIndex::new("by-name").field("name.last", FieldKind::String))
+            .with_index(
+                DynamicIndex::new("by-tag-and-age")
+                    .field("tags.0", FieldKind::String)
+                    .field("age", FieldKind::Unsigned),
+            );
+    collection.validate().unwrap();
+    let views = collection.views().collect::<Vec<_>>();
+
+    let document = BorrowedDocument::new(
+        DocumentId::new(&1_u64).unwrap(),
+        br#"{"name":{"last":"Smith"},"tags":["admin"],"age":42}"#.to_vec(),
+    );
+    let mappings = views[0].map(&document).unwrap();
+    assert_eq!(mappings.len(), 1);
+    assert_eq!(
+        &mappings[0].key[..],
+        &String::from("Smith").as_ord_bytes().unwrap()[..]
+    );
+
+    let mappings = views[1].map(&document).unwrap();
+    let expected = (String::from("admin"), 42_u64).as_ord_bytes().unwrap();
+    assert_eq!(&mappings[0].key[..], &expected[..]);
+    assert_eq!(
+        views[1].group_key(&mappings[0].key, 1).unwrap(),
+        <(String, u64)>::encode_composite_prefix(ByteSource::Borrowed(&expected), 1).unwrap()
+    );
+
+    // Documents missing a field, or with a field of the wrong type, aren't
+    // indexed.
+    let document = BorrowedDocument::new(
+        DocumentId::new(&2_u64).unwrap(),
+        br#"{"name":{"last":null},"age":"unknown"}"#.to_vec(),
+    );
+    assert!(views[0].map(&document).unwrap().is_empty());
+    assert!(views[1].map(&document).unwrap().is_empty());
+
+    let duplicated = collection
+        .clone()
+        .with_index(DynamicIndex::new("by-name").field("id", FieldKind::Unsigned));
+    assert!(matches!(
+        duplicated.validate(),
+        Err(Error::ViewAlreadyRegistered(_))
+    ));
+}
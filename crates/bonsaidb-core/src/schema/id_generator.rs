@@ -0,0 +1,423 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use derive_where::derive_where;
+
+use crate::document::DocumentId;
+use crate::key::{Key, NextValueError};
+use crate::schema::{Collection, CollectionName};
+use crate::Error;
+
+/// Generates the ids of documents that are pushed into a collection without
+/// an id.
+///
+/// The generator used by a collection is returned from
+/// [`Collection::id_generator()`]. By default, [`KeyIdGenerator`] is used. When
+/// using the `Collection` derive macro, the `id_generator` parameter can be
+/// used to specify a different generator:
+///
+/// ```rust
+/// use bonsaidb_core::schema::{Collection, SnowflakeGenerator};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Collection)]
+/// #[collection(name = "events", primary_key = u64, id_generator = SnowflakeGenerator)]
+/// # #[collection(core = bonsaidb_core)]
+/// struct Event {
+///     pub kind: String,
+/// }
+/// ```
+///
+/// The generated id must be able to be deserialized as the collection's
+/// [`PrimaryKey`](Collection::PrimaryKey), which is checked at compile time
+/// using [`IdGeneratorFor`].
+pub trait IdGenerator: Debug + Send + Sync {
+    /// Returns the id for a new document.
+    fn next_id(&self, context: IdGeneratorContext<'_>) -> Result<DocumentId, Error>;
+}
+
+/// An [`IdGenerator`] that creates ids that can be deserialized as
+/// `PrimaryKey`.
+///
+/// The `Collection` derive macro requires the `id_generator` to implement this
+/// trait for the collection's primary key, which prevents using a generator
+/// with an incompatible key type:
+///
+/// ```rust,compile_fail
+/// use bonsaidb_core::schema::{Collection, SnowflakeGenerator};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Collection)]
+/// #[collection(name = "events", primary_key = String, id_generator = SnowflakeGenerator)]
+/// # #[collection(core = bonsaidb_core)]
+/// struct Event {
+///     pub kind: String,
+/// }
+/// ```
+pub trait IdGeneratorFor<PrimaryKey>: IdGenerator {}
+
+/// Information provided to an [`IdGenerator`] when generating an id.
+#[derive(Debug, Clone, Copy)]
+pub struct IdGeneratorContext<'a> {
+    /// The collection the document is being inserted into.
+    pub collection: &'a CollectionName,
+    /// The largest id currently stored in the collection, if the collection
+    /// contains any documents.
+    pub last_id: Option<&'a DocumentId>,
+    /// The unique id of the storage the document is being inserted into.
+    pub storage_id: u64,
+}
+
+/// An [`IdGenerator`] that uses [`Key::first_value()`] and
+/// [`Key::next_value()`] of the collection's primary key to generate ids. Each
+/// generated id is the next value after the largest id stored in the
+/// collection.
+#[derive_where(Default, Debug)]
+pub struct KeyIdGenerator<C: Collection>(PhantomData<C>);
+
+impl<C> IdGenerator for KeyIdGenerator<C>
+where
+    C: Collection,
+{
+    fn next_id(&self, context: IdGeneratorContext<'_>) -> Result<DocumentId, Error> {
+        let key = context
+            .last_id
+            .map(DocumentId::deserialize::<C::PrimaryKey>)
+            .transpose()?;
+        let key = if let Some(key) = key {
+            key
+        } else {
+            <C::PrimaryKey as Key<'_>>::first_value()
+                .map_err(|err| Error::DocumentPush(C::collection_name(), err))?
+        };
+        let next_value = key
+            .next_value()
+            .map_err(|err| Error::DocumentPush(C::collection_name(), err))?;
+        DocumentId::new(&next_value)
+    }
+}
+
+impl<C> IdGeneratorFor<C::PrimaryKey> for KeyIdGenerator<C> where C: Collection {}
+
+/// An [`IdGenerator`] that creates 64-bit, time-ordered ids that incorporate
+/// the id of the storage generating them. The generated ids are compatible
+/// with `u64` primary keys.
+///
+/// Each id is made up of:
+///
+/// - 42 bits containing the number of milliseconds since the Unix epoch.
+/// - 10 bits containing the lowest 10 bits of the storage's unique id.
+/// - 12 bits containing a sequence number.
+///
+/// Ids generated by the same storage are always greater than the largest id
+/// already stored in the collection. If multiple ids are generated within the
+/// same millisecond, the sequence number is incremented. If the sequence number
+/// is exhausted, the generator waits for the next millisecond rather than
+/// allowing the sequence to overflow into the storage's id.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct SnowflakeGenerator;
+
+impl SnowflakeGenerator {
+    const NODE_BITS: u32 = 10;
+    const NODE_MASK: u64 = (1 << Self::NODE_BITS) - 1;
+    const SEQUENCE_BITS: u32 = 12;
+    const SEQUENCE_MASK: u64 = (1 << Self::SEQUENCE_BITS) - 1;
+    const TIMESTAMP_BITS: u32 = 64 - Self::NODE_BITS - Self::SEQUENCE_BITS;
+
+    fn assemble(timestamp: u64, node: u64, sequence: u64) -> Option<u64> {
+        (timestamp < 1 << Self::TIMESTAMP_BITS).then(|| {
+            (timestamp << (Self::NODE_BITS + Self::SEQUENCE_BITS))
+                | (node << Self::SEQUENCE_BITS)
+                | sequence
+        })
+    }
+
+    fn disassemble(id: u64) -> (u64, u64, u64) {
+        (
+            id >> (Self::NODE_BITS + Self::SEQUENCE_BITS),
+            (id >> Self::SEQUENCE_BITS) & Self::NODE_MASK,
+            id & Self::SEQUENCE_MASK,
+        )
+    }
+}
+
+impl IdGenerator for SnowflakeGenerator {
+    fn next_id(&self, context: IdGeneratorContext<'_>) -> Result<DocumentId, Error> {
+        let would_wrap =
+            || Error::DocumentPush(context.collection.clone(), NextValueError::WouldWrap);
+        let node = context.storage_id & Self::NODE_MASK;
+        let last_id = context
+            .last_id
+            .map(DocumentId::deserialize::<u64>)
+            .transpose()?;
+        let id = loop {
+            let timestamp = u64::try_from(millis_since_unix_epoch()).map_err(|_| would_wrap())?;
+            let Some((last_timestamp, last_node, last_sequence)) = last_id.map(Self::disassemble)
+            else {
+                break Self::assemble(timestamp, node, 0);
+            };
+
+            if timestamp > last_timestamp {
+                break Self::assemble(timestamp, node, 0);
+            } else if node > last_node {
+                break Self::assemble(last_timestamp, node, 0);
+            } else if node == last_node && last_sequence < Self::SEQUENCE_MASK {
+                break Self::assemble(last_timestamp, node, last_sequence + 1);
+            } else if timestamp == last_timestamp {
+                // The sequence is exhausted for the current millisecond.
+                std::thread::sleep(Duration::from_micros(100));
+            } else {
+                // The largest id is ahead of this storage's clock. Rather than
+                // waiting for the clock to catch up, use the millisecond after
+                // it.
+                break Self::assemble(last_timestamp + 1, node, 0);
+            }
+        };
+        DocumentId::new(&id.ok_or_else(would_wrap)?)
+    }
+}
+
+impl IdGeneratorFor<u64> for SnowflakeGenerator {}
+
+/// An [`IdGenerator`] that creates version 7 UUIDs, which are ordered by the
+/// time they were created. The generated ids are compatible with `uuid::Uuid`
+/// and `u128` primary keys.
+///
+/// Ids generated are always greater than the largest id already stored in the
+/// collection. If the current time would produce a smaller id, the random bits
+/// of the largest stored id are incremented instead, as described by RFC 9562.
+#[cfg(feature = "uuid")]
+#[derive(Default, Debug, Clone, Copy)]
+pub struct UuidV7Generator;
+
+#[cfg(feature = "uuid")]
+impl UuidV7Generator {
+    const RAND_A_BITS: u32 = 12;
+    const RAND_B_BITS: u32 = 62;
+    const VERSION: u128 = 0b0111 << (Self::RAND_A_BITS + 2 + Self::RAND_B_BITS);
+    const VARIANT: u128 = 0b10 << Self::RAND_B_BITS;
+
+    fn assemble(timestamp: u128, counter: u128) -> u128 {
+        let rand_a = counter >> Self::RAND_B_BITS;
+        let rand_b = counter & ((1 << Self::RAND_B_BITS) - 1);
+        (timestamp << (4 + Self::RAND_A_BITS + 2 + Self::RAND_B_BITS))
+            | Self::VERSION
+            | (rand_a << (2 + Self::RAND_B_BITS))
+            | Self::VARIANT
+            | rand_b
+    }
+
+    fn disassemble(uuid: u128) -> (u128, u128) {
+        let timestamp = uuid >> (4 + Self::RAND_A_BITS + 2 + Self::RAND_B_BITS);
+        let rand_a = (uuid >> (2 + Self::RAND_B_BITS)) & ((1 << Self::RAND_A_BITS) - 1);
+        let rand_b = uuid & ((1 << Self::RAND_B_BITS) - 1);
+        (timestamp, (rand_a << Self::RAND_B_BITS) | rand_b)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl IdGenerator for UuidV7Generator {
+    fn next_id(&self, context: IdGeneratorContext<'_>) -> Result<DocumentId, Error> {
+        const COUNTER_LIMIT: u128 =
+            1 << (UuidV7Generator::RAND_A_BITS + UuidV7Generator::RAND_B_BITS);
+        let timestamp = millis_since_unix_epoch() & ((1 << 48) - 1);
+        let counter = rand::random::<u128>() % COUNTER_LIMIT;
+        let mut id = Self::assemble(timestamp, counter);
+        if let Some(last_id) = context
+            .last_id
+            .map(DocumentId::deserialize::<u128>)
+            .transpose()?
+        {
+            if last_id >= id {
+                let (timestamp, counter) = Self::disassemble(last_id);
+                id = if counter + 1 < COUNTER_LIMIT {
+                    Self::assemble(timestamp, counter + 1)
+                } else if timestamp + 1 < 1 << 48 {
+                    Self::assemble(timestamp + 1, 0)
+                } else {
+                    return Err(Error::DocumentPush(
+                        context.collection.clone(),
+                        NextValueError::WouldWrap,
+                    ));
+                };
+            }
+        }
+        DocumentId::new(&id)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl IdGeneratorFor<u128> for UuidV7Generator {}
+
+#[cfg(feature = "uuid")]
+impl IdGeneratorFor<uuid::Uuid> for UuidV7Generator {}
+
+/// An [`IdGenerator`] that creates
+/// [ULIDs](https://github.com/ulid/spec), which are ordered by the time they
+/// were created. The generated ids are compatible with `ulid::Ulid`,
+/// `uuid::Uuid`, and `u128` primary keys.
+///
+/// Ids generated are always greater than the largest id already stored in the
+/// collection. If the current time would produce a smaller id, the largest
+/// stored id is incremented instead, matching the specification's monotonic
+/// behavior.
+#[cfg(feature = "ulid")]
+#[derive(Default, Debug, Clone, Copy)]
+pub struct UlidGenerator;
+
+#[cfg(feature = "ulid")]
+impl IdGenerator for UlidGenerator {
+    fn next_id(&self, context: IdGeneratorContext<'_>) -> Result<DocumentId, Error> {
+        const RANDOM_BITS: u32 = 80;
+        let timestamp = millis_since_unix_epoch() & ((1 << 48) - 1);
+        let random = rand::random::<u128>() & ((1 << RANDOM_BITS) - 1);
+        let mut id = (timestamp << RANDOM_BITS) | random;
+        if let Some(last_id) = context
+            .last_id
+            .map(DocumentId::deserialize::<u128>)
+            .transpose()?
+        {
+            if last_id >= id {
+                id = last_id.checked_add(1).ok_or_else(|| {
+                    Error::DocumentPush(context.collection.clone(), NextValueError::WouldWrap)
+                })?;
+            }
+        }
+        DocumentId::new(&id)
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl IdGeneratorFor<u128> for UlidGenerator {}
+
+#[cfg(feature = "ulid")]
+impl IdGeneratorFor<ulid::Ulid> for UlidGenerator {}
+
+#[cfg(all(feature = "ulid", feature = "uuid"))]
+impl IdGeneratorFor<uuid::Uuid> for UlidGenerator {}
+
+fn millis_since_unix_epoch() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is before the unix epoch")
+        .as_millis()
+}
+
+#[test]
+fn snowflake_tests() -> anyhow::Result<()> {
+    let collection = CollectionName::private("snowflakes");
+    let context = IdGeneratorContext {
+        collection: &collection,
+        last_id: None,
+        storage_id: 0xFFFF_FFFF_FFFF_F123,
+    };
+    let first = SnowflakeGenerator.next_id(context)?;
+    let first_value = first.deserialize::<u64>()?;
+    assert_eq!((first_value >> 12) & 0x3FF, 0x123);
+    assert_eq!(first_value & 0xFFF, 0);
+
+    // Generating another id within the same millisecond increments the
+    // sequence.
+    let second = SnowflakeGenerator.next_id(IdGeneratorContext {
+        last_id: Some(&first),
+        ..context
+    })?;
+    assert!(second.deserialize::<u64>()? > first_value);
+
+    // Exhausting the sequence waits for the next millisecond instead of
+    // overflowing into the node bits.
+    let now = u64::try_from(millis_since_unix_epoch())?;
+    let exhausted = DocumentId::new(
+        &SnowflakeGenerator::assemble(now, 0x123, SnowflakeGenerator::SEQUENCE_MASK).unwrap(),
+    )?;
+    let next = SnowflakeGenerator
+        .next_id(IdGeneratorContext {
+            last_id: Some(&exhausted),
+            ..context
+        })?
+        .deserialize::<u64>()?;
+    let (timestamp, node, sequence) = SnowflakeGenerator::disassemble(next);
+    assert!(timestamp > now);
+    assert_eq!(node, 0x123);
+    assert_eq!(sequence, 0);
+
+    // When the largest id is ahead of the clock, the millisecond after it is
+    // used once its sequence is exhausted.
+    let ahead = now + 60_000;
+    let exhausted = DocumentId::new(
+        &SnowflakeGenerator::assemble(ahead, 0x123, SnowflakeGenerator::SEQUENCE_MASK).unwrap(),
+    )?;
+    let next = SnowflakeGenerator
+        .next_id(IdGeneratorContext {
+            last_id: Some(&exhausted),
+            ..context
+        })?
+        .deserialize::<u64>()?;
+    assert_eq!(SnowflakeGenerator::disassemble(next), (ahead + 1, 0x123, 0));
+
+    let future = DocumentId::new(&u64::MAX)?;
+    assert!(SnowflakeGenerator
+        .next_id(IdGeneratorContext {
+            last_id: Some(&future),
+            ..context
+        })
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "uuid")]
+fn uuid_v7_tests() -> anyhow::Result<()> {
+    let collection = CollectionName::private("uuids");
+    let context = IdGeneratorContext {
+        collection: &collection,
+        last_id: None,
+        storage_id: 0,
+    };
+    let first = UuidV7Generator.next_id(context)?;
+    let uuid = first.deserialize::<uuid::Uuid>()?;
+    assert_eq!(uuid.get_version_num(), 7);
+    assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+
+    // When the stored id is in the future, the stored id's counter is
+    // incremented.
+    let future = DocumentId::new(&UuidV7Generator::assemble((1 << 48) - 1, 0))?;
+    let next = UuidV7Generator.next_id(IdGeneratorContext {
+        last_id: Some(&future),
+        ..context
+    })?;
+    assert_eq!(
+        next.deserialize::<u128>()?,
+        UuidV7Generator::assemble((1 << 48) - 1, 1)
+    );
+    let next = next.deserialize::<uuid::Uuid>()?;
+    assert_eq!(next.get_version_num(), 7);
+    assert_eq!(next.get_variant(), uuid::Variant::RFC4122);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "ulid")]
+fn ulid_tests() -> anyhow::Result<()> {
+    let collection = CollectionName::private("ulids");
+    let context = IdGeneratorContext {
+        collection: &collection,
+        last_id: None,
+        storage_id: 0,
+    };
+    let first = UlidGenerator.next_id(context)?;
+    let ulid = first.deserialize::<ulid::Ulid>()?;
+    let now = u64::try_from(millis_since_unix_epoch())?;
+    assert!(now - ulid.timestamp_ms() < 60_000);
+
+    let second = UlidGenerator.next_id(IdGeneratorContext {
+        last_id: Some(&first),
+        ..context
+    })?;
+    assert!(second.deserialize::<ulid::Ulid>()? > ulid);
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! Serializes maps as sequences of key-value pairs in human-readable formats.
+//!
+//! Formats like JSON only support string keys, which prevents maps keyed by
+//! types like [`DocumentId`](crate::document::DocumentId) or
+//! [`CollectionName`](crate::schema::CollectionName) from being serialized.
+//! Formats that aren't human-readable, such as `Pot`, continue to serialize
+//! these fields as maps.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Serialize + 'a,
+    V: Serialize + 'a,
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.collect_seq(map)
+    } else {
+        serializer.collect_map(map)
+    }
+}
+
+pub fn deserialize<'de, M, K, V, D>(deserializer: D) -> Result<M, D::Error>
+where
+    M: Deserialize<'de> + FromIterator<(K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        Vec::<(K, V)>::deserialize(deserializer).map(|pairs| pairs.into_iter().collect())
+    } else {
+        M::deserialize(deserializer)
+    }
+}
+
+/// Serializes maps whose values are also maps, using pairs for both the outer
+/// and inner maps in human-readable formats.
+pub mod nested {
+    use super::{BTreeMap, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<K, K2, V2, S>(
+        map: &BTreeMap<K, BTreeMap<K2, V2>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        K2: Serialize,
+        V2: Serialize,
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_seq(map.iter().map(|(key, inner)| (key, Pairs(inner))))
+        } else {
+            serializer.collect_map(map)
+        }
+    }
+
+    pub fn deserialize<'de, K, K2, V2, D>(
+        deserializer: D,
+    ) -> Result<BTreeMap<K, BTreeMap<K2, V2>>, D::Error>
+    where
+        K: Deserialize<'de> + Ord,
+        K2: Deserialize<'de> + Ord,
+        V2: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            Vec::<(K, OwnedPairs<K2, V2>)>::deserialize(deserializer).map(|pairs| {
+                pairs
+                    .into_iter()
+                    .map(|(key, inner)| (key, inner.0))
+                    .collect()
+            })
+        } else {
+            BTreeMap::deserialize(deserializer)
+        }
+    }
+
+    struct Pairs<'a, K, V>(&'a BTreeMap<K, V>);
+
+    impl<K, V> Serialize for Pairs<'_, K, V>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            super::serialize(self.0, serializer)
+        }
+    }
+
+    struct OwnedPairs<K, V>(BTreeMap<K, V>);
+
+    impl<'de, K, V> Deserialize<'de> for OwnedPairs<K, V>
+    where
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            super::deserialize(deserializer).map(Self)
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_pairs() {
+    use crate::document::{DocumentId, Header, OwnedDocument, Revision};
+    use crate::schema::view::map::MappedSerializedDocuments;
+    use crate::schema::CollectionName;
+
+    let id = DocumentId::from_u64(1);
+    let document = OwnedDocument {
+        header: Header {
+            id: id.clone(),
+            revision: Revision::new(b"contents"),
+        },
+        contents: b"contents".to_vec().into(),
+    };
+    let mapped = MappedSerializedDocuments {
+        mappings: Vec::new(),
+        documents: BTreeMap::from([(id.clone(), document.clone())]),
+        linked_documents: BTreeMap::from([(
+            CollectionName::new("khonsulabs", "linked"),
+            BTreeMap::from([(id.clone(), document)]),
+        )]),
+    };
+
+    let json = serde_json::to_vec(&mapped).unwrap();
+    let from_json = serde_json::from_slice::<MappedSerializedDocuments>(&json).unwrap();
+    let from_pot =
+        pot::from_slice::<MappedSerializedDocuments>(&pot::to_vec(&mapped).unwrap()).unwrap();
+    for decoded in [from_json, from_pot] {
+        assert_eq!(decoded.documents.keys().collect::<Vec<_>>(), [&id]);
+        assert_eq!(
+            decoded.linked_documents[&CollectionName::new("khonsulabs", "linked")]
+                .keys()
+                .collect::<Vec<_>>(),
+            [&id]
+        );
+    }
+}
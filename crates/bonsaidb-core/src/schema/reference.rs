@@ -0,0 +1,220 @@
+use std::fmt::Debug;
+
+use derive_where::derive_where;
+use serde::{Deserialize, Serialize};
+
+use crate::document::{BorrowedDocument, CollectionDocument, DocumentId, Emit};
+use crate::schema::view::map::Mappings;
+use crate::schema::view::{
+    CollectionMapReduce, DefaultViewSerialization, ViewMapResult, ViewSchema, ViewUpdatePolicy,
+};
+use crate::schema::{Collection, CollectionName, Name, SerializedCollection, View, ViewName};
+use crate::Error;
+
+/// A relationship from documents in [`Reference::Collection`] to documents in
+/// [`Reference::Parent`].
+///
+/// Once a reference is defined using
+/// [`Schematic::define_reference()`](crate::schema::Schematic::define_reference)
+/// or the `references` parameter of the `Collection` derive macro, the
+/// database will enforce that:
+///
+/// - When a document is inserted or updated in `Collection`, every id
+///   returned from [`Reference::parent_ids()`] must exist in `Parent`. If any
+///   are missing, [`Error::ReferencedDocumentNotFound`] is returned.
+/// - When a document is deleted from `Parent`, the
+///   [`OnDelete`] action returned from [`Reference::on_delete()`] is applied
+///   to all documents that reference it.
+///
+/// All checks are performed within the transaction being executed. Operations
+/// within a transaction are applied in order, which means a parent document
+/// must be inserted before its children, and children must be deleted before
+/// their parents when using [`OnDelete::Restrict`].
+///
+/// References are indexed using an eagerly updated view named
+/// [`Reference::name()`] on `Collection`. The view can be queried using
+/// [`ReferenceView<Self>`](ReferenceView) to find all documents that refer to
+/// a parent document.
+///
+/// ```rust
+/// use bonsaidb_core::document::{CollectionDocument, DocumentId};
+/// use bonsaidb_core::schema::{Collection, Name, OnDelete, Reference};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Collection)]
+/// #[collection(name = "orders")]
+/// # #[collection(core = bonsaidb_core)]
+/// struct Order {
+///     pub customer: String,
+/// }
+///
+/// #[derive(Debug, Serialize, Deserialize, Collection)]
+/// #[collection(name = "line-items", references = [LineItemOrder])]
+/// # #[collection(core = bonsaidb_core)]
+/// struct LineItem {
+///     pub order_id: u64,
+///     pub sku: String,
+/// }
+///
+/// #[derive(Debug, Clone)]
+/// struct LineItemOrder;
+///
+/// impl Reference for LineItemOrder {
+///     type Collection = LineItem;
+///     type Parent = Order;
+///
+///     fn name(&self) -> Name {
+///         Name::new("order")
+///     }
+///
+///     fn on_delete(&self) -> OnDelete {
+///         OnDelete::Cascade
+///     }
+///
+///     fn parent_ids(&self, document: &CollectionDocument<LineItem>) -> Vec<u64> {
+///         vec![document.contents.order_id]
+///     }
+/// }
+/// ```
+pub trait Reference: Debug + Clone + Send + Sync + 'static {
+    /// The collection containing the documents that refer to `Parent`.
+    type Collection: SerializedCollection;
+    /// The collection being referred to.
+    type Parent: Collection;
+
+    /// The name of this reference. Must be unique among the references and
+    /// views of [`Reference::Collection`].
+    fn name(&self) -> Name;
+
+    /// The action to take when a document in [`Reference::Parent`] that is
+    /// referred to is deleted. The default implementation returns
+    /// [`OnDelete::Restrict`].
+    fn on_delete(&self) -> OnDelete {
+        OnDelete::Restrict
+    }
+
+    /// Returns the ids of the documents in [`Reference::Parent`] that
+    /// `document` refers to. Returning an empty list indicates that `document`
+    /// does not refer to any parent documents.
+    fn parent_ids(
+        &self,
+        document: &CollectionDocument<Self::Collection>,
+    ) -> Vec<<Self::Parent as Collection>::PrimaryKey>;
+}
+
+/// The action taken when a document that is referred to by a [`Reference`] is
+/// deleted.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub enum OnDelete {
+    /// The deletion is rejected with [`Error::DocumentStillReferenced`].
+    #[default]
+    Restrict,
+    /// All documents that refer to the deleted document are deleted within the
+    /// same transaction. Deletions cascade through any references to the
+    /// deleted documents.
+    Cascade,
+}
+
+/// The eagerly updated view that indexes a [`Reference`]. Each document in
+/// [`Reference::Collection`] is emitted once for each parent id it refers to,
+/// using the parent's [`DocumentId`] as the key.
+#[derive_where(Debug, Clone)]
+pub struct ReferenceView<R: Reference>(pub R);
+
+impl<R> View for ReferenceView<R>
+where
+    R: Reference,
+{
+    type Collection = R::Collection;
+    type Key = DocumentId;
+    type Value = ();
+
+    fn name(&self) -> Name {
+        self.0.name()
+    }
+}
+
+impl<R> ViewSchema for ReferenceView<R>
+where
+    R: Reference,
+{
+    type MappedKey<'doc> = DocumentId;
+    type View = Self;
+
+    fn update_policy(&self) -> ViewUpdatePolicy {
+        ViewUpdatePolicy::Eager
+    }
+
+    fn implements_reduce(&self) -> bool {
+        false
+    }
+}
+
+impl<R> CollectionMapReduce for ReferenceView<R>
+where
+    R: Reference,
+{
+    fn map<'doc>(&self, document: CollectionDocument<R::Collection>) -> ViewMapResult<'doc, Self>
+    where
+        CollectionDocument<R::Collection>: 'doc,
+    {
+        let mut mappings = Mappings::none();
+        for parent_id in self.0.parent_ids(&document) {
+            let parent_id = DocumentId::new::<ParentKey<R>, ParentKey<R>>(&parent_id)?;
+            mappings = mappings.and(document.header.emit_key(parent_id)?);
+        }
+        Ok(mappings)
+    }
+}
+
+impl<R> DefaultViewSerialization for ReferenceView<R> where R: Reference {}
+
+/// Wraps a [`Reference`] to erase its associated types.
+pub trait SerializedReference: Debug + Send + Sync {
+    /// The collection containing the documents that refer to
+    /// [`SerializedReference::parent()`].
+    fn collection(&self) -> CollectionName;
+    /// The collection being referred to.
+    fn parent(&self) -> CollectionName;
+    /// The name of the [`ReferenceView`] indexing this reference.
+    fn view_name(&self) -> ViewName;
+    /// Wraps [`Reference::on_delete`]
+    fn on_delete(&self) -> OnDelete;
+    /// Wraps [`Reference::parent_ids`]
+    fn parent_ids(&self, document: &BorrowedDocument<'_>) -> Result<Vec<DocumentId>, Error>;
+}
+
+type ParentKey<R> = <<R as Reference>::Parent as Collection>::PrimaryKey;
+
+#[derive(Debug)]
+pub(crate) struct ReferenceInstance<R>(pub R);
+
+impl<R> SerializedReference for ReferenceInstance<R>
+where
+    R: Reference,
+{
+    fn collection(&self) -> CollectionName {
+        R::Collection::collection_name()
+    }
+
+    fn parent(&self) -> CollectionName {
+        R::Parent::collection_name()
+    }
+
+    fn view_name(&self) -> ViewName {
+        ReferenceView(self.0.clone()).view_name()
+    }
+
+    fn on_delete(&self) -> OnDelete {
+        self.0.on_delete()
+    }
+
+    fn parent_ids(&self, document: &BorrowedDocument<'_>) -> Result<Vec<DocumentId>, Error> {
+        let document = CollectionDocument::<R::Collection>::try_from(document)?;
+        self.0
+            .parent_ids(&document)
+            .iter()
+            .map(DocumentId::new::<ParentKey<R>, ParentKey<R>>)
+            .collect()
+    }
+}
@@ -1,18 +1,21 @@
 use std::any::TypeId;
 use std::collections::{hash_map, HashMap};
 use std::fmt::Debug;
-use std::marker::PhantomData;
-
-use derive_where::derive_where;
 
 use crate::document::{BorrowedDocument, DocumentId, KeyId};
 use crate::key::{ByteSource, Key, KeyDescription};
 use crate::schema::collection::Collection;
+use crate::schema::dynamic::{DynamicCollection, DynamicIdGenerator};
+use crate::schema::reference::{Reference, ReferenceInstance, ReferenceView, SerializedReference};
+use crate::schema::text::{TextIndex, TextIndexView};
+use crate::schema::vector::{VectorIndex, VectorIndexView};
 use crate::schema::view::map::{self, MappedValue};
 use crate::schema::view::{
-    self, MapReduce, Serialized, SerializedView, ViewSchema, ViewUpdatePolicy,
+    self, MapReduce, Serialized, SerializedView, ViewProjection, ViewSchema, ViewUpdatePolicy,
+};
+use crate::schema::{
+    CollectionName, IdGenerator, IdGeneratorContext, Schema, SchemaName, View, ViewName,
 };
-use crate::schema::{CollectionName, Schema, SchemaName, View, ViewName};
 use crate::Error;
 
 /// A collection of defined collections and views.
@@ -23,10 +26,13 @@ pub struct Schematic {
     collections_by_type_id: HashMap<TypeId, CollectionName>,
     collection_encryption_keys: HashMap<CollectionName, KeyId>,
     collection_id_generators: HashMap<CollectionName, Box<dyn IdGenerator>>,
-    views: HashMap<TypeId, Box<dyn view::Serialized>>,
-    views_by_name: HashMap<ViewName, TypeId>,
-    views_by_collection: HashMap<CollectionName, Vec<TypeId>>,
-    eager_views_by_collection: HashMap<CollectionName, Vec<TypeId>>,
+    views: HashMap<ViewName, Box<dyn view::Serialized>>,
+    views_by_type_id: HashMap<TypeId, ViewName>,
+    views_by_collection: HashMap<CollectionName, Vec<ViewName>>,
+    eager_views_by_collection: HashMap<CollectionName, Vec<ViewName>>,
+    views_by_linked_collection: HashMap<CollectionName, Vec<ViewName>>,
+    references: Vec<Box<dyn SerializedReference>>,
+    dynamic_collections: HashMap<CollectionName, DynamicCollection>,
 }
 
 impl Schematic {
@@ -39,14 +45,44 @@ impl Schematic {
             collection_encryption_keys: HashMap::new(),
             collection_id_generators: HashMap::new(),
             views: HashMap::new(),
-            views_by_name: HashMap::new(),
+            views_by_type_id: HashMap::new(),
             views_by_collection: HashMap::new(),
             eager_views_by_collection: HashMap::new(),
+            views_by_linked_collection: HashMap::new(),
+            references: Vec::new(),
+            dynamic_collections: HashMap::new(),
         };
         S::define_collections(&mut schematic)?;
+        schematic.check_linked_collections()?;
         Ok(schematic)
     }
 
+    /// Ensures that each collection linked by a view is part of the schema and
+    /// has the same primary key type as the view's collection. The sources of
+    /// a view's mappings are deserialized using the primary key of the view's
+    /// collection.
+    fn check_linked_collections(&self) -> Result<(), Error> {
+        for (collection, views) in &self.views_by_linked_collection {
+            let linked_key = self.contained_collections.get(collection);
+            for view_name in views {
+                let Some(linked_key) = linked_key else {
+                    return Err(Error::LinkedCollectionNotFound {
+                        view: view_name.clone(),
+                        collection: collection.clone(),
+                    });
+                };
+                let view = &self.views[view_name];
+                if self.contained_collections.get(&view.collection()) != Some(linked_key) {
+                    return Err(Error::LinkedCollectionKeyMismatch {
+                        view: view_name.clone(),
+                        collection: collection.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Adds the collection `C` and its views.
     pub fn define_collection<C: Collection + 'static>(&mut self) -> Result<(), Error> {
         let name = C::collection_name();
@@ -58,7 +94,7 @@ impl Schematic {
                     self.collection_encryption_keys.insert(name.clone(), key);
                 }
                 self.collection_id_generators
-                    .insert(name, Box::<KeyIdGenerator<C>>::default());
+                    .insert(name, C::id_generator());
                 entry.insert(KeyDescription::for_key::<C::PrimaryKey>());
                 C::define_views(self)
             }
@@ -83,30 +119,98 @@ impl Schematic {
         view: V,
         schema: S,
     ) -> Result<(), Error> {
-        let instance = ViewInstance { view, schema };
-        let name = instance.view_name();
-        if self.views_by_name.contains_key(&name) {
+        let name = self.insert_view(Box::new(ViewInstance { view, schema }))?;
+        self.views_by_type_id.insert(TypeId::of::<V>(), name);
+        Ok(())
+    }
+
+    fn insert_view(&mut self, view: Box<dyn view::Serialized>) -> Result<ViewName, Error> {
+        let name = view.view_name();
+        if self.views.contains_key(&name) {
             return Err(Error::ViewAlreadyRegistered(name));
         }
 
-        let collection = instance.collection();
-        let eager = instance.update_policy().is_eager();
-        self.views.insert(TypeId::of::<V>(), Box::new(instance));
-        self.views_by_name.insert(name, TypeId::of::<V>());
+        let collection = view.collection();
+        let eager = view.update_policy().is_eager();
+        let linked_collections = view.linked_collections();
+        if eager && !linked_collections.is_empty() {
+            return Err(Error::EagerViewLinksCollections(name));
+        }
+        self.views.insert(name.clone(), view);
 
         if eager {
             let unique_views = self
                 .eager_views_by_collection
                 .entry(collection.clone())
                 .or_default();
-            unique_views.push(TypeId::of::<V>());
+            unique_views.push(name.clone());
+        }
+        for linked_collection in linked_collections {
+            let linked_views = self
+                .views_by_linked_collection
+                .entry(linked_collection)
+                .or_default();
+            linked_views.push(name.clone());
         }
         let views = self.views_by_collection.entry(collection).or_default();
-        views.push(TypeId::of::<V>());
+        views.push(name.clone());
+
+        Ok(name)
+    }
+
+    /// Adds the [`DynamicCollection`] `collection` and the views that store its
+    /// indexes.
+    pub fn define_dynamic_collection(
+        &mut self,
+        collection: DynamicCollection,
+    ) -> Result<(), Error> {
+        collection.validate()?;
+        match self.contained_collections.entry(collection.name.clone()) {
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(KeyDescription::for_key::<u64>());
+                self.collection_id_generators
+                    .insert(collection.name.clone(), Box::new(DynamicIdGenerator));
+                for view in collection.views() {
+                    self.insert_view(Box::new(view))?;
+                }
+                self.dynamic_collections
+                    .insert(collection.name.clone(), collection);
+                Ok(())
+            }
+            hash_map::Entry::Occupied(_) => Err(Error::CollectionAlreadyDefined),
+        }
+    }
 
+    /// Returns the definition of the [`DynamicCollection`] named `collection`,
+    /// or `None` if the collection wasn't defined at runtime.
+    #[must_use]
+    pub fn dynamic_collection(&self, collection: &CollectionName) -> Option<&DynamicCollection> {
+        self.dynamic_collections.get(collection)
+    }
+
+    /// Iterates over all collections defined at runtime.
+    pub fn dynamic_collections(&self) -> impl Iterator<Item = &DynamicCollection> {
+        self.dynamic_collections.values()
+    }
+
+    /// Adds the reference `R`, which is indexed by a [`ReferenceView<R>`].
+    pub fn define_reference<R: Reference>(&mut self, reference: R) -> Result<(), Error> {
+        let view = ReferenceView(reference.clone());
+        self.define_view(view)?;
+        self.references.push(Box::new(ReferenceInstance(reference)));
         Ok(())
     }
 
+    /// Adds the text index `I`, which is stored in a [`TextIndexView<I>`].
+    pub fn define_text_index<I: TextIndex>(&mut self, index: I) -> Result<(), Error> {
+        self.define_view(TextIndexView(index))
+    }
+
+    /// Adds the vector index `I`, which is stored in a [`VectorIndexView<I>`].
+    pub fn define_vector_index<I: VectorIndex>(&mut self, index: I) -> Result<(), Error> {
+        self.define_view(VectorIndexView(index))
+    }
+
     /// Returns `true` if this schema contains the collection `C`.
     #[must_use]
     pub fn contains_collection<C: Collection + 'static>(&self) -> bool {
@@ -123,33 +227,40 @@ impl Schematic {
         self.contained_collections.get(collection)
     }
 
-    /// Returns the next id in sequence for the collection, if the primary key
-    /// type supports the operation and the next id would not overflow.
+    /// Returns the id for a new document in the collection using the
+    /// collection's [`IdGenerator`]. `last_id` is the largest id stored in the
+    /// collection, and `storage_id` is the unique id of the storage the
+    /// document is being inserted into.
     pub fn next_id_for_collection(
         &self,
         collection: &CollectionName,
-        id: Option<DocumentId>,
+        last_id: Option<&DocumentId>,
+        storage_id: u64,
     ) -> Result<DocumentId, Error> {
         let generator = self
             .collection_id_generators
             .get(collection)
             .ok_or(Error::CollectionNotFound)?;
-        generator.next_id(id)
+        generator.next_id(IdGeneratorContext {
+            collection,
+            last_id,
+            storage_id,
+        })
     }
 
     /// Looks up a [`view::Serialized`] by name.
     pub fn view_by_name(&self, name: &ViewName) -> Result<&'_ dyn view::Serialized, Error> {
-        self.views_by_name
+        self.views
             .get(name)
-            .and_then(|type_id| self.views.get(type_id))
             .map(AsRef::as_ref)
             .ok_or(Error::ViewNotFound)
     }
 
     /// Looks up a [`view::Serialized`] through the the type `V`.
     pub fn view<V: View + 'static>(&self) -> Result<&'_ dyn view::Serialized, Error> {
-        self.views
+        self.views_by_type_id
             .get(&TypeId::of::<V>())
+            .and_then(|name| self.views.get(name))
             .map(AsRef::as_ref)
             .ok_or(Error::ViewNotFound)
     }
@@ -167,10 +278,10 @@ impl Schematic {
         self.views_by_collection
             .get(collection)
             .into_iter()
-            .flat_map(|view_ids| {
-                view_ids
+            .flat_map(|view_names| {
+                view_names
                     .iter()
-                    .filter_map(|id| self.views.get(id).map(AsRef::as_ref))
+                    .filter_map(|name| self.views.get(name).map(AsRef::as_ref))
             })
     }
 
@@ -183,13 +294,54 @@ impl Schematic {
         self.eager_views_by_collection
             .get(collection)
             .into_iter()
-            .flat_map(|view_ids| {
-                view_ids
+            .flat_map(|view_names| {
+                view_names
+                    .iter()
+                    .filter_map(|name| self.views.get(name).map(AsRef::as_ref))
+            })
+    }
+
+    /// Iterates over all views that map documents from `collection` in
+    /// addition to documents from their own collection. See
+    /// [`ViewSchema::linked_collections()`].
+    pub fn views_linked_to_collection(
+        &self,
+        collection: &CollectionName,
+    ) -> impl Iterator<Item = &'_ dyn view::Serialized> {
+        self.views_by_linked_collection
+            .get(collection)
+            .into_iter()
+            .flat_map(|view_names| {
+                view_names
                     .iter()
-                    .filter_map(|id| self.views.get(id).map(AsRef::as_ref))
+                    .filter_map(|name| self.views.get(name).map(AsRef::as_ref))
             })
     }
 
+    /// Iterates over all references from documents in `collection` to other
+    /// documents.
+    pub fn references_from_collection<'a>(
+        &'a self,
+        collection: &'a CollectionName,
+    ) -> impl Iterator<Item = &'a dyn SerializedReference> + 'a {
+        self.references
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(move |reference| &reference.collection() == collection)
+    }
+
+    /// Iterates over all references to documents in `collection` from other
+    /// documents.
+    pub fn references_to_collection<'a>(
+        &'a self,
+        collection: &'a CollectionName,
+    ) -> impl Iterator<Item = &'a dyn SerializedReference> + 'a {
+        self.references
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(move |reference| &reference.parent() == collection)
+    }
+
     /// Returns a collection's default encryption key, if one was defined.
     #[must_use]
     pub fn encryption_key_for_collection(&self, collection: &CollectionName) -> Option<&KeyId> {
@@ -221,9 +373,15 @@ impl Debug for Schematic {
             )
             .field("collection_id_generators", &self.collection_id_generators)
             .field("views", &views)
-            .field("views_by_name", &self.views_by_name)
+            .field("views_by_type_id", &self.views_by_type_id)
             .field("views_by_collection", &self.views_by_collection)
             .field("eager_views_by_collection", &self.eager_views_by_collection)
+            .field(
+                "views_by_linked_collection",
+                &self.views_by_linked_collection,
+            )
+            .field("references", &self.references)
+            .field("dynamic_collections", &self.dynamic_collections)
             .finish()
     }
 }
@@ -255,10 +413,18 @@ where
         self.schema.version()
     }
 
+    fn compatible_with_version(&self, version: u64) -> bool {
+        self.schema.compatible_with_version(version)
+    }
+
     fn view_name(&self) -> ViewName {
         self.view.view_name()
     }
 
+    fn linked_collections(&self) -> Vec<CollectionName> {
+        self.schema.linked_collections()
+    }
+
     fn map(&self, document: &BorrowedDocument<'_>) -> Result<Vec<map::Serialized>, view::Error> {
         let mappings = self.schema.map(document)?;
 
@@ -269,6 +435,35 @@ where
             .map_err(view::Error::key_serialization)
     }
 
+    fn map_linked(
+        &self,
+        collection: &CollectionName,
+        document: &BorrowedDocument<'_>,
+    ) -> Result<Vec<map::Serialized>, view::Error> {
+        let mappings = self.schema.map_linked(collection, document)?;
+
+        mappings
+            .iter()
+            .map(|mapping| {
+                let mut mapping = mapping.serialized::<V>()?;
+                mapping.collection = Some(collection.clone());
+                Ok(mapping)
+            })
+            .collect::<Result<_, view::Error>>()
+    }
+
+    fn projection(&self) -> ViewProjection {
+        self.schema.projection()
+    }
+
+    fn project(&self, document: &BorrowedDocument<'_>) -> Result<Vec<u8>, view::Error> {
+        self.schema.project(document).map_err(view::Error::from)
+    }
+
+    fn implements_reduce(&self) -> bool {
+        self.schema.implements_reduce()
+    }
+
     fn reduce(&self, mappings: &[(&[u8], &[u8])], rereduce: bool) -> Result<Vec<u8>, view::Error> {
         let mappings = mappings
             .iter()
@@ -287,31 +482,10 @@ where
 
         V::serialize(&reduced_value).map_err(view::Error::from)
     }
-}
-
-pub trait IdGenerator: Debug + Send + Sync {
-    fn next_id(&self, id: Option<DocumentId>) -> Result<DocumentId, Error>;
-}
 
-#[derive_where(Default, Debug)]
-pub struct KeyIdGenerator<C: Collection>(PhantomData<C>);
-
-impl<C> IdGenerator for KeyIdGenerator<C>
-where
-    C: Collection,
-{
-    fn next_id(&self, id: Option<DocumentId>) -> Result<DocumentId, Error> {
-        let key = id.map(|id| id.deserialize::<C::PrimaryKey>()).transpose()?;
-        let key = if let Some(key) = key {
-            key
-        } else {
-            <C::PrimaryKey as Key<'_>>::first_value()
-                .map_err(|err| Error::DocumentPush(C::collection_name(), err))?
-        };
-        let next_value = key
-            .next_value()
-            .map_err(|err| Error::DocumentPush(C::collection_name(), err))?;
-        DocumentId::new(&next_value)
+    fn group_key(&self, key: &[u8], group_level: usize) -> Result<Vec<u8>, view::Error> {
+        <V::Key as Key>::encode_composite_prefix(ByteSource::Borrowed(key), group_level)
+            .map_err(view::Error::key_serialization)
     }
 }
 
@@ -325,9 +499,9 @@ fn schema_tests() -> anyhow::Result<()> {
         schema.collections_by_type_id[&TypeId::of::<Basic>()],
         Basic::collection_name()
     );
-    assert_eq!(schema.views.len(), 6);
+    assert_eq!(schema.views.len(), 8);
     assert_eq!(
-        schema.views[&TypeId::of::<BasicCount>()].view_name(),
+        schema.view::<BasicCount>()?.view_name(),
         View::view_name(&BasicCount)
     );
 
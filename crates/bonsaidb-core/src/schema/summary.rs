@@ -16,6 +16,7 @@ use crate::schema::{CollectionName, SchemaName, Schematic, ViewName};
 pub struct SchemaSummary {
     /// The name of the [`Schema`](crate::schema::Schema) this summary is of.
     pub name: SchemaName,
+    #[serde(with = "crate::schema::map_pairs")]
     collections: HashMap<CollectionName, CollectionSummary>,
 }
 
@@ -76,6 +77,7 @@ pub struct CollectionSummary {
     pub name: CollectionName,
     /// The description of [`Collection::PrimaryKey`](crate::schema::Collection::PrimaryKey).
     pub primary_key: KeyDescription,
+    #[serde(with = "crate::schema::map_pairs")]
     views: HashMap<ViewName, ViewSummary>,
 }
 
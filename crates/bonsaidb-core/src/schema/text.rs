@@ -0,0 +1,817 @@
+//! Full-text indexes.
+//!
+//! A [`TextIndex`] tokenizes text extracted from each document in a collection
+//! and stores it in an inverted index, which can be searched using
+//! [`TextQuery`]s. Search results are ranked using the [BM25 ranking
+//! function](https://en.wikipedia.org/wiki/Okapi_BM25).
+//!
+//! Text indexes are stored using a [`TextIndexView`], which means they are
+//! updated and persisted the same way any other view is. Searches are
+//! performed using view queries, which allows searching any
+//! [`Connection`]/[`AsyncConnection`], including remote connections using
+//! `bonsaidb-client`.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Debug;
+
+use derive_where::derive_where;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{AccessPolicy, AsyncConnection, Connection};
+use crate::document::{CollectionDocument, DocumentId, Emit, Header};
+use crate::schema::view::map::ViewMappings;
+use crate::schema::view::{
+    CollectionMapReduce, DefaultViewSerialization, ReduceResult, ViewMapResult, ViewSchema,
+    ViewUpdatePolicy,
+};
+use crate::schema::{Collection, Name, SerializedCollection, View, ViewMappedValue};
+use crate::Error;
+
+mod query;
+mod tokenizer;
+
+pub use self::query::TextQuery;
+#[cfg(feature = "stemming")]
+pub use self::tokenizer::Language;
+pub use self::tokenizer::{Token, Tokenizer, ENGLISH_STOP_WORDS};
+
+/// A full-text index of the text contained in documents from
+/// [`TextIndex::Collection`].
+///
+/// Once a text index is defined using
+/// [`Schematic::define_text_index()`](crate::schema::Schematic::define_text_index)
+/// or the `text_indexes` parameter of the `Collection` derive macro, it can be
+/// searched using [`TextIndex::search()`]/[`TextIndex::search_async()`].
+///
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// use bonsaidb_core::document::CollectionDocument;
+/// use bonsaidb_core::schema::text::{TextIndex, Tokenizer};
+/// use bonsaidb_core::schema::{Collection, Name};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Collection)]
+/// #[collection(name = "messages", text_indexes = [MessageText])]
+/// # #[collection(core = bonsaidb_core)]
+/// struct Message {
+///     pub subject: String,
+///     pub body: String,
+/// }
+///
+/// #[derive(Debug, Clone)]
+/// struct MessageText;
+///
+/// impl TextIndex for MessageText {
+///     type Collection = Message;
+///
+///     fn name(&self) -> Name {
+///         Name::new("text")
+///     }
+///
+///     fn tokenizer(&self) -> Tokenizer {
+///         Tokenizer::new().with_english_stop_words()
+///     }
+///
+///     fn text<'doc>(&self, document: &'doc CollectionDocument<Message>) -> Vec<Cow<'doc, str>> {
+///         vec![
+///             Cow::Borrowed(&document.contents.subject),
+///             Cow::Borrowed(&document.contents.body),
+///         ]
+///     }
+/// }
+/// ```
+///
+/// Once defined, the index can be searched:
+///
+/// ```rust
+/// # bonsaidb_core::__doctest_prelude!();
+/// # use std::borrow::Cow;
+/// # use bonsaidb_core::connection::Connection;
+/// # use bonsaidb_core::schema::text::TextIndex;
+/// # #[derive(Debug, Clone)]
+/// # struct MessageText;
+/// # impl TextIndex for MessageText {
+/// #     type Collection = MyCollection;
+/// #     fn name(&self) -> Name {
+/// #         Name::new("text")
+/// #     }
+/// #     fn text<'doc>(&self, document: &'doc CollectionDocument<MyCollection>) -> Vec<Cow<'doc, str>> {
+/// #         vec![Cow::Borrowed(&document.contents.name)]
+/// #     }
+/// # }
+/// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+/// for result in MessageText.search(db, "eggs -\"dozen eggs\"").limit(10).query()? {
+///     println!("Document {} has a score of {}", result.id, result.score);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait TextIndex: Debug + Clone + Send + Sync + 'static {
+    /// The collection containing the documents being indexed.
+    type Collection: SerializedCollection;
+
+    /// The name of this index. Must be unique among the views of
+    /// [`TextIndex::Collection`].
+    fn name(&self) -> Name;
+
+    /// Returns the [`Tokenizer`] used to tokenize documents and queries. The
+    /// default implementation returns [`Tokenizer::new()`].
+    fn tokenizer(&self) -> Tokenizer {
+        Tokenizer::new()
+    }
+
+    /// Returns the update policy for the index. The default implementation
+    /// returns [`ViewUpdatePolicy::Lazy`].
+    fn update_policy(&self) -> ViewUpdatePolicy {
+        ViewUpdatePolicy::Lazy
+    }
+
+    /// The version of the index. Changing this value will cause the index to
+    /// be rebuilt, which is required after changing [`TextIndex::text()`] or
+    /// [`TextIndex::tokenizer()`].
+    fn version(&self) -> u64 {
+        0
+    }
+
+    /// Returns the text to index from `document`. Each entry is tokenized
+    /// separately, which prevents phrases from matching across entries.
+    fn text<'doc>(
+        &self,
+        document: &'doc CollectionDocument<Self::Collection>,
+    ) -> Vec<Cow<'doc, str>>;
+
+    /// Returns a builder for searching this index using `query`. The query is
+    /// parsed using [`TextQuery::parse()`].
+    fn search<'a, Database: Connection>(
+        &self,
+        database: &'a Database,
+        query: &'a str,
+    ) -> TextSearch<'a, Database, Self> {
+        TextSearch::new(database, self.clone(), SearchQuery::Unparsed(query))
+    }
+
+    /// Returns a builder for searching this index using `query`.
+    fn search_with<Database: Connection>(
+        &self,
+        database: &Database,
+        query: TextQuery,
+    ) -> TextSearch<'_, Database, Self> {
+        TextSearch::new(database, self.clone(), SearchQuery::Parsed(query))
+    }
+
+    /// Returns a builder for searching this index using `query`. The query is
+    /// parsed using [`TextQuery::parse()`].
+    fn search_async<'a, Database: AsyncConnection>(
+        &self,
+        database: &'a Database,
+        query: &'a str,
+    ) -> AsyncTextSearch<'a, Database, Self> {
+        AsyncTextSearch::new(database, self.clone(), SearchQuery::Unparsed(query))
+    }
+
+    /// Returns a builder for searching this index using `query`.
+    fn search_with_async<Database: AsyncConnection>(
+        &self,
+        database: &Database,
+        query: TextQuery,
+    ) -> AsyncTextSearch<'_, Database, Self> {
+        AsyncTextSearch::new(database, self.clone(), SearchQuery::Parsed(query))
+    }
+}
+
+/// The view that stores a [`TextIndex`].
+///
+/// Each document is emitted once for each unique token it contains, using the
+/// token as the key. Each document is also emitted once with an empty key,
+/// which is used to gather statistics about the indexed documents.
+#[derive_where(Debug, Clone)]
+pub struct TextIndexView<I: TextIndex>(pub I);
+
+/// The value stored in a [`TextIndexView`].
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TextIndexEntry {
+    /// The number of documents this entry represents.
+    pub documents: u64,
+    /// The total number of tokens in the documents this entry represents.
+    pub tokens: u64,
+    /// The positions of the token within the document. This is empty for
+    /// reduced entries and for entries with an empty key.
+    pub positions: Vec<u32>,
+}
+
+impl<I> View for TextIndexView<I>
+where
+    I: TextIndex,
+{
+    type Collection = I::Collection;
+    type Key = String;
+    type Value = TextIndexEntry;
+
+    fn name(&self) -> Name {
+        self.0.name()
+    }
+}
+
+impl<I> ViewSchema for TextIndexView<I>
+where
+    I: TextIndex,
+{
+    type MappedKey<'doc> = String;
+    type View = Self;
+
+    fn update_policy(&self) -> ViewUpdatePolicy {
+        self.0.update_policy()
+    }
+
+    fn version(&self) -> u64 {
+        self.0.version()
+    }
+}
+
+impl<I> CollectionMapReduce for TextIndexView<I>
+where
+    I: TextIndex,
+{
+    fn map<'doc>(&self, document: CollectionDocument<I::Collection>) -> ViewMapResult<'doc, Self>
+    where
+        CollectionDocument<I::Collection>: 'doc,
+    {
+        let tokenizer = self.0.tokenizer();
+        let mut tokens = Vec::new();
+        let mut position = 0;
+        for text in self.0.text(&document) {
+            // Leave a gap between each entry so that phrases can't span them.
+            position = tokenizer
+                .tokenize_into(&text, position, &mut tokens)
+                .saturating_add(1);
+        }
+
+        let token_count = tokens.len() as u64;
+        let mut positions_by_token = BTreeMap::<String, Vec<u32>>::new();
+        for token in tokens {
+            positions_by_token
+                .entry(token.text)
+                .or_default()
+                .push(token.position);
+        }
+
+        let mut mappings = document.header.emit_key_and_value(
+            String::new(),
+            TextIndexEntry {
+                documents: 1,
+                tokens: token_count,
+                positions: Vec::new(),
+            },
+        )?;
+        for (token, positions) in positions_by_token {
+            mappings = mappings.and(document.header.emit_key_and_value(
+                token,
+                TextIndexEntry {
+                    documents: 1,
+                    tokens: token_count,
+                    positions,
+                },
+            )?);
+        }
+        Ok(mappings)
+    }
+
+    fn reduce(
+        &self,
+        mappings: &[ViewMappedValue<'_, Self>],
+        _rereduce: bool,
+    ) -> ReduceResult<Self::View> {
+        Ok(mappings
+            .iter()
+            .fold(TextIndexEntry::default(), |mut total, mapping| {
+                total.documents += mapping.value.documents;
+                total.tokens += mapping.value.tokens;
+                total
+            }))
+    }
+}
+
+impl<I> DefaultViewSerialization for TextIndexView<I> where I: TextIndex {}
+
+/// A document matched by a [`TextIndex`] search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextMatch<PrimaryKey> {
+    /// The id of the matched document.
+    pub id: PrimaryKey,
+    /// The BM25 relevance score of the document. Higher scores are more
+    /// relevant.
+    pub score: f32,
+}
+
+/// A list of [`TextMatch`]es for the index `I`, ordered from most relevant to
+/// least relevant.
+pub type TextMatches<I> = Vec<TextMatch<<<I as TextIndex>::Collection as Collection>::PrimaryKey>>;
+
+#[derive(Debug)]
+enum SearchQuery<'a> {
+    Unparsed(&'a str),
+    Parsed(TextQuery),
+}
+
+impl SearchQuery<'_> {
+    fn into_query(self) -> Result<TextQuery, Error> {
+        match self {
+            SearchQuery::Unparsed(query) => TextQuery::parse(query),
+            SearchQuery::Parsed(query) => Ok(query),
+        }
+    }
+}
+
+/// A full-text search of a [`TextIndex`].
+#[must_use]
+pub struct TextSearch<'a, Cn, I>
+where
+    I: TextIndex,
+{
+    connection: &'a Cn,
+    index: I,
+    query: SearchQuery<'a>,
+
+    /// The maximum number of results to return.
+    pub limit: Option<usize>,
+
+    /// The index's data access policy. The default value is
+    /// [`AccessPolicy::UpdateBefore`].
+    pub access_policy: AccessPolicy,
+}
+
+impl<'a, Cn, I> TextSearch<'a, Cn, I>
+where
+    Cn: Connection,
+    I: TextIndex,
+{
+    fn new(connection: &'a Cn, index: I, query: SearchQuery<'a>) -> Self {
+        Self {
+            connection,
+            index,
+            query,
+            limit: None,
+            access_policy: AccessPolicy::UpdateBefore,
+        }
+    }
+
+    /// Limits the results to the `maximum_results` most relevant documents.
+    pub const fn limit(mut self, maximum_results: usize) -> Self {
+        self.limit = Some(maximum_results);
+        self
+    }
+
+    /// Sets the access policy for the search.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Executes the search, returning the matching documents ordered from
+    /// most relevant to least relevant.
+    pub fn query(self) -> Result<TextMatches<I>, Error> {
+        let Some(plan) = SearchPlan::new(&self.index, &self.query.into_query()?) else {
+            return Ok(Vec::new());
+        };
+        let statistics = self
+            .connection
+            .view::<TextIndexView<I>>()
+            .with_access_policy(self.access_policy)
+            .with_key("")
+            .reduce()?;
+        let postings = if plan.tokens.is_empty() {
+            Vec::new()
+        } else {
+            self.connection
+                .view::<TextIndexView<I>>()
+                .with_access_policy(self.access_policy)
+                .with_keys(&plan.tokens)
+                .query()?
+        };
+        let all_documents = if plan.matches_unindexed_documents() {
+            Some(
+                self.connection
+                    .view::<TextIndexView<I>>()
+                    .with_access_policy(self.access_policy)
+                    .with_key("")
+                    .query()?,
+            )
+        } else {
+            None
+        };
+
+        plan.execute::<I>(&statistics, postings, all_documents, self.limit)
+    }
+}
+
+/// A full-text search of a [`TextIndex`].
+#[must_use]
+pub struct AsyncTextSearch<'a, Cn, I>
+where
+    I: TextIndex,
+{
+    connection: &'a Cn,
+    index: I,
+    query: SearchQuery<'a>,
+
+    /// The maximum number of results to return.
+    pub limit: Option<usize>,
+
+    /// The index's data access policy. The default value is
+    /// [`AccessPolicy::UpdateBefore`].
+    pub access_policy: AccessPolicy,
+}
+
+impl<'a, Cn, I> AsyncTextSearch<'a, Cn, I>
+where
+    Cn: AsyncConnection,
+    I: TextIndex,
+{
+    fn new(connection: &'a Cn, index: I, query: SearchQuery<'a>) -> Self {
+        Self {
+            connection,
+            index,
+            query,
+            limit: None,
+            access_policy: AccessPolicy::UpdateBefore,
+        }
+    }
+
+    /// Limits the results to the `maximum_results` most relevant documents.
+    pub const fn limit(mut self, maximum_results: usize) -> Self {
+        self.limit = Some(maximum_results);
+        self
+    }
+
+    /// Sets the access policy for the search.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Executes the search, returning the matching documents ordered from
+    /// most relevant to least relevant.
+    pub async fn query(self) -> Result<TextMatches<I>, Error> {
+        let Some(plan) = SearchPlan::new(&self.index, &self.query.into_query()?) else {
+            return Ok(Vec::new());
+        };
+        let statistics = self
+            .connection
+            .view::<TextIndexView<I>>()
+            .with_access_policy(self.access_policy)
+            .with_key("")
+            .reduce()
+            .await?;
+        let postings = if plan.tokens.is_empty() {
+            Vec::new()
+        } else {
+            self.connection
+                .view::<TextIndexView<I>>()
+                .with_access_policy(self.access_policy)
+                .with_keys(&plan.tokens)
+                .query()
+                .await?
+        };
+        let all_documents = if plan.matches_unindexed_documents() {
+            Some(
+                self.connection
+                    .view::<TextIndexView<I>>()
+                    .with_access_policy(self.access_policy)
+                    .with_key("")
+                    .query()
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        plan.execute::<I>(&statistics, postings, all_documents, self.limit)
+    }
+}
+
+/// A [`TextQuery`] whose text has been tokenized.
+#[derive(Debug)]
+enum TokenizedQuery {
+    Tokens(Vec<String>),
+    Phrase(Vec<Token>),
+    And(Vec<TokenizedQuery>),
+    Or(Vec<TokenizedQuery>),
+    Not(Box<TokenizedQuery>),
+}
+
+impl TokenizedQuery {
+    /// Tokenizes `query`, returning `None` if the query contains no tokens.
+    /// `scored_tokens` is populated with every token that isn't negated.
+    fn new(
+        query: &TextQuery,
+        tokenizer: &Tokenizer,
+        negated: bool,
+        scored_tokens: &mut BTreeSet<String>,
+    ) -> Option<Self> {
+        let mut tokenize = |text: &str| {
+            let tokens = tokenizer.tokenize(text);
+            if !negated {
+                scored_tokens.extend(tokens.iter().map(|token| token.text.clone()));
+            }
+            tokens
+        };
+        let combine = |queries: &[TextQuery],
+                       scored_tokens: &mut BTreeSet<String>,
+                       operator: fn(Vec<Self>) -> Self| {
+            let mut queries = queries
+                .iter()
+                .filter_map(|query| Self::new(query, tokenizer, negated, scored_tokens))
+                .collect::<Vec<_>>();
+            match queries.len() {
+                0 => None,
+                1 => queries.pop(),
+                _ => Some(operator(queries)),
+            }
+        };
+
+        match query {
+            TextQuery::Term(text) => {
+                let tokens = tokenize(text);
+                (!tokens.is_empty())
+                    .then(|| Self::Tokens(tokens.into_iter().map(|token| token.text).collect()))
+            }
+            TextQuery::Phrase(text) => {
+                let tokens = tokenize(text);
+                match tokens.len() {
+                    0 => None,
+                    1 => Some(Self::Tokens(
+                        tokens.into_iter().map(|token| token.text).collect(),
+                    )),
+                    _ => Some(Self::Phrase(tokens)),
+                }
+            }
+            TextQuery::And(queries) => combine(queries, scored_tokens, Self::And),
+            TextQuery::Or(queries) => combine(queries, scored_tokens, Self::Or),
+            TextQuery::Not(query) => Self::new(query, tokenizer, !negated, scored_tokens)
+                .map(|query| Self::Not(Box::new(query))),
+        }
+    }
+
+    fn collect_tokens(&self, tokens: &mut BTreeSet<String>) {
+        match self {
+            Self::Tokens(query_tokens) => tokens.extend(query_tokens.iter().cloned()),
+            Self::Phrase(query_tokens) => {
+                tokens.extend(query_tokens.iter().map(|token| token.text.clone()));
+            }
+            Self::And(queries) | Self::Or(queries) => {
+                for query in queries {
+                    query.collect_tokens(tokens);
+                }
+            }
+            Self::Not(query) => query.collect_tokens(tokens),
+        }
+    }
+
+    /// Returns true if this query's matches are represented by
+    /// [`Matches::AllExcept`].
+    fn matches_unindexed_documents(&self) -> bool {
+        match self {
+            Self::Tokens(_) | Self::Phrase(_) => false,
+            Self::And(queries) => queries.iter().all(Self::matches_unindexed_documents),
+            Self::Or(queries) => queries.iter().any(Self::matches_unindexed_documents),
+            Self::Not(query) => !query.matches_unindexed_documents(),
+        }
+    }
+
+    fn evaluate(&self, postings: &Postings) -> Matches {
+        match self {
+            Self::Tokens(tokens) => {
+                let mut tokens = tokens.iter();
+                let mut documents = tokens
+                    .next()
+                    .map(|token| postings.documents_containing(token))
+                    .unwrap_or_default();
+                for token in tokens {
+                    let containing = postings.documents_containing(token);
+                    documents.retain(|id| containing.contains(id));
+                }
+                Matches::Only(documents)
+            }
+            Self::Phrase(tokens) => Matches::Only(postings.documents_containing_phrase(tokens)),
+            Self::And(queries) => queries
+                .iter()
+                .map(|query| query.evaluate(postings))
+                .reduce(Matches::and)
+                .unwrap_or_default(),
+            Self::Or(queries) => queries
+                .iter()
+                .map(|query| query.evaluate(postings))
+                .reduce(Matches::or)
+                .unwrap_or_default(),
+            Self::Not(query) => query.evaluate(postings).not(),
+        }
+    }
+}
+
+/// A set of documents matched by a query.
+#[derive(Debug)]
+enum Matches {
+    /// Only the contained documents match.
+    Only(BTreeSet<DocumentId>),
+    /// Every document matches except the contained documents.
+    AllExcept(BTreeSet<DocumentId>),
+}
+
+impl Default for Matches {
+    fn default() -> Self {
+        Self::Only(BTreeSet::new())
+    }
+}
+
+impl Matches {
+    fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Only(a), Self::Only(b)) => Self::Only(a.intersection(&b).cloned().collect()),
+            (Self::Only(mut only), Self::AllExcept(except))
+            | (Self::AllExcept(except), Self::Only(mut only)) => {
+                only.retain(|id| !except.contains(id));
+                Self::Only(only)
+            }
+            (Self::AllExcept(mut a), Self::AllExcept(b)) => {
+                a.extend(b);
+                Self::AllExcept(a)
+            }
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Only(mut a), Self::Only(b)) => {
+                a.extend(b);
+                Self::Only(a)
+            }
+            (Self::Only(only), Self::AllExcept(mut except))
+            | (Self::AllExcept(mut except), Self::Only(only)) => {
+                except.retain(|id| !only.contains(id));
+                Self::AllExcept(except)
+            }
+            (Self::AllExcept(a), Self::AllExcept(b)) => {
+                Self::AllExcept(a.intersection(&b).cloned().collect())
+            }
+        }
+    }
+
+    fn not(self) -> Self {
+        match self {
+            Self::Only(documents) => Self::AllExcept(documents),
+            Self::AllExcept(documents) => Self::Only(documents),
+        }
+    }
+}
+
+/// The indexed entries for the tokens in a query.
+#[derive(Default)]
+struct Postings(HashMap<String, BTreeMap<DocumentId, TextIndexEntry>>);
+
+impl Postings {
+    fn documents_containing(&self, token: &str) -> BTreeSet<DocumentId> {
+        self.0
+            .get(token)
+            .map(|documents| documents.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn documents_containing_phrase(&self, tokens: &[Token]) -> BTreeSet<DocumentId> {
+        let (first, rest) = tokens.split_first().expect("phrases have multiple tokens");
+        let Some(candidates) = self.0.get(&first.text) else {
+            return BTreeSet::new();
+        };
+        candidates
+            .iter()
+            .filter(|(id, entry)| {
+                entry.positions.iter().any(|&start| {
+                    rest.iter().all(|token| {
+                        let Some(position) = start.checked_add(token.position - first.position)
+                        else {
+                            return false;
+                        };
+                        self.0
+                            .get(&token.text)
+                            .and_then(|documents| documents.get(*id))
+                            .is_some_and(|entry| entry.positions.binary_search(&position).is_ok())
+                    })
+                })
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+struct SearchPlan {
+    query: TokenizedQuery,
+    tokens: Vec<String>,
+    scored_tokens: BTreeSet<String>,
+}
+
+impl SearchPlan {
+    const B: f32 = 0.75;
+    const K1: f32 = 1.2;
+
+    fn new<I: TextIndex>(index: &I, query: &TextQuery) -> Option<Self> {
+        let tokenizer = index.tokenizer();
+        let mut scored_tokens = BTreeSet::new();
+        let query = TokenizedQuery::new(query, &tokenizer, false, &mut scored_tokens)?;
+        let mut tokens = BTreeSet::new();
+        query.collect_tokens(&mut tokens);
+        Some(Self {
+            query,
+            tokens: tokens.into_iter().collect(),
+            scored_tokens,
+        })
+    }
+
+    fn matches_unindexed_documents(&self) -> bool {
+        self.query.matches_unindexed_documents()
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn execute<I: TextIndex>(
+        self,
+        statistics: &TextIndexEntry,
+        postings: ViewMappings<TextIndexView<I>>,
+        all_documents: Option<ViewMappings<TextIndexView<I>>>,
+        limit: Option<usize>,
+    ) -> Result<TextMatches<I>, Error> {
+        let mut by_token = Postings::default();
+        for mapping in postings {
+            let id = Header::try_from(mapping.source)?.id;
+            by_token
+                .0
+                .entry(mapping.key)
+                .or_default()
+                .insert(id, mapping.value);
+        }
+
+        let matched = match self.query.evaluate(&by_token) {
+            Matches::Only(documents) => documents,
+            Matches::AllExcept(excluded) => {
+                let mut documents = BTreeSet::new();
+                for mapping in all_documents.unwrap_or_default() {
+                    let id = Header::try_from(mapping.source)?.id;
+                    if !excluded.contains(&id) {
+                        documents.insert(id);
+                    }
+                }
+                documents
+            }
+        };
+
+        let document_count = statistics.documents as f32;
+        let average_length = if statistics.documents > 0 {
+            statistics.tokens as f32 / document_count
+        } else {
+            0.
+        };
+        let mut results = matched
+            .into_iter()
+            .map(|id| {
+                let mut score = 0.;
+                for token in &self.scored_tokens {
+                    let Some(documents) = by_token.0.get(token) else {
+                        continue;
+                    };
+                    let Some(entry) = documents.get(&id) else {
+                        continue;
+                    };
+                    let containing = documents.len() as f32;
+                    let inverse_document_frequency =
+                        ((document_count - containing + 0.5) / (containing + 0.5)).ln_1p();
+                    let frequency = entry.positions.len() as f32;
+                    let length_ratio = if average_length > 0. {
+                        entry.tokens as f32 / average_length
+                    } else {
+                        1.
+                    };
+                    score += inverse_document_frequency * frequency * (Self::K1 + 1.)
+                        / (frequency + Self::K1 * (1. - Self::B + Self::B * length_ratio));
+                }
+                (id, score)
+            })
+            .collect::<Vec<_>>();
+        results.sort_by(|(left_id, left_score), (right_id, right_score)| {
+            right_score
+                .total_cmp(left_score)
+                .then_with(|| left_id.cmp(right_id))
+        });
+        if let Some(limit) = limit {
+            results.truncate(limit);
+        }
+
+        results
+            .into_iter()
+            .map(|(id, score)| {
+                Ok(TextMatch {
+                    id: id.deserialize()?,
+                    score,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,257 @@
+use std::str::FromStr;
+
+use crate::Error;
+
+/// A full-text search query.
+///
+/// Queries can be constructed directly or parsed from a string using
+/// [`TextQuery::parse()`]. Text contained in a query is tokenized using the
+/// index's [`Tokenizer`](super::Tokenizer) before searching. Text that doesn't
+/// produce any tokens, such as a stop word, is ignored.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TextQuery {
+    /// Matches documents that contain every token in the text.
+    Term(String),
+    /// Matches documents that contain the tokens in the text adjacent to each
+    /// other, in the same order.
+    Phrase(String),
+    /// Matches documents that match every query.
+    And(Vec<TextQuery>),
+    /// Matches documents that match at least one query.
+    Or(Vec<TextQuery>),
+    /// Matches documents that don't match the query.
+    Not(Box<TextQuery>),
+}
+
+impl TextQuery {
+    /// Parses `query`.
+    ///
+    /// The query syntax supports:
+    ///
+    /// - Words: `milk` matches documents containing "milk".
+    /// - Phrases: `"dozen eggs"` matches documents containing "dozen"
+    ///   immediately followed by "eggs".
+    /// - `AND`: `milk AND eggs` matches documents containing both words.
+    ///   Adjacent queries without an operator are also combined using `AND`:
+    ///   `milk eggs` is the same query.
+    /// - `OR`: `milk OR eggs` matches documents containing either word. `AND`
+    ///   takes precedence over `OR`.
+    /// - `NOT` or `-`: `milk NOT eggs` and `milk -eggs` match documents
+    ///   containing "milk" but not "eggs".
+    /// - Parentheses: `milk (eggs OR bread)` groups queries.
+    ///
+    /// Operators must be uppercase. Lowercase operators are searched for as
+    /// words.
+    ///
+    /// ```rust
+    /// use bonsaidb_core::schema::text::TextQuery;
+    ///
+    /// assert_eq!(
+    ///     TextQuery::parse("milk -\"skim milk\"").unwrap(),
+    ///     TextQuery::And(vec![
+    ///         TextQuery::Term(String::from("milk")),
+    ///         TextQuery::Not(Box::new(TextQuery::Phrase(String::from("skim milk")))),
+    ///     ])
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidTextQuery`] if `query` is empty or isn't a valid
+    /// query.
+    pub fn parse(query: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            tokens: lex(query)?,
+            position: 0,
+        };
+        if parser.tokens.is_empty() {
+            return Err(invalid("the query is empty"));
+        }
+
+        let query = parser.parse_or()?;
+        if parser.position < parser.tokens.len() {
+            return Err(invalid("unexpected `)`"));
+        }
+        Ok(query)
+    }
+}
+
+impl FromStr for TextQuery {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum QueryToken<'a> {
+    Word(&'a str),
+    Phrase(&'a str),
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidTextQuery(message.to_string())
+}
+
+fn lex(query: &str) -> Result<Vec<QueryToken<'_>>, Error> {
+    let mut tokens = Vec::new();
+    let mut remaining = query.trim_start();
+    while let Some(ch) = remaining.chars().next() {
+        let rest = &remaining[ch.len_utf8()..];
+        remaining = match ch {
+            '(' => {
+                tokens.push(QueryToken::Open);
+                rest
+            }
+            ')' => {
+                tokens.push(QueryToken::Close);
+                rest
+            }
+            '"' => {
+                let end = rest
+                    .find('"')
+                    .ok_or_else(|| invalid("a phrase is missing its closing `\"`"))?;
+                tokens.push(QueryToken::Phrase(&rest[..end]));
+                &rest[end + 1..]
+            }
+            '-' if rest.starts_with(|ch: char| !ch.is_whitespace()) => {
+                tokens.push(QueryToken::Not);
+                rest
+            }
+            _ => {
+                let end = remaining
+                    .find(|ch: char| ch.is_whitespace() || matches!(ch, '(' | ')' | '"'))
+                    .unwrap_or(remaining.len());
+                tokens.push(match &remaining[..end] {
+                    "AND" => QueryToken::And,
+                    "OR" => QueryToken::Or,
+                    "NOT" => QueryToken::Not,
+                    word => QueryToken::Word(word),
+                });
+                &remaining[end..]
+            }
+        }
+        .trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<QueryToken<'a>>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<QueryToken<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn advance(&mut self) -> Option<QueryToken<'a>> {
+        let token = self.peek();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<TextQuery, Error> {
+        let mut queries = vec![self.parse_and()?];
+        while self.peek() == Some(QueryToken::Or) {
+            self.position += 1;
+            queries.push(self.parse_and()?);
+        }
+        Ok(combine(queries, TextQuery::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<TextQuery, Error> {
+        let mut queries = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(QueryToken::And) => {
+                    self.position += 1;
+                }
+                Some(
+                    QueryToken::Word(_)
+                    | QueryToken::Phrase(_)
+                    | QueryToken::Open
+                    | QueryToken::Not,
+                ) => {}
+                Some(QueryToken::Or | QueryToken::Close) | None => break,
+            }
+            queries.push(self.parse_unary()?);
+        }
+        Ok(combine(queries, TextQuery::And))
+    }
+
+    fn parse_unary(&mut self) -> Result<TextQuery, Error> {
+        match self.advance() {
+            Some(QueryToken::Word(word)) => Ok(TextQuery::Term(word.to_string())),
+            Some(QueryToken::Phrase(phrase)) => Ok(TextQuery::Phrase(phrase.to_string())),
+            Some(QueryToken::Not) => Ok(TextQuery::Not(Box::new(self.parse_unary()?))),
+            Some(QueryToken::Open) => {
+                let query = self.parse_or()?;
+                if self.advance() == Some(QueryToken::Close) {
+                    Ok(query)
+                } else {
+                    Err(invalid("a group is missing its closing `)`"))
+                }
+            }
+            Some(QueryToken::Close) => Err(invalid("unexpected `)`")),
+            Some(QueryToken::And | QueryToken::Or) => {
+                Err(invalid("an operator is missing its left side"))
+            }
+            None => Err(invalid("the query ends with an operator")),
+        }
+    }
+}
+
+fn combine(mut queries: Vec<TextQuery>, operator: fn(Vec<TextQuery>) -> TextQuery) -> TextQuery {
+    if queries.len() == 1 {
+        queries.pop().expect("length checked")
+    } else {
+        operator(queries)
+    }
+}
+
+#[test]
+fn parse_tests() {
+    fn term(text: &str) -> TextQuery {
+        TextQuery::Term(text.to_string())
+    }
+
+    assert_eq!(TextQuery::parse("milk").unwrap(), term("milk"));
+    assert_eq!(
+        TextQuery::parse("milk eggs AND bread OR -cheese").unwrap(),
+        TextQuery::Or(vec![
+            TextQuery::And(vec![term("milk"), term("eggs"), term("bread")]),
+            TextQuery::Not(Box::new(term("cheese"))),
+        ])
+    );
+    assert_eq!(
+        TextQuery::parse("NOT (milk OR \"2% milk\") and").unwrap(),
+        TextQuery::And(vec![
+            TextQuery::Not(Box::new(TextQuery::Or(vec![
+                term("milk"),
+                TextQuery::Phrase(String::from("2% milk")),
+            ]))),
+            term("and"),
+        ])
+    );
+    assert_eq!(
+        TextQuery::parse("e-mail - x").unwrap(),
+        TextQuery::And(vec![term("e-mail"), term("-"), term("x")])
+    );
+
+    for invalid in ["", "  ", "milk OR", "AND milk", "(milk", "milk)", "\"milk"] {
+        assert!(
+            matches!(TextQuery::parse(invalid), Err(Error::InvalidTextQuery(_))),
+            "{invalid:?} parsed"
+        );
+    }
+}
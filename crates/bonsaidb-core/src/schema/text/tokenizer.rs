@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+
+/// A list of common English words that are typically excluded from full-text
+/// indexes.
+pub const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+/// A normalized word produced by a [`Tokenizer`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Token {
+    /// The normalized text of this token.
+    pub text: String,
+    /// The position of the word that produced this token within the source
+    /// text.
+    pub position: u32,
+}
+
+/// Splits text into normalized [`Token`]s for a full-text index.
+///
+/// Text is split into words at every character that isn't alphanumeric. Each
+/// word is then:
+///
+/// - Converted to lowercase, unless [`case_sensitive()`](Self::case_sensitive)
+///   was used.
+/// - Discarded if it is shorter than the [minimum
+///   length](Self::with_min_length), longer than the [maximum
+///   length](Self::with_max_length), or is a [stop
+///   word](Self::with_stop_words).
+/// - Reduced to its stem, if stemming has been enabled using
+///   `with_stemming()`. Stemming requires the `stemming` feature.
+///
+/// Discarded words still occupy a position, which ensures phrases only match
+/// words that are adjacent in the original text.
+///
+/// The same tokenizer is used to index documents and to parse queries.
+/// Changing the configuration of an existing index requires changing
+/// [`TextIndex::version()`](super::TextIndex::version) so that the index is
+/// rebuilt.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct Tokenizer {
+    case_sensitive: bool,
+    min_length: usize,
+    max_length: usize,
+    stop_words: HashSet<String>,
+    #[cfg(feature = "stemming")]
+    stemming: Option<Language>,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tokenizer {
+    /// Returns a tokenizer that converts words to lowercase, discards words
+    /// longer than 64 characters, and has no stop words.
+    pub fn new() -> Self {
+        Self {
+            case_sensitive: false,
+            min_length: 1,
+            max_length: 64,
+            stop_words: HashSet::new(),
+            #[cfg(feature = "stemming")]
+            stemming: None,
+        }
+    }
+
+    /// Preserves the case of words rather than converting them to lowercase.
+    pub fn case_sensitive(mut self) -> Self {
+        self.case_sensitive = true;
+        self
+    }
+
+    /// Discards words containing fewer than `min_length` characters.
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Discards words containing more than `max_length` characters.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Discards each word in `words`. Stop words are compared after words
+    /// have been converted to lowercase, but before stemming.
+    pub fn with_stop_words<Words, Word>(mut self, words: Words) -> Self
+    where
+        Words: IntoIterator<Item = Word>,
+        Word: Into<String>,
+    {
+        self.stop_words.extend(words.into_iter().map(Into::into));
+        self
+    }
+
+    /// Discards each word in [`ENGLISH_STOP_WORDS`].
+    pub fn with_english_stop_words(self) -> Self {
+        self.with_stop_words(ENGLISH_STOP_WORDS.iter().copied())
+    }
+
+    /// Reduces each word to its stem using the Snowball stemming algorithm
+    /// for `language`. For example, "fishing", "fished", and "fisher" are all
+    /// reduced to "fish" in [`Language::English`].
+    #[cfg(feature = "stemming")]
+    pub fn with_stemming(mut self, language: Language) -> Self {
+        self.stemming = Some(language);
+        self
+    }
+
+    /// Returns the tokens contained in `text`.
+    #[must_use]
+    pub fn tokenize(&self, text: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        self.tokenize_into(text, 0, &mut tokens);
+        tokens
+    }
+
+    /// Pushes the tokens contained in `text` to `tokens`, with positions
+    /// starting at `first_position`. Returns the position after the last word
+    /// in `text`.
+    pub(crate) fn tokenize_into(
+        &self,
+        text: &str,
+        first_position: u32,
+        tokens: &mut Vec<Token>,
+    ) -> u32 {
+        #[cfg(feature = "stemming")]
+        let stemmer = self
+            .stemming
+            .map(|language| rust_stemmers::Stemmer::create(language.algorithm()));
+        let mut position = first_position;
+        for word in text
+            .split(|ch: char| !ch.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            let word_position = position;
+            position = position.saturating_add(1);
+
+            let length = word.chars().count();
+            if length < self.min_length || length > self.max_length {
+                continue;
+            }
+            let word = if self.case_sensitive {
+                word.to_string()
+            } else {
+                word.to_lowercase()
+            };
+            if self.stop_words.contains(&word) {
+                continue;
+            }
+            #[cfg(feature = "stemming")]
+            let word = match &stemmer {
+                Some(stemmer) => stemmer.stem(&word).into_owned(),
+                None => word,
+            };
+            tokens.push(Token {
+                text: word,
+                position: word_position,
+            });
+        }
+        position
+    }
+}
+
+/// A language supported by [`Tokenizer::with_stemming()`].
+#[cfg(feature = "stemming")]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Language {
+    /// Danish
+    Danish,
+    /// Dutch
+    Dutch,
+    /// English
+    English,
+    /// Finnish
+    Finnish,
+    /// French
+    French,
+    /// German
+    German,
+    /// Hungarian
+    Hungarian,
+    /// Italian
+    Italian,
+    /// Norwegian
+    Norwegian,
+    /// Portuguese
+    Portuguese,
+    /// Romanian
+    Romanian,
+    /// Russian
+    Russian,
+    /// Spanish
+    Spanish,
+    /// Swedish
+    Swedish,
+    /// Turkish
+    Turkish,
+}
+
+#[cfg(feature = "stemming")]
+impl Language {
+    const fn algorithm(self) -> rust_stemmers::Algorithm {
+        match self {
+            Self::Danish => rust_stemmers::Algorithm::Danish,
+            Self::Dutch => rust_stemmers::Algorithm::Dutch,
+            Self::English => rust_stemmers::Algorithm::English,
+            Self::Finnish => rust_stemmers::Algorithm::Finnish,
+            Self::French => rust_stemmers::Algorithm::French,
+            Self::German => rust_stemmers::Algorithm::German,
+            Self::Hungarian => rust_stemmers::Algorithm::Hungarian,
+            Self::Italian => rust_stemmers::Algorithm::Italian,
+            Self::Norwegian => rust_stemmers::Algorithm::Norwegian,
+            Self::Portuguese => rust_stemmers::Algorithm::Portuguese,
+            Self::Romanian => rust_stemmers::Algorithm::Romanian,
+            Self::Russian => rust_stemmers::Algorithm::Russian,
+            Self::Spanish => rust_stemmers::Algorithm::Spanish,
+            Self::Swedish => rust_stemmers::Algorithm::Swedish,
+            Self::Turkish => rust_stemmers::Algorithm::Turkish,
+        }
+    }
+}
+
+#[test]
+fn tokenizer_tests() {
+    let tokens = Tokenizer::new()
+        .with_english_stop_words()
+        .with_max_length(10)
+        .tokenize("The Bank of América, e-mail: supercalifragilistic!");
+    assert_eq!(
+        tokens,
+        vec![
+            Token {
+                text: String::from("bank"),
+                position: 1,
+            },
+            Token {
+                text: String::from("américa"),
+                position: 3,
+            },
+            Token {
+                text: String::from("e"),
+                position: 4,
+            },
+            Token {
+                text: String::from("mail"),
+                position: 5,
+            },
+        ]
+    );
+
+    let tokens = Tokenizer::new()
+        .case_sensitive()
+        .with_min_length(2)
+        .tokenize("A Quick fox");
+    let texts = tokens
+        .iter()
+        .map(|token| token.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(texts, ["Quick", "fox"]);
+}
+
+#[test]
+#[cfg(feature = "stemming")]
+fn stemming_tests() {
+    let tokens = Tokenizer::new()
+        .with_stemming(Language::English)
+        .tokenize("Fishing fished");
+    let texts = tokens
+        .iter()
+        .map(|token| token.text.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(texts, ["fish", "fish"]);
+}
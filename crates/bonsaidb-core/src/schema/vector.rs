@@ -0,0 +1,574 @@
+//! Vector similarity indexes.
+//!
+//! A [`VectorIndex`] extracts a fixed-length vector, such as a text embedding,
+//! from each document in a collection. The vectors are stored in a
+//! [`VectorIndexView`], which is updated eagerly by default, and can be
+//! searched for the documents whose vectors are nearest to a query vector.
+//!
+//! Searches are exact by default: the query vector is compared to every
+//! vector in the index. For large indexes, an [`Hnsw`] graph can be used to
+//! perform approximate searches that only compare a small portion of the
+//! vectors. Both kinds of searches are performed using the
+//! [`Connection`]/[`AsyncConnection`] traits, which allows searching remote
+//! connections using `bonsaidb-client`.
+
+use std::fmt::Debug;
+
+use derive_where::derive_where;
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{AccessPolicy, AsyncConnection, Connection, Range, RangeRef};
+use crate::document::{CollectionDocument, Emit};
+use crate::key::Key;
+use crate::schema::view::map::{Mappings, ViewMappings};
+use crate::schema::view::{CollectionMapReduce, DefaultViewSerialization, ViewSchema};
+use crate::schema::view::{ViewMapResult, ViewUpdatePolicy};
+use crate::schema::{Collection, Name, SerializedCollection, View};
+use crate::Error;
+
+mod hnsw;
+
+pub use self::hnsw::{Hnsw, HnswParameters};
+
+/// The default number of results returned by a [`VectorSearch`].
+pub const DEFAULT_VECTOR_SEARCH_LIMIT: usize = 10;
+
+/// An index of vectors extracted from documents in
+/// [`VectorIndex::Collection`].
+///
+/// Once a vector index is defined using
+/// [`Schematic::define_vector_index()`](crate::schema::Schematic::define_vector_index)
+/// or the `vector_indexes` parameter of the `Collection` derive macro, it can
+/// be searched using [`VectorIndex::search()`]/[`VectorIndex::search_async()`].
+///
+/// ```rust
+/// use bonsaidb_core::document::CollectionDocument;
+/// use bonsaidb_core::schema::vector::{Metric, VectorIndex};
+/// use bonsaidb_core::schema::{Collection, Name};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Collection)]
+/// #[collection(name = "articles", vector_indexes = [ArticleEmbeddings])]
+/// # #[collection(core = bonsaidb_core)]
+/// struct Article {
+///     pub language: String,
+///     pub embedding: Vec<f32>,
+/// }
+///
+/// #[derive(Debug, Clone)]
+/// struct ArticleEmbeddings;
+///
+/// impl VectorIndex for ArticleEmbeddings {
+///     type Collection = Article;
+///     type Key = String;
+///
+///     fn name(&self) -> Name {
+///         Name::new("embeddings")
+///     }
+///
+///     fn dimensions(&self) -> usize {
+///         384
+///     }
+///
+///     fn metric(&self) -> Metric {
+///         Metric::Cosine
+///     }
+///
+///     fn vector(&self, document: &CollectionDocument<Article>) -> Option<(String, Vec<f32>)> {
+///         Some((
+///             document.contents.language.clone(),
+///             document.contents.embedding.clone(),
+///         ))
+///     }
+/// }
+/// ```
+///
+/// Once defined, the index can be searched:
+///
+/// ```rust
+/// # bonsaidb_core::__doctest_prelude!();
+/// # use bonsaidb_core::connection::Connection;
+/// # use bonsaidb_core::schema::vector::VectorIndex;
+/// # #[derive(Debug, Clone)]
+/// # struct ArticleEmbeddings;
+/// # impl VectorIndex for ArticleEmbeddings {
+/// #     type Collection = MyCollection;
+/// #     type Key = String;
+/// #     fn name(&self) -> Name {
+/// #         Name::new("embeddings")
+/// #     }
+/// #     fn dimensions(&self) -> usize {
+/// #         3
+/// #     }
+/// #     fn vector(&self, _document: &CollectionDocument<MyCollection>) -> Option<(String, Vec<f32>)> {
+/// #         None
+/// #     }
+/// # }
+/// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+/// let query = [0.2, 0.1, 0.7];
+/// for result in ArticleEmbeddings
+///     .search(db, &query)
+///     .with_key_range(String::from("en")..=String::from("en"))
+///     .limit(5)
+///     .query()?
+/// {
+///     println!("Document {} is {} away", result.id, result.distance);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait VectorIndex: Debug + Clone + Send + Sync + 'static {
+    /// The collection containing the documents being indexed.
+    type Collection: SerializedCollection;
+
+    /// The key stored with each vector. Searches can be limited to a range of
+    /// keys using [`VectorSearch::with_key_range()`]. Use `()` if searches
+    /// don't need to be filtered.
+    type Key: for<'k> Key<'k> + PartialEq + Clone + Debug + Send + Sync + 'static;
+
+    /// The name of this index. Must be unique among the views of
+    /// [`VectorIndex::Collection`].
+    fn name(&self) -> Name;
+
+    /// The number of dimensions of each vector in this index. Storing or
+    /// searching for a vector with a different number of dimensions results
+    /// in [`Error::InvalidVectorDimensions`].
+    fn dimensions(&self) -> usize;
+
+    /// Returns the metric used to measure the distance between vectors. The
+    /// default implementation returns [`Metric::Cosine`].
+    fn metric(&self) -> Metric {
+        Metric::Cosine
+    }
+
+    /// Returns the update policy for the index. The default implementation
+    /// returns [`ViewUpdatePolicy::Eager`], which ensures vectors with the
+    /// wrong number of dimensions are rejected when documents are saved.
+    fn update_policy(&self) -> ViewUpdatePolicy {
+        ViewUpdatePolicy::Eager
+    }
+
+    /// The version of the index. Changing this value will cause the index to
+    /// be rebuilt, which is required after changing [`VectorIndex::vector()`]
+    /// or [`VectorIndex::dimensions()`].
+    fn version(&self) -> u64 {
+        0
+    }
+
+    /// Returns the key and vector to index for `document`, or `None` if the
+    /// document shouldn't be indexed.
+    fn vector(
+        &self,
+        document: &CollectionDocument<Self::Collection>,
+    ) -> Option<(Self::Key, Vec<f32>)>;
+
+    /// Returns a builder for finding the documents with vectors nearest to
+    /// `vector`.
+    fn search<'a, Database: Connection>(
+        &self,
+        database: &'a Database,
+        vector: &'a [f32],
+    ) -> VectorSearch<'a, Database, Self> {
+        VectorSearch::new(database, self.clone(), vector)
+    }
+
+    /// Returns a builder for finding the documents with vectors nearest to
+    /// `vector`.
+    fn search_async<'a, Database: AsyncConnection>(
+        &self,
+        database: &'a Database,
+        vector: &'a [f32],
+    ) -> AsyncVectorSearch<'a, Database, Self> {
+        AsyncVectorSearch::new(database, self.clone(), vector)
+    }
+}
+
+/// A method of measuring the distance between two vectors. Smaller distances
+/// indicate more similar vectors.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Metric {
+    /// One minus the cosine similarity of the vectors. Vectors pointing in the
+    /// same direction have a distance of 0, regardless of their magnitude.
+    Cosine,
+    /// The negated dot product of the vectors. For vectors that are
+    /// normalized, this produces the same order as [`Metric::Cosine`] while
+    /// requiring less computation.
+    DotProduct,
+    /// The Euclidean (L2) distance between the vectors.
+    Euclidean,
+}
+
+impl Metric {
+    /// Returns the distance between `a` and `b` using this metric.
+    ///
+    /// ```rust
+    /// use bonsaidb_core::schema::vector::Metric;
+    ///
+    /// assert_eq!(Metric::Euclidean.distance(&[0., 0.], &[3., 4.]), 5.);
+    /// assert_eq!(Metric::Cosine.distance(&[1., 0.], &[2., 0.]), 0.);
+    /// ```
+    #[must_use]
+    pub fn distance(self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            Self::Cosine => {
+                let (mut dot, mut a_norm, mut b_norm) = (0., 0., 0.);
+                for (a, b) in a.iter().zip(b) {
+                    dot += a * b;
+                    a_norm += a * a;
+                    b_norm += b * b;
+                }
+                let magnitude = (a_norm * b_norm).sqrt();
+                if magnitude > 0. {
+                    1. - dot / magnitude
+                } else {
+                    1.
+                }
+            }
+            Self::DotProduct => -a.iter().zip(b).map(|(a, b)| a * b).sum::<f32>(),
+            Self::Euclidean => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f32>()
+                .sqrt(),
+        }
+    }
+}
+
+fn check_dimensions<I: VectorIndex>(index: &I, vector: &[f32]) -> Result<(), Error> {
+    let expected = index.dimensions();
+    if vector.len() == expected {
+        Ok(())
+    } else {
+        Err(Error::InvalidVectorDimensions {
+            expected,
+            actual: vector.len(),
+        })
+    }
+}
+
+/// The view that stores a [`VectorIndex`].
+///
+/// Each indexed document emits its vector as the value, using the
+/// [`VectorIndex::Key`] as the key.
+#[derive_where(Debug, Clone)]
+pub struct VectorIndexView<I: VectorIndex>(pub I);
+
+impl<I> View for VectorIndexView<I>
+where
+    I: VectorIndex,
+{
+    type Collection = I::Collection;
+    type Key = I::Key;
+    type Value = Vec<f32>;
+
+    fn name(&self) -> Name {
+        self.0.name()
+    }
+}
+
+impl<I> ViewSchema for VectorIndexView<I>
+where
+    I: VectorIndex,
+{
+    type MappedKey<'doc> = I::Key;
+    type View = Self;
+
+    fn update_policy(&self) -> ViewUpdatePolicy {
+        self.0.update_policy()
+    }
+
+    fn version(&self) -> u64 {
+        self.0.version()
+    }
+
+    fn implements_reduce(&self) -> bool {
+        false
+    }
+}
+
+impl<I> CollectionMapReduce for VectorIndexView<I>
+where
+    I: VectorIndex,
+{
+    fn map<'doc>(&self, document: CollectionDocument<I::Collection>) -> ViewMapResult<'doc, Self>
+    where
+        CollectionDocument<I::Collection>: 'doc,
+    {
+        let Some((key, vector)) = self.0.vector(&document) else {
+            return Ok(Mappings::none());
+        };
+        check_dimensions(&self.0, &vector)?;
+        document.header.emit_key_and_value(key, vector)
+    }
+}
+
+impl<I> DefaultViewSerialization for VectorIndexView<I> where I: VectorIndex {}
+
+/// A document matched by a [`VectorIndex`] search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorMatch<PrimaryKey, Key> {
+    /// The id of the matched document.
+    pub id: PrimaryKey,
+    /// The key stored with the matched vector.
+    pub key: Key,
+    /// The distance between the matched vector and the query vector, measured
+    /// using [`VectorIndex::metric()`].
+    pub distance: f32,
+}
+
+/// A list of [`VectorMatch`]es for the index `I`, ordered from nearest to
+/// farthest.
+pub type VectorMatches<I> = Vec<
+    VectorMatch<
+        <<I as VectorIndex>::Collection as Collection>::PrimaryKey,
+        <I as VectorIndex>::Key,
+    >,
+>;
+
+/// A nearest neighbor search of a [`VectorIndex`].
+#[must_use]
+pub struct VectorSearch<'a, Cn, I>
+where
+    I: VectorIndex,
+{
+    connection: &'a Cn,
+    index: I,
+    vector: &'a [f32],
+    graph: Option<&'a Hnsw<I>>,
+    key_range: Option<RangeRef<'a, I::Key>>,
+
+    /// The maximum number of results to return. The default value is
+    /// [`DEFAULT_VECTOR_SEARCH_LIMIT`].
+    pub limit: usize,
+
+    /// The index's data access policy. The default value is
+    /// [`AccessPolicy::UpdateBefore`].
+    pub access_policy: AccessPolicy,
+}
+
+impl<'a, Cn, I> VectorSearch<'a, Cn, I>
+where
+    Cn: Connection,
+    I: VectorIndex,
+{
+    fn new(connection: &'a Cn, index: I, vector: &'a [f32]) -> Self {
+        Self {
+            connection,
+            index,
+            vector,
+            graph: None,
+            key_range: None,
+            limit: DEFAULT_VECTOR_SEARCH_LIMIT,
+            access_policy: AccessPolicy::UpdateBefore,
+        }
+    }
+
+    /// Limits the results to the `maximum_results` nearest documents.
+    pub const fn limit(mut self, maximum_results: usize) -> Self {
+        self.limit = maximum_results;
+        self
+    }
+
+    /// Only searches vectors whose keys are within `range`.
+    pub fn with_key_range<R: Into<RangeRef<'a, I::Key>>>(mut self, range: R) -> Self {
+        self.key_range = Some(range.into());
+        self
+    }
+
+    /// Performs an approximate search using `graph` rather than comparing
+    /// every vector in the index.
+    pub const fn approximate(mut self, graph: &'a Hnsw<I>) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+
+    /// Sets the access policy for the search.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Executes the search, returning the matching documents ordered from
+    /// nearest to farthest.
+    pub fn query(self) -> Result<VectorMatches<I>, Error> {
+        check_dimensions(&self.index, self.vector)?;
+        if let Some(graph) = self.graph {
+            let key_range = self
+                .key_range
+                .as_ref()
+                .map(serialize_key_range::<I>)
+                .transpose()?;
+            graph.nearest(
+                self.connection,
+                self.vector,
+                key_range.as_ref(),
+                self.limit,
+                self.access_policy,
+            )
+        } else {
+            let view = self
+                .connection
+                .view::<VectorIndexView<I>>()
+                .with_access_policy(self.access_policy);
+            let view = match self.key_range {
+                Some(range) => view.with_key_range(range),
+                None => view,
+            };
+            Ok(nearest::<I>(
+                self.index.metric(),
+                self.vector,
+                view.query()?,
+                self.limit,
+            ))
+        }
+    }
+}
+
+/// A nearest neighbor search of a [`VectorIndex`].
+#[must_use]
+pub struct AsyncVectorSearch<'a, Cn, I>
+where
+    I: VectorIndex,
+{
+    connection: &'a Cn,
+    index: I,
+    vector: &'a [f32],
+    graph: Option<&'a Hnsw<I>>,
+    key_range: Option<RangeRef<'a, I::Key>>,
+
+    /// The maximum number of results to return. The default value is
+    /// [`DEFAULT_VECTOR_SEARCH_LIMIT`].
+    pub limit: usize,
+
+    /// The index's data access policy. The default value is
+    /// [`AccessPolicy::UpdateBefore`].
+    pub access_policy: AccessPolicy,
+}
+
+impl<'a, Cn, I> AsyncVectorSearch<'a, Cn, I>
+where
+    Cn: AsyncConnection,
+    I: VectorIndex,
+{
+    fn new(connection: &'a Cn, index: I, vector: &'a [f32]) -> Self {
+        Self {
+            connection,
+            index,
+            vector,
+            graph: None,
+            key_range: None,
+            limit: DEFAULT_VECTOR_SEARCH_LIMIT,
+            access_policy: AccessPolicy::UpdateBefore,
+        }
+    }
+
+    /// Limits the results to the `maximum_results` nearest documents.
+    pub const fn limit(mut self, maximum_results: usize) -> Self {
+        self.limit = maximum_results;
+        self
+    }
+
+    /// Only searches vectors whose keys are within `range`.
+    pub fn with_key_range<R: Into<RangeRef<'a, I::Key>>>(mut self, range: R) -> Self {
+        self.key_range = Some(range.into());
+        self
+    }
+
+    /// Performs an approximate search using `graph` rather than comparing
+    /// every vector in the index.
+    pub const fn approximate(mut self, graph: &'a Hnsw<I>) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+
+    /// Sets the access policy for the search.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Executes the search, returning the matching documents ordered from
+    /// nearest to farthest.
+    pub async fn query(self) -> Result<VectorMatches<I>, Error> {
+        check_dimensions(&self.index, self.vector)?;
+        if let Some(graph) = self.graph {
+            let key_range = self
+                .key_range
+                .as_ref()
+                .map(serialize_key_range::<I>)
+                .transpose()?;
+            graph
+                .nearest_async(
+                    self.connection,
+                    self.vector,
+                    key_range.as_ref(),
+                    self.limit,
+                    self.access_policy,
+                )
+                .await
+        } else {
+            let view = self
+                .connection
+                .view::<VectorIndexView<I>>()
+                .with_access_policy(self.access_policy);
+            let view = match self.key_range {
+                Some(range) => view.with_key_range(range),
+                None => view,
+            };
+            Ok(nearest::<I>(
+                self.index.metric(),
+                self.vector,
+                view.query().await?,
+                self.limit,
+            ))
+        }
+    }
+}
+
+fn serialize_key_range<I: VectorIndex>(
+    range: &RangeRef<'_, I::Key>,
+) -> Result<Range<Vec<u8>>, Error> {
+    let range = range
+        .as_ord_bytes()
+        .map_err(|err| Error::other("key serialization", err))?;
+    Ok(Range {
+        start: range.start.map(|bytes| bytes.to_vec()),
+        end: range.end.map(|bytes| bytes.to_vec()),
+    })
+}
+
+/// Returns the `limit` mappings with vectors nearest to `vector`.
+fn nearest<I: VectorIndex>(
+    metric: Metric,
+    vector: &[f32],
+    mappings: ViewMappings<VectorIndexView<I>>,
+    limit: usize,
+) -> VectorMatches<I> {
+    let mut results = mappings
+        .into_iter()
+        .map(|mapping| VectorMatch {
+            distance: metric.distance(vector, &mapping.value),
+            id: mapping.source.id,
+            key: mapping.key,
+        })
+        .collect::<Vec<_>>();
+    if results.len() > limit && limit > 0 {
+        results.select_nth_unstable_by(limit - 1, |left, right| {
+            left.distance.total_cmp(&right.distance)
+        });
+    }
+    results.truncate(limit);
+    results.sort_by(|left, right| left.distance.total_cmp(&right.distance));
+    results
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn metric_tests() {
+    assert_eq!(Metric::Cosine.distance(&[1., 0.], &[0., 1.]), 1.);
+    assert_eq!(Metric::Cosine.distance(&[1., 0.], &[-1., 0.]), 2.);
+    assert_eq!(Metric::Cosine.distance(&[0., 0.], &[1., 0.]), 1.);
+    assert_eq!(Metric::DotProduct.distance(&[1., 2.], &[3., 4.]), -11.);
+    assert_eq!(Metric::Euclidean.distance(&[1., 1.], &[1., 1.]), 0.);
+}
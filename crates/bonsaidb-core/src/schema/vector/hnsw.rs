@@ -0,0 +1,756 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::ops::RangeBounds;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::connection::{AccessPolicy, AsyncConnection, Connection, Range};
+use crate::document::{CollectionDocument, DocumentId, Header, OwnedDocument};
+use crate::key::KeyEncoding;
+use crate::schema::vector::{
+    check_dimensions, AsyncVectorSearch, Metric, VectorIndex, VectorIndexView, VectorMatch,
+    VectorMatches, VectorSearch,
+};
+use crate::schema::view::map::ViewMappings;
+use crate::schema::{CollectionName, SerializedCollection};
+use crate::transaction::Executed;
+use crate::Error;
+
+/// The highest layer a vector can be placed in.
+const MAX_LAYER: usize = 16;
+
+/// Parameters that control the structure of an [`Hnsw`] graph.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct HnswParameters {
+    /// The maximum number of neighbors of each vector in the upper layers of
+    /// the graph. The bottom layer allows twice as many neighbors. Larger
+    /// values improve the accuracy of searches at the cost of memory and
+    /// insertion speed. The default is 16.
+    pub connections: usize,
+    /// The number of candidates considered when inserting a vector. Larger
+    /// values improve the quality of the graph at the cost of insertion speed.
+    /// The default is 100.
+    pub construction_candidates: usize,
+    /// The minimum number of candidates considered when searching. Larger
+    /// values improve the accuracy of searches at the cost of search speed.
+    /// The default is 64.
+    pub search_candidates: usize,
+}
+
+impl Default for HnswParameters {
+    fn default() -> Self {
+        Self {
+            connections: 16,
+            construction_candidates: 100,
+            search_candidates: 64,
+        }
+    }
+}
+
+/// An in-memory [Hierarchical Navigable Small
+/// World](https://arxiv.org/abs/1603.09320) graph for approximate searches of
+/// a [`VectorIndex`].
+///
+/// The graph is built from the index's [`VectorIndexView`] the first time it
+/// is searched. Each subsequent search first applies the changes made to
+/// [`VectorIndex::Collection`] since the previous search, which are found
+/// using [`Connection::list_executed_transactions()`]. Searches using
+/// [`AccessPolicy::NoUpdate`] skip applying changes once the graph has been
+/// built.
+///
+/// Because building the graph requires loading every vector in the index, a
+/// graph should be created once and shared between searches.
+///
+/// ```rust
+/// # bonsaidb_core::__doctest_prelude!();
+/// # use bonsaidb_core::connection::Connection;
+/// # use bonsaidb_core::schema::vector::{Hnsw, VectorIndex};
+/// # #[derive(Debug, Clone)]
+/// # struct ArticleEmbeddings;
+/// # impl VectorIndex for ArticleEmbeddings {
+/// #     type Collection = MyCollection;
+/// #     type Key = String;
+/// #     fn name(&self) -> Name {
+/// #         Name::new("embeddings")
+/// #     }
+/// #     fn dimensions(&self) -> usize {
+/// #         3
+/// #     }
+/// #     fn vector(&self, _document: &CollectionDocument<MyCollection>) -> Option<(String, Vec<f32>)> {
+/// #         None
+/// #     }
+/// # }
+/// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+/// let graph = Hnsw::new(ArticleEmbeddings);
+/// for result in graph.search(db, &[0.2, 0.1, 0.7]).limit(5).query()? {
+///     println!("Document {} is {} away", result.id, result.distance);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Hnsw<I>
+where
+    I: VectorIndex,
+{
+    index: I,
+    parameters: HnswParameters,
+    state: Mutex<State<I::Key>>,
+}
+
+impl<I> Hnsw<I>
+where
+    I: VectorIndex,
+{
+    /// Returns an empty graph for `index` using the default parameters.
+    #[must_use]
+    pub fn new(index: I) -> Self {
+        Self::with_parameters(index, HnswParameters::default())
+    }
+
+    /// Returns an empty graph for `index` using `parameters`.
+    #[must_use]
+    pub fn with_parameters(index: I, parameters: HnswParameters) -> Self {
+        let graph = Graph::new(index.metric(), parameters);
+        Self {
+            index,
+            parameters,
+            state: Mutex::new(State {
+                graph,
+                synchronized: Synchronized::Never,
+            }),
+        }
+    }
+
+    /// Returns a builder for finding the documents with vectors nearest to
+    /// `vector` using this graph.
+    pub fn search<'a, Database: Connection>(
+        &'a self,
+        database: &'a Database,
+        vector: &'a [f32],
+    ) -> VectorSearch<'a, Database, I> {
+        self.index.search(database, vector).approximate(self)
+    }
+
+    /// Returns a builder for finding the documents with vectors nearest to
+    /// `vector` using this graph.
+    pub fn search_async<'a, Database: AsyncConnection>(
+        &'a self,
+        database: &'a Database,
+        vector: &'a [f32],
+    ) -> AsyncVectorSearch<'a, Database, I> {
+        self.index.search_async(database, vector).approximate(self)
+    }
+
+    fn state(&self) -> MutexGuard<'_, State<I::Key>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(super) fn nearest<Cn: Connection>(
+        &self,
+        connection: &Cn,
+        vector: &[f32],
+        key_range: Option<&Range<Vec<u8>>>,
+        limit: usize,
+        access_policy: AccessPolicy,
+    ) -> Result<VectorMatches<I>, Error> {
+        let synchronized = self.state().synchronized;
+        let update = match synchronized {
+            Synchronized::Never => {
+                let through = connection.last_transaction_id()?;
+                // The view must be current with `through`, regardless of the
+                // search's access policy.
+                let mappings = connection
+                    .view::<VectorIndexView<I>>()
+                    .with_access_policy(AccessPolicy::UpdateBefore)
+                    .query()?;
+                Some(self.rebuild(through, mappings)?)
+            }
+            Synchronized::Through(_) if matches!(access_policy, AccessPolicy::NoUpdate) => None,
+            Synchronized::Through(mut through) => {
+                let collection = I::Collection::collection_name();
+                let mut changed = BTreeSet::new();
+                loop {
+                    let transactions =
+                        connection.list_executed_transactions(through.map(|id| id + 1), None)?;
+                    let Some(last) = transactions.last() else {
+                        break;
+                    };
+                    through = Some(last.id);
+                    collect_changed_documents(&collection, &transactions, &mut changed);
+                }
+                let changed = changed.into_iter().collect::<Vec<_>>();
+                let documents = if changed.is_empty() {
+                    Vec::new()
+                } else {
+                    connection.get_multiple_from_collection(&changed, &collection)?
+                };
+                Some(self.changes(through, changed, &documents)?)
+            }
+        };
+
+        let mut state = self.state();
+        if let Some(update) = update {
+            state.apply(update);
+        }
+        state.graph.nearest(vector, key_range, limit)
+    }
+
+    pub(super) async fn nearest_async<Cn: AsyncConnection>(
+        &self,
+        connection: &Cn,
+        vector: &[f32],
+        key_range: Option<&Range<Vec<u8>>>,
+        limit: usize,
+        access_policy: AccessPolicy,
+    ) -> Result<VectorMatches<I>, Error> {
+        let synchronized = self.state().synchronized;
+        let update = match synchronized {
+            Synchronized::Never => {
+                let through = connection.last_transaction_id().await?;
+                // The view must be current with `through`, regardless of the
+                // search's access policy.
+                let mappings = connection
+                    .view::<VectorIndexView<I>>()
+                    .with_access_policy(AccessPolicy::UpdateBefore)
+                    .query()
+                    .await?;
+                Some(self.rebuild(through, mappings)?)
+            }
+            Synchronized::Through(_) if matches!(access_policy, AccessPolicy::NoUpdate) => None,
+            Synchronized::Through(mut through) => {
+                let collection = I::Collection::collection_name();
+                let mut changed = BTreeSet::new();
+                loop {
+                    let transactions = connection
+                        .list_executed_transactions(through.map(|id| id + 1), None)
+                        .await?;
+                    let Some(last) = transactions.last() else {
+                        break;
+                    };
+                    through = Some(last.id);
+                    collect_changed_documents(&collection, &transactions, &mut changed);
+                }
+                let changed = changed.into_iter().collect::<Vec<_>>();
+                let documents = if changed.is_empty() {
+                    Vec::new()
+                } else {
+                    connection
+                        .get_multiple_from_collection(&changed, &collection)
+                        .await?
+                };
+                Some(self.changes(through, changed, &documents)?)
+            }
+        };
+
+        let mut state = self.state();
+        if let Some(update) = update {
+            state.apply(update);
+        }
+        state.graph.nearest(vector, key_range, limit)
+    }
+
+    fn rebuild(
+        &self,
+        through: Option<u64>,
+        mappings: ViewMappings<VectorIndexView<I>>,
+    ) -> Result<Update<I::Key>, Error> {
+        let mut entries = Vec::with_capacity(mappings.len());
+        for mapping in mappings {
+            let id = Header::try_from(mapping.source)?.id;
+            entries.push((id, Some(Entry::new(mapping.key, mapping.value)?)));
+        }
+        Ok(Update {
+            through,
+            rebuild: true,
+            parameters: self.parameters,
+            metric: self.index.metric(),
+            entries,
+        })
+    }
+
+    fn changes(
+        &self,
+        through: Option<u64>,
+        changed: Vec<DocumentId>,
+        documents: &[OwnedDocument],
+    ) -> Result<Update<I::Key>, Error> {
+        // Documents that weren't returned have been deleted.
+        let mut entries = changed
+            .into_iter()
+            .map(|id| (id, None))
+            .collect::<BTreeMap<_, _>>();
+        for document in documents {
+            let contents = CollectionDocument::<I::Collection>::try_from(document)?;
+            let entry = match self.index.vector(&contents) {
+                Some((key, vector)) => {
+                    check_dimensions(&self.index, &vector)?;
+                    Some(Entry::new(key, vector)?)
+                }
+                None => None,
+            };
+            entries.insert(document.header.id.clone(), entry);
+        }
+        Ok(Update {
+            through,
+            rebuild: false,
+            parameters: self.parameters,
+            metric: self.index.metric(),
+            entries: entries.into_iter().collect(),
+        })
+    }
+}
+
+fn collect_changed_documents(
+    collection: &CollectionName,
+    transactions: &[Executed],
+    changed: &mut BTreeSet<DocumentId>,
+) {
+    for transaction in transactions {
+        if let Some(documents) = transaction.changes.documents() {
+            for (document_collection, document) in documents {
+                if document_collection == collection {
+                    changed.insert(document.id.clone());
+                }
+            }
+        }
+    }
+}
+
+struct State<K> {
+    graph: Graph<K>,
+    synchronized: Synchronized,
+}
+
+impl<K> State<K>
+where
+    K: Clone,
+{
+    fn apply(&mut self, update: Update<K>) {
+        let synchronized = Synchronized::Through(update.through);
+        // Another search may have already applied a more recent update.
+        if synchronized <= self.synchronized {
+            return;
+        }
+
+        if update.rebuild {
+            self.graph = Graph::new(update.metric, update.parameters);
+        }
+        for (id, entry) in update.entries {
+            self.graph.remove(&id);
+            if let Some(entry) = entry {
+                self.graph.insert(id, entry);
+            }
+        }
+        self.graph.compact_if_needed();
+        self.synchronized = synchronized;
+    }
+}
+
+/// The most recent transaction a graph contains the changes from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+enum Synchronized {
+    Never,
+    Through(Option<u64>),
+}
+
+struct Update<K> {
+    through: Option<u64>,
+    rebuild: bool,
+    parameters: HnswParameters,
+    metric: Metric,
+    entries: Vec<(DocumentId, Option<Entry<K>>)>,
+}
+
+struct Entry<K> {
+    key: K,
+    key_bytes: Vec<u8>,
+    vector: Vec<f32>,
+}
+
+impl<K> Entry<K> {
+    fn new(key: K, vector: Vec<f32>) -> Result<Self, Error>
+    where
+        K: KeyEncoding,
+    {
+        let key_bytes = key
+            .as_ord_bytes()
+            .map_err(|err| Error::other("key serialization", err))?
+            .to_vec();
+        Ok(Self {
+            key,
+            key_bytes,
+            vector,
+        })
+    }
+}
+
+struct Node<K> {
+    document: DocumentId,
+    entry: Entry<K>,
+    /// The neighbors of this node in each layer it is present in.
+    neighbors: Vec<Vec<usize>>,
+    removed: bool,
+}
+
+struct Graph<K> {
+    metric: Metric,
+    parameters: HnswParameters,
+    nodes: Vec<Node<K>>,
+    by_document: HashMap<DocumentId, usize>,
+    entry_point: Option<usize>,
+    removed: usize,
+}
+
+impl<K> Graph<K>
+where
+    K: Clone,
+{
+    fn new(metric: Metric, parameters: HnswParameters) -> Self {
+        Self {
+            metric,
+            parameters: HnswParameters {
+                connections: parameters.connections.max(2),
+                construction_candidates: parameters.construction_candidates.max(1),
+                search_candidates: parameters.search_candidates.max(1),
+            },
+            nodes: Vec::new(),
+            by_document: HashMap::new(),
+            entry_point: None,
+            removed: 0,
+        }
+    }
+
+    fn distance(&self, vector: &[f32], node: usize) -> f32 {
+        self.metric.distance(vector, &self.nodes[node].entry.vector)
+    }
+
+    /// Returns the highest layer `document` is present in. Layers are
+    /// assigned using an exponentially decaying distribution derived from a
+    /// hash of the id, which keeps graphs deterministic.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn layer(&self, document: &DocumentId) -> usize {
+        // FNV-1a, followed by the SplitMix64 finalizer to spread the bits of
+        // similar ids.
+        let mut hash = document
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^= hash >> 31;
+
+        // A uniformly distributed value in (0, 1].
+        let uniform = ((hash >> 11) + 1) as f64 / (1_u64 << 53) as f64;
+        let layer = -uniform.ln() / (self.parameters.connections as f64).ln();
+        (layer as usize).min(MAX_LAYER)
+    }
+
+    fn insert(&mut self, document: DocumentId, entry: Entry<K>) {
+        let layer = self.layer(&document);
+        let index = self.nodes.len();
+        let vector = entry.vector.clone();
+        self.by_document.insert(document.clone(), index);
+        self.nodes.push(Node {
+            document,
+            entry,
+            neighbors: vec![Vec::new(); layer + 1],
+            removed: false,
+        });
+
+        let Some(mut entry_point) = self.entry_point else {
+            self.entry_point = Some(index);
+            return;
+        };
+        let top_layer = self.nodes[entry_point].neighbors.len() - 1;
+        for search_layer in (layer + 1..=top_layer).rev() {
+            entry_point = self.search_layer(&vector, &[entry_point], 1, search_layer)[0].node;
+        }
+
+        let mut entry_points = vec![entry_point];
+        for search_layer in (0..=layer.min(top_layer)).rev() {
+            let nearest = self.search_layer(
+                &vector,
+                &entry_points,
+                self.parameters.construction_candidates,
+                search_layer,
+            );
+            let neighbors = nearest
+                .iter()
+                .take(self.parameters.connections)
+                .map(|candidate| candidate.node)
+                .collect::<Vec<_>>();
+            for &neighbor in &neighbors {
+                self.connect(neighbor, index, search_layer);
+            }
+            self.nodes[index].neighbors[search_layer] = neighbors;
+            entry_points = nearest
+                .into_iter()
+                .map(|candidate| candidate.node)
+                .collect();
+        }
+
+        if layer > top_layer {
+            self.entry_point = Some(index);
+        }
+    }
+
+    /// Adds `neighbor` to `node`'s neighbors in `layer`, removing the
+    /// farthest neighbor if `node` has too many neighbors.
+    fn connect(&mut self, node: usize, neighbor: usize, layer: usize) {
+        let maximum = if layer == 0 {
+            self.parameters.connections * 2
+        } else {
+            self.parameters.connections
+        };
+        self.nodes[node].neighbors[layer].push(neighbor);
+        if self.nodes[node].neighbors[layer].len() > maximum {
+            let vector = &self.nodes[node].entry.vector;
+            let mut neighbors = self.nodes[node].neighbors[layer]
+                .iter()
+                .map(|&neighbor| Candidate {
+                    distance: self.distance(vector, neighbor),
+                    node: neighbor,
+                })
+                .collect::<Vec<_>>();
+            neighbors.sort_unstable();
+            self.nodes[node].neighbors[layer] = neighbors
+                .into_iter()
+                .take(maximum)
+                .map(|candidate| candidate.node)
+                .collect();
+        }
+    }
+
+    /// Marks the node for `document` as removed. Removed nodes remain in the
+    /// graph to preserve its connectivity, but are excluded from results.
+    fn remove(&mut self, document: &DocumentId) {
+        if let Some(index) = self.by_document.remove(document) {
+            self.nodes[index].removed = true;
+            self.removed += 1;
+        }
+    }
+
+    /// Rebuilds the graph without removed nodes once they make up the
+    /// majority of the graph.
+    fn compact_if_needed(&mut self) {
+        if self.removed > self.nodes.len() / 2 {
+            let nodes = std::mem::take(&mut self.nodes);
+            self.by_document.clear();
+            self.entry_point = None;
+            self.removed = 0;
+            for node in nodes.into_iter().filter(|node| !node.removed) {
+                self.insert(node.document, node.entry);
+            }
+        }
+    }
+
+    /// Returns up to `candidates` nodes nearest to `vector` in `layer`,
+    /// ordered from nearest to farthest.
+    fn search_layer(
+        &self,
+        vector: &[f32],
+        entry_points: &[usize],
+        candidates: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited = entry_points.iter().copied().collect::<HashSet<_>>();
+        let mut to_visit = BinaryHeap::new();
+        let mut nearest = BinaryHeap::new();
+        for &node in entry_points {
+            let candidate = Candidate {
+                distance: self.distance(vector, node),
+                node,
+            };
+            to_visit.push(Reverse(candidate));
+            nearest.push(candidate);
+        }
+        while nearest.len() > candidates {
+            nearest.pop();
+        }
+
+        while let Some(Reverse(current)) = to_visit.pop() {
+            if nearest.len() >= candidates
+                && nearest
+                    .peek()
+                    .is_some_and(|farthest: &Candidate| current.distance > farthest.distance)
+            {
+                break;
+            }
+
+            for &neighbor in &self.nodes[current.node].neighbors[layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let distance = self.distance(vector, neighbor);
+                if nearest.len() < candidates
+                    || nearest
+                        .peek()
+                        .is_some_and(|farthest: &Candidate| distance < farthest.distance)
+                {
+                    let candidate = Candidate {
+                        distance,
+                        node: neighbor,
+                    };
+                    to_visit.push(Reverse(candidate));
+                    nearest.push(candidate);
+                    if nearest.len() > candidates {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+
+        nearest.into_sorted_vec()
+    }
+
+    fn nearest<PrimaryKey>(
+        &self,
+        vector: &[f32],
+        key_range: Option<&Range<Vec<u8>>>,
+        limit: usize,
+    ) -> Result<Vec<VectorMatch<PrimaryKey, K>>, Error>
+    where
+        PrimaryKey: for<'k> crate::key::Key<'k>,
+    {
+        let Some(mut entry_point) = self.entry_point else {
+            return Ok(Vec::new());
+        };
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let top_layer = self.nodes[entry_point].neighbors.len() - 1;
+        for layer in (1..=top_layer).rev() {
+            entry_point = self.search_layer(vector, &[entry_point], 1, layer)[0].node;
+        }
+
+        // When results are filtered, the nearest candidates may not contain
+        // enough matches. The number of candidates is increased until either
+        // enough matches are found or the entire graph has been searched.
+        let mut candidates = self.parameters.search_candidates.max(limit);
+        let matches = loop {
+            let nearest = self.search_layer(vector, &[entry_point], candidates, 0);
+            let searched_everything = nearest.len() < candidates || candidates >= self.nodes.len();
+            let matches = nearest
+                .into_iter()
+                .filter(|candidate| {
+                    let node = &self.nodes[candidate.node];
+                    !node.removed
+                        && key_range.map_or(true, |range| range.contains(&node.entry.key_bytes))
+                })
+                .take(limit)
+                .collect::<Vec<_>>();
+            if matches.len() >= limit || searched_everything {
+                break matches;
+            }
+            candidates = candidates.saturating_mul(2);
+        };
+
+        matches
+            .into_iter()
+            .map(|candidate| {
+                let node = &self.nodes[candidate.node];
+                Ok(VectorMatch {
+                    id: node.document.deserialize()?,
+                    key: node.entry.key.clone(),
+                    distance: candidate.distance,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    node: usize,
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+#[test]
+fn hnsw_recall_tests() {
+    // A deterministic set of vectors spread around a unit sphere.
+    let vectors = (0_u16..2_000)
+        .map(|index| {
+            let index = f32::from(index);
+            vec![
+                (index * 0.37).sin(),
+                (index * 0.71).cos(),
+                (index * 1.13).sin(),
+                (index * 0.19).cos(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let mut graph = Graph::<u64>::new(Metric::Euclidean, HnswParameters::default());
+    for (index, vector) in vectors.iter().enumerate() {
+        let index = index as u64;
+        graph.insert(
+            DocumentId::from_u64(index),
+            Entry::new(index % 2, vector.clone()).unwrap(),
+        );
+    }
+
+    let mut found = 0;
+    for query in vectors.iter().step_by(40) {
+        let mut expected = (0..vectors.len())
+            .map(|node| Candidate {
+                distance: Metric::Euclidean.distance(query, &vectors[node]),
+                node,
+            })
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+        let expected = expected
+            .iter()
+            .take(10)
+            .map(|candidate| DocumentId::from_u64(candidate.node as u64))
+            .collect::<HashSet<_>>();
+
+        let results = graph.nearest::<u64>(query, None, 10).unwrap();
+        assert_eq!(results.len(), 10);
+        found += results
+            .iter()
+            .filter(|result| expected.contains(&DocumentId::from_u64(result.id)))
+            .count();
+    }
+    // Approximate searches should find nearly all of the exact results.
+    assert!(found >= 450, "recall too low: {found}/500");
+
+    // Only odd keys.
+    let odd = Range::from(1_u64..).map(|key| key.as_ord_bytes().unwrap().to_vec());
+    let results = graph.nearest::<u64>(&vectors[0], Some(&odd), 10).unwrap();
+    assert_eq!(results.len(), 10);
+    assert!(results.iter().all(|result| result.key == 1));
+
+    for index in 0..1_500_u64 {
+        graph.remove(&DocumentId::from_u64(index));
+    }
+    graph.compact_if_needed();
+    assert_eq!(graph.nodes.len(), 500);
+    let results = graph.nearest::<u64>(&vectors[0], None, 10).unwrap();
+    assert!(results.iter().all(|result| result.id >= 1_500));
+}
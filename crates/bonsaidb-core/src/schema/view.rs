@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use std::time::Duration;
 
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -14,6 +15,8 @@ use crate::AnyError;
 
 /// Types for defining a `Map` within a `View`.
 pub mod map;
+/// Reusable reduce implementations for views.
+pub mod reducers;
 
 /// Errors that arise when interacting with views.
 #[derive(thiserror::Error, Debug)]
@@ -103,6 +106,18 @@ pub trait View: Sized + Send + Sync + 'static {
 /// - [`version()`](Self::version): An integer representing the view's version.
 ///   Changing this number will cause the view to be re-indexed. This is useful
 ///   when there are fundamental changes in how the view is implemented.
+/// - [`compatible_with_version()`](Self::compatible_with_version): Controls
+///   whether the previous version's index can be queried while the view is
+///   re-indexed in the background.
+/// - [`linked_collections()`](Self::linked_collections): Additional collections
+///   whose documents are mapped by this view using
+///   [`MapReduce::map_linked()`].
+/// - [`projection()`](Self::projection): Controls whether a copy or projection
+///   of each source document is stored with the view's entries.
+/// - [`default_reduce()`](Self::default_reduce): The reduce function used when
+///   [`MapReduce::reduce()`]/[`CollectionMapReduce::reduce()`] are not
+///   implemented, typically provided by a built-in
+///   [`Reducer`](reducers::Reducer).
 ///
 /// ## Where is this trait used?
 ///
@@ -131,6 +146,25 @@ pub trait View: Sized + Send + Sync + 'static {
 ///   - [`Unique`](ViewUpdatePolicy::Unique)
 ///
 ///   If not provided, the [`Lazy`](ViewUpdatePolicy::Lazy) policy will be used.
+/// - `projection`: Sets the [`ViewProjection`]. The accepted projections are:
+///   - [`None`](ViewProjection::None)
+///   - [`Document`](ViewProjection::Document)
+///   - [`Custom`](ViewProjection::Custom)
+///
+///   If not provided, the [`None`](ViewProjection::None) projection will be
+///   used.
+/// - `reduce`: Implements [`default_reduce()`](Self::default_reduce) using a
+///   [`Reducer`](reducers::Reducer). The built-in reducers can be specified by
+///   name:
+///   - [`Count`](reducers::Count)
+///   - [`Sum`](reducers::Sum)
+///   - [`Min`](reducers::Min)
+///   - [`Max`](reducers::Max)
+///   - [`Stats`](reducers::Stats)
+///
+///   Any other type implementing [`Reducer`](reducers::Reducer) for the view's
+///   value can be specified by its path. If not provided, the view does not
+///   implement reduce unless [`MapReduce::reduce()`] is implemented.
 ///
 /// Here is an example that showcases most of the options:
 /// ```rust
@@ -196,6 +230,84 @@ pub trait ViewSchema: Send + Sync + 'static {
     fn version(&self) -> u64 {
         0
     }
+
+    /// Returns true if an index built by `version` of this view can continue
+    /// to be queried while the index is rebuilt for the current version. The
+    /// provided implementation returns false.
+    ///
+    /// When this returns true, the index is rebuilt in the background and
+    /// replaces the previous version's index once it is complete. Otherwise,
+    /// the previous index is removed and queries wait for the view to be
+    /// rebuilt. Views with an eager update policy are always rebuilt before
+    /// they can be queried.
+    #[allow(unused_variables)]
+    fn compatible_with_version(&self, version: u64) -> bool {
+        false
+    }
+
+    /// Returns the collections, in addition to [`View::Collection`], whose
+    /// documents are mapped by this view. Documents from these collections are
+    /// passed to [`MapReduce::map_linked()`], and entries they emit are stored
+    /// alongside the entries of the view's own collection. The provided
+    /// implementation returns an empty list.
+    ///
+    /// Changing this list requires changing the view's
+    /// [version](Self::version). Linked collections are not supported by views
+    /// with an eager update policy, and views with linked collections are
+    /// always rebuilt before they can be queried when their version changes.
+    ///
+    /// Each linked collection must be part of the schema, or
+    /// [`Error::LinkedCollectionNotFound`](crate::Error::LinkedCollectionNotFound)
+    /// is returned when the schema is created. Linked collections must also
+    /// have the same primary key type as [`View::Collection`], as the source of
+    /// every mapping is deserialized using the view's collection's primary key.
+    /// Otherwise,
+    /// [`Error::LinkedCollectionKeyMismatch`](crate::Error::LinkedCollectionKeyMismatch)
+    /// is returned when the schema is created.
+    fn linked_collections(&self) -> Vec<CollectionName> {
+        Vec::new()
+    }
+
+    /// Returns what is stored alongside each of the view's entries, which
+    /// allows queries that include documents to be answered without
+    /// retrieving each document from its collection. The provided
+    /// implementation returns [`ViewProjection::None`].
+    ///
+    /// Changing the projection requires changing the view's
+    /// [version](Self::version).
+    fn projection(&self) -> ViewProjection {
+        ViewProjection::default()
+    }
+
+    /// Returns true if this view implements reduce. The provided
+    /// implementation returns true.
+    ///
+    /// When this returns false, the view's reduce function is never called:
+    /// reduced values are not stored while the view is indexed, and reduce
+    /// queries return
+    /// [`Error::ReduceUnimplemented`](crate::Error::ReduceUnimplemented).
+    /// Views that don't reduce should return false to avoid the cost of
+    /// attempting to reduce their entries.
+    fn implements_reduce(&self) -> bool {
+        true
+    }
+
+    /// The reduce function used by the provided implementations of
+    /// [`MapReduce::reduce()`] and [`CollectionMapReduce::reduce()`]. The
+    /// provided implementation returns
+    /// [`Error::ReduceUnimplemented`](crate::Error::ReduceUnimplemented).
+    ///
+    /// This is typically implemented by using the `reduce` parameter when
+    /// deriving this trait, or by calling a built-in
+    /// [`Reducer`](reducers::Reducer).
+    #[allow(unused_variables)]
+    fn default_reduce(
+        &self,
+        mappings: &[ViewMappedValue<'_, Self>],
+        rereduce: bool,
+    ) -> ReduceResult<Self::View> {
+        Err(crate::Error::ReduceUnimplemented)
+    }
 }
 
 /// The policy under which a [`View`] is updated when documents are saved.
@@ -216,6 +328,18 @@ pub enum ViewUpdatePolicy {
     /// [`Error::UniqueKeyViolation`](crate::Error::UniqueKeyViolation) will be
     /// returned.
     Unique,
+    /// The view is updated when a query is made, like [`Lazy`](Self::Lazy),
+    /// and is also updated in the background. This keeps queries using
+    /// [`AccessPolicy::NoUpdate`](crate::connection::AccessPolicy::NoUpdate)
+    /// reasonably up-to-date without waiting for the view to be updated.
+    Periodic {
+        /// If provided, the view is updated in the background at this
+        /// interval.
+        interval: Option<Duration>,
+        /// If provided, the view is updated in the background once this many
+        /// documents have been changed since the view was last updated.
+        invalidated_documents: Option<u64>,
+    },
 }
 
 impl ViewUpdatePolicy {
@@ -235,6 +359,69 @@ impl std::fmt::Display for ViewUpdatePolicy {
     }
 }
 
+/// Controls what is stored alongside each entry in a [`View`].
+///
+/// When a view stores projections, queries that include documents, such as
+/// [`View::query_with_docs()`](crate::connection::View::query_with_docs),
+/// return the stored projections rather than retrieving each document from
+/// its collection. This trades additional storage for fewer lookups when
+/// querying. Because the projections are stored in the view, they are only as
+/// up-to-date as the view itself.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ViewProjection {
+    /// Only the header of each source document is stored. Documents are
+    /// retrieved from the collection when queried.
+    #[default]
+    None,
+    /// A copy of each source document is stored.
+    Document,
+    /// The result of [`MapReduce::project()`] is stored as the contents of each
+    /// source document. Documents returned from queries will contain the
+    /// projected contents instead of the document's actual contents.
+    ///
+    /// Queries that deserialize the returned documents, such as
+    /// [`View::query_with_collection_docs()`](crate::connection::View::query_with_collection_docs),
+    /// require the projection to be deserializable as the collection's
+    /// contents.
+    Custom,
+}
+
+impl std::fmt::Display for ViewProjection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+/// The indexing status of a [`View`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ViewStatus {
+    /// The id of the last transaction the view has been updated through. This
+    /// is `None` if the view hasn't been updated since the database was
+    /// opened.
+    pub last_indexed_transaction_id: Option<u64>,
+    /// The number of changed documents that have not been mapped into the
+    /// view.
+    pub invalidated_documents: u64,
+    /// True if the view is currently being updated.
+    pub update_in_progress: bool,
+    /// True if the view's integrity has been checked since the database was
+    /// opened.
+    pub integrity_checked: bool,
+    /// The progress of rebuilding the view in the background, if it is being
+    /// rebuilt. See [`ViewSchema::compatible_with_version()`].
+    pub rebuild: Option<ViewRebuildProgress>,
+}
+
+/// The progress of a view being rebuilt in the background after its version
+/// changed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ViewRebuildProgress {
+    /// The number of documents that have been mapped into the new index.
+    pub documents_mapped: u64,
+    /// The number of documents that existed when the rebuild started.
+    pub total_documents: u64,
+}
+
 /// The Map/Reduce functionality for a [`ViewSchema`].
 ///
 /// This trait implementation provides the behavior for mapping data from
@@ -248,6 +435,27 @@ pub trait MapReduce: ViewSchema {
     /// works](https://dev.bonsaidb.io/main/guide/about/concepts/view.html#map).
     fn map<'doc>(&self, document: &'doc BorrowedDocument<'_>) -> ViewMapResult<'doc, Self>;
 
+    /// The map function for documents from the view's
+    /// [linked collections](ViewSchema::linked_collections). `collection` is
+    /// the name of the collection `document` belongs to. The provided
+    /// implementation does not emit any entries.
+    #[allow(unused_variables)]
+    fn map_linked<'doc>(
+        &self,
+        collection: &CollectionName,
+        document: &'doc BorrowedDocument<'_>,
+    ) -> ViewMapResult<'doc, Self> {
+        Ok(Mappings::none())
+    }
+
+    /// Returns the projection of `document` to store alongside its entries
+    /// when the view's [projection](ViewSchema::projection) is
+    /// [`ViewProjection::Custom`]. The provided implementation returns the
+    /// document's contents.
+    fn project(&self, document: &BorrowedDocument<'_>) -> Result<Vec<u8>, crate::Error> {
+        Ok(document.contents.to_vec())
+    }
+
     /// Returns a value that is produced by reducing a list of `mappings` into a
     /// single value. If `rereduce` is true, the values contained in the
     /// mappings have already been reduced at least one time. If an error of
@@ -255,13 +463,14 @@ pub trait MapReduce: ViewSchema {
     /// queries that ask for a reduce operation will return an error. See [the
     /// user guide's chapter on views for more information on how reduce
     /// works](https://dev.bonsaidb.io/main/guide/about/concepts/view.html#reduce).
-    #[allow(unused_variables)]
+    ///
+    /// The provided implementation calls [`ViewSchema::default_reduce()`].
     fn reduce(
         &self,
         mappings: &[MappedValue<Self::MappedKey<'_>, <Self::View as View>::Value>],
         rereduce: bool,
     ) -> Result<<Self::View as View>::Value, crate::Error> {
-        Err(crate::Error::ReduceUnimplemented)
+        self.default_reduce(mappings, rereduce)
     }
 }
 
@@ -338,18 +547,44 @@ where
     where
         CollectionDocument<<Self::View as View>::Collection>: 'doc;
 
+    /// The map function for documents from the view's
+    /// [linked collections](ViewSchema::linked_collections). `collection` is
+    /// the name of the collection `document` belongs to. The provided
+    /// implementation does not emit any entries.
+    #[allow(unused_variables)]
+    fn map_linked<'doc>(
+        &self,
+        collection: &CollectionName,
+        document: &'doc BorrowedDocument<'_>,
+    ) -> ViewMapResult<'doc, Self> {
+        Ok(Mappings::none())
+    }
+
+    /// Returns the projection of `document` to store alongside its entries
+    /// when the view's [projection](ViewSchema::projection) is
+    /// [`ViewProjection::Custom`]. The returned bytes are stored as the
+    /// contents of the projected document. The provided implementation
+    /// serializes the document's contents.
+    fn project(
+        &self,
+        document: CollectionDocument<<Self::View as View>::Collection>,
+    ) -> Result<Vec<u8>, crate::Error> {
+        <<Self::View as View>::Collection as SerializedCollection>::serialize(&document.contents)
+    }
+
     /// The reduce function for this view. If `Err(Error::ReduceUnimplemented)`
     /// is returned, queries that ask for a reduce operation will return an
     /// error. See [`CouchDB`'s Reduce/Rereduce
     /// documentation](https://docs.couchdb.org/en/stable/ddocs/views/intro.html#reduce-rereduce)
     /// for the design this implementation will be inspired by
-    #[allow(unused_variables)]
+    ///
+    /// The provided implementation calls [`ViewSchema::default_reduce()`].
     fn reduce(
         &self,
         mappings: &[ViewMappedValue<'_, Self>],
         rereduce: bool,
     ) -> ReduceResult<Self::View> {
-        Err(crate::Error::ReduceUnimplemented)
+        self.default_reduce(mappings, rereduce)
     }
 }
 
@@ -363,6 +598,18 @@ where
         T::map(self, CollectionDocument::try_from(document)?)
     }
 
+    fn map_linked<'doc>(
+        &self,
+        collection: &CollectionName,
+        document: &'doc BorrowedDocument<'_>,
+    ) -> ViewMapResult<'doc, Self> {
+        T::map_linked(self, collection, document)
+    }
+
+    fn project(&self, document: &BorrowedDocument<'_>) -> Result<Vec<u8>, crate::Error> {
+        T::project(self, CollectionDocument::try_from(document)?)
+    }
+
     fn reduce(
         &self,
         mappings: &[ViewMappedValue<'_, Self>],
@@ -383,12 +630,31 @@ pub trait Serialized: Send + Sync {
 
     /// Wraps [`ViewSchema::version`]
     fn version(&self) -> u64;
+    /// Wraps [`ViewSchema::compatible_with_version`]
+    fn compatible_with_version(&self, version: u64) -> bool;
     /// Wraps [`View::view_name`]
     fn view_name(&self) -> ViewName;
+    /// Wraps [`ViewSchema::linked_collections`]
+    fn linked_collections(&self) -> Vec<CollectionName>;
     /// Wraps [`MapReduce::map`]
     fn map(&self, document: &BorrowedDocument<'_>) -> Result<Vec<map::Serialized>, Error>;
+    /// Wraps [`MapReduce::map_linked`]
+    fn map_linked(
+        &self,
+        collection: &CollectionName,
+        document: &BorrowedDocument<'_>,
+    ) -> Result<Vec<map::Serialized>, Error>;
+    /// Wraps [`ViewSchema::projection`]
+    fn projection(&self) -> ViewProjection;
+    /// Wraps [`MapReduce::project`]
+    fn project(&self, document: &BorrowedDocument<'_>) -> Result<Vec<u8>, Error>;
+    /// Wraps [`ViewSchema::implements_reduce`]
+    fn implements_reduce(&self) -> bool;
     /// Wraps [`MapReduce::reduce`]
     fn reduce(&self, mappings: &[(&[u8], &[u8])], rereduce: bool) -> Result<Vec<u8>, Error>;
+    /// Returns the encoded key containing the first `group_level` fields of
+    /// the composite `key`. See [`Key::encode_composite_prefix`].
+    fn group_key(&self, key: &[u8], group_level: usize) -> Result<Vec<u8>, Error>;
 }
 
 /// Defines an unique view named `$view_name` for `$collection` with the
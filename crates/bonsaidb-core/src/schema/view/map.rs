@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::document::{CollectionHeader, DocumentId, Header, OwnedDocument};
 use crate::schema::view::{self, ByteSource, Key, SerializedView, View, ViewSchema};
-use crate::schema::Collection;
+use crate::schema::{Collection, CollectionName};
 
 /// A document's entry in a View's mappings.
 #[derive(Eq, PartialEq, Debug)]
@@ -30,6 +30,7 @@ impl<K, V> Map<K, V> {
     {
         Ok(Serialized {
             source: self.source.clone(),
+            collection: None,
             key: Bytes::from(
                 self.key
                     .as_ord_bytes()
@@ -54,6 +55,13 @@ pub struct CollectionMap<PrimaryKey, K = (), V = ()> {
     /// The header of the document that emitted this entry.
     pub source: CollectionHeader<PrimaryKey>,
 
+    /// The collection of the document that emitted this entry, if it belongs
+    /// to one of the view's
+    /// [linked collections](crate::schema::ViewSchema::linked_collections).
+    /// This is `None` for entries emitted by documents from the view's
+    /// collection.
+    pub collection: Option<CollectionName>,
+
     /// The key used to index the View.
     pub key: K,
 
@@ -238,6 +246,10 @@ pub struct MappedDocuments<D, V: View> {
     ///
     /// Documents can appear in a mapping query multiple times. As a result, they are stored separately to avoid duplication.
     pub documents: BTreeMap<<V::Collection as Collection>::PrimaryKey, D>,
+    /// The associated documents from the view's
+    /// [linked collections](crate::schema::ViewSchema::linked_collections),
+    /// by collection and ID.
+    pub linked_documents: BTreeMap<CollectionName, BTreeMap<DocumentId, OwnedDocument>>,
 }
 
 impl<D, V: View> MappedDocuments<D, V> {
@@ -254,12 +266,17 @@ impl<D, V: View> MappedDocuments<D, V> {
     }
 
     /// Returns the mapped document at`index`, or `None` if `index >=
-    /// self.len()`.
+    /// self.len()` or the mapping was emitted by a document from a linked
+    /// collection. Documents from linked collections are stored in
+    /// [`linked_documents`](Self::linked_documents).
     #[must_use]
     #[allow(clippy::missing_panics_doc)]
     pub fn get(&self, index: usize) -> Option<MappedDocument<'_, D, V::Key, V::Value>> {
         if index < self.len() {
             let mapping = &self.mappings[index];
+            if mapping.collection.is_some() {
+                return None;
+            }
             let document = self
                 .documents
                 .get(&mapping.source.id)
@@ -274,7 +291,8 @@ impl<D, V: View> MappedDocuments<D, V> {
         }
     }
 
-    /// Returns an iterator over the contained mapped documents.
+    /// Returns an iterator over the contained mapped documents. Mappings
+    /// emitted by documents from linked collections are skipped.
     #[must_use]
     pub const fn iter(&self) -> MappedDocumentsIter<'_, D, V> {
         MappedDocumentsIter {
@@ -295,6 +313,7 @@ where
         f.debug_struct("MappedDocuments")
             .field("mappings", &self.mappings)
             .field("documents", &self.documents)
+            .field("linked_documents", &self.linked_documents)
             .finish()
     }
 }
@@ -318,9 +337,14 @@ impl<'a, D, V: View> Iterator for MappedDocumentsIter<'a, D, V> {
     type Item = MappedDocument<'a, D, V::Key, V::Value>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let doc = self.docs.get(self.index);
-        self.index = self.index.saturating_add(1);
-        doc
+        while self.index < self.docs.len() {
+            let doc = self.docs.get(self.index);
+            self.index = self.index.saturating_add(1);
+            if doc.is_some() {
+                return doc;
+            }
+        }
+        None
     }
 }
 
@@ -340,6 +364,12 @@ pub struct Serialized {
     /// The header of the document that emitted this entry.
     pub source: Header,
 
+    /// The collection of the document that emitted this entry, if it belongs
+    /// to one of the view's
+    /// [linked collections](crate::schema::ViewSchema::linked_collections).
+    #[serde(default)]
+    pub collection: Option<CollectionName>,
+
     /// The key used to index the View.Operation
     pub key: Bytes,
 
@@ -367,7 +397,11 @@ pub struct MappedSerializedDocuments {
     /// The serialized mapped value.
     pub mappings: Vec<Serialized>,
     /// The source document.
+    #[serde(with = "crate::schema::map_pairs")]
     pub documents: BTreeMap<DocumentId, OwnedDocument>,
+    /// The source documents from the view's linked collections.
+    #[serde(default, with = "crate::schema::map_pairs::nested")]
+    pub linked_documents: BTreeMap<CollectionName, BTreeMap<DocumentId, OwnedDocument>>,
 }
 
 impl MappedSerializedDocuments {
@@ -382,6 +416,7 @@ impl MappedSerializedDocuments {
                 let deserialized = Serialized::deserialized::<View>(mapping)?;
                 Ok(CollectionMap {
                     source: deserialized.source.try_into()?,
+                    collection: mapping.collection.clone(),
                     key: deserialized.key,
                     value: deserialized.value,
                 })
@@ -398,6 +433,7 @@ impl MappedSerializedDocuments {
                     Ok((key, value))
                 })
                 .collect::<Result<BTreeMap<_, _>, crate::Error>>()?,
+            linked_documents: self.linked_documents,
         })
     }
 }
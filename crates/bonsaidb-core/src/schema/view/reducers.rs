@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+
+use crate::schema::view::map::MappedValue;
+
+/// A reusable implementation of a view's reduce function.
+///
+/// Reducers can be used by implementing
+/// [`ViewSchema::default_reduce()`](crate::schema::ViewSchema::default_reduce)
+/// or [`MapReduce::reduce()`](crate::schema::MapReduce::reduce), or by using
+/// the `reduce` parameter when deriving
+/// [`ViewSchema`](crate::schema::ViewSchema):
+///
+/// ```rust
+/// # mod collection {
+/// # bonsaidb_core::__doctest_prelude!();
+/// # }
+/// # use collection::MyCollection;
+/// use bonsaidb_core::document::{CollectionDocument, Emit};
+/// use bonsaidb_core::schema::view::ViewMapResult;
+/// use bonsaidb_core::schema::{CollectionMapReduce, View, ViewSchema};
+///
+/// #[derive(View, ViewSchema)]
+/// #[view(collection = MyCollection, key = (), value = u64)]
+/// #[view_schema(reduce = Count)]
+/// # #[view(core = bonsaidb_core)]
+/// # #[view_schema(core = bonsaidb_core)]
+/// struct DocumentCount;
+///
+/// impl CollectionMapReduce for DocumentCount {
+///     fn map<'doc>(&self, document: CollectionDocument<MyCollection>) -> ViewMapResult<'doc, Self> {
+///         document.header.emit_key_and_value((), 1)
+///     }
+/// }
+/// ```
+pub trait Reducer<Value> {
+    /// Reduces `mappings` into a single value. If `rereduce` is true, the
+    /// values contained in `mappings` have already been reduced at least one
+    /// time.
+    fn reduce<Key>(
+        mappings: &[MappedValue<Key, Value>],
+        rereduce: bool,
+    ) -> Result<Value, crate::Error>;
+}
+
+/// Counts the number of mappings. The values emitted by the view's map
+/// function are ignored.
+#[derive(Debug, Clone, Copy)]
+pub struct Count;
+
+impl<Value> Reducer<Value> for Count
+where
+    Value: Numeric,
+{
+    fn reduce<Key>(
+        mappings: &[MappedValue<Key, Value>],
+        rereduce: bool,
+    ) -> Result<Value, crate::Error> {
+        if rereduce {
+            sum(mappings)
+        } else {
+            Value::from_count(mappings.len()).ok_or(crate::Error::ReduceOverflow)
+        }
+    }
+}
+
+/// Sums the values of the mappings.
+#[derive(Debug, Clone, Copy)]
+pub struct Sum;
+
+impl<Value> Reducer<Value> for Sum
+where
+    Value: Numeric,
+{
+    fn reduce<Key>(
+        mappings: &[MappedValue<Key, Value>],
+        _rereduce: bool,
+    ) -> Result<Value, crate::Error> {
+        sum(mappings)
+    }
+}
+
+fn sum<Key, Value: Numeric>(mappings: &[MappedValue<Key, Value>]) -> Result<Value, crate::Error> {
+    mappings.iter().try_fold(Value::ZERO, |sum, mapping| {
+        sum.checked_add(mapping.value)
+            .ok_or(crate::Error::ReduceOverflow)
+    })
+}
+
+/// Returns the smallest value of the mappings. Reducing no mappings returns
+/// [`Error::NothingToReduce`](crate::Error::NothingToReduce).
+#[derive(Debug, Clone, Copy)]
+pub struct Min;
+
+impl<Value> Reducer<Value> for Min
+where
+    Value: PartialOrd + Clone,
+{
+    fn reduce<Key>(
+        mappings: &[MappedValue<Key, Value>],
+        _rereduce: bool,
+    ) -> Result<Value, crate::Error> {
+        select(mappings, |candidate, current| candidate < current)
+    }
+}
+
+/// Returns the largest value of the mappings. Reducing no mappings returns
+/// [`Error::NothingToReduce`](crate::Error::NothingToReduce).
+#[derive(Debug, Clone, Copy)]
+pub struct Max;
+
+impl<Value> Reducer<Value> for Max
+where
+    Value: PartialOrd + Clone,
+{
+    fn reduce<Key>(
+        mappings: &[MappedValue<Key, Value>],
+        _rereduce: bool,
+    ) -> Result<Value, crate::Error> {
+        select(mappings, |candidate, current| candidate > current)
+    }
+}
+
+fn select<Key, Value: Clone>(
+    mappings: &[MappedValue<Key, Value>],
+    replaces: impl Fn(&Value, &Value) -> bool,
+) -> Result<Value, crate::Error> {
+    let mut values = mappings.iter().map(|mapping| &mapping.value);
+    let mut selected = values.next().ok_or(crate::Error::NothingToReduce)?;
+    for value in values {
+        if replaces(value, selected) {
+            selected = value;
+        }
+    }
+    Ok(selected.clone())
+}
+
+/// Combines [`Statistics`] emitted by a view's map function. Reducing no
+/// mappings returns [`Error::NothingToReduce`](crate::Error::NothingToReduce).
+///
+/// Views using this reducer must use [`Statistics<T>`] as their value, and
+/// emit a value created using [`Statistics::new()`] for each mapping.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats;
+
+impl<T> Reducer<Statistics<T>> for Stats
+where
+    T: Numeric,
+{
+    fn reduce<Key>(
+        mappings: &[MappedValue<Key, Statistics<T>>],
+        _rereduce: bool,
+    ) -> Result<Statistics<T>, crate::Error> {
+        let mut values = mappings.iter().map(|mapping| mapping.value);
+        let first = values.next().ok_or(crate::Error::NothingToReduce)?;
+        values.try_fold(first, Statistics::combine)
+    }
+}
+
+/// Aggregate statistics of a set of values, produced by the [`Stats`]
+/// reducer.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Statistics<T> {
+    /// The number of values.
+    pub count: u64,
+    /// The sum of the values.
+    pub sum: T,
+    /// The smallest value.
+    pub min: T,
+    /// The largest value.
+    pub max: T,
+}
+
+impl<T> Statistics<T>
+where
+    T: Numeric,
+{
+    /// Returns the statistics of a single `value`.
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self {
+            count: 1,
+            sum: value,
+            min: value,
+            max: value,
+        }
+    }
+
+    /// Returns the arithmetic mean of the values.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mean(&self) -> f64 {
+        self.sum.to_f64() / self.count as f64
+    }
+
+    fn combine(self, other: Self) -> Result<Self, crate::Error> {
+        Ok(Self {
+            count: self
+                .count
+                .checked_add(other.count)
+                .ok_or(crate::Error::ReduceOverflow)?,
+            sum: self
+                .sum
+                .checked_add(other.sum)
+                .ok_or(crate::Error::ReduceOverflow)?,
+            min: if other.min < self.min {
+                other.min
+            } else {
+                self.min
+            },
+            max: if other.max > self.max {
+                other.max
+            } else {
+                self.max
+            },
+        })
+    }
+}
+
+impl<T> From<T> for Statistics<T>
+where
+    T: Numeric,
+{
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+/// A numeric type that can be used with the [`Count`], [`Sum`], and [`Stats`]
+/// reducers.
+pub trait Numeric: Copy + PartialOrd + Send + Sync + 'static {
+    /// The value `0`.
+    const ZERO: Self;
+
+    /// Returns `count` as this type, or `None` if it cannot be represented.
+    fn from_count(count: usize) -> Option<Self>;
+
+    /// Returns the sum of `self` and `other`, or `None` if the result
+    /// overflowed.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Returns this value as an `f64`.
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_numeric_for_integers {
+    ($($type:ident),+) => {
+        $(
+            impl Numeric for $type {
+                const ZERO: Self = 0;
+
+                fn from_count(count: usize) -> Option<Self> {
+                    Self::try_from(count).ok()
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    $type::checked_add(self, other)
+                }
+
+                #[allow(clippy::cast_precision_loss, clippy::cast_lossless)]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )+
+    };
+}
+
+impl_numeric_for_integers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_numeric_for_floats {
+    ($($type:ident),+) => {
+        $(
+            impl Numeric for $type {
+                const ZERO: Self = 0.;
+
+                #[allow(clippy::cast_precision_loss)]
+                fn from_count(count: usize) -> Option<Self> {
+                    Some(count as $type)
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    Some(self + other)
+                }
+
+                #[allow(clippy::cast_lossless)]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )+
+    };
+}
+
+impl_numeric_for_floats!(f32, f64);
+
+#[test]
+fn reducers() {
+    fn mappings<T: Copy>(values: &[T]) -> Vec<MappedValue<(), T>> {
+        values
+            .iter()
+            .map(|&value| MappedValue::new((), value))
+            .collect()
+    }
+
+    assert_eq!(
+        <Count as Reducer<u64>>::reduce(&mappings(&[5, 7, 9]), false).unwrap(),
+        3
+    );
+    assert_eq!(
+        <Count as Reducer<u64>>::reduce(&mappings(&[3, 4]), true).unwrap(),
+        7
+    );
+    assert!(matches!(
+        <Count as Reducer<u8>>::reduce(&mappings(&[1; 256]), false),
+        Err(crate::Error::ReduceOverflow)
+    ));
+    assert_eq!(
+        <Sum as Reducer<i32>>::reduce(&mappings(&[1, -2, 3]), false).unwrap(),
+        2
+    );
+    assert_eq!(
+        <Sum as Reducer<u32>>::reduce(&mappings::<u32>(&[]), false).unwrap(),
+        0
+    );
+    assert!(matches!(
+        <Sum as Reducer<u8>>::reduce(&mappings(&[255, 1]), false),
+        Err(crate::Error::ReduceOverflow)
+    ));
+    assert_eq!(
+        <Min as Reducer<i32>>::reduce(&mappings(&[3, -1, 2]), false).unwrap(),
+        -1
+    );
+    assert_eq!(
+        <Max as Reducer<i32>>::reduce(&mappings(&[3, -1, 2]), false).unwrap(),
+        3
+    );
+    assert!(matches!(
+        <Max as Reducer<i32>>::reduce(&mappings::<i32>(&[]), false),
+        Err(crate::Error::NothingToReduce)
+    ));
+
+    let stats = <Stats as Reducer<Statistics<u32>>>::reduce(
+        &mappings(&[Statistics::new(2), Statistics::new(8), Statistics::new(5)]),
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        stats,
+        Statistics {
+            count: 3,
+            sum: 15,
+            min: 2,
+            max: 8
+        }
+    );
+    assert!((stats.mean() - 5.).abs() < f64::EPSILON);
+}
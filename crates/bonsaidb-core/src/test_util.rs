@@ -15,17 +15,22 @@ use transmog_pot::Pot;
 
 use crate::admin::{PermissionGroup, Role, User};
 use crate::connection::{
-    AccessPolicy, AsyncConnection, AsyncStorageConnection, Connection, StorageConnection,
+    AccessPolicy, AsyncConnection, AsyncLowLevelConnection, AsyncStorageConnection, Connection,
+    DocumentFilter, LowLevelConnection, SerializedDocumentFilter, Sort, StorageConnection,
+    MAX_FILTER_DEPTH,
 };
 use crate::document::{
     BorrowedDocument, CollectionDocument, CollectionHeader, DocumentId, Emit, Header, KeyId,
+    OwnedDocument,
 };
 use crate::keyvalue::{AsyncKeyValue, KeyValue};
 use crate::limits::{LIST_TRANSACTIONS_DEFAULT_RESULT_COUNT, LIST_TRANSACTIONS_MAX_RESULTS};
+use crate::schema::text::{TextIndex, TextQuery, Tokenizer};
+use crate::schema::vector::{Hnsw, Metric, VectorIndex, VectorMatch};
 use crate::schema::view::map::{Mappings, ViewMappedValue};
 use crate::schema::view::{MapReduce, ReduceResult, SerializedView, ViewUpdatePolicy};
 use crate::schema::{
-    Collection, CollectionName, MappedValue, NamedCollection, Qualified, Schema, SchemaName,
+    Collection, CollectionName, MappedValue, Name, NamedCollection, Qualified, Schema, SchemaName,
     Schematic, SerializedCollection, View, ViewMapResult, ViewSchema,
 };
 use crate::transaction::{Operation, OperationResult, Transaction};
@@ -39,7 +44,7 @@ use crate::{
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone, Collection)]
 // This collection purposely uses names with characters that need
 // escaping, since it's used in backup/restore.
-#[collection(name = "_basic", authority = "khonsulabs_", views = [BasicCount, BasicByParentId, BasicByParentIdEager, BasicByTag, BasicByCategory, BasicByCategoryCow], core = crate)]
+#[collection(name = "_basic", authority = "khonsulabs_", views = [BasicCount, BasicByParentId, BasicByParentIdEager, BasicByTag, BasicByCategory, BasicByCategoryCow, BasicByCategoryAndTag], text_indexes = [BasicText], core = crate)]
 #[must_use]
 pub struct Basic {
     pub value: String,
@@ -227,6 +232,37 @@ impl MapReduce for BasicByTag {
     }
 }
 
+#[derive(Debug, Clone, View, ViewSchema)]
+#[view(collection = Basic, key = (String, String), value = usize, name = "by-category-and-tag", core = crate)]
+#[view_schema(core = crate)]
+pub struct BasicByCategoryAndTag;
+
+impl MapReduce for BasicByCategoryAndTag {
+    fn map<'doc>(&self, document: &'doc BorrowedDocument<'_>) -> ViewMapResult<'doc, Self> {
+        let contents = Basic::document_contents(document)?;
+        let Some(category) = contents.category else {
+            return Ok(Mappings::none());
+        };
+        contents
+            .tags
+            .iter()
+            .map(|tag| {
+                document
+                    .header
+                    .emit_key_and_value((category.to_lowercase(), tag.clone()), 1)
+            })
+            .collect()
+    }
+
+    fn reduce(
+        &self,
+        mappings: &[ViewMappedValue<'_, Self>],
+        _rereduce: bool,
+    ) -> ReduceResult<Self::View> {
+        Ok(mappings.iter().map(|map| map.value).sum())
+    }
+}
+
 #[derive(Debug, Clone, View, ViewSchema)]
 #[view(collection = Basic, key = (), value = (), name = "by-parent-id", core = crate)]
 #[view_schema(core = crate)]
@@ -333,8 +369,81 @@ impl MapReduce for EncryptedBasicByCategory {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct BasicText;
+
+impl TextIndex for BasicText {
+    type Collection = Basic;
+
+    fn name(&self) -> Name {
+        Name::new("text")
+    }
+
+    fn tokenizer(&self) -> Tokenizer {
+        Tokenizer::new().with_english_stop_words()
+    }
+
+    fn text<'doc>(&self, document: &'doc CollectionDocument<Basic>) -> Vec<Cow<'doc, str>> {
+        let mut text = vec![Cow::Borrowed(document.contents.value.as_str())];
+        text.extend(
+            document
+                .contents
+                .tags
+                .iter()
+                .map(|tag| Cow::Borrowed(tag.as_str())),
+        );
+        text
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Collection)]
+#[collection(name = "embedding", authority = "khonsulabs", vector_indexes = [EmbeddingVector], core = crate)]
+pub struct Embedding {
+    pub category: String,
+    pub vector: Vec<f32>,
+}
+
+impl Embedding {
+    pub fn new(category: impl Display, vector: Vec<f32>) -> Self {
+        Self {
+            category: category.to_string(),
+            vector,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EmbeddingVector;
+
+impl VectorIndex for EmbeddingVector {
+    type Collection = Embedding;
+    type Key = String;
+
+    fn name(&self) -> Name {
+        Name::new("vector")
+    }
+
+    fn dimensions(&self) -> usize {
+        3
+    }
+
+    fn metric(&self) -> Metric {
+        Metric::Euclidean
+    }
+
+    fn vector(&self, document: &CollectionDocument<Embedding>) -> Option<(String, Vec<f32>)> {
+        // Documents without a category aren't indexed.
+        (!document.contents.category.is_empty()).then(|| {
+            (
+                document.contents.category.clone(),
+                document.contents.vector.clone(),
+            )
+        })
+    }
+}
+
 #[derive(Debug, Schema)]
-#[schema(name = "basic", collections = [Basic, EncryptedBasic, Unique], core = crate)]
+#[schema(name = "basic", collections = [Basic, EncryptedBasic, Unique, Embedding], core = crate)]
 pub struct BasicSchema;
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, Default, Collection)]
@@ -495,6 +604,10 @@ pub enum HarnessTest {
     KvExpiration,
     KvDeleteExpire,
     KvTransactions,
+    TextSearch,
+    VectorSearch,
+    DocumentFilters,
+    ViewGroupLevels,
 }
 
 impl HarnessTest {
@@ -716,6 +829,44 @@ macro_rules! define_async_connection_test_suite {
                 harness.shutdown().await
             }
 
+            #[tokio::test]
+            async fn text_search() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::TextSearch).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::text_search_tests(&db).await?;
+                harness.shutdown().await
+            }
+
+            #[tokio::test]
+            async fn vector_search() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::VectorSearch).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::vector_search_tests(&db).await?;
+                harness.shutdown().await
+            }
+
+            #[tokio::test]
+            async fn document_filters() -> anyhow::Result<()> {
+                let harness =
+                    $harness::new($crate::test_util::HarnessTest::DocumentFilters).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::document_filter_tests(&db).await?;
+                harness.shutdown().await
+            }
+
+            #[tokio::test]
+            async fn view_group_levels() -> anyhow::Result<()> {
+                let harness =
+                    $harness::new($crate::test_util::HarnessTest::ViewGroupLevels).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::view_group_level_tests(&db).await?;
+                harness.shutdown().await
+            }
+
             #[tokio::test]
             async fn user_management() -> anyhow::Result<()> {
                 use $crate::connection::AsyncStorageConnection;
@@ -965,6 +1116,42 @@ macro_rules! define_blocking_connection_test_suite {
                 harness.shutdown()
             }
 
+            #[test]
+            fn text_search() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::TextSearch)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_text_search_tests(&db)?;
+                harness.shutdown()
+            }
+
+            #[test]
+            fn vector_search() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::VectorSearch)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_vector_search_tests(&db)?;
+                harness.shutdown()
+            }
+
+            #[test]
+            fn document_filters() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::DocumentFilters)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_document_filter_tests(&db)?;
+                harness.shutdown()
+            }
+
+            #[test]
+            fn view_group_levels() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::ViewGroupLevels)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_view_group_level_tests(&db)?;
+                harness.shutdown()
+            }
+
             #[test]
             fn user_management() -> anyhow::Result<()> {
                 use $crate::connection::StorageConnection;
@@ -2696,6 +2883,674 @@ pub fn blocking_named_collection_tests<C: Connection>(db: &C) -> anyhow::Result<
     Ok(())
 }
 
+pub async fn text_search_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    let milk = Basic::new("Can you pick up some milk on the way home?")
+        .push_into_async(db)
+        .await?;
+    let eggs = Basic::new("2% milk? How are our eggs?")
+        .push_into_async(db)
+        .await?;
+    let dozen = Basic::new("Yes. We could use another dozen eggs.")
+        .with_tag("eggs")
+        .push_into_async(db)
+        .await?;
+
+    let search = move |query: &'static str| async move {
+        BasicText
+            .search_async(db, query)
+            .query()
+            .await
+            .map(|results| {
+                results
+                    .into_iter()
+                    .map(|result| result.id)
+                    .collect::<Vec<_>>()
+            })
+    };
+    // The document containing "eggs" twice is more relevant.
+    assert_eq!(search("eggs").await?, vec![dozen.header.id, eggs.header.id]);
+    assert_eq!(search("milk -eggs").await?, vec![milk.header.id]);
+    assert_eq!(search("NOT milk").await?, vec![dozen.header.id]);
+    assert_eq!(search("\"dozen eggs\"").await?, vec![dozen.header.id]);
+    assert!(search("\"eggs dozen\"").await?.is_empty());
+    // Stop words are skipped within phrases.
+    assert_eq!(search("\"the way home\"").await?, vec![milk.header.id]);
+    assert_eq!(search("milk OR dozen").await?.len(), 3);
+    assert_eq!(
+        BasicText
+            .search_async(db, "milk OR dozen")
+            .limit(1)
+            .query()
+            .await?
+            .into_iter()
+            .map(|result| result.id)
+            .collect::<Vec<_>>(),
+        vec![dozen.header.id]
+    );
+    assert!(matches!(
+        search("(milk").await,
+        Err(Error::InvalidTextQuery(_))
+    ));
+
+    Ok(())
+}
+
+pub fn blocking_text_search_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let milk = Basic::new("Can you pick up some milk on the way home?").push_into(db)?;
+    let eggs = Basic::new("2% milk? How are our eggs?").push_into(db)?;
+    let dozen = Basic::new("Yes. We could use another dozen eggs.")
+        .with_tag("eggs")
+        .push_into(db)?;
+
+    let search = |query: &str| {
+        BasicText.search(db, query).query().map(|results| {
+            results
+                .into_iter()
+                .map(|result| result.id)
+                .collect::<Vec<_>>()
+        })
+    };
+    // The document containing "eggs" twice is more relevant.
+    assert_eq!(search("eggs")?, vec![dozen.header.id, eggs.header.id]);
+    assert_eq!(search("milk -eggs")?, vec![milk.header.id]);
+    assert_eq!(search("NOT milk")?, vec![dozen.header.id]);
+    assert_eq!(search("\"dozen eggs\"")?, vec![dozen.header.id]);
+    assert!(search("\"eggs dozen\"")?.is_empty());
+    // Stop words are skipped within phrases.
+    assert_eq!(search("\"the way home\"")?, vec![milk.header.id]);
+    assert_eq!(search("milk OR dozen")?.len(), 3);
+    assert_eq!(
+        BasicText
+            .search(db, "milk OR dozen")
+            .limit(1)
+            .query()?
+            .into_iter()
+            .map(|result| result.id)
+            .collect::<Vec<_>>(),
+        vec![dozen.header.id]
+    );
+    assert!(matches!(search("(milk"), Err(Error::InvalidTextQuery(_))));
+
+    let results = BasicText
+        .search_with(db, TextQuery::Phrase(String::from("Dozen Eggs")))
+        .query()?;
+    assert_eq!(results.len(), 1);
+    assert!(results[0].score > 0.);
+
+    Ok(())
+}
+
+pub async fn vector_search_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    let exact = Embedding::new("fruit", vec![1., 0., 0.])
+        .push_into_async(db)
+        .await?;
+    let near = Embedding::new("fruit", vec![0.9, 0.1, 0.])
+        .push_into_async(db)
+        .await?;
+    let far = Embedding::new("vegetable", vec![0., 1., 0.])
+        .push_into_async(db)
+        .await?;
+    // Documents without a category aren't indexed.
+    Embedding::new("", vec![1., 0., 0.])
+        .push_into_async(db)
+        .await?;
+
+    let ids = |results: Vec<VectorMatch<u64, String>>| {
+        results
+            .into_iter()
+            .map(|result| result.id)
+            .collect::<Vec<_>>()
+    };
+    let query = [1_f32, 0., 0.];
+    let results = EmbeddingVector.search_async(db, &query).query().await?;
+    assert_eq!(results[0].key, "fruit");
+    assert!(results[0].distance.abs() < f32::EPSILON);
+    assert_eq!(
+        ids(results),
+        vec![exact.header.id, near.header.id, far.header.id]
+    );
+    assert_eq!(
+        ids(EmbeddingVector
+            .search_async(db, &query)
+            .limit(1)
+            .query()
+            .await?),
+        vec![exact.header.id]
+    );
+    assert_eq!(
+        ids(EmbeddingVector
+            .search_async(db, &query)
+            .with_key_range(String::from("vegetable")..)
+            .query()
+            .await?),
+        vec![far.header.id]
+    );
+
+    let graph = Hnsw::new(EmbeddingVector);
+    assert_eq!(
+        ids(graph.search_async(db, &query).query().await?),
+        vec![exact.header.id, near.header.id, far.header.id]
+    );
+    // Changes are applied to the graph before it is searched.
+    let closer = Embedding::new("fruit", vec![1., 0., 0.05])
+        .push_into_async(db)
+        .await?;
+    near.delete_async(db).await?;
+    assert_eq!(
+        ids(graph.search_async(db, &query).query().await?),
+        vec![exact.header.id, closer.header.id, far.header.id]
+    );
+    assert_eq!(
+        ids(graph
+            .search_async(db, &query)
+            .with_key_range(String::from("vegetable")..)
+            .query()
+            .await?),
+        vec![far.header.id]
+    );
+
+    assert!(Embedding::new("fruit", vec![1., 0.])
+        .push_into_async(db)
+        .await
+        .is_err());
+    assert!(matches!(
+        EmbeddingVector.search_async(db, &[1_f32, 0.]).query().await,
+        Err(Error::InvalidVectorDimensions {
+            expected: 3,
+            actual: 2
+        })
+    ));
+
+    Ok(())
+}
+
+pub fn blocking_vector_search_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let exact = Embedding::new("fruit", vec![1., 0., 0.]).push_into(db)?;
+    let near = Embedding::new("fruit", vec![0.9, 0.1, 0.]).push_into(db)?;
+    let far = Embedding::new("vegetable", vec![0., 1., 0.]).push_into(db)?;
+    // Documents without a category aren't indexed.
+    Embedding::new("", vec![1., 0., 0.]).push_into(db)?;
+
+    let ids = |results: Vec<VectorMatch<u64, String>>| {
+        results
+            .into_iter()
+            .map(|result| result.id)
+            .collect::<Vec<_>>()
+    };
+    let query = [1_f32, 0., 0.];
+    let results = EmbeddingVector.search(db, &query).query()?;
+    assert_eq!(results[0].key, "fruit");
+    assert!(results[0].distance.abs() < f32::EPSILON);
+    assert_eq!(
+        ids(results),
+        vec![exact.header.id, near.header.id, far.header.id]
+    );
+    assert_eq!(
+        ids(EmbeddingVector.search(db, &query).limit(1).query()?),
+        vec![exact.header.id]
+    );
+    assert_eq!(
+        ids(EmbeddingVector
+            .search(db, &query)
+            .with_key_range(String::from("vegetable")..)
+            .query()?),
+        vec![far.header.id]
+    );
+
+    let graph = Hnsw::new(EmbeddingVector);
+    assert_eq!(
+        ids(graph.search(db, &query).query()?),
+        vec![exact.header.id, near.header.id, far.header.id]
+    );
+    // Changes are applied to the graph before it is searched.
+    let closer = Embedding::new("fruit", vec![1., 0., 0.05]).push_into(db)?;
+    near.delete(db)?;
+    assert_eq!(
+        ids(graph.search(db, &query).query()?),
+        vec![exact.header.id, closer.header.id, far.header.id]
+    );
+    // Searches that don't update the graph skip applying changes.
+    Embedding::new("fruit", vec![1., 0., 0.]).push_into(db)?;
+    assert_eq!(
+        graph
+            .search(db, &query)
+            .with_access_policy(AccessPolicy::NoUpdate)
+            .query()?
+            .len(),
+        3
+    );
+    assert_eq!(graph.search(db, &query).query()?.len(), 4);
+
+    assert!(Embedding::new("fruit", vec![1., 0.]).push_into(db).is_err());
+    assert!(matches!(
+        EmbeddingVector.search(db, &[1_f32, 0.]).query(),
+        Err(Error::InvalidVectorDimensions {
+            expected: 3,
+            actual: 2
+        })
+    ));
+
+    Ok(())
+}
+
+pub async fn document_filter_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    let rush_shipped = Basic::new("a")
+        .with_category("shipped")
+        .with_tag("rush")
+        .push_into_async(db)
+        .await?;
+    let shipped = Basic::new("b")
+        .with_category("shipped")
+        .push_into_async(db)
+        .await?;
+    let rush_pending = Basic::new("c")
+        .with_category("pending")
+        .with_tag("rush")
+        .push_into_async(db)
+        .await?;
+    let gift = Basic::new("d").with_tag("gift").push_into_async(db).await?;
+
+    let ids = |documents: Vec<OwnedDocument>| {
+        documents
+            .into_iter()
+            .map(|doc| doc.header.id.deserialize::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let collection = db.collection::<Basic>();
+    let is_shipped = DocumentFilter::key(&BasicByCategory, "shipped")?;
+    let is_rush = DocumentFilter::key(&BasicByTag, "rush")?;
+
+    assert_eq!(
+        ids(collection
+            .filter(is_shipped.clone().and(is_rush.clone()))
+            .query()
+            .await?)?,
+        vec![rush_shipped.header.id]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(is_shipped.clone().or(is_rush.clone()))
+            .query()
+            .await?)?,
+        vec![
+            rush_shipped.header.id,
+            shipped.header.id,
+            rush_pending.header.id
+        ]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(
+                is_shipped
+                    .clone()
+                    .or(DocumentFilter::key(&BasicByCategory, "pending")?)
+                    .and(is_rush.clone())
+            )
+            .query()
+            .await?)?,
+        vec![rush_shipped.header.id, rush_pending.header.id]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(DocumentFilter::keys(&BasicByTag, ["rush", "gift"])?)
+            .descending()
+            .limit(2)
+            .query()
+            .await?)?,
+        vec![gift.header.id, rush_pending.header.id]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(DocumentFilter::view(&BasicByTag))
+            .query()
+            .await?)?,
+        vec![
+            rush_shipped.header.id,
+            rush_pending.header.id,
+            gift.header.id
+        ]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(DocumentFilter::key_range::<_, String, _>(
+                &BasicByCategory,
+                String::from("p")..String::from("q")
+            )?)
+            .query()
+            .await?)?,
+        vec![rush_pending.header.id]
+    );
+    assert_eq!(
+        collection
+            .filter(is_shipped)
+            .headers()
+            .await?
+            .into_iter()
+            .map(|header| header.id.deserialize::<u64>())
+            .collect::<Result<Vec<_>, _>>()?,
+        vec![rush_shipped.header.id, shipped.header.id]
+    );
+    assert!(collection
+        .filter(is_rush.and(DocumentFilter::key(&BasicByTag, "gift")?))
+        .query()
+        .await?
+        .is_empty());
+
+    // An empty intersection matches every document, while an empty union
+    // matches none.
+    let header_ids = |headers: Vec<Header>| {
+        headers
+            .into_iter()
+            .map(|header| header.id.deserialize::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+    };
+    assert_eq!(
+        header_ids(
+            db.filter_headers_by_name(
+                &Basic::collection_name(),
+                SerializedDocumentFilter::All(Vec::new()),
+                Sort::Ascending,
+                None,
+                AccessPolicy::UpdateBefore,
+            )
+            .await?
+        )?,
+        vec![
+            rush_shipped.header.id,
+            shipped.header.id,
+            rush_pending.header.id,
+            gift.header.id
+        ]
+    );
+    assert!(db
+        .filter_headers_by_name(
+            &Basic::collection_name(),
+            SerializedDocumentFilter::Any(Vec::new()),
+            Sort::Ascending,
+            None,
+            AccessPolicy::UpdateBefore,
+        )
+        .await?
+        .is_empty());
+
+    // Filters nested too deeply are rejected rather than evaluated.
+    let is_rush = DocumentFilter::key(&BasicByTag, "rush")?.into_serialized();
+    let nested = |depth: usize| {
+        (0..depth).fold(is_rush.clone(), |filter, _| {
+            SerializedDocumentFilter::All(vec![filter])
+        })
+    };
+    assert_eq!(
+        db.filter_headers_by_name(
+            &Basic::collection_name(),
+            nested(MAX_FILTER_DEPTH),
+            Sort::Ascending,
+            None,
+            AccessPolicy::UpdateBefore,
+        )
+        .await?
+        .len(),
+        2
+    );
+    assert!(db
+        .filter_headers_by_name(
+            &Basic::collection_name(),
+            nested(MAX_FILTER_DEPTH + 1),
+            Sort::Ascending,
+            None,
+            AccessPolicy::UpdateBefore,
+        )
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+pub fn blocking_document_filter_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let rush_shipped = Basic::new("a")
+        .with_category("shipped")
+        .with_tag("rush")
+        .push_into(db)?;
+    let shipped = Basic::new("b").with_category("shipped").push_into(db)?;
+    let rush_pending = Basic::new("c")
+        .with_category("pending")
+        .with_tag("rush")
+        .push_into(db)?;
+    let gift = Basic::new("d").with_tag("gift").push_into(db)?;
+
+    let ids = |documents: Vec<OwnedDocument>| {
+        documents
+            .into_iter()
+            .map(|doc| doc.header.id.deserialize::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let collection = db.collection::<Basic>();
+    let is_shipped = DocumentFilter::key(&BasicByCategory, "shipped")?;
+    let is_rush = DocumentFilter::key(&BasicByTag, "rush")?;
+
+    assert_eq!(
+        ids(collection
+            .filter(is_shipped.clone().and(is_rush.clone()))
+            .query()?)?,
+        vec![rush_shipped.header.id]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(is_shipped.clone().or(is_rush.clone()))
+            .query()?)?,
+        vec![
+            rush_shipped.header.id,
+            shipped.header.id,
+            rush_pending.header.id
+        ]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(
+                is_shipped
+                    .clone()
+                    .or(DocumentFilter::key(&BasicByCategory, "pending")?)
+                    .and(is_rush.clone())
+            )
+            .query()?)?,
+        vec![rush_shipped.header.id, rush_pending.header.id]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(DocumentFilter::keys(&BasicByTag, ["rush", "gift"])?)
+            .descending()
+            .limit(2)
+            .query()?)?,
+        vec![gift.header.id, rush_pending.header.id]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(DocumentFilter::view(&BasicByTag))
+            .query()?)?,
+        vec![
+            rush_shipped.header.id,
+            rush_pending.header.id,
+            gift.header.id
+        ]
+    );
+    assert_eq!(
+        ids(collection
+            .filter(DocumentFilter::key_range::<_, String, _>(
+                &BasicByCategory,
+                String::from("p")..String::from("q")
+            )?)
+            .query()?)?,
+        vec![rush_pending.header.id]
+    );
+    assert_eq!(
+        collection
+            .filter(is_shipped)
+            .headers()?
+            .into_iter()
+            .map(|header| header.id.deserialize::<u64>())
+            .collect::<Result<Vec<_>, _>>()?,
+        vec![rush_shipped.header.id, shipped.header.id]
+    );
+    assert!(collection
+        .filter(is_rush.and(DocumentFilter::key(&BasicByTag, "gift")?))
+        .query()?
+        .is_empty());
+
+    // An empty intersection matches every document, while an empty union
+    // matches none.
+    let header_ids = |headers: Vec<Header>| {
+        headers
+            .into_iter()
+            .map(|header| header.id.deserialize::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+    };
+    assert_eq!(
+        header_ids(db.filter_headers_by_name(
+            &Basic::collection_name(),
+            SerializedDocumentFilter::All(Vec::new()),
+            Sort::Ascending,
+            None,
+            AccessPolicy::UpdateBefore,
+        )?)?,
+        vec![
+            rush_shipped.header.id,
+            shipped.header.id,
+            rush_pending.header.id,
+            gift.header.id
+        ]
+    );
+    assert!(db
+        .filter_headers_by_name(
+            &Basic::collection_name(),
+            SerializedDocumentFilter::Any(Vec::new()),
+            Sort::Ascending,
+            None,
+            AccessPolicy::UpdateBefore,
+        )?
+        .is_empty());
+
+    Ok(())
+}
+
+pub async fn view_group_level_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    for (category, tag) in [
+        ("shipped", "rush"),
+        ("shipped", "rush"),
+        ("shipped", "gift"),
+        ("pending", "rush"),
+    ] {
+        Basic::new("a")
+            .with_category(category)
+            .with_tag(tag)
+            .push_into_async(db)
+            .await?;
+    }
+    Basic::new("b").with_tag("rush").push_into_async(db).await?;
+
+    let by_key = db
+        .view::<BasicByCategoryAndTag>()
+        .reduce_grouped()
+        .await?
+        .into_iter()
+        .map(|mapping| (mapping.key, mapping.value))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        by_key,
+        vec![
+            ((String::from("pending"), String::from("rush")), 1),
+            ((String::from("shipped"), String::from("gift")), 1),
+            ((String::from("shipped"), String::from("rush")), 2),
+        ]
+    );
+
+    let by_category = db
+        .view::<BasicByCategoryAndTag>()
+        .reduce_grouped_by_level::<(String,)>(1)
+        .await?
+        .into_iter()
+        .map(|mapping| (mapping.key.0, mapping.value))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        by_category,
+        vec![(String::from("pending"), 1), (String::from("shipped"), 3)]
+    );
+
+    let by_full_key = db
+        .view::<BasicByCategoryAndTag>()
+        .reduce_grouped_by_level::<(String, String)>(2)
+        .await?
+        .into_iter()
+        .map(|mapping| (mapping.key, mapping.value))
+        .collect::<Vec<_>>();
+    assert_eq!(by_full_key, by_key);
+
+    // Keys that aren't composite keys can't be grouped.
+    assert!(db
+        .view::<BasicByTag>()
+        .reduce_grouped_by_level::<(String,)>(1)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+pub fn blocking_view_group_level_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    for (category, tag) in [
+        ("shipped", "rush"),
+        ("shipped", "rush"),
+        ("shipped", "gift"),
+        ("pending", "rush"),
+    ] {
+        Basic::new("a")
+            .with_category(category)
+            .with_tag(tag)
+            .push_into(db)?;
+    }
+    Basic::new("b").with_tag("rush").push_into(db)?;
+
+    let by_key = db
+        .view::<BasicByCategoryAndTag>()
+        .reduce_grouped()?
+        .into_iter()
+        .map(|mapping| (mapping.key, mapping.value))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        by_key,
+        vec![
+            ((String::from("pending"), String::from("rush")), 1),
+            ((String::from("shipped"), String::from("gift")), 1),
+            ((String::from("shipped"), String::from("rush")), 2),
+        ]
+    );
+
+    let by_category = db
+        .view::<BasicByCategoryAndTag>()
+        .reduce_grouped_by_level::<(String,)>(1)?
+        .into_iter()
+        .map(|mapping| (mapping.key.0, mapping.value))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        by_category,
+        vec![(String::from("pending"), 1), (String::from("shipped"), 3)]
+    );
+
+    let by_full_key = db
+        .view::<BasicByCategoryAndTag>()
+        .reduce_grouped_by_level::<(String, String)>(2)?
+        .into_iter()
+        .map(|mapping| (mapping.key, mapping.value))
+        .collect::<Vec<_>>();
+    assert_eq!(by_full_key, by_key);
+
+    // Keys that aren't composite keys can't be grouped.
+    assert!(db
+        .view::<BasicByTag>()
+        .reduce_grouped_by_level::<(String,)>(1)
+        .is_err());
+
+    Ok(())
+}
+
 pub async fn compaction_tests<C: AsyncConnection + AsyncKeyValue>(db: &C) -> anyhow::Result<()> {
     let original_value = Basic::new("initial_value");
     let collection = db.collection::<Basic>();
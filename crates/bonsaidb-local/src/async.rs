@@ -4,13 +4,15 @@ use async_trait::async_trait;
 use bonsaidb_core::connection::{
     self, AccessPolicy, AsyncConnection, AsyncLowLevelConnection, AsyncStorageConnection,
     Connection, HasSchema, HasSession, IdentityReference, LowLevelConnection, Range,
-    SerializedQueryKey, Session, Sort, StorageConnection,
+    SerializedDocumentFilter, SerializedQueryKey, Session, Sort, StorageConnection,
 };
 use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
 use bonsaidb_core::keyvalue::{AsyncKeyValue, KeyOperation, KeyValue, Output};
 use bonsaidb_core::permissions::Permissions;
 use bonsaidb_core::pubsub::{self, AsyncPubSub, AsyncSubscriber, PubSub, Receiver};
+use bonsaidb_core::schema::dynamic::DynamicCollection;
 use bonsaidb_core::schema::view::map::MappedSerializedValue;
+use bonsaidb_core::schema::view::ViewStatus;
 use bonsaidb_core::schema::{
     self, CollectionName, Nameable, Schema, SchemaName, SchemaSummary, Schematic, ViewName,
 };
@@ -19,7 +21,7 @@ use bonsaidb_core::transaction::{self, OperationResult, Transaction};
 use crate::config::StorageConfiguration;
 use crate::database::DatabaseNonBlocking;
 use crate::storage::{AnyBackupLocation, StorageNonBlocking};
-use crate::{Database, Error, Storage, Subscriber};
+use crate::{Database, Error, Storage, Subscriber, TransactionWatcher};
 
 /// A file-based, multi-database, multi-user database engine. This type is
 /// designed for use with [Tokio](https://tokio.rs). For blocking
@@ -321,6 +323,13 @@ impl AsyncDatabase {
     pub fn as_blocking(&self) -> &Database {
         &self.database
     }
+
+    /// Returns a [`TransactionWatcher`] that is notified each time a
+    /// transaction is committed to this database, including transactions
+    /// persisting changes to the key-value store.
+    pub fn watch_transactions(&self) -> TransactionWatcher {
+        self.database.watch_transactions()
+    }
 }
 
 impl From<AsyncDatabase> for Database {
@@ -866,6 +875,7 @@ impl AsyncLowLevelConnection for AsyncDatabase {
         &self,
         view: &ViewName,
         key: Option<SerializedQueryKey>,
+        group_level: Option<usize>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<MappedSerializedValue>, bonsaidb_core::Error> {
         let task_self = self.clone();
@@ -874,7 +884,7 @@ impl AsyncLowLevelConnection for AsyncDatabase {
             .spawn_blocking(move || {
                 task_self
                     .database
-                    .reduce_grouped_by_name(&view, key, access_policy)
+                    .reduce_grouped_by_name(&view, key, group_level, access_policy)
             })
             .await
             .map_err(Error::from)?
@@ -897,4 +907,75 @@ impl AsyncLowLevelConnection for AsyncDatabase {
             .await
             .map_err(Error::from)?
     }
+
+    async fn filter_documents_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        let task_self = self.clone();
+        let collection = collection.clone();
+        self.runtime
+            .spawn_blocking(move || {
+                task_self.database.filter_documents_by_name(
+                    &collection,
+                    filter,
+                    order,
+                    limit,
+                    access_policy,
+                )
+            })
+            .await
+            .map_err(Error::from)?
+    }
+
+    async fn filter_headers_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, bonsaidb_core::Error> {
+        let task_self = self.clone();
+        let collection = collection.clone();
+        self.runtime
+            .spawn_blocking(move || {
+                task_self.database.filter_headers_by_name(
+                    &collection,
+                    filter,
+                    order,
+                    limit,
+                    access_policy,
+                )
+            })
+            .await
+            .map_err(Error::from)?
+    }
+
+    async fn view_status_by_name(
+        &self,
+        view: &ViewName,
+    ) -> Result<ViewStatus, bonsaidb_core::Error> {
+        let task_self = self.clone();
+        let view = view.clone();
+        self.runtime
+            .spawn_blocking(move || task_self.database.view_status_by_name(&view))
+            .await
+            .map_err(Error::from)?
+    }
+
+    async fn define_dynamic_collection(
+        &self,
+        collection: DynamicCollection,
+    ) -> Result<(), bonsaidb_core::Error> {
+        let task_self = self.clone();
+        self.runtime
+            .spawn_blocking(move || task_self.database.define_dynamic_collection(collection))
+            .await
+            .map_err(Error::from)?
+    }
 }
@@ -10,6 +10,8 @@ use crate::{Error, Storage};
 pub mod admin;
 /// Commands for querying the schemas.
 pub mod schema;
+/// Commands for inspecting views.
+pub mod view;
 
 /// Commands operating on local database storage.
 #[derive(Subcommand, Debug)]
@@ -25,6 +27,9 @@ pub enum StorageCommand {
     Admin(admin::Command),
     /// Executes a schema query.
     Schema(schema::Command),
+    /// Executes a view command.
+    #[clap(subcommand)]
+    View(view::Command),
 }
 
 /// A backup location.
@@ -51,6 +56,7 @@ impl StorageCommand {
             StorageCommand::Restore(location) => location.restore(storage),
             StorageCommand::Admin(admin) => admin.execute(storage),
             StorageCommand::Schema(schema) => schema.execute(storage),
+            StorageCommand::View(view) => view.execute(storage),
         }
     }
 
@@ -62,6 +68,7 @@ impl StorageCommand {
             StorageCommand::Restore(location) => location.restore_async(storage).await,
             StorageCommand::Admin(admin) => admin.execute_async(storage).await,
             StorageCommand::Schema(schema) => schema.execute_async(storage).await,
+            StorageCommand::View(view) => view.execute_async(storage).await,
         }
     }
 }
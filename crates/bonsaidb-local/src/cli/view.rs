@@ -0,0 +1,67 @@
+use bonsaidb_core::connection::LowLevelConnection;
+use bonsaidb_core::schema::view::ViewStatus;
+use bonsaidb_core::schema::ViewName;
+use clap::Subcommand;
+
+use crate::Storage;
+
+/// A command operating on a view.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Prints the indexing status of a view.
+    Status {
+        /// The name of the database containing the view.
+        database: String,
+        /// The name of the view.
+        view: ViewName,
+    },
+}
+
+impl Command {
+    /// Executes the command on `storage`.
+    pub fn execute(self, storage: &Storage) -> Result<(), crate::Error> {
+        match self {
+            Command::Status { database, view } => {
+                let status = storage
+                    .database_without_schema(&database)?
+                    .view_status_by_name(&view)?;
+                print_status(&status);
+                Ok(())
+            }
+        }
+    }
+
+    /// Executes the command on `storage`.
+    #[cfg(feature = "async")]
+    pub async fn execute_async(self, storage: &crate::AsyncStorage) -> Result<(), crate::Error> {
+        use bonsaidb_core::connection::AsyncLowLevelConnection;
+
+        match self {
+            Command::Status { database, view } => {
+                let status = storage
+                    .database_without_schema(&database)
+                    .await?
+                    .view_status_by_name(&view)
+                    .await?;
+                print_status(&status);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn print_status(status: &ViewStatus) {
+    match status.last_indexed_transaction_id {
+        Some(transaction_id) => println!("Last Indexed Transaction: {transaction_id}"),
+        None => println!("Last Indexed Transaction: none"),
+    }
+    println!("Invalidated Documents: {}", status.invalidated_documents);
+    println!("Update In Progress: {}", status.update_in_progress);
+    println!("Integrity Checked: {}", status.integrity_checked);
+    if let Some(rebuild) = &status.rebuild {
+        println!(
+            "Rebuild Progress: {}/{}",
+            rebuild.documents_mapped, rebuild.total_documents
+        );
+    }
+}
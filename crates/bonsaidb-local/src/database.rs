@@ -1,15 +1,17 @@
 use std::borrow::{Borrow, Cow};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::Infallible;
+use std::fmt::Debug;
 use std::ops::{self, Deref};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::u8;
 
-use bonsaidb_core::arc_bytes::serde::CowBytes;
+use bonsaidb_core::arc_bytes::serde::{Bytes, CowBytes};
 use bonsaidb_core::arc_bytes::ArcBytes;
 use bonsaidb_core::connection::{
     self, AccessPolicy, Connection, HasSchema, HasSession, LowLevelConnection, Range,
-    SerializedQueryKey, Session, Sort, StorageConnection,
+    SerializedDocumentFilter, SerializedQueryKey, Session, Sort, StorageConnection,
+    MAX_FILTER_DEPTH,
 };
 #[cfg(any(feature = "encryption", feature = "compression"))]
 use bonsaidb_core::document::KeyId;
@@ -24,9 +26,10 @@ use bonsaidb_core::permissions::bonsai::{
     ViewAction,
 };
 use bonsaidb_core::permissions::Permissions;
+use bonsaidb_core::schema::dynamic::DynamicCollection;
 use bonsaidb_core::schema::view::map::MappedSerializedValue;
-use bonsaidb_core::schema::view::{self};
-use bonsaidb_core::schema::{self, CollectionName, Schema, Schematic, ViewName};
+use bonsaidb_core::schema::view::{self, ViewRebuildProgress, ViewStatus};
+use bonsaidb_core::schema::{self, CollectionName, OnDelete, Schema, Schematic, ViewName};
 use bonsaidb_core::transaction::{
     self, ChangedDocument, Changes, Command, DocumentChanges, Operation, OperationResult,
     Transaction,
@@ -38,23 +41,28 @@ use nebari::tree::{
     Unversioned, Versioned,
 };
 use nebari::{AbortError, ExecutingTransaction, Roots, Tree};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
-use watchable::Watchable;
+use watchable::{Watchable, Watcher};
 
 use crate::config::{Builder, KeyValuePersistence, StorageConfiguration};
 use crate::database::keyvalue::BackgroundWorkerProcessTarget;
 use crate::error::Error;
 use crate::open_trees::OpenTrees;
-use crate::storage::StorageLock;
 #[cfg(feature = "encryption")]
 use crate::storage::TreeVault;
+use crate::storage::{StorageLock, WeakStorageInstance};
+use crate::views::integrity_scanner::ViewVersion;
+use crate::views::reductions::PartialReductions;
 use crate::views::{
     mapper, view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
+    view_linked_invalidated_docs_tree_name, view_reductions_tree_name, view_versions_tree_name,
     ViewEntry,
 };
 use crate::Storage;
 
+pub mod bulk_import;
+mod dynamic;
 pub mod keyvalue;
 
 pub(crate) mod compat;
@@ -123,6 +131,24 @@ pub struct Data {
     pub(crate) schema: Arc<Schematic>,
 }
 
+/// A weak reference to a [`Database`] that doesn't keep the database open.
+/// Upgrading returns a database without an authenticated session, which makes
+/// this only suitable for background tasks.
+#[derive(Debug, Clone)]
+pub struct WeakDatabase {
+    data: Weak<Data>,
+    storage: WeakStorageInstance,
+}
+
+impl WeakDatabase {
+    pub(crate) fn upgrade(&self) -> Option<Database> {
+        Some(Database {
+            data: self.data.upgrade()?,
+            storage: Storage::from(self.storage.upgrade()?),
+        })
+    }
+}
+
 impl Database {
     /// Opens a local file as a bonsaidb.
     pub(crate) fn new<DB: Schema, S: Into<Cow<'static, str>> + Send>(
@@ -131,7 +157,11 @@ impl Database {
         storage: &Storage,
     ) -> Result<Self, Error> {
         let name = name.into();
-        let schema = Arc::new(DB::schematic()?);
+        let mut schema = DB::schematic()?;
+        for collection in context.dynamic_collections()?.iter() {
+            schema.define_dynamic_collection(collection.clone())?;
+        }
+        let schema = Arc::new(schema);
         let db = Self {
             storage: storage.clone(),
             data: Arc::new(Data {
@@ -152,6 +182,8 @@ impl Database {
             .tasks()
             .spawn_key_value_expiration_loader(&db);
 
+        storage.instance.tasks().schedule_view_refreshes(&db);
+
         Ok(db)
     }
 
@@ -193,10 +225,40 @@ impl Database {
         &self.data.schema
     }
 
+    /// Returns the progress of rebuilding view `V` in the background, if it is
+    /// being rebuilt. Views are rebuilt in the background when their version
+    /// changes and
+    /// [`ViewSchema::compatible_with_version()`](schema::ViewSchema::compatible_with_version)
+    /// returns true for the previous version.
+    pub fn view_rebuild_progress<V: schema::View + 'static>(
+        &self,
+    ) -> Result<Option<ViewRebuildProgress>, bonsaidb_core::Error> {
+        let view = self.data.schema.view::<V>()?;
+        Ok(self.storage.instance.tasks().view_rebuild_progress(
+            self.data.name.clone(),
+            view.collection(),
+            view.view_name(),
+        ))
+    }
+
     pub(crate) fn roots(&self) -> &'_ nebari::Roots<AnyFile> {
         &self.data.context.roots
     }
 
+    pub(crate) fn downgrade(&self) -> WeakDatabase {
+        WeakDatabase {
+            data: Arc::downgrade(&self.data),
+            storage: self.storage.instance.downgrade(),
+        }
+    }
+
+    /// Returns a [`TransactionWatcher`] that is notified each time a
+    /// transaction is committed to this database, including transactions
+    /// persisting changes to the key-value store.
+    pub fn watch_transactions(&self) -> TransactionWatcher {
+        TransactionWatcher(self.data.context.last_transaction_id.watch())
+    }
+
     fn for_each_in_view<F: FnMut(ViewEntry) -> Result<(), bonsaidb_core::Error> + Send + Sync>(
         &self,
         view: &dyn view::Serialized,
@@ -206,6 +268,110 @@ impl Database {
         access_policy: AccessPolicy,
         mut callback: F,
     ) -> Result<(), bonsaidb_core::Error> {
+        self.access_view(view, access_policy, || {
+            let generation = self.view_generation(view)?;
+            let generation = generation.read();
+            let view_entries = self
+                .roots()
+                .tree(self.collection_tree(
+                    &view.collection(),
+                    view_entries_tree_name(&view.view_name(), *generation),
+                )?)
+                .map_err(Error::from)?;
+
+            for entry in Self::create_view_iterator(&view_entries, key, order, limit)? {
+                callback(entry)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Reduces the entries of `view` contained in `range` using the view's
+    /// partial reductions.
+    fn reduce_range_in_view(
+        &self,
+        view: &dyn view::Serialized,
+        range: Option<Range<Bytes>>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<u8>, bonsaidb_core::Error> {
+        self.access_view(view, access_policy, || {
+            if !view.implements_reduce() {
+                return Err(bonsaidb_core::Error::ReduceUnimplemented);
+            }
+
+            let view_name = view.view_name();
+            let generation = self.view_generation(view)?;
+            let generation = generation.read();
+            let mut view_entries = self
+                .roots()
+                .tree(self.collection_tree::<Unversioned, _>(
+                    &view.collection(),
+                    view_entries_tree_name(&view_name, *generation),
+                )?)
+                .map_err(Error::from)?;
+            let mut view_reductions = self
+                .roots()
+                .tree(self.collection_tree::<Unversioned, _>(
+                    &view.collection(),
+                    view_reductions_tree_name(&view_name, *generation),
+                )?)
+                .map_err(Error::from)?;
+            let mut reductions = PartialReductions {
+                view,
+                view_entries: &mut view_entries,
+                reductions: &mut view_reductions,
+            };
+            let reduced = if let Some(range) = range {
+                let range = range.map_ref(|bytes| &bytes[..]);
+                reductions.reduce(
+                    ops::RangeBounds::start_bound(&range).cloned(),
+                    ops::RangeBounds::end_bound(&range).cloned(),
+                )?
+            } else {
+                reductions.reduce(ops::Bound::Unbounded, ops::Bound::Unbounded)?
+            };
+
+            Ok(reduced)
+        })
+    }
+
+    /// Reduces the entries of the view named `view_name` that match `key` by
+    /// reducing each matching key's reduced value.
+    fn reduce_matching_keys(
+        &self,
+        view_name: &ViewName,
+        key: SerializedQueryKey,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<u8>, bonsaidb_core::Error> {
+        let mut mappings =
+            self.reduce_grouped_by_name(view_name, Some(key), None, access_policy)?;
+
+        let result = if mappings.len() == 1 {
+            mappings.pop().unwrap().value.into_vec()
+        } else {
+            let view = self.data.schema.view_by_name(view_name)?;
+            view.reduce(
+                &mappings
+                    .iter()
+                    .map(|map| (map.key.as_ref(), map.value.as_ref()))
+                    .collect::<Vec<_>>(),
+                true,
+            )
+            .map_err(Error::from)?
+        };
+
+        Ok(result)
+    }
+
+    /// Ensures `view` is ready to be queried according to `access_policy`
+    /// before invoking `query`.
+    fn access_view<T>(
+        &self,
+        view: &dyn view::Serialized,
+        access_policy: AccessPolicy,
+        query: impl FnOnce() -> Result<T, bonsaidb_core::Error>,
+    ) -> Result<T, bonsaidb_core::Error> {
         if matches!(access_policy, AccessPolicy::UpdateBefore) {
             self.storage
                 .instance
@@ -223,19 +389,7 @@ impl Database {
                 .map_err(Error::from)?;
         }
 
-        let view_entries = self
-            .roots()
-            .tree(self.collection_tree(
-                &view.collection(),
-                view_entries_tree_name(&view.view_name()),
-            )?)
-            .map_err(Error::from)?;
-
-        {
-            for entry in Self::create_view_iterator(&view_entries, key, order, limit)? {
-                callback(entry)?;
-            }
-        }
+        let result = query()?;
 
         if matches!(access_policy, AccessPolicy::UpdateAfter) {
             let db = self.clone();
@@ -251,7 +405,241 @@ impl Database {
                 .update_view_if_needed(view, &db, false)?;
         }
 
-        Ok(())
+        Ok(result)
+    }
+
+    /// Returns the headers of the documents in `collection` that match
+    /// `filter`, keyed by their ids. The headers are read from the views'
+    /// mappings, so no documents are loaded.
+    ///
+    /// `depth` is the number of filters `filter` is nested within. Filters
+    /// can be received from the network, so they are limited to
+    /// [`MAX_FILTER_DEPTH`] levels of nesting.
+    fn headers_matching(
+        &self,
+        collection: &CollectionName,
+        filter: &SerializedDocumentFilter,
+        access_policy: AccessPolicy,
+        depth: usize,
+    ) -> Result<BTreeMap<DocumentId, Header>, bonsaidb_core::Error> {
+        if depth > MAX_FILTER_DEPTH {
+            return Err(bonsaidb_core::Error::other(
+                "document filter",
+                format!("filters cannot be nested more than {MAX_FILTER_DEPTH} levels deep"),
+            ));
+        }
+        match filter {
+            SerializedDocumentFilter::View { view, key } => {
+                let view = self.data.schema.view_by_name(view)?;
+                if &view.collection() != collection {
+                    return Err(bonsaidb_core::Error::other(
+                        "document filter",
+                        format!(
+                            "view {} does not belong to collection {collection}",
+                            view.view_name()
+                        ),
+                    ));
+                }
+                self.check_permission(
+                    view_resource_name(self.name(), &view.view_name()),
+                    &BonsaiAction::Database(DatabaseAction::View(ViewAction::Query)),
+                )?;
+                let mut headers = BTreeMap::new();
+                self.for_each_in_view(
+                    view,
+                    key.clone(),
+                    Sort::Ascending,
+                    None,
+                    access_policy,
+                    |entry| {
+                        headers.extend(
+                            entry
+                                .mappings
+                                .into_iter()
+                                .filter(|mapping| mapping.collection.is_none())
+                                .map(|mapping| (mapping.source.id.clone(), mapping.source)),
+                        );
+                        Ok(())
+                    },
+                )?;
+                Ok(headers)
+            }
+            SerializedDocumentFilter::All(filters) => {
+                let mut filters = filters.iter();
+                let Some(first) = filters.next() else {
+                    // An empty intersection matches every document.
+                    return Ok(self
+                        .list_headers_from_collection(
+                            Range::from(..),
+                            Sort::Ascending,
+                            None,
+                            collection,
+                        )?
+                        .into_iter()
+                        .map(|header| (header.id.clone(), header))
+                        .collect());
+                };
+                let mut headers =
+                    self.headers_matching(collection, first, access_policy, depth + 1)?;
+                for filter in filters {
+                    // Once nothing matches, the remaining views don't need to
+                    // be queried.
+                    if headers.is_empty() {
+                        break;
+                    }
+                    let matching =
+                        self.headers_matching(collection, filter, access_policy, depth + 1)?;
+                    headers.retain(|id, _| matching.contains_key(id));
+                }
+                Ok(headers)
+            }
+            SerializedDocumentFilter::Any(filters) => {
+                let mut headers = BTreeMap::new();
+                for filter in filters {
+                    headers.extend(self.headers_matching(
+                        collection,
+                        filter,
+                        access_policy,
+                        depth + 1,
+                    )?);
+                }
+                Ok(headers)
+            }
+        }
+    }
+
+    fn filter_headers(
+        &self,
+        collection: &CollectionName,
+        filter: &SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, bonsaidb_core::Error> {
+        let headers = self
+            .headers_matching(collection, filter, access_policy, 0)?
+            .into_values();
+        let limit = limit.map_or(usize::MAX, |limit| limit as usize);
+        Ok(match order {
+            Sort::Ascending => headers.take(limit).collect(),
+            Sort::Descending => headers.rev().take(limit).collect(),
+        })
+    }
+
+    fn filter_documents(
+        &self,
+        collection: &CollectionName,
+        filter: &SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        let ids = self
+            .filter_headers(collection, filter, order, limit, access_policy)?
+            .into_iter()
+            .map(|header| header.id)
+            .collect::<Vec<_>>();
+        let mut documents = self.get_multiple_from_collection(&ids, collection)?;
+        if matches!(order, Sort::Descending) {
+            documents.reverse();
+        }
+        Ok(documents)
+    }
+
+    /// Re-reduces the reduced values of `mappings`, grouping them by the first
+    /// `group_level` fields of their composite keys. `mappings` must be sorted
+    /// by key, which ensures each group's entries are contiguous.
+    fn reduce_groups(
+        view: &dyn view::Serialized,
+        mappings: Vec<MappedSerializedValue>,
+        group_level: usize,
+    ) -> Result<Vec<MappedSerializedValue>, bonsaidb_core::Error> {
+        fn reduce_group(
+            view: &dyn view::Serialized,
+            key: Vec<u8>,
+            group: &mut Vec<MappedSerializedValue>,
+        ) -> Result<MappedSerializedValue, bonsaidb_core::Error> {
+            let value = if group.len() == 1 {
+                group.pop().unwrap().value
+            } else {
+                let value = view
+                    .reduce(
+                        &group
+                            .iter()
+                            .map(|map| (map.key.as_ref(), map.value.as_ref()))
+                            .collect::<Vec<_>>(),
+                        true,
+                    )
+                    .map_err(Error::from)?;
+                group.clear();
+                Bytes::from(value)
+            };
+            Ok(MappedSerializedValue {
+                key: Bytes::from(key),
+                value,
+            })
+        }
+
+        let mut grouped = Vec::new();
+        let mut group = Vec::new();
+        let mut group_key = None;
+        for mapping in mappings {
+            let key = view
+                .group_key(&mapping.key, group_level)
+                .map_err(Error::from)?;
+            if group_key.as_ref() != Some(&key) {
+                if let Some(previous) = group_key.take() {
+                    grouped.push(reduce_group(view, previous, &mut group)?);
+                }
+                group_key = Some(key);
+            }
+            group.push(mapping);
+        }
+        if let Some(last) = group_key {
+            grouped.push(reduce_group(view, last, &mut group)?);
+        }
+
+        Ok(grouped)
+    }
+
+    /// Returns the collections whose trees are needed to execute
+    /// `transaction`. In addition to the collections being modified, this
+    /// includes the collections referred to by the modified collections and
+    /// all collections that refer to the modified collections, recursively.
+    fn collections_for_transaction(&self, transaction: &Transaction) -> Vec<CollectionName> {
+        let mut collections = Vec::new();
+        let mut visited = HashSet::new();
+        for op in &transaction.operations {
+            if visited.insert(op.collection.clone()) {
+                collections.push(op.collection.clone());
+            }
+        }
+
+        // Deleting a document can cascade to the documents that refer to it.
+        let mut index = 0;
+        while index < collections.len() {
+            let collection = collections[index].clone();
+            for reference in self.data.schema.references_to_collection(&collection) {
+                let child = reference.collection();
+                if visited.insert(child.clone()) {
+                    collections.push(child);
+                }
+            }
+            index += 1;
+        }
+
+        // Inserting or updating a document requires checking that the
+        // documents it refers to exist.
+        for op in &transaction.operations {
+            for reference in self.data.schema.references_from_collection(&op.collection) {
+                let parent = reference.parent();
+                if visited.insert(parent.clone()) {
+                    collections.push(parent);
+                }
+            }
+        }
+
+        collections
     }
 
     fn open_trees_for_transaction(&self, transaction: &Transaction) -> Result<OpenTrees, Error> {
@@ -265,10 +653,12 @@ impl Database {
             {
                 return Err(Error::Core(bonsaidb_core::Error::CollectionNotFound));
             }
+        }
 
+        for collection in self.collections_for_transaction(transaction) {
             #[cfg(any(feature = "encryption", feature = "compression"))]
             let vault = if let Some(encryption_key) =
-                self.collection_encryption_key(&op.collection).cloned()
+                self.collection_encryption_key(&collection).cloned()
             {
                 #[cfg(feature = "encryption")]
                 if let Some(mut vault) = self.storage().tree_vault().cloned() {
@@ -293,11 +683,11 @@ impl Database {
             };
 
             open_trees.open_trees_for_document_change(
-                &op.collection,
-                &self.data.schema,
+                &collection,
+                self,
                 #[cfg(any(feature = "encryption", feature = "compression"))]
                 vault,
-            );
+            )?;
         }
 
         Ok(open_trees)
@@ -319,44 +709,49 @@ impl Database {
         let mut changed_documents = Vec::new();
         let mut collection_indexes = HashMap::new();
         let mut collections = Vec::new();
+        let mut cascaded_deletes = Vec::new();
         for op in &transaction.operations {
             let result = self.execute_operation(
                 op,
                 &mut roots_transaction,
                 &open_trees.trees_index_by_name,
+                &mut cascaded_deletes,
             )?;
 
-            if let Some((collection, id, deleted)) = match &result {
-                OperationResult::DocumentUpdated { header, collection } => {
-                    Some((collection, header.id.clone(), false))
-                }
-                OperationResult::DocumentDeleted { id, collection } => {
-                    Some((collection, id.clone(), true))
-                }
-                OperationResult::Success => None,
-            } {
-                let collection = match collection_indexes.get(collection) {
-                    Some(index) => *index,
-                    None => {
-                        if let Ok(id) = u16::try_from(collections.len()) {
-                            collection_indexes.insert(collection.clone(), id);
-                            collections.push(collection.clone());
-                            id
-                        } else {
-                            return Err(Error::TransactionTooLarge);
-                        }
+            for change in std::iter::once(&result).chain(&cascaded_deletes) {
+                if let Some((collection, id, deleted)) = match change {
+                    OperationResult::DocumentUpdated { header, collection } => {
+                        Some((collection, header.id.clone(), false))
                     }
-                };
-                changed_documents.push(ChangedDocument {
-                    collection,
-                    id,
-                    deleted,
-                });
+                    OperationResult::DocumentDeleted { id, collection } => {
+                        Some((collection, id.clone(), true))
+                    }
+                    OperationResult::Success => None,
+                } {
+                    let collection = match collection_indexes.get(collection) {
+                        Some(index) => *index,
+                        None => {
+                            if let Ok(id) = u16::try_from(collections.len()) {
+                                collection_indexes.insert(collection.clone(), id);
+                                collections.push(collection.clone());
+                                id
+                            } else {
+                                return Err(Error::TransactionTooLarge);
+                            }
+                        }
+                    };
+                    changed_documents.push(ChangedDocument {
+                        collection,
+                        id,
+                        deleted,
+                    });
+                }
             }
+            cascaded_deletes.clear();
             results.push(result);
         }
 
-        self.invalidate_changed_documents(
+        let invalidated_views = self.invalidate_changed_documents(
             &mut roots_transaction,
             &open_trees,
             &collections,
@@ -374,6 +769,17 @@ impl Database {
 
         roots_transaction.commit()?;
 
+        self.data
+            .context
+            .transaction_committed(self.roots().transactions().current_transaction_id());
+
+        for (view, documents) in invalidated_views {
+            self.storage
+                .instance
+                .tasks()
+                .view_documents_invalidated(view, self, documents);
+        }
+
         Ok(results)
     }
 
@@ -384,7 +790,8 @@ impl Database {
         open_trees: &OpenTrees,
         collections: &[CollectionName],
         changed_documents: &[ChangedDocument],
-    ) -> Result<(), Error> {
+    ) -> Result<Vec<(&dyn view::Serialized, u64)>, Error> {
+        let mut invalidated_views = Vec::new();
         for (collection, changed_documents) in &changed_documents
             .iter()
             .group_by(|doc| &collections[usize::from(doc.collection)])
@@ -394,22 +801,37 @@ impl Database {
                 .schema
                 .views_in_collection(collection)
                 .filter(|view| !view.update_policy().is_eager())
+                .map(|view| {
+                    let tree_name = view_invalidated_docs_tree_name(&view.view_name());
+                    (view, tree_name)
+                })
+                .chain(
+                    self.data
+                        .schema
+                        .views_linked_to_collection(collection)
+                        .map(|view| {
+                            let tree_name = view_linked_invalidated_docs_tree_name(
+                                &view.view_name(),
+                                collection,
+                            );
+                            (view, tree_name)
+                        }),
+                )
                 .peekable();
             if views.peek().is_some() {
                 let changed_documents = changed_documents.collect::<Vec<_>>();
-                for view in views {
-                    let view_name = view.view_name();
-                    let tree_name = view_invalidated_docs_tree_name(&view_name);
+                for (view, tree_name) in views {
                     for changed_document in &changed_documents {
                         let mut invalidated_docs = roots_transaction
                             .tree::<Unversioned>(open_trees.trees_index_by_name[&tree_name])
                             .unwrap();
                         invalidated_docs.set(changed_document.id.as_ref().to_vec(), b"")?;
                     }
+                    invalidated_views.push((view, changed_documents.len() as u64));
                 }
             }
         }
-        Ok(())
+        Ok(invalidated_views)
     }
 
     fn execute_operation(
@@ -417,6 +839,7 @@ impl Database {
         operation: &Operation,
         transaction: &mut ExecutingTransaction<AnyFile>,
         tree_index_map: &HashMap<String, usize>,
+        cascaded_deletes: &mut Vec<OperationResult>,
     ) -> Result<OperationResult, Error> {
         match &operation.command {
             Command::Insert { id, contents } => {
@@ -433,9 +856,13 @@ impl Database {
             Command::Overwrite { id, contents } => {
                 self.execute_update(operation, transaction, tree_index_map, id, None, contents)
             }
-            Command::Delete { header } => {
-                self.execute_delete(operation, transaction, tree_index_map, header)
-            }
+            Command::Delete { header } => self.execute_delete(
+                operation,
+                transaction,
+                tree_index_map,
+                header,
+                cascaded_deletes,
+            ),
             Command::Check { id, revision } => Self::execute_check(
                 operation,
                 transaction,
@@ -552,11 +979,24 @@ impl Database {
         )?;
         drop(documents);
 
+        let result =
+            result.expect("nebari should invoke the callback even when the key isn't found");
         if updated {
             self.update_eager_views(&document_id, operation, transaction, tree_index_map)?;
+            if let Ok(OperationResult::DocumentUpdated { header, .. }) = &result {
+                self.check_references(
+                    &operation.collection,
+                    &BorrowedDocument {
+                        header: header.clone(),
+                        contents: CowBytes::from(contents),
+                    },
+                    transaction,
+                    tree_index_map,
+                )?;
+            }
         }
 
-        result.expect("nebari should invoke the callback even when the key isn't found")
+        result
     }
 
     #[cfg_attr(
@@ -584,15 +1024,16 @@ impl Database {
             .unwrap();
         let id = if let Some(id) = id {
             id
-        } else if let Some(last_key) = documents.last_key()? {
-            let id = DocumentId::try_from(last_key.as_slice())?;
-            self.data
-                .schema
-                .next_id_for_collection(&operation.collection, Some(id))?
         } else {
-            self.data
-                .schema
-                .next_id_for_collection(&operation.collection, None)?
+            let last_id = documents
+                .last_key()?
+                .map(|last_key| DocumentId::try_from(last_key.as_slice()))
+                .transpose()?;
+            self.data.schema.next_id_for_collection(
+                &operation.collection,
+                last_id.as_ref(),
+                self.storage.unique_id().as_u64(),
+            )?
         };
 
         let doc = BorrowedDocument::new(id, contents);
@@ -607,6 +1048,7 @@ impl Database {
         } else {
             drop(documents);
             self.update_eager_views(&document_id, operation, transaction, tree_index_map)?;
+            self.check_references(&operation.collection, &doc, transaction, tree_index_map)?;
 
             Ok(OperationResult::DocumentUpdated {
                 collection: operation.collection.clone(),
@@ -617,7 +1059,7 @@ impl Database {
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level = "trace",
-        skip(self, operation, transaction, tree_index_map),
+        skip(self, operation, transaction, tree_index_map, cascaded_deletes),
         fields(
             database = self.name(),
             collection.name = operation.collection.name.as_ref(),
@@ -630,6 +1072,7 @@ impl Database {
         transaction: &mut ExecutingTransaction<AnyFile>,
         tree_index_map: &HashMap<String, usize>,
         header: &Header,
+        cascaded_deletes: &mut Vec<OperationResult>,
     ) -> Result<OperationResult, Error> {
         let mut documents = transaction
             .tree::<Versioned>(tree_index_map[&document_tree_name(&operation.collection)])
@@ -644,6 +1087,13 @@ impl Database {
                     transaction,
                     tree_index_map,
                 )?;
+                self.apply_references_on_delete(
+                    &operation.collection,
+                    header,
+                    transaction,
+                    tree_index_map,
+                    cascaded_deletes,
+                )?;
 
                 Ok(OperationResult::DocumentDeleted {
                     collection: operation.collection.clone(),
@@ -663,6 +1113,103 @@ impl Database {
         }
     }
 
+    /// Verifies that every document `document` refers to exists.
+    fn check_references(
+        &self,
+        collection: &CollectionName,
+        document: &BorrowedDocument<'_>,
+        transaction: &mut ExecutingTransaction<AnyFile>,
+        tree_index_map: &HashMap<String, usize>,
+    ) -> Result<(), Error> {
+        for reference in self.data.schema.references_from_collection(collection) {
+            let parent_ids = reference.parent_ids(document)?;
+            if parent_ids.is_empty() {
+                continue;
+            }
+
+            let parent = reference.parent();
+            let mut parents = transaction
+                .tree::<Versioned>(tree_index_map[&document_tree_name(&parent)])
+                .unwrap();
+            for parent_id in parent_ids {
+                if parents.get(parent_id.as_ref())?.is_none() {
+                    return Err(Error::Core(
+                        bonsaidb_core::Error::ReferencedDocumentNotFound {
+                            document: Box::new(document.header.clone()),
+                            parent,
+                            parent_id: Box::new(parent_id),
+                        },
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the [`OnDelete`] action of each reference to the deleted
+    /// document. Documents deleted by [`OnDelete::Cascade`] are appended to
+    /// `cascaded_deletes`.
+    fn apply_references_on_delete(
+        &self,
+        collection: &CollectionName,
+        header: &Header,
+        transaction: &mut ExecutingTransaction<AnyFile>,
+        tree_index_map: &HashMap<String, usize>,
+        cascaded_deletes: &mut Vec<OperationResult>,
+    ) -> Result<(), Error> {
+        for reference in self.data.schema.references_to_collection(collection) {
+            let view_name = reference.view_name();
+            let generation = *self
+                .view_generation(self.data.schema.view_by_name(&view_name)?)?
+                .read();
+            let Some(entry) = transaction
+                .tree::<Unversioned>(
+                    tree_index_map[&view_entries_tree_name(&view_name, generation)],
+                )
+                .unwrap()
+                .get(header.id.as_ref())?
+            else {
+                continue;
+            };
+            let entry = bincode::deserialize::<ViewEntry>(&entry)?;
+
+            match reference.on_delete() {
+                OnDelete::Restrict => {
+                    if let Some(mapping) = entry.mappings.into_iter().next() {
+                        return Err(Error::Core(bonsaidb_core::Error::DocumentStillReferenced {
+                            view: view_name,
+                            document: Box::new(header.clone()),
+                            referencing_document: Box::new(mapping.source),
+                        }));
+                    }
+                }
+                OnDelete::Cascade => {
+                    let child_collection = reference.collection();
+                    for mapping in entry.mappings {
+                        let operation =
+                            Operation::delete(child_collection.clone(), mapping.source.clone());
+                        match self.execute_delete(
+                            &operation,
+                            transaction,
+                            tree_index_map,
+                            &mapping.source,
+                            cascaded_deletes,
+                        ) {
+                            Ok(result) => cascaded_deletes.push(result),
+                            // The document may have already been deleted by
+                            // another cascading delete.
+                            Err(Error::Core(bonsaidb_core::Error::DocumentNotFound(..))) => {}
+                            Err(err) => return Err(err),
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level = "trace",
         skip(self, operation, transaction, tree_index_map),
@@ -690,11 +1237,17 @@ impl Database {
                 .unwrap();
             for view in eager_views {
                 let name = view.view_name();
+                // Eager views are never rebuilt in the background, so their
+                // generation can't change while the transaction is applied.
+                let generation = *self.view_generation(view)?.read();
                 let document_map = transaction
-                    .unlocked_tree(tree_index_map[&view_document_map_tree_name(&name)])
+                    .unlocked_tree(tree_index_map[&view_document_map_tree_name(&name, generation)])
                     .unwrap();
                 let view_entries = transaction
-                    .unlocked_tree(tree_index_map[&view_entries_tree_name(&name)])
+                    .unlocked_tree(tree_index_map[&view_entries_tree_name(&name, generation)])
+                    .unwrap();
+                let view_reductions = transaction
+                    .unlocked_tree(tree_index_map[&view_reductions_tree_name(&name, generation)])
                     .unwrap();
                 mapper::DocumentRequest {
                     database: self,
@@ -707,6 +1260,7 @@ impl Database {
                     document_map,
                     documents,
                     view_entries,
+                    view_reductions,
                     view,
                 }
                 .map()?;
@@ -896,6 +1450,39 @@ impl Database {
         Ok(tree)
     }
 
+    /// Returns the generation of the trees storing `view`'s index. The
+    /// generation is only changed by a background rebuild, which holds the
+    /// returned lock for writing while it replaces the view's trees.
+    /// Anything reading or updating the view's trees should hold the lock for
+    /// reading while it uses them.
+    pub(crate) fn view_generation(
+        &self,
+        view: &dyn view::Serialized,
+    ) -> Result<Arc<RwLock<u64>>, Error> {
+        let view_name = view.view_name();
+        if let Some(generation) = self.data.context.view_generations.lock().get(&view_name) {
+            return Ok(generation.clone());
+        }
+
+        let collection = view.collection();
+        let view_versions = self.roots().tree(self.collection_tree::<Unversioned, _>(
+            &collection,
+            view_versions_tree_name(&collection),
+        )?)?;
+        let generation = view_versions
+            .get(view_name.to_string().as_bytes())?
+            .and_then(|version| ViewVersion::from_bytes(&version).ok())
+            .map_or(0, |version| version.generation());
+        Ok(self
+            .data
+            .context
+            .view_generations
+            .lock()
+            .entry(view_name)
+            .or_insert_with(|| Arc::new(RwLock::new(generation)))
+            .clone())
+    }
+
     pub(crate) fn update_key_expiration<'key>(
         &self,
         tree_key: impl Into<Cow<'key, str>>,
@@ -1153,16 +1740,23 @@ impl LowLevelConnection for Database {
                 ),
             };
             self.check_permission(resource, &action)?;
+
+            // Dynamic collections are indexed by deserializing their
+            // documents, so their contents must be valid for the collection's
+            // format.
+            if let Command::Insert { contents, .. }
+            | Command::Update { contents, .. }
+            | Command::Overwrite { contents, .. } = &op.command
+            {
+                if let Some(collection) = self.data.schema.dynamic_collection(&op.collection) {
+                    collection.validate_contents(contents)?;
+                }
+            }
         }
 
         let mut eager_view_tasks = Vec::new();
-        for collection_name in transaction
-            .operations
-            .iter()
-            .map(|op| &op.collection)
-            .collect::<HashSet<_>>()
-        {
-            for view in self.data.schema.eager_views_in_collection(collection_name) {
+        for collection_name in self.collections_for_transaction(&transaction) {
+            for view in self.data.schema.eager_views_in_collection(&collection_name) {
                 if let Some(task) = self
                     .storage
                     .instance
@@ -1468,11 +2062,13 @@ impl LowLevelConnection for Database {
             view_resource_name(self.name(), &view.view_name()),
             &BonsaiAction::Database(DatabaseAction::View(ViewAction::Query)),
         )?;
+        let collection = view.collection();
         let mut results = Vec::new();
         self.for_each_in_view(view, key, order, limit, access_policy, |entry| {
             for mapping in entry.mappings {
                 results.push(bonsaidb_core::schema::view::map::Serialized {
                     source: mapping.source,
+                    collection: mapping.collection,
                     key: entry.key.clone(),
                     value: mapping.value,
                 });
@@ -1501,25 +2097,90 @@ impl LowLevelConnection for Database {
         limit: Option<u32>,
         access_policy: AccessPolicy,
     ) -> Result<schema::view::map::MappedSerializedDocuments, bonsaidb_core::Error> {
-        let results = self.query_by_name(view, key, order, limit, access_policy)?;
-        let view = self.schematic().view_by_name(view).unwrap(); // query() will fail if it's not present
+        let view = self.schematic().view_by_name(view)?;
+        self.check_permission(
+            view_resource_name(self.name(), &view.view_name()),
+            &BonsaiAction::Database(DatabaseAction::View(ViewAction::Query)),
+        )?;
+        let collection = view.collection();
+        let mut results = Vec::new();
+        let mut projected = BTreeMap::<_, BTreeMap<_, _>>::new();
+        self.for_each_in_view(view, key, order, limit, access_policy, |entry| {
+            for mapping in entry.mappings {
+                if let Some(projection) = mapping.projection {
+                    // Views that store projections can return the document
+                    // without retrieving it from the collection.
+                    projected
+                        .entry(
+                            mapping
+                                .collection
+                                .clone()
+                                .unwrap_or_else(|| collection.clone()),
+                        )
+                        .or_default()
+                        .insert(
+                            mapping.source.id.clone(),
+                            OwnedDocument {
+                                header: mapping.source.clone(),
+                                contents: projection,
+                            },
+                        );
+                }
+                results.push(bonsaidb_core::schema::view::map::Serialized {
+                    source: mapping.source,
+                    collection: mapping.collection,
+                    key: entry.key.clone(),
+                    value: mapping.value,
+                });
+            }
+            Ok(())
+        })?;
 
-        let documents = self
-            .get_multiple_from_collection(
-                &results
-                    .iter()
-                    .map(|m| m.source.id.clone())
-                    .collect::<Vec<_>>(),
-                &view.collection(),
-            )?
-            .into_iter()
-            .map(|doc| (doc.header.id.clone(), doc))
-            .collect::<BTreeMap<_, _>>();
+        let mut ids_by_collection = BTreeMap::<_, Vec<_>>::new();
+        for mapping in &results {
+            let source_collection = mapping.collection.as_ref().unwrap_or(&collection);
+            let is_projected = projected.get(source_collection).map_or(false, |documents| {
+                documents.contains_key(&mapping.source.id)
+            });
+            if !is_projected {
+                ids_by_collection
+                    .entry(source_collection)
+                    .or_default()
+                    .push(mapping.source.id.clone());
+            }
+        }
+
+        let mut documents_by_collection = projected;
+        for (collection, documents) in &documents_by_collection {
+            for id in documents.keys() {
+                self.check_permission(
+                    document_resource_name(self.name(), collection, id),
+                    &BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Get)),
+                )?;
+            }
+        }
+        for (source_collection, ids) in ids_by_collection {
+            let source_documents = self.get_multiple_from_collection(&ids, source_collection)?;
+            documents_by_collection
+                .entry(source_collection.clone())
+                .or_default()
+                .extend(
+                    source_documents
+                        .into_iter()
+                        .map(|doc| (doc.header.id.clone(), doc)),
+                );
+        }
+
+        let documents = documents_by_collection
+            .remove(&collection)
+            .unwrap_or_default();
+        let linked_documents = documents_by_collection;
 
         Ok(
             bonsaidb_core::schema::view::map::MappedSerializedDocuments {
                 mappings: results,
                 documents,
+                linked_documents,
             },
         )
     }
@@ -1540,23 +2201,19 @@ impl LowLevelConnection for Database {
         key: Option<SerializedQueryKey>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<u8>, bonsaidb_core::Error> {
-        let mut mappings = self.reduce_grouped_by_name(view_name, key, access_policy)?;
-
-        let result = if mappings.len() == 1 {
-            mappings.pop().unwrap().value.into_vec()
-        } else {
-            let view = self.data.schema.view_by_name(view_name)?;
-            view.reduce(
-                &mappings
-                    .iter()
-                    .map(|map| (map.key.as_ref(), map.value.as_ref()))
-                    .collect::<Vec<_>>(),
-                true,
-            )
-            .map_err(Error::from)?
+        let key = match key {
+            None => None,
+            Some(SerializedQueryKey::Range(range)) => Some(range),
+            Some(key) => {
+                return self.reduce_matching_keys(view_name, key, access_policy);
+            }
         };
-
-        Ok(result)
+        let view = self.data.schema.view_by_name(view_name)?;
+        self.check_permission(
+            view_resource_name(self.name(), &view.view_name()),
+            &BonsaiAction::Database(DatabaseAction::View(ViewAction::Reduce)),
+        )?;
+        self.reduce_range_in_view(view, key, access_policy)
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1573,6 +2230,7 @@ impl LowLevelConnection for Database {
         &self,
         view_name: &ViewName,
         key: Option<SerializedQueryKey>,
+        group_level: Option<usize>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<MappedSerializedValue>, bonsaidb_core::Error> {
         let view = self.data.schema.view_by_name(view_name)?;
@@ -1589,6 +2247,10 @@ impl LowLevelConnection for Database {
             Ok(())
         })?;
 
+        if let Some(group_level) = group_level {
+            mappings = Self::reduce_groups(view, mappings, group_level)?;
+        }
+
         Ok(mappings)
     }
 
@@ -1613,7 +2275,10 @@ impl LowLevelConnection for Database {
         let mut transaction = Transaction::default();
         self.for_each_in_view(view, key, Sort::Ascending, None, access_policy, |entry| {
             for mapping in entry.mappings {
-                transaction.push(Operation::delete(collection.clone(), mapping.source));
+                transaction.push(Operation::delete(
+                    mapping.collection.unwrap_or_else(|| collection.clone()),
+                    mapping.source,
+                ));
             }
 
             Ok(())
@@ -1623,6 +2288,109 @@ impl LowLevelConnection for Database {
 
         Ok(results.len() as u64)
     }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = "trace",
+        skip(self, collection, filter),
+        fields(
+            database = self.name(),
+            collection.name = collection.name.as_ref(),
+            collection.authority = collection.authority.as_ref(),
+        )
+    ))]
+    fn filter_documents_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        self.filter_documents(collection, &filter, order, limit, access_policy)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = "trace",
+        skip(self, collection, filter),
+        fields(
+            database = self.name(),
+            collection.name = collection.name.as_ref(),
+            collection.authority = collection.authority.as_ref(),
+        )
+    ))]
+    fn filter_headers_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, bonsaidb_core::Error> {
+        self.filter_headers(collection, &filter, order, limit, access_policy)
+    }
+
+    fn view_status_by_name(&self, view: &ViewName) -> Result<ViewStatus, bonsaidb_core::Error> {
+        let view = self.data.schema.view_by_name(view)?;
+        let view_name = view.view_name();
+        self.check_permission(
+            view_resource_name(self.name(), &view_name),
+            &BonsaiAction::Database(DatabaseAction::View(ViewAction::Status)),
+        )?;
+
+        let collection = view.collection();
+        let mut invalidated_trees = vec![self.collection_tree::<Unversioned, _>(
+            &collection,
+            view_invalidated_docs_tree_name(&view_name),
+        )?];
+        for linked_collection in view.linked_collections() {
+            invalidated_trees.push(self.collection_tree::<Unversioned, _>(
+                &linked_collection,
+                view_linked_invalidated_docs_tree_name(&view_name, &linked_collection),
+            )?);
+        }
+        let mut invalidated_documents = 0;
+        for tree in invalidated_trees {
+            self.roots().tree(tree)?.scan::<Infallible, _, _, _, _>(
+                &(..),
+                true,
+                |_, _, _| ScanEvaluation::ReadData,
+                |_, _| {
+                    invalidated_documents += 1;
+                    ScanEvaluation::Skip
+                },
+                |_, _, _| unreachable!(),
+            )?;
+        }
+
+        let tasks = self.storage.instance.tasks();
+        Ok(ViewStatus {
+            last_indexed_transaction_id: tasks.view_last_indexed_transaction_id(
+                self.data.name.clone(),
+                collection.clone(),
+                view_name.clone(),
+            ),
+            invalidated_documents,
+            update_in_progress: tasks.view_update_in_progress(
+                self.data.name.clone(),
+                collection.clone(),
+                view_name.clone(),
+            ),
+            integrity_checked: tasks.view_integrity_checked(
+                self.data.name.clone(),
+                collection.clone(),
+                view_name.clone(),
+            ),
+            rebuild: tasks.view_rebuild_progress(self.data.name.clone(), collection, view_name),
+        })
+    }
+
+    fn define_dynamic_collection(
+        &self,
+        collection: DynamicCollection,
+    ) -> Result<(), bonsaidb_core::Error> {
+        self.store_dynamic_collection(collection)?;
+        Ok(())
+    }
 }
 
 impl HasSchema for Database {
@@ -1666,6 +2434,9 @@ impl Deref for Context {
 pub(crate) struct ContextData {
     pub(crate) roots: Roots<AnyFile>,
     key_value_state: Arc<Mutex<keyvalue::KeyValueState>>,
+    last_transaction_id: Arc<Watchable<Option<u64>>>,
+    dynamic_collections: Mutex<Option<Arc<Vec<DynamicCollection>>>>,
+    view_generations: Mutex<HashMap<ViewName, Arc<RwLock<u64>>>>,
 }
 
 impl Borrow<Roots<AnyFile>> for Context {
@@ -1682,16 +2453,23 @@ impl Context {
     ) -> Self {
         let background_worker_target = Watchable::new(BackgroundWorkerProcessTarget::Never);
         let mut background_worker_target_watcher = background_worker_target.watch();
+        let last_transaction_id = Arc::new(Watchable::new(
+            roots.transactions().current_transaction_id(),
+        ));
         let key_value_state = Arc::new(Mutex::new(keyvalue::KeyValueState::new(
             key_value_persistence,
             roots.clone(),
             background_worker_target,
+            last_transaction_id.clone(),
         )));
         let background_worker_state = Arc::downgrade(&key_value_state);
         let context = Self {
             data: Arc::new(ContextData {
                 roots,
                 key_value_state,
+                last_transaction_id,
+                dynamic_collections: Mutex::default(),
+                view_generations: Mutex::default(),
             }),
         };
         std::thread::Builder::new()
@@ -1724,6 +2502,10 @@ impl Context {
         state.update_key_expiration(tree_key, expiration);
     }
 
+    fn transaction_committed(&self, transaction_id: Option<u64>) {
+        self.data.last_transaction_id.replace(transaction_id);
+    }
+
     #[cfg(test)]
     pub(crate) fn kv_persistence_watcher(&self) -> watchable::Watcher<Timestamp> {
         let state = self.data.key_value_state.lock();
@@ -1742,6 +2524,52 @@ impl Drop for ContextData {
     }
 }
 
+/// Watches a [`Database`] for newly committed transactions.
+///
+/// Only the most recently committed transaction id is tracked. If multiple
+/// transactions are committed between calls to wait, only the most recent id
+/// is returned. Use
+/// [`Connection::list_executed_transactions()`](bonsaidb_core::connection::Connection::list_executed_transactions)
+/// to retrieve the details of every transaction committed since an
+/// id was last observed.
+#[must_use]
+pub struct TransactionWatcher(Watcher<Option<u64>>);
+
+impl TransactionWatcher {
+    /// Blocks the current thread until a transaction is committed, returning
+    /// the id of the most recently committed transaction. Returns `None` if
+    /// the database has been closed.
+    pub fn wait_for_transaction(&mut self) -> Option<u64> {
+        loop {
+            match self.0.next_value() {
+                Ok(Some(transaction_id)) => return Some(transaction_id),
+                Ok(None) => {}
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Waits until a transaction is committed, returning the id of the most
+    /// recently committed transaction. Returns `None` if the database has been
+    /// closed.
+    #[cfg(feature = "async")]
+    pub async fn wait_for_transaction_async(&mut self) -> Option<u64> {
+        loop {
+            match self.0.next_value_async().await {
+                Ok(Some(transaction_id)) => return Some(transaction_id),
+                Ok(None) => {}
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+impl Debug for TransactionWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TransactionWatcher").finish()
+    }
+}
+
 pub fn document_tree_name(collection: &CollectionName) -> String {
     format!("collection.{collection:#}")
 }
@@ -0,0 +1,456 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+#[cfg(feature = "async")]
+use std::sync::Arc;
+
+use bonsaidb_core::arc_bytes::ArcBytes;
+use bonsaidb_core::connection::HasSession;
+use bonsaidb_core::document::{BorrowedDocument, CollectionHeader, DocumentId};
+use bonsaidb_core::permissions::bonsai::{
+    collection_resource_name, BonsaiAction, DatabaseAction, DocumentAction,
+};
+use bonsaidb_core::schema::{CollectionName, SerializedCollection};
+use bonsaidb_core::transaction::{ChangedDocument, Changes, DocumentChanges};
+use nebari::io::any::AnyFile;
+use nebari::tree::{AnyTreeRoot, CompareSwap, KeyOperation, Operation, Unversioned, Versioned};
+
+use crate::database::{compat, deserialize_document, document_tree_name, serialize_document};
+use crate::views::{view_invalidated_docs_tree_name, view_linked_invalidated_docs_tree_name};
+use crate::{Database, DatabaseNonBlocking, Error};
+
+/// The number of documents written per transaction by [`BulkImport`] unless
+/// changed using [`BulkImport::batch_size()`].
+pub const DEFAULT_BULK_IMPORT_BATCH_SIZE: usize = 10_000;
+
+/// Imports a large number of documents into a collection.
+///
+/// Unlike inserting documents using transactions, documents imported using
+/// this type:
+///
+/// - are written in large batches, each using a single transaction. Each batch
+///   is recorded as one entry in the transaction log, which is returned from
+///   [`Connection::list_executed_transactions()`](bonsaidb_core::connection::Connection::list_executed_transactions).
+/// - do not update the collection's views, including
+///   [eager](bonsaidb_core::schema::view::ViewUpdatePolicy::Eager) and
+///   [unique](bonsaidb_core::schema::view::ViewUpdatePolicy::Unique) views,
+///   as they are written. Instead, every view is updated in a single pass when
+///   [`BulkImport::finish()`] is called.
+///
+/// Each batch is committed as it is written. If an error occurs, the batches
+/// written prior to the error remain in the collection. Because unique
+/// constraints are not checked until [`BulkImport::finish()`] is called, a
+/// [`UniqueKeyViolation`](bonsaidb_core::Error::UniqueKeyViolation) returned
+/// from it means the conflicting documents have already been imported. The
+/// unique view will be unable to update until the conflicts are resolved, for
+/// example by deleting one of the conflicting documents.
+///
+/// Documents are only written when a batch is full or when
+/// [`BulkImport::finish()`] is called. Dropping this type without calling
+/// [`BulkImport::finish()`] discards any documents that have not been written.
+///
+/// For async code, see [`AsyncBulkImport`](crate::AsyncBulkImport).
+///
+/// ```rust
+/// # use bonsaidb_core::test_util::{Basic, BasicSchema, TestDirectory};
+/// # use bonsaidb_local::config::{Builder, StorageConfiguration};
+/// # use bonsaidb_local::Database;
+/// # fn test() -> Result<(), bonsaidb_local::Error> {
+/// # let path = TestDirectory::new("bulk-import-doctest");
+/// # let db = Database::open::<BasicSchema>(StorageConfiguration::new(&path))?;
+/// let mut import = db.bulk_import::<Basic>()?;
+/// for index in 0..100 {
+///     import.push(&Basic::new(format!("document {index}")))?;
+/// }
+/// assert_eq!(import.finish()?, 100);
+/// # Ok(())
+/// # }
+/// # test().unwrap();
+/// ```
+#[must_use]
+pub struct BulkImport<C> {
+    database: Database,
+    collection: CollectionName,
+    pending: BTreeMap<ArcBytes<'static>, BorrowedDocument<'static>>,
+    last_id: Option<DocumentId>,
+    batch_size: usize,
+    imported: u64,
+    _collection: PhantomData<C>,
+}
+
+impl Database {
+    /// Begins importing documents into the collection `C`. See
+    /// [`BulkImport`] for more information.
+    pub fn bulk_import<C: SerializedCollection>(&self) -> Result<BulkImport<C>, Error> {
+        let collection = C::collection_name();
+        self.check_permission(
+            collection_resource_name(self.name(), &collection),
+            &BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Insert)),
+        )?;
+        if self
+            .data
+            .schema
+            .collection_primary_key_description(&collection)
+            .is_none()
+        {
+            return Err(Error::Core(bonsaidb_core::Error::CollectionNotFound));
+        }
+
+        let documents = self.roots().tree(
+            self.collection_tree::<Versioned, _>(&collection, document_tree_name(&collection))?,
+        )?;
+        let last_id = documents
+            .last_key()?
+            .map(|last_key| DocumentId::try_from(last_key.as_slice()))
+            .transpose()?;
+
+        Ok(BulkImport {
+            database: self.clone(),
+            collection,
+            pending: BTreeMap::new(),
+            last_id,
+            batch_size: DEFAULT_BULK_IMPORT_BATCH_SIZE,
+            imported: 0,
+            _collection: PhantomData,
+        })
+    }
+}
+
+impl<C> BulkImport<C>
+where
+    C: SerializedCollection,
+{
+    /// Sets the number of documents written per transaction. The default is
+    /// [`DEFAULT_BULK_IMPORT_BATCH_SIZE`].
+    pub fn batch_size(mut self, documents: usize) -> Self {
+        self.batch_size = documents.max(1);
+        self
+    }
+
+    /// Imports a document containing `contents`, assigning it the next
+    /// available id. Returns the header of the imported document.
+    ///
+    /// Ids are assigned when the document is pushed. If another connection
+    /// inserts a document with the same id before this document is written,
+    /// writing the batch containing this document will return
+    /// [`DocumentConflict`](bonsaidb_core::Error::DocumentConflict).
+    pub fn push(
+        &mut self,
+        contents: &C::Contents,
+    ) -> Result<CollectionHeader<C::PrimaryKey>, Error> {
+        let id = self.next_id()?;
+        self.import(id, C::serialize(contents)?)
+    }
+
+    /// Imports a document with the given `id` containing `contents`. Returns
+    /// the header of the imported document.
+    ///
+    /// If a document with `id` already exists,
+    /// [`DocumentConflict`](bonsaidb_core::Error::DocumentConflict) is returned
+    /// when the batch containing this document is written.
+    pub fn insert(
+        &mut self,
+        id: &C::PrimaryKey,
+        contents: &C::Contents,
+    ) -> Result<CollectionHeader<C::PrimaryKey>, Error> {
+        let id = DocumentId::new(id)?;
+        self.import(id, C::serialize(contents)?)
+    }
+
+    fn next_id(&self) -> Result<DocumentId, Error> {
+        Ok(self.database.data.schema.next_id_for_collection(
+            &self.collection,
+            self.last_id.as_ref(),
+            self.database.storage.unique_id().as_u64(),
+        )?)
+    }
+
+    fn import(
+        &mut self,
+        id: DocumentId,
+        contents: Vec<u8>,
+    ) -> Result<CollectionHeader<C::PrimaryKey>, Error> {
+        let header = self.queue(id, contents)?;
+        if self.is_batch_full() {
+            self.write_pending()?;
+        }
+        Ok(header)
+    }
+
+    /// Adds a document to the pending batch without writing it.
+    fn queue(
+        &mut self,
+        id: DocumentId,
+        contents: Vec<u8>,
+    ) -> Result<CollectionHeader<C::PrimaryKey>, Error> {
+        let document = BorrowedDocument::new(id, contents);
+        let header = document.header.clone();
+        match self.pending.entry(ArcBytes::from(header.id.to_vec())) {
+            Entry::Occupied(existing) => {
+                return Err(Error::Core(bonsaidb_core::Error::DocumentConflict(
+                    self.collection.clone(),
+                    Box::new(existing.get().header.clone()),
+                )));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(document);
+            }
+        }
+        if self.last_id.as_ref() < Some(&header.id) {
+            self.last_id = Some(header.id.clone());
+        }
+
+        Ok(CollectionHeader::try_from(header)?)
+    }
+
+    fn is_batch_full(&self) -> bool {
+        self.pending.len() >= self.batch_size
+    }
+
+    /// Writes the remaining documents and updates all views that index this
+    /// collection. Returns the number of documents imported.
+    ///
+    /// Each view is updated regardless of its update policy, which checks the
+    /// constraints of [unique
+    /// views](bonsaidb_core::schema::view::ViewUpdatePolicy::Unique).
+    pub fn finish(mut self) -> Result<u64, Error> {
+        self.write_pending()?;
+
+        let schema = &self.database.data.schema;
+        for view in schema
+            .views_in_collection(&self.collection)
+            .chain(schema.views_linked_to_collection(&self.collection))
+        {
+            self.database
+                .storage
+                .instance
+                .tasks()
+                .update_view_if_needed(view, &self.database, true)
+                .map_err(|err| match err {
+                    // The mapping job's error may be shared with other
+                    // callers waiting on the same job.
+                    Error::Job(err) => match &*err {
+                        Error::Core(core) => Error::Core(core.clone()),
+                        _ => Error::Job(err),
+                    },
+                    other => other,
+                })?;
+        }
+
+        Ok(self.imported)
+    }
+
+    /// Writes the pending documents. See [`write_batch()`].
+    fn write_pending(&mut self) -> Result<(), Error> {
+        let pending = std::mem::take(&mut self.pending);
+        self.imported += write_batch(&self.database, &self.collection, &pending)?;
+        Ok(())
+    }
+}
+
+/// Writes `pending` to `collection` in a single transaction, marking the
+/// documents as invalidated in every view that indexes the collection. Returns
+/// the number of documents written.
+fn write_batch(
+    database: &Database,
+    collection: &CollectionName,
+    pending: &BTreeMap<ArcBytes<'static>, BorrowedDocument<'static>>,
+) -> Result<u64, Error> {
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let schema = &database.data.schema;
+    let mut trees: Vec<Box<dyn AnyTreeRoot<AnyFile>>> = vec![Box::new(
+        database.collection_tree::<Versioned, _>(collection, document_tree_name(collection))?,
+    )];
+    let mut tree_index_map = HashMap::new();
+    tree_index_map.insert(document_tree_name(collection), 0);
+    for view in schema.views_in_collection(collection) {
+        trees.push(Box::new(database.collection_tree::<Unversioned, _>(
+            collection,
+            view_invalidated_docs_tree_name(&view.view_name()),
+        )?));
+    }
+    for view in schema.views_linked_to_collection(collection) {
+        trees.push(Box::new(database.collection_tree::<Unversioned, _>(
+            collection,
+            view_linked_invalidated_docs_tree_name(&view.view_name(), collection),
+        )?));
+    }
+    let invalidated_trees = 1..trees.len();
+    // Referenced documents must exist, which requires reading the parent
+    // collections' documents.
+    for reference in schema.references_from_collection(collection) {
+        let parent = reference.parent();
+        let tree_name = document_tree_name(&parent);
+        if !tree_index_map.contains_key(&tree_name) {
+            tree_index_map.insert(tree_name.clone(), trees.len());
+            trees.push(Box::new(
+                database.collection_tree::<Versioned, _>(&parent, tree_name)?,
+            ));
+        }
+    }
+
+    let mut transaction = database
+        .roots()
+        .transaction::<_, dyn AnyTreeRoot<AnyFile>>(&trees)?;
+
+    for document in pending.values() {
+        database.check_references(collection, document, &mut transaction, &tree_index_map)?;
+    }
+
+    let ids = pending.keys().cloned().collect::<Vec<_>>();
+    let mut result: Result<(), Error> = Ok(());
+    transaction.tree::<Versioned>(0).unwrap().modify(
+        ids.clone(),
+        Operation::CompareSwap(CompareSwap::new(&mut |key, existing| {
+            if result.is_err() {
+                return KeyOperation::Skip;
+            }
+            if let Some(existing) = existing {
+                result = Err(match deserialize_document(&existing) {
+                    Ok(existing) => Error::Core(bonsaidb_core::Error::DocumentConflict(
+                        collection.clone(),
+                        Box::new(existing.header),
+                    )),
+                    Err(err) => err,
+                });
+                return KeyOperation::Skip;
+            }
+            match serialize_document(&pending[key.as_slice()]) {
+                Ok(serialized) => KeyOperation::Set(ArcBytes::from(serialized)),
+                Err(err) => {
+                    result = Err(Error::from(err));
+                    KeyOperation::Skip
+                }
+            }
+        })),
+    )?;
+    result?;
+
+    for index in invalidated_trees {
+        transaction
+            .tree::<Unversioned>(index)
+            .unwrap()
+            .modify(ids.clone(), Operation::Set(ArcBytes::default()))?;
+    }
+
+    // Recording the batch allows transaction log consumers to observe the
+    // imported documents.
+    transaction
+        .entry_mut()
+        .set_data(compat::serialize_executed_transaction_changes(
+            &Changes::Documents(DocumentChanges {
+                collections: vec![collection.clone()],
+                documents: pending
+                    .values()
+                    .map(|document| ChangedDocument {
+                        collection: 0,
+                        id: document.header.id.clone(),
+                        deleted: false,
+                    })
+                    .collect(),
+            }),
+        )?)?;
+
+    transaction.commit()?;
+    database
+        .data
+        .context
+        .transaction_committed(database.roots().transactions().current_transaction_id());
+
+    Ok(ids.len() as u64)
+}
+
+#[cfg(feature = "async")]
+impl crate::AsyncDatabase {
+    /// Begins importing documents into the collection `C`. See
+    /// [`AsyncBulkImport`] for more information.
+    pub async fn bulk_import<C: SerializedCollection + 'static>(
+        &self,
+    ) -> Result<AsyncBulkImport<C>, Error> {
+        let database = self.database.clone();
+        let blocking = self
+            .runtime
+            .spawn_blocking(move || database.bulk_import::<C>())
+            .await??;
+        Ok(AsyncBulkImport {
+            blocking,
+            runtime: self.runtime.clone(),
+        })
+    }
+}
+
+/// Imports a large number of documents into a collection. This is the async
+/// counterpart of [`BulkImport`], which describes how the documents are
+/// written.
+#[cfg(feature = "async")]
+#[must_use]
+pub struct AsyncBulkImport<C> {
+    blocking: BulkImport<C>,
+    runtime: Arc<tokio::runtime::Handle>,
+}
+
+#[cfg(feature = "async")]
+impl<C> AsyncBulkImport<C>
+where
+    C: SerializedCollection + 'static,
+{
+    /// Sets the number of documents written per transaction. The default is
+    /// [`DEFAULT_BULK_IMPORT_BATCH_SIZE`].
+    pub fn batch_size(mut self, documents: usize) -> Self {
+        self.blocking = self.blocking.batch_size(documents);
+        self
+    }
+
+    /// Imports a document containing `contents`, assigning it the next
+    /// available id. Returns the header of the imported document. See
+    /// [`BulkImport::push()`].
+    pub async fn push(
+        &mut self,
+        contents: &C::Contents,
+    ) -> Result<CollectionHeader<C::PrimaryKey>, Error> {
+        let id = self.blocking.next_id()?;
+        self.import(id, C::serialize(contents)?).await
+    }
+
+    /// Imports a document with the given `id` containing `contents`. Returns
+    /// the header of the imported document. See [`BulkImport::insert()`].
+    pub async fn insert(
+        &mut self,
+        id: &C::PrimaryKey,
+        contents: &C::Contents,
+    ) -> Result<CollectionHeader<C::PrimaryKey>, Error> {
+        let id = DocumentId::new(id)?;
+        self.import(id, C::serialize(contents)?).await
+    }
+
+    async fn import(
+        &mut self,
+        id: DocumentId,
+        contents: Vec<u8>,
+    ) -> Result<CollectionHeader<C::PrimaryKey>, Error> {
+        let header = self.blocking.queue(id, contents)?;
+        if self.blocking.is_batch_full() {
+            let pending = std::mem::take(&mut self.blocking.pending);
+            let database = self.blocking.database.clone();
+            let collection = self.blocking.collection.clone();
+            self.blocking.imported += self
+                .runtime
+                .spawn_blocking(move || write_batch(&database, &collection, &pending))
+                .await??;
+        }
+        Ok(header)
+    }
+
+    /// Writes the remaining documents and updates all views that index this
+    /// collection. Returns the number of documents imported. See
+    /// [`BulkImport::finish()`].
+    pub async fn finish(self) -> Result<u64, Error> {
+        let blocking = self.blocking;
+        self.runtime
+            .spawn_blocking(move || blocking.finish())
+            .await?
+    }
+}
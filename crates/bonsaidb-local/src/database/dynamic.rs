@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use bonsaidb_core::connection::HasSession;
+use bonsaidb_core::permissions::bonsai::{collection_resource_name, BonsaiAction, DatabaseAction};
+use bonsaidb_core::schema::dynamic::DynamicCollection;
+use nebari::tree::Unversioned;
+
+use crate::database::Context;
+use crate::{Database, DatabaseNonBlocking, Error};
+
+/// The tree containing the definitions of collections defined using
+/// [`Database::define_dynamic_collection()`](bonsaidb_core::connection::LowLevelConnection::define_dynamic_collection).
+pub(crate) const DYNAMIC_COLLECTIONS_TREE: &str = "dynamic-collections";
+
+impl Context {
+    /// Returns the collections that have been defined at runtime. The
+    /// definitions are loaded from disk the first time this is called.
+    pub(crate) fn dynamic_collections(&self) -> Result<Arc<Vec<DynamicCollection>>, Error> {
+        let mut cached = self.data.dynamic_collections.lock();
+        if let Some(collections) = &*cached {
+            return Ok(collections.clone());
+        }
+
+        let collections = Arc::new(self.load_dynamic_collections()?);
+        *cached = Some(collections.clone());
+        Ok(collections)
+    }
+
+    fn load_dynamic_collections(&self) -> Result<Vec<DynamicCollection>, Error> {
+        self.roots
+            .tree(Unversioned::tree(DYNAMIC_COLLECTIONS_TREE))?
+            .get_range(&(..))?
+            .into_iter()
+            .map(|(_, definition)| pot::from_slice(&definition).map_err(Error::from))
+            .collect()
+    }
+}
+
+impl Database {
+    pub(crate) fn store_dynamic_collection(
+        &self,
+        collection: DynamicCollection,
+    ) -> Result<(), Error> {
+        self.check_permission(
+            collection_resource_name(self.name(), &collection.name),
+            &BonsaiAction::Database(DatabaseAction::DefineCollection),
+        )?;
+        collection.validate()?;
+        let schema = &self.data.schema;
+        if schema
+            .collection_primary_key_description(&collection.name)
+            .is_some()
+            && schema.dynamic_collection(&collection.name).is_none()
+        {
+            return Err(Error::Core(bonsaidb_core::Error::CollectionAlreadyDefined));
+        }
+
+        // Holding the lock while writing ensures concurrent definitions are
+        // applied to the cache in the same order they are persisted.
+        let context = &self.data.context;
+        let mut cached = context.data.dynamic_collections.lock();
+        let mut collections = match &*cached {
+            Some(collections) => Vec::clone(collections),
+            None => context.load_dynamic_collections()?,
+        };
+        context
+            .roots
+            .tree(Unversioned::tree(DYNAMIC_COLLECTIONS_TREE))?
+            .set(
+                collection.name.to_string().into_bytes(),
+                pot::to_vec(&collection)?,
+            )?;
+        collections.retain(|existing| existing.name != collection.name);
+        collections.push(collection);
+        *cached = Some(Arc::new(collections));
+        Ok(())
+    }
+}
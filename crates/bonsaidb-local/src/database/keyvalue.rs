@@ -215,6 +215,7 @@ pub struct KeyValueState {
     dirty_keys: BTreeMap<String, Option<Entry>>,
     keys_being_persisted: Option<Arc<BTreeMap<String, Option<Entry>>>>,
     last_persistence: Watchable<Timestamp>,
+    last_transaction_id: Arc<Watchable<Option<u64>>>,
     shutdown: Option<flume::Sender<()>>,
 }
 
@@ -223,6 +224,7 @@ impl KeyValueState {
         persistence: KeyValuePersistence,
         roots: Roots<AnyFile>,
         background_worker_target: Watchable<BackgroundWorkerProcessTarget>,
+        last_transaction_id: Arc<Watchable<Option<u64>>>,
     ) -> Self {
         Self {
             roots,
@@ -234,6 +236,7 @@ impl KeyValueState {
             dirty_keys: BTreeMap::new(),
             keys_being_persisted: None,
             last_persistence: Watchable::new(Timestamp::MIN),
+            last_transaction_id,
             shutdown: None,
         }
     }
@@ -725,7 +728,9 @@ impl KeyValueState {
             )
             .map_err(Error::from)?;
 
-        if !changed_keys.is_empty() {
+        let transaction_id = if changed_keys.is_empty() {
+            None
+        } else {
             transaction
                 .entry_mut()
                 .set_data(compat::serialize_executed_transaction_changes(
@@ -733,11 +738,15 @@ impl KeyValueState {
                 )?)
                 .map_err(Error::from)?;
             transaction.commit().map_err(Error::from)?;
-        }
+            Some(roots.transactions().current_transaction_id())
+        };
 
         // If we are shutting down, check if we still have dirty keys.
         let final_keys = {
             let mut state = key_value_state.lock();
+            if let Some(transaction_id) = transaction_id {
+                state.last_transaction_id.replace(transaction_id);
+            }
             state.last_persistence.replace(Timestamp::now());
             state.keys_being_persisted = None;
             state.update_background_worker_target();
@@ -931,6 +940,30 @@ mod tests {
         })
     }
 
+    #[test]
+    fn persistence_notifies_transaction_watchers() -> anyhow::Result<()> {
+        run_test("kv-transaction-watchers", |context, roots| {
+            let mut transaction_watcher = context.last_transaction_id.watch();
+            roots.delete_tree(KEY_TREE)?;
+            let tree = roots.tree(Unversioned::tree(KEY_TREE))?;
+            tree.set(b"atree\0akey", b"somevalue")?;
+
+            // Expiring the key persists its removal in a transaction.
+            context.update_key_expiration(
+                full_key(Some("atree"), "akey"),
+                Some(Timestamp::now() + Duration::from_millis(100)),
+            );
+            let transaction_id = transaction_watcher.next_value()?;
+            assert!(transaction_id.is_some());
+            assert_eq!(
+                transaction_id,
+                roots.transactions().current_transaction_id()
+            );
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn updating_expiration() -> anyhow::Result<()> {
         run_test("kv-updating-expiration", |context, roots| {
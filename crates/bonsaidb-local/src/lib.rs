@@ -36,8 +36,9 @@ pub use argon2;
 #[cfg(not(feature = "included-from-omnibus"))]
 pub use bonsaidb_core as core;
 
+pub use self::database::bulk_import::{BulkImport, DEFAULT_BULK_IMPORT_BATCH_SIZE};
 pub use self::database::pubsub::Subscriber;
-pub use self::database::{Database, DatabaseNonBlocking};
+pub use self::database::{Database, DatabaseNonBlocking, TransactionWatcher};
 pub use self::error::Error;
 pub use self::storage::{BackupLocation, Storage, StorageId, StorageNonBlocking};
 
@@ -47,5 +48,8 @@ mod r#async;
 #[cfg(feature = "async")]
 pub use r#async::*;
 
+#[cfg(feature = "async")]
+pub use self::database::bulk_import::AsyncBulkImport;
+
 #[cfg(test)]
 mod tests;
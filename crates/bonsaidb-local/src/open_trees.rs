@@ -1,15 +1,17 @@
 use std::collections::HashMap;
 
-use bonsaidb_core::schema::{CollectionName, Schematic};
+use bonsaidb_core::schema::CollectionName;
 use nebari::io::any::AnyFile;
 use nebari::tree::{AnyTreeRoot, Root, Unversioned, Versioned};
 
-use crate::database::document_tree_name;
+use crate::database::{document_tree_name, Database};
 #[cfg(any(feature = "encryption", feature = "compression"))]
 use crate::storage::TreeVault;
 use crate::views::{
     view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
+    view_linked_invalidated_docs_tree_name, view_reductions_tree_name,
 };
+use crate::Error;
 
 #[derive(Default)]
 pub(crate) struct OpenTrees {
@@ -43,9 +45,10 @@ impl OpenTrees {
     pub fn open_trees_for_document_change(
         &mut self,
         collection: &CollectionName,
-        schema: &Schematic,
+        database: &Database,
         #[cfg(any(feature = "encryption", feature = "compression"))] vault: Option<TreeVault>,
-    ) {
+    ) -> Result<(), Error> {
+        let schema = &database.data.schema;
         self.open_tree::<Versioned>(
             &document_tree_name(collection),
             #[cfg(any(feature = "encryption", feature = "compression"))]
@@ -55,13 +58,19 @@ impl OpenTrees {
         for view in schema.views_in_collection(collection) {
             let view_name = view.view_name();
             if view.update_policy().is_eager() {
+                let generation = *database.view_generation(view)?.read();
+                self.open_tree::<Unversioned>(
+                    &view_document_map_tree_name(&view_name, generation),
+                    #[cfg(any(feature = "encryption", feature = "compression"))]
+                    vault.clone(),
+                );
                 self.open_tree::<Unversioned>(
-                    &view_document_map_tree_name(&view_name),
+                    &view_entries_tree_name(&view_name, generation),
                     #[cfg(any(feature = "encryption", feature = "compression"))]
                     vault.clone(),
                 );
                 self.open_tree::<Unversioned>(
-                    &view_entries_tree_name(&view_name),
+                    &view_reductions_tree_name(&view_name, generation),
                     #[cfg(any(feature = "encryption", feature = "compression"))]
                     vault.clone(),
                 );
@@ -73,5 +82,15 @@ impl OpenTrees {
                 );
             }
         }
+
+        for view in schema.views_linked_to_collection(collection) {
+            self.open_tree::<Unversioned>(
+                &view_linked_invalidated_docs_tree_name(&view.view_name(), collection),
+                #[cfg(any(feature = "encryption", feature = "compression"))]
+                vault.clone(),
+            );
+        }
+
+        Ok(())
     }
 }
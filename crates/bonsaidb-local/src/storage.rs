@@ -223,6 +223,18 @@ pub struct StorageInstance {
     data: Arc<Data>,
 }
 
+/// A weak reference to a [`StorageInstance`] that doesn't keep its data alive.
+#[derive(Debug, Clone)]
+pub struct WeakStorageInstance {
+    data: Weak<Data>,
+}
+
+impl WeakStorageInstance {
+    pub(crate) fn upgrade(&self) -> Option<StorageInstance> {
+        self.data.upgrade().map(|data| StorageInstance { data })
+    }
+}
+
 impl From<StorageInstance> for Storage {
     fn from(instance: StorageInstance) -> Self {
         Self {
@@ -672,6 +684,12 @@ impl StorageInstance {
         &self.data.tasks
     }
 
+    pub(crate) fn downgrade(&self) -> WeakStorageInstance {
+        WeakStorageInstance {
+            data: Arc::downgrade(&self.data),
+        }
+    }
+
     pub(crate) fn check_view_integrity_on_database_open(&self) -> bool {
         self.data.check_view_integrity_on_database_open
     }
@@ -87,10 +87,18 @@ impl Storage {
                 // The admin database is already going to be created by the process of creating a database.
                 self.create_database_with_schema(&database, schema.clone(), true)?;
 
-                let database =
+                let mut opened =
                     self.instance
                         .database_without_schema(&database, Some(self), None)?;
-                Self::restore_database(&database, location)?;
+                // Collections defined at runtime must be defined before their
+                // documents can be restored, which requires reopening the
+                // database to include them.
+                if Self::restore_dynamic_collections(&opened, location)? {
+                    opened = self
+                        .instance
+                        .database_without_schema(&database, Some(self), None)?;
+                }
+                Self::restore_database(&opened, location)?;
             }
         }
 
@@ -131,9 +139,34 @@ impl Storage {
                 )?;
             }
         }
+        for collection in database.schematic().dynamic_collections() {
+            location.store(
+                &schema,
+                database.name(),
+                "_dynamic",
+                &collection.name.encoded(),
+                &pot::to_vec(collection)?,
+            )?;
+        }
         Ok(())
     }
 
+    /// Defines the collections that were defined at runtime in the backed up
+    /// database. Returns true if any collections were defined.
+    fn restore_dynamic_collections(
+        database: &Database,
+        location: &dyn AnyBackupLocation,
+    ) -> Result<bool, Error> {
+        let schema = database.schematic().name.clone();
+        let mut restored = false;
+        for name in location.list_stored(&schema, database.name(), "_dynamic")? {
+            let definition = location.load(&schema, database.name(), "_dynamic", &name)?;
+            database.store_dynamic_collection(pot::from_slice(&definition)?)?;
+            restored = true;
+        }
+        Ok(restored)
+    }
+
     pub(crate) fn restore_database(
         database: &Database,
         location: &dyn AnyBackupLocation,
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use bonsaidb_core::connection::Connection;
 use bonsaidb_core::keyvalue::Timestamp;
+use bonsaidb_core::schema::view::{ViewRebuildProgress, ViewUpdatePolicy};
 use bonsaidb_core::schema::{view, CollectionName, ViewName};
 use parking_lot::RwLock;
 
@@ -12,6 +13,7 @@ use crate::database::Database;
 use crate::tasks::compactor::Compactor;
 use crate::tasks::handle::Handle;
 use crate::tasks::manager::Manager;
+use crate::tasks::refresher::RefreshRequest;
 use crate::views::integrity_scanner::{IntegrityScan, IntegrityScanner, OptionalViewMapHandle};
 use crate::views::mapper::{Map, Mapper};
 use crate::Error;
@@ -25,6 +27,7 @@ mod traits;
 pub use self::traits::{Job, Keyed};
 
 mod compactor;
+mod refresher;
 mod task;
 
 pub use task::Task;
@@ -33,6 +36,7 @@ pub use task::Task;
 pub struct TaskManager {
     pub jobs: Manager<Task>,
     statuses: Arc<RwLock<Statuses>>,
+    refresh_requests: flume::Sender<RefreshRequest>,
 }
 
 type ViewKey = (Arc<Cow<'static, str>>, CollectionName, ViewName);
@@ -42,13 +46,21 @@ pub struct Statuses {
     completed_integrity_checks: HashSet<ViewKey>,
     key_value_expiration_loads: HashSet<Arc<Cow<'static, str>>>,
     view_update_last_status: HashMap<ViewKey, u64>,
+    view_invalidated_documents: HashMap<ViewKey, u64>,
+    view_rebuilds: HashMap<ViewKey, ViewRebuildProgress>,
 }
 
 impl TaskManager {
     pub fn new(jobs: Manager<Task>) -> Self {
+        let (refresh_requests, receiver) = flume::unbounded();
+        std::thread::Builder::new()
+            .name(String::from("bonsaidb-view-refresh"))
+            .spawn(move || refresher::refresh_worker(&receiver))
+            .unwrap();
         Self {
             jobs,
             statuses: Arc::default(),
+            refresh_requests,
         }
     }
 
@@ -176,9 +188,142 @@ impl TaskManager {
         transaction_id: u64,
     ) {
         let mut statuses = self.statuses.write();
+        let key = (database, collection, view_name);
+        statuses.view_invalidated_documents.remove(&key);
+        statuses.view_update_last_status.insert(key, transaction_id);
+    }
+
+    pub fn view_last_indexed_transaction_id(
+        &self,
+        database: Arc<Cow<'static, str>>,
+        collection: CollectionName,
+        view_name: ViewName,
+    ) -> Option<u64> {
+        let statuses = self.statuses.read();
         statuses
             .view_update_last_status
-            .insert((database, collection, view_name), transaction_id);
+            .get(&(database, collection, view_name))
+            .copied()
+    }
+
+    pub fn view_update_in_progress(
+        &self,
+        database: Arc<Cow<'static, str>>,
+        collection: CollectionName,
+        view_name: ViewName,
+    ) -> bool {
+        self.jobs.is_keyed_job_active(&Task::ViewMap(Map {
+            database,
+            collection,
+            view_name,
+        }))
+    }
+
+    pub fn update_view_rebuild_progress(
+        &self,
+        database: Arc<Cow<'static, str>>,
+        collection: CollectionName,
+        view_name: ViewName,
+        progress: ViewRebuildProgress,
+    ) {
+        let mut statuses = self.statuses.write();
+        statuses
+            .view_rebuilds
+            .insert((database, collection, view_name), progress);
+    }
+
+    pub fn mark_view_rebuilt(
+        &self,
+        database: Arc<Cow<'static, str>>,
+        collection: CollectionName,
+        view_name: ViewName,
+    ) {
+        let mut statuses = self.statuses.write();
+        let key = (database, collection, view_name);
+        statuses.view_rebuilds.remove(&key);
+        // Documents changed during the rebuild were invalidated when the
+        // rebuilt view replaced the previous index, so the view needs to be
+        // updated again.
+        statuses.view_update_last_status.remove(&key);
+    }
+
+    pub fn view_rebuild_progress(
+        &self,
+        database: Arc<Cow<'static, str>>,
+        collection: CollectionName,
+        view_name: ViewName,
+    ) -> Option<ViewRebuildProgress> {
+        let statuses = self.statuses.read();
+        statuses
+            .view_rebuilds
+            .get(&(database, collection, view_name))
+            .copied()
+    }
+
+    /// Schedules the views in `database` with a
+    /// [`ViewUpdatePolicy::Periodic`] interval to be refreshed in the
+    /// background.
+    pub fn schedule_view_refreshes(&self, database: &Database) {
+        for view in database.data.schema.views() {
+            if let ViewUpdatePolicy::Periodic {
+                interval: Some(interval),
+                ..
+            } = view.update_policy()
+            {
+                let _: Result<_, _> = self.refresh_requests.send(RefreshRequest::Schedule {
+                    key: (
+                        database.data.name.clone(),
+                        view.collection(),
+                        view.view_name(),
+                    ),
+                    database: database.downgrade(),
+                    interval,
+                });
+            }
+        }
+    }
+
+    /// Records that `documents` have been invalidated in `view`. If the view
+    /// has a [`ViewUpdatePolicy::Periodic`] policy and enough documents have
+    /// been invalidated since it was last updated, the view will be refreshed
+    /// in the background.
+    pub fn view_documents_invalidated(
+        &self,
+        view: &dyn view::Serialized,
+        database: &Database,
+        documents: u64,
+    ) {
+        let ViewUpdatePolicy::Periodic {
+            invalidated_documents: Some(threshold),
+            ..
+        } = view.update_policy()
+        else {
+            return;
+        };
+
+        let reached_threshold = {
+            let mut statuses = self.statuses.write();
+            let invalidated = statuses
+                .view_invalidated_documents
+                .entry((
+                    database.data.name.clone(),
+                    view.collection(),
+                    view.view_name(),
+                ))
+                .or_default();
+            // Only request a refresh when crossing the threshold, because the
+            // count isn't reset until the view has been updated.
+            let reached_threshold = *invalidated < threshold;
+            *invalidated = invalidated.saturating_add(documents);
+            reached_threshold && *invalidated >= threshold
+        };
+
+        if reached_threshold {
+            let _: Result<_, _> = self.refresh_requests.send(RefreshRequest::Refresh {
+                database: database.clone(),
+                view_name: view.view_name(),
+            });
+        }
     }
 
     pub fn spawn_key_value_expiration_loader(
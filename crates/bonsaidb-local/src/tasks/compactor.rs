@@ -9,7 +9,8 @@ use crate::database::{document_tree_name, DatabaseNonBlocking};
 use crate::tasks::{Job, Keyed, Task};
 use crate::views::{
     view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
-    view_versions_tree_name,
+    view_linked_document_map_tree_name, view_linked_invalidated_docs_tree_name,
+    view_reductions_tree_name, view_versions_tree_name,
 };
 use crate::{Database, Error};
 
@@ -65,14 +66,14 @@ impl Target {
             Target::VersionedTree(name) => compact_tree::<Versioned, _>(database, name),
             Target::Collection(collection) => {
                 let mut trees = Vec::new();
-                gather_collection_trees(database, &collection, &mut trees);
+                gather_collection_trees(database, &collection, &mut trees)?;
                 compact_trees(database, trees)
             }
             Target::KeyValue => compact_tree::<Unversioned, _>(database, KEY_TREE),
             Target::Database => {
                 let mut trees = Vec::new();
                 for collection in database.schematic().collections() {
-                    gather_collection_trees(database, collection, &mut trees);
+                    gather_collection_trees(database, collection, &mut trees)?;
                 }
                 trees.push(Target::KeyValue);
                 compact_trees(database, trees)
@@ -101,18 +102,38 @@ fn gather_collection_trees(
     database: &Database,
     collection: &CollectionName,
     trees: &mut Vec<Target>,
-) {
+) -> Result<(), Error> {
     trees.push(Target::VersionedTree(document_tree_name(collection)));
     trees.push(Target::UnversionedTree(view_versions_tree_name(collection)));
 
     for view in database.data.schema.views_in_collection(collection) {
         let name = view.view_name();
-        trees.push(Target::UnversionedTree(view_entries_tree_name(&name)));
-        trees.push(Target::UnversionedTree(view_document_map_tree_name(&name)));
+        let generation = *database.view_generation(view)?.read();
+        trees.push(Target::UnversionedTree(view_entries_tree_name(
+            &name, generation,
+        )));
+        trees.push(Target::UnversionedTree(view_document_map_tree_name(
+            &name, generation,
+        )));
+        trees.push(Target::UnversionedTree(view_reductions_tree_name(
+            &name, generation,
+        )));
         trees.push(Target::UnversionedTree(view_invalidated_docs_tree_name(
             &name,
         )));
     }
+
+    for view in database.data.schema.views_linked_to_collection(collection) {
+        let name = view.view_name();
+        trees.push(Target::UnversionedTree(view_linked_document_map_tree_name(
+            &name, collection,
+        )));
+        trees.push(Target::UnversionedTree(
+            view_linked_invalidated_docs_tree_name(&name, collection),
+        ));
+    }
+
+    Ok(())
 }
 
 fn compact_trees(database: &Database, targets: Vec<Target>) -> Result<(), Error> {
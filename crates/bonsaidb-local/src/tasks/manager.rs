@@ -47,6 +47,12 @@ where
         jobs.lookup_or_enqueue(job, self.clone())
     }
 
+    /// Returns true if a job with `key` is queued or running.
+    pub fn is_keyed_job_active(&self, key: &Key) -> bool {
+        let jobs = self.jobs.read();
+        jobs.contains_key(key)
+    }
+
     fn job_completed<T: Clone + Send + Sync + 'static, E: Send + Sync + 'static>(
         &self,
         id: Id,
@@ -101,6 +101,10 @@ where
         }
     }
 
+    pub fn contains_key(&self, key: &Key) -> bool {
+        self.keyed.contains_key(key)
+    }
+
     pub fn job_completed<T: Clone + Send + Sync + 'static, E: Send + Sync + 'static>(
         &mut self,
         id: Id,
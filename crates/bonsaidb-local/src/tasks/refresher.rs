@@ -0,0 +1,103 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use bonsaidb_core::schema::ViewName;
+
+use crate::database::{Database, WeakDatabase};
+use crate::tasks::ViewKey;
+
+/// A request sent to the thread that refreshes views with a
+/// [`Periodic`](bonsaidb_core::schema::view::ViewUpdatePolicy::Periodic)
+/// update policy.
+pub enum RefreshRequest {
+    /// Refreshes the view every `interval` while `database` is open.
+    Schedule {
+        key: ViewKey,
+        database: WeakDatabase,
+        interval: Duration,
+    },
+    /// Refreshes the view as soon as possible.
+    Refresh {
+        database: Database,
+        view_name: ViewName,
+    },
+}
+
+struct ScheduledView {
+    database: WeakDatabase,
+    interval: Duration,
+    next_refresh: Instant,
+}
+
+/// Processes [`RefreshRequest`]s until every sender has been dropped.
+pub fn refresh_worker(requests: &flume::Receiver<RefreshRequest>) {
+    let mut scheduled = HashMap::<ViewKey, ScheduledView>::new();
+    loop {
+        let request = match scheduled.values().map(|view| view.next_refresh).min() {
+            Some(next_refresh) => match requests.recv_deadline(next_refresh) {
+                Ok(request) => Some(request),
+                Err(flume::RecvTimeoutError::Timeout) => None,
+                Err(flume::RecvTimeoutError::Disconnected) => break,
+            },
+            None => match requests.recv() {
+                Ok(request) => Some(request),
+                Err(flume::RecvError::Disconnected) => break,
+            },
+        };
+
+        match request {
+            Some(RefreshRequest::Schedule {
+                key,
+                database,
+                interval,
+            }) => {
+                // The same database may be opened many times, so only the
+                // first request determines when the view is refreshed.
+                match scheduled.entry(key) {
+                    Entry::Occupied(mut entry) => entry.get_mut().database = database,
+                    Entry::Vacant(entry) => {
+                        entry.insert(ScheduledView {
+                            database,
+                            interval,
+                            next_refresh: Instant::now() + interval,
+                        });
+                    }
+                }
+            }
+            Some(RefreshRequest::Refresh {
+                database,
+                view_name,
+            }) => refresh(&database, &view_name),
+            None => {
+                let now = Instant::now();
+                scheduled.retain(|(_, _, view_name), view| {
+                    if view.next_refresh > now {
+                        return true;
+                    }
+
+                    // Once the database has been closed, the view no longer
+                    // needs to be refreshed.
+                    let Some(database) = view.database.upgrade() else {
+                        return false;
+                    };
+                    refresh(&database, view_name);
+                    view.next_refresh = Instant::now() + view.interval;
+                    true
+                });
+            }
+        }
+    }
+}
+
+fn refresh(database: &Database, view_name: &ViewName) {
+    if let Ok(view) = database.data.schema.view_by_name(view_name) {
+        // Errors will be encountered again and returned by the next query
+        // that updates the view.
+        let _: Result<_, _> = database
+            .storage
+            .instance
+            .tasks()
+            .update_view_if_needed(view, database, false);
+    }
+}
@@ -4,11 +4,13 @@ use std::sync::Arc;
 use crate::tasks::compactor::Compaction;
 use crate::views::integrity_scanner::IntegrityScan;
 use crate::views::mapper::Map;
+use crate::views::rebuilder::Rebuild;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum Task {
     IntegrityScan(IntegrityScan),
     ViewMap(Map),
+    ViewRebuild(Rebuild),
     Compaction(Compaction),
     ExpirationLoader(Arc<Cow<'static, str>>),
 }
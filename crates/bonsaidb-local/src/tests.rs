@@ -318,3 +318,1538 @@ fn expiration_after_close() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn references() -> anyhow::Result<()> {
+    use bonsaidb_core::document::CollectionDocument;
+    use bonsaidb_core::schema::{
+        Collection, Name, OnDelete, Reference, Schema, SerializedCollection,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Schema, Debug)]
+    #[schema(name = "references", collections = [Author, Book, Review], core = bonsaidb_core)]
+    struct ReferencesSchema;
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "authors", core = bonsaidb_core)]
+    struct Author;
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "books", references = [BookAuthor], core = bonsaidb_core)]
+    struct Book {
+        author_id: u64,
+    }
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "reviews", references = [ReviewBook], core = bonsaidb_core)]
+    struct Review {
+        book_id: u64,
+    }
+
+    #[derive(Debug, Clone)]
+    struct BookAuthor;
+
+    impl Reference for BookAuthor {
+        type Collection = Book;
+        type Parent = Author;
+
+        fn name(&self) -> Name {
+            Name::new("author")
+        }
+
+        fn parent_ids(&self, document: &CollectionDocument<Book>) -> Vec<u64> {
+            vec![document.contents.author_id]
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct ReviewBook;
+
+    impl Reference for ReviewBook {
+        type Collection = Review;
+        type Parent = Book;
+
+        fn name(&self) -> Name {
+            Name::new("book")
+        }
+
+        fn on_delete(&self) -> OnDelete {
+            OnDelete::Cascade
+        }
+
+        fn parent_ids(&self, document: &CollectionDocument<Review>) -> Vec<u64> {
+            vec![document.contents.book_id]
+        }
+    }
+
+    let path = TestDirectory::new("references");
+    let db = Database::open::<ReferencesSchema>(StorageConfiguration::new(&path))?;
+
+    // Inserting a document that refers to a missing document fails.
+    let err = Book { author_id: 1 }.push_into(&db).unwrap_err().error;
+    assert!(matches!(
+        err,
+        bonsaidb_core::Error::ReferencedDocumentNotFound { .. }
+    ));
+
+    let author = Author.push_into(&db)?;
+    let book = Book {
+        author_id: author.header.id,
+    }
+    .push_into(&db)?;
+    let review = Review {
+        book_id: book.header.id,
+    }
+    .push_into(&db)?;
+
+    // Updating a document to refer to a missing document fails.
+    let mut missing_book = Review::get(&review.header.id, &db)?.unwrap();
+    missing_book.contents.book_id = u64::MAX;
+    assert!(matches!(
+        missing_book.update(&db),
+        Err(bonsaidb_core::Error::ReferencedDocumentNotFound { .. })
+    ));
+
+    // The author can't be deleted while the book refers to it.
+    assert!(matches!(
+        author.delete(&db),
+        Err(bonsaidb_core::Error::DocumentStillReferenced { .. })
+    ));
+
+    // Deleting the book deletes its reviews.
+    book.delete(&db)?;
+    assert!(Review::get(&review.header.id, &db)?.is_none());
+    let transactions = db.list_executed_transactions(None, None)?;
+    let changes = transactions.last().unwrap().changes.documents().unwrap();
+    assert_eq!(changes.documents.len(), 2);
+
+    author.delete(&db)?;
+
+    Ok(())
+}
+
+#[test]
+fn case_insensitive_names() -> anyhow::Result<()> {
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::key::{CaseInsensitive, Collated};
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, NamedCollection, SerializedCollection, View,
+        ViewMapResult, ViewSchema,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "fruits", views = [FruitsByName], core = bonsaidb_core)]
+    struct Fruit {
+        name: String,
+    }
+
+    impl NamedCollection for Fruit {
+        type ByNameView = FruitsByName;
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Fruit, key = Collated<CaseInsensitive>, name = "by-name", core = bonsaidb_core)]
+    #[view_schema(policy = Unique, core = bonsaidb_core)]
+    struct FruitsByName;
+
+    impl CollectionMapReduce for FruitsByName {
+        fn map<'doc>(&self, document: CollectionDocument<Fruit>) -> ViewMapResult<'doc, Self> {
+            document
+                .header
+                .emit_key(Collated::new(&document.contents.name))
+        }
+    }
+
+    let path = TestDirectory::new("case-insensitive-names");
+    let db = Database::open::<Fruit>(StorageConfiguration::new(&path))?;
+    for name in ["Zebra", "apple", "Banana"] {
+        Fruit {
+            name: name.to_string(),
+        }
+        .push_into(&db)?;
+    }
+
+    let apple = Fruit::load("APPLE", &db)?.expect("case-insensitive lookup failed");
+    assert_eq!(apple.contents.name, "apple");
+
+    // Names that only differ by case violate the unique view.
+    assert!(Fruit {
+        name: String::from("ZEBRA")
+    }
+    .push_into(&db)
+    .is_err());
+
+    let sorted = db
+        .view::<FruitsByName>()
+        .query_with_collection_docs()?
+        .into_iter()
+        .map(|mapping| mapping.document.contents.name.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(sorted, ["apple", "Banana", "Zebra"]);
+
+    Ok(())
+}
+
+#[test]
+fn range_reductions() -> anyhow::Result<()> {
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, ReduceResult, SerializedCollection, View, ViewMapResult,
+        ViewMappedValue, ViewSchema,
+    };
+    use bonsaidb_core::transaction::Transaction;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "numbers", views = [NumbersByValue], core = bonsaidb_core)]
+    struct Number {
+        value: u32,
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Number, key = u32, value = u64, name = "by-value", core = bonsaidb_core)]
+    #[view_schema(core = bonsaidb_core)]
+    struct NumbersByValue;
+
+    impl CollectionMapReduce for NumbersByValue {
+        fn map<'doc>(&self, document: CollectionDocument<Number>) -> ViewMapResult<'doc, Self> {
+            document
+                .header
+                .emit_key_and_value(document.contents.value, u64::from(document.contents.value))
+        }
+
+        fn reduce(
+            &self,
+            mappings: &[ViewMappedValue<'_, Self>],
+            _rereduce: bool,
+        ) -> ReduceResult<Self::View> {
+            Ok(mappings.iter().map(|map| map.value).sum())
+        }
+    }
+
+    fn check_ranges(db: &Database, values: &[u32]) -> anyhow::Result<()> {
+        let sum = |filter: &dyn Fn(u32) -> bool| {
+            values
+                .iter()
+                .copied()
+                .filter(|value| filter(*value))
+                .map(u64::from)
+                .sum::<u64>()
+        };
+        let view = || db.view::<NumbersByValue>();
+
+        assert_eq!(view().reduce()?, sum(&|_| true));
+        for (start, end) in [
+            (0, 1),
+            (0, 17),
+            (5, 6),
+            (13, 1_500),
+            (250, 1_999),
+            (999, 4_000),
+        ] {
+            assert_eq!(
+                view().with_key_range(start..end).reduce()?,
+                sum(&|value| (start..end).contains(&value))
+            );
+            assert_eq!(
+                view().with_key_range(start..=end).reduce()?,
+                sum(&|value| (start..=end).contains(&value))
+            );
+            assert_eq!(
+                view().with_key_range(..end).reduce()?,
+                sum(&|value| value < end)
+            );
+            assert_eq!(
+                view().with_key_range(start..).reduce()?,
+                sum(&|value| value >= start)
+            );
+        }
+        Ok(())
+    }
+
+    let path = TestDirectory::new("range-reductions");
+    let db = Database::open::<Number>(StorageConfiguration::new(&path))?;
+
+    let mut tx = Transaction::new();
+    for value in 0..2_000 {
+        Number { value }.push_in_transaction(&mut tx)?;
+    }
+    tx.apply(&db)?;
+    let mut values = (0..2_000).collect::<Vec<u32>>();
+    check_ranges(&db, &values)?;
+
+    // Removing and changing entries must update the stored partial reductions.
+    let mut tx = Transaction::new();
+    for mut number in Number::all(&db).query()? {
+        if number.contents.value % 7 == 0 {
+            number.delete_in_transaction(&mut tx)?;
+        } else if number.contents.value % 5 == 0 {
+            number.contents.value += 3_000;
+            number.update_in_transaction(&mut tx)?;
+        }
+    }
+    tx.apply(&db)?;
+    values = values
+        .into_iter()
+        .filter(|value| value % 7 != 0)
+        .map(|value| if value % 5 == 0 { value + 3_000 } else { value })
+        .collect();
+    check_ranges(&db, &values)?;
+
+    Ok(())
+}
+
+#[test]
+fn reduce_support_is_declared() -> anyhow::Result<()> {
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, ReduceResult, SerializedCollection, View, ViewMapResult,
+        ViewMappedValue, ViewSchema,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "scores", views = [MaxScoreByName, ScoreNames], core = bonsaidb_core)]
+    struct Score {
+        name: String,
+        value: u32,
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Score, key = String, value = u32, name = "max-by-name", core = bonsaidb_core)]
+    #[view_schema(core = bonsaidb_core)]
+    struct MaxScoreByName;
+
+    impl CollectionMapReduce for MaxScoreByName {
+        fn map<'doc>(&self, document: CollectionDocument<Score>) -> ViewMapResult<'doc, Self> {
+            document
+                .header
+                .emit_key_and_value(document.contents.name, document.contents.value)
+        }
+
+        fn reduce(
+            &self,
+            mappings: &[ViewMappedValue<'_, Self>],
+            _rereduce: bool,
+        ) -> ReduceResult<Self::View> {
+            // Panics if the entries are reduced without any mappings while
+            // the view is being indexed.
+            Ok(mappings
+                .iter()
+                .map(|mapping| mapping.value)
+                .fold(mappings[0].value, u32::max))
+        }
+    }
+
+    #[derive(View, Debug, Clone)]
+    #[view(collection = Score, key = String, name = "names", core = bonsaidb_core)]
+    struct ScoreNames;
+
+    impl ViewSchema for ScoreNames {
+        type MappedKey<'doc> = String;
+        type View = Self;
+
+        fn implements_reduce(&self) -> bool {
+            false
+        }
+    }
+
+    impl CollectionMapReduce for ScoreNames {
+        fn map<'doc>(&self, document: CollectionDocument<Score>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.contents.name)
+        }
+
+        fn reduce(
+            &self,
+            _mappings: &[ViewMappedValue<'_, Self>],
+            _rereduce: bool,
+        ) -> ReduceResult<Self::View> {
+            unreachable!("reduce is not implemented by this view")
+        }
+    }
+
+    let path = TestDirectory::new("reduce-support-is-declared");
+    let db = Database::open::<Score>(StorageConfiguration::new(&path))?;
+
+    for (name, value) in [("a", 1), ("a", 3), ("b", 2), ("c", 7)] {
+        let mut score = Score {
+            name: String::from(name),
+            value,
+        }
+        .push_into(&db)?;
+        // Replacing a document's mapping must not reduce an empty entry.
+        score.contents.value += 1;
+        score.update(&db)?;
+    }
+
+    assert_eq!(db.view::<MaxScoreByName>().with_key("a").reduce()?, 4);
+    assert_eq!(db.view::<MaxScoreByName>().reduce()?, 8);
+    assert_eq!(db.view::<ScoreNames>().query()?.len(), 4);
+    assert!(matches!(
+        db.view::<ScoreNames>().reduce(),
+        Err(bonsaidb_core::Error::ReduceUnimplemented)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn periodic_view_refresh() -> anyhow::Result<()> {
+    use std::time::Instant;
+
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::schema::view::ViewUpdatePolicy;
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, SerializedCollection, SerializedView, View, ViewMapResult,
+        ViewSchema,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "events", views = [EventsByInterval, EventsByCount], core = bonsaidb_core)]
+    struct Event {
+        kind: u32,
+    }
+
+    #[derive(View, Debug, Clone)]
+    #[view(collection = Event, key = u32, name = "by-interval", core = bonsaidb_core)]
+    struct EventsByInterval;
+
+    impl ViewSchema for EventsByInterval {
+        type MappedKey<'doc> = u32;
+        type View = Self;
+
+        fn update_policy(&self) -> ViewUpdatePolicy {
+            ViewUpdatePolicy::Periodic {
+                interval: Some(Duration::from_millis(50)),
+                invalidated_documents: None,
+            }
+        }
+    }
+
+    impl CollectionMapReduce for EventsByInterval {
+        fn map<'doc>(&self, document: CollectionDocument<Event>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.contents.kind)
+        }
+    }
+
+    #[derive(View, Debug, Clone)]
+    #[view(collection = Event, key = u32, name = "by-count", core = bonsaidb_core)]
+    struct EventsByCount;
+
+    impl ViewSchema for EventsByCount {
+        type MappedKey<'doc> = u32;
+        type View = Self;
+
+        fn update_policy(&self) -> ViewUpdatePolicy {
+            ViewUpdatePolicy::Periodic {
+                interval: None,
+                invalidated_documents: Some(3),
+            }
+        }
+    }
+
+    impl CollectionMapReduce for EventsByCount {
+        fn map<'doc>(&self, document: CollectionDocument<Event>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.contents.kind)
+        }
+    }
+
+    fn wait_for_entries<V: SerializedView>(db: &Database, expected: usize) -> anyhow::Result<()> {
+        let started = Instant::now();
+        loop {
+            let entries = db
+                .view::<V>()
+                .with_access_policy(AccessPolicy::NoUpdate)
+                .query()?;
+            if entries.len() == expected {
+                return Ok(());
+            }
+            anyhow::ensure!(
+                started.elapsed() < Duration::from_secs(10),
+                "view was not refreshed in the background"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    let path = TestDirectory::new("periodic-view-refresh");
+    let db = Database::open::<Event>(StorageConfiguration::new(&path))?;
+
+    for kind in 0..2 {
+        Event { kind }.push_into(&db)?;
+    }
+    wait_for_entries::<EventsByInterval>(&db, 2)?;
+    // Not enough documents have been invalidated to refresh this view.
+    assert!(db
+        .view::<EventsByCount>()
+        .with_access_policy(AccessPolicy::NoUpdate)
+        .query()?
+        .is_empty());
+
+    Event { kind: 2 }.push_into(&db)?;
+    wait_for_entries::<EventsByCount>(&db, 3)?;
+    wait_for_entries::<EventsByInterval>(&db, 3)?;
+
+    Ok(())
+}
+
+#[test]
+fn background_view_rebuilds() -> anyhow::Result<()> {
+    use std::time::Instant;
+
+    use bonsaidb_core::connection::LowLevelConnection;
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, SerializedCollection, View, ViewMapResult, ViewSchema,
+    };
+    use bonsaidb_core::transaction::Transaction;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "items", views = [ItemsByValue], core = bonsaidb_core)]
+    struct Item {
+        value: u32,
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Item, key = u32, name = "by-value", core = bonsaidb_core)]
+    #[view_schema(version = 1, core = bonsaidb_core)]
+    struct ItemsByValue;
+
+    impl CollectionMapReduce for ItemsByValue {
+        fn map<'doc>(&self, document: CollectionDocument<Item>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.contents.value)
+        }
+    }
+
+    // The same collection, with a new version of the view that emits each
+    // value multiplied by 10.
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "items", views = [ItemsByValueV2], core = bonsaidb_core)]
+    struct ItemV2 {
+        value: u32,
+    }
+
+    #[derive(View, Debug, Clone)]
+    #[view(collection = ItemV2, key = u32, name = "by-value", core = bonsaidb_core)]
+    struct ItemsByValueV2;
+
+    impl ViewSchema for ItemsByValueV2 {
+        type MappedKey<'doc> = u32;
+        type View = Self;
+
+        fn version(&self) -> u64 {
+            2
+        }
+
+        fn compatible_with_version(&self, version: u64) -> bool {
+            version == 1
+        }
+    }
+
+    impl CollectionMapReduce for ItemsByValueV2 {
+        fn map<'doc>(&self, document: CollectionDocument<ItemV2>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.contents.value * 10)
+        }
+    }
+
+    let path = TestDirectory::new("background-view-rebuilds");
+    {
+        let db = Database::open::<Item>(StorageConfiguration::new(&path))?;
+        let mut tx = Transaction::new();
+        for value in 1..=2_500 {
+            Item { value }.push_in_transaction(&mut tx)?;
+        }
+        tx.apply(&db)?;
+        assert_eq!(db.view::<ItemsByValue>().query()?.len(), 2_500);
+    }
+
+    let db = Database::open::<ItemV2>(StorageConfiguration::new(&path))?;
+    // The previous version's index is queried until the rebuilt index
+    // replaces it.
+    let previous_keys = (1..=2_500).collect::<Vec<u32>>();
+    let rebuilt_keys = (1..=2_500).map(|value| value * 10).collect::<Vec<u32>>();
+    let started = Instant::now();
+    loop {
+        let keys = db
+            .view::<ItemsByValueV2>()
+            .with_access_policy(AccessPolicy::NoUpdate)
+            .query()?
+            .into_iter()
+            .map(|mapping| mapping.key)
+            .collect::<Vec<_>>();
+        if keys == rebuilt_keys && db.view_status::<ItemsByValueV2>()?.rebuild.is_none() {
+            break;
+        }
+        assert!(keys == previous_keys || keys == rebuilt_keys);
+        anyhow::ensure!(
+            started.elapsed() < Duration::from_secs(10),
+            "view was not rebuilt in the background"
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    // Changes made after the rebuild are mapped using the new version.
+    ItemV2 { value: 2_501 }.push_into(&db)?;
+    let keys = db
+        .view::<ItemsByValueV2>()
+        .query()?
+        .into_iter()
+        .map(|mapping| mapping.key)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        (1..=2_501).map(|value| value * 10).collect::<Vec<_>>()
+    );
+    drop(db);
+
+    // The view continues to use the rebuilt index after being reopened.
+    let db = Database::open::<ItemV2>(StorageConfiguration::new(&path))?;
+    let keys = db
+        .view::<ItemsByValueV2>()
+        .with_access_policy(AccessPolicy::NoUpdate)
+        .query()?
+        .into_iter()
+        .map(|mapping| mapping.key)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        (1..=2_501).map(|value| value * 10).collect::<Vec<_>>()
+    );
+    assert!(db.view_status::<ItemsByValueV2>()?.rebuild.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn view_status() -> anyhow::Result<()> {
+    use bonsaidb_core::connection::LowLevelConnection;
+    use bonsaidb_core::schema::SerializedCollection;
+
+    let path = TestDirectory::new("view-status");
+    let db = Database::open::<BasicSchema>(StorageConfiguration::new(&path))?;
+
+    let parent = Basic::new("parent").push_into(&db)?;
+    Basic::new("child")
+        .with_parent_id(parent.header.id)
+        .push_into(&db)?;
+    db.view::<BasicByParentId>().query()?;
+
+    let status = db.view_status::<BasicByParentId>()?;
+    assert!(status.integrity_checked);
+    assert!(!status.update_in_progress);
+    assert_eq!(status.invalidated_documents, 0);
+    assert_eq!(
+        status.last_indexed_transaction_id,
+        db.last_transaction_id()?
+    );
+    assert!(status.rebuild.is_none());
+
+    // Lazy views only index invalidated documents when queried.
+    Basic::new("child")
+        .with_parent_id(parent.header.id)
+        .push_into(&db)?;
+    let status = db.view_status::<BasicByParentId>()?;
+    assert_eq!(status.invalidated_documents, 1);
+    assert_ne!(
+        status.last_indexed_transaction_id,
+        db.last_transaction_id()?
+    );
+
+    db.view::<BasicByParentId>().query()?;
+    let status = db.view_status::<BasicByParentId>()?;
+    assert_eq!(status.invalidated_documents, 0);
+    assert_eq!(
+        status.last_indexed_transaction_id,
+        db.last_transaction_id()?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn linked_collection_views() -> anyhow::Result<()> {
+    use bonsaidb_core::connection::LowLevelConnection;
+    use bonsaidb_core::document::{BorrowedDocument, CollectionDocument, Emit};
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, CollectionName, Schema, SerializedCollection, View,
+        ViewMapResult, ViewSchema,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Schema, Debug)]
+    #[schema(name = "blog", collections = [Post, Comment], core = bonsaidb_core)]
+    struct Blog;
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "posts", views = [PostsWithComments], core = bonsaidb_core)]
+    struct Post {
+        title: String,
+    }
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "comments", core = bonsaidb_core)]
+    struct Comment {
+        post_id: u64,
+        text: String,
+    }
+
+    #[derive(View, Debug, Clone)]
+    #[view(collection = Post, key = (u64, u8), value = String, name = "with-comments", core = bonsaidb_core)]
+    struct PostsWithComments;
+
+    impl ViewSchema for PostsWithComments {
+        type MappedKey<'doc> = (u64, u8);
+        type View = Self;
+
+        fn linked_collections(&self) -> Vec<CollectionName> {
+            vec![Comment::collection_name()]
+        }
+    }
+
+    impl CollectionMapReduce for PostsWithComments {
+        fn map<'doc>(&self, document: CollectionDocument<Post>) -> ViewMapResult<'doc, Self> {
+            document
+                .header
+                .emit_key_and_value((document.header.id, 0), document.contents.title)
+        }
+
+        fn map_linked<'doc>(
+            &self,
+            _collection: &CollectionName,
+            document: &'doc BorrowedDocument<'_>,
+        ) -> ViewMapResult<'doc, Self> {
+            let comment = Comment::document_contents(document)?;
+            document
+                .header
+                .emit_key_and_value((comment.post_id, 1), comment.text)
+        }
+    }
+
+    fn view_keys(db: &Database) -> anyhow::Result<Vec<(u64, u8)>> {
+        Ok(db
+            .view::<PostsWithComments>()
+            .query()?
+            .into_iter()
+            .map(|mapping| mapping.key)
+            .collect())
+    }
+
+    let path = TestDirectory::new("linked-collection-views");
+    let db = Database::open::<Blog>(StorageConfiguration::new(&path))?;
+
+    let first = Post {
+        title: String::from("first"),
+    }
+    .push_into(&db)?;
+    let second = Post {
+        title: String::from("second"),
+    }
+    .push_into(&db)?;
+    // The comments' ids are the same as the posts' ids.
+    let mut hello = Comment {
+        post_id: first.header.id,
+        text: String::from("hello"),
+    }
+    .push_into(&db)?;
+    assert_eq!(hello.header.id, first.header.id);
+    Comment {
+        post_id: second.header.id,
+        text: String::from("world"),
+    }
+    .push_into(&db)?;
+
+    let entries = db
+        .view::<PostsWithComments>()
+        .query()?
+        .into_iter()
+        .map(|mapping| (mapping.key, mapping.collection, mapping.value))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![
+            ((first.header.id, 0), None, String::from("first")),
+            (
+                (first.header.id, 1),
+                Some(Comment::collection_name()),
+                String::from("hello")
+            ),
+            ((second.header.id, 0), None, String::from("second")),
+            (
+                (second.header.id, 1),
+                Some(Comment::collection_name()),
+                String::from("world")
+            ),
+        ]
+    );
+
+    let mapped = db
+        .view::<PostsWithComments>()
+        .query_with_collection_docs()?;
+    let titles = mapped
+        .iter()
+        .map(|mapped| mapped.document.contents.title.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["first", "second"]);
+    let comment_texts = mapped.linked_documents[&Comment::collection_name()]
+        .values()
+        .map(|document| Comment::document_contents(document).map(|comment| comment.text))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(comment_texts, ["hello", "world"]);
+
+    // Changing a document in a linked collection invalidates it.
+    hello.contents.post_id = second.header.id;
+    hello.update(&db)?;
+    assert_eq!(
+        db.view_status::<PostsWithComments>()?.invalidated_documents,
+        1
+    );
+    assert_eq!(
+        view_keys(&db)?,
+        vec![
+            (first.header.id, 0),
+            (second.header.id, 0),
+            (second.header.id, 1),
+            (second.header.id, 1),
+        ]
+    );
+
+    // Removing a post doesn't remove the comment with the same id.
+    first.delete(&db)?;
+    assert_eq!(
+        view_keys(&db)?,
+        vec![
+            (second.header.id, 0),
+            (second.header.id, 1),
+            (second.header.id, 1),
+        ]
+    );
+
+    hello.delete(&db)?;
+    assert_eq!(
+        view_keys(&db)?,
+        vec![(second.header.id, 0), (second.header.id, 1)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn linked_collection_key_mismatch() -> anyhow::Result<()> {
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, CollectionName, Schema, Schematic, View, ViewMapResult,
+        ViewSchema,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Schema, Debug)]
+    #[schema(name = "blog", collections = [Post, Tag], core = bonsaidb_core)]
+    struct Blog;
+
+    #[derive(Schema, Debug)]
+    #[schema(name = "untagged-blog", collections = [Post], core = bonsaidb_core)]
+    struct UntaggedBlog;
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "posts", views = [PostsWithTags], core = bonsaidb_core)]
+    struct Post {
+        title: String,
+    }
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "tags", primary_key = String, core = bonsaidb_core)]
+    struct Tag {
+        post_id: u64,
+    }
+
+    #[derive(View, Debug, Clone)]
+    #[view(collection = Post, key = u64, name = "with-tags", core = bonsaidb_core)]
+    struct PostsWithTags;
+
+    impl ViewSchema for PostsWithTags {
+        type MappedKey<'doc> = u64;
+        type View = Self;
+
+        fn linked_collections(&self) -> Vec<CollectionName> {
+            vec![Tag::collection_name()]
+        }
+    }
+
+    impl CollectionMapReduce for PostsWithTags {
+        fn map<'doc>(&self, document: CollectionDocument<Post>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.header.id)
+        }
+    }
+
+    // The tags' string ids can't be returned as the posts' u64 ids.
+    let error = Schematic::from_schema::<Blog>().unwrap_err();
+    assert!(matches!(
+        error,
+        bonsaidb_core::Error::LinkedCollectionKeyMismatch { view, collection }
+            if view == PostsWithTags.view_name() && collection == Tag::collection_name()
+    ));
+
+    let path = TestDirectory::new("linked-collection-key-mismatch");
+    assert!(Database::open::<Blog>(StorageConfiguration::new(&path)).is_err());
+
+    // Linked collections must be part of the schema.
+    let error = Schematic::from_schema::<UntaggedBlog>().unwrap_err();
+    assert!(matches!(
+        error,
+        bonsaidb_core::Error::LinkedCollectionNotFound { view, collection }
+            if view == PostsWithTags.view_name() && collection == Tag::collection_name()
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn projection_views() -> anyhow::Result<()> {
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::schema::view::ViewProjection;
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, SerializedCollection, View, ViewMapResult, ViewSchema,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Collection, Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+    #[collection(name = "articles", views = [ArticlesByTitle, ArticleSummaries], core = bonsaidb_core)]
+    struct Article {
+        title: String,
+        body: String,
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Article, key = String, name = "by-title", core = bonsaidb_core)]
+    #[view_schema(projection = Document, core = bonsaidb_core)]
+    struct ArticlesByTitle;
+
+    impl CollectionMapReduce for ArticlesByTitle {
+        fn map<'doc>(&self, document: CollectionDocument<Article>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.contents.title)
+        }
+    }
+
+    #[derive(View, Debug, Clone)]
+    #[view(collection = Article, key = String, name = "summaries", core = bonsaidb_core)]
+    struct ArticleSummaries;
+
+    impl ViewSchema for ArticleSummaries {
+        type MappedKey<'doc> = String;
+        type View = Self;
+
+        fn projection(&self) -> ViewProjection {
+            ViewProjection::Custom
+        }
+    }
+
+    impl CollectionMapReduce for ArticleSummaries {
+        fn map<'doc>(&self, document: CollectionDocument<Article>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.contents.title)
+        }
+
+        fn project(
+            &self,
+            document: CollectionDocument<Article>,
+        ) -> Result<Vec<u8>, bonsaidb_core::Error> {
+            // Only the title is needed when listing articles.
+            Article::serialize(&Article {
+                title: document.contents.title,
+                body: String::new(),
+            })
+        }
+    }
+
+    let path = TestDirectory::new("projection-views");
+    let db = Database::open::<Article>(StorageConfiguration::new(&path))?;
+
+    let mut article = Article {
+        title: String::from("hello"),
+        body: String::from("world"),
+    }
+    .push_into(&db)?;
+
+    let by_title = db.view::<ArticlesByTitle>().query_with_collection_docs()?;
+    assert_eq!(
+        by_title.documents[&article.header.id].contents,
+        article.contents
+    );
+
+    let summaries = db.view::<ArticleSummaries>().query_with_collection_docs()?;
+    let summary = &summaries.documents[&article.header.id];
+    assert_eq!(summary.header, article.header);
+    assert_eq!(summary.contents.title, "hello");
+    assert!(summary.contents.body.is_empty());
+
+    // Updating the document updates the stored projections, even when the
+    // mapped keys do not change.
+    article.contents.body = String::from("everyone");
+    article.update(&db)?;
+    let by_title = db.view::<ArticlesByTitle>().query_with_collection_docs()?;
+    let projected = &by_title.documents[&article.header.id];
+    assert_eq!(projected.header, article.header);
+    assert_eq!(projected.contents.body, "everyone");
+
+    article.delete(&db)?;
+    assert!(db
+        .view::<ArticlesByTitle>()
+        .query_with_docs()?
+        .documents
+        .is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn geo_queries() -> anyhow::Result<()> {
+    use std::collections::BTreeSet;
+
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::key::{GeoArea, GeoPoint};
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, SerializedCollection, View, ViewMapResult, ViewSchema,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Collection, Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[collection(name = "landmarks", views = [LandmarksByLocation], core = bonsaidb_core)]
+    struct Landmark {
+        location: GeoPoint,
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Landmark, key = GeoPoint, name = "by-location", core = bonsaidb_core)]
+    #[view_schema(core = bonsaidb_core)]
+    struct LandmarksByLocation;
+
+    impl CollectionMapReduce for LandmarksByLocation {
+        fn map<'doc>(&self, document: CollectionDocument<Landmark>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key(document.contents.location)
+        }
+    }
+
+    let path = TestDirectory::new("geo-queries");
+    let db = Database::open::<Landmark>(StorageConfiguration::new(&path))?;
+
+    // The equator and the prime meridian divide the largest cells of the
+    // Z-order curve, so a circle centered where they cross touches four cells
+    // whose keys are far apart from each other.
+    let center = GeoPoint::new(0., 0.);
+    let area = GeoArea::radius(center, 55_000.);
+    let mut expected = BTreeSet::new();
+    for latitude in -10..=10 {
+        for longitude in -10..=10 {
+            let location = GeoPoint::new(f64::from(latitude) / 10., f64::from(longitude) / 10.);
+            let landmark = Landmark { location }.push_into(&db)?;
+            // The grid's spacing is about 11km, which keeps every point at
+            // least 500m away from the edge of the circle.
+            if center.distance_to(&location) <= 55_000. {
+                expected.insert(landmark.header.id);
+            }
+        }
+    }
+    assert_eq!(expected.len(), 69);
+
+    let within = db
+        .view::<LandmarksByLocation>()
+        .within(area)
+        .query()?
+        .into_iter()
+        .map(|mapping| mapping.source.id)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(within, expected);
+
+    // Using fewer ranges returns more false positives from the key ranges,
+    // which must still be filtered out.
+    let within = db
+        .view::<LandmarksByLocation>()
+        .within(area)
+        .with_max_key_ranges(1)
+        .query()?
+        .into_iter()
+        .map(|mapping| mapping.source.id)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(within, expected);
+
+    let nearest = db
+        .view::<LandmarksByLocation>()
+        .within(area)
+        .sorted_by_distance()
+        .limit(5)
+        .query()?;
+    assert_eq!(nearest.len(), 5);
+    assert!(nearest[0].key.distance_to(&center) < 1.);
+    assert!(nearest
+        .windows(2)
+        .all(|pair| pair[0].key.distance_to(&center) <= pair[1].key.distance_to(&center)));
+
+    Ok(())
+}
+
+#[test]
+fn built_in_reducers() -> anyhow::Result<()> {
+    use bonsaidb_core::document::{CollectionDocument, Emit};
+    use bonsaidb_core::schema::view::reducers::Statistics;
+    use bonsaidb_core::schema::{
+        Collection, CollectionMapReduce, SerializedCollection, View, ViewMapResult, ViewSchema,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Collection, Serialize, Deserialize, Debug)]
+    #[collection(name = "scores", views = [ScoreCount, ScoreTotal, HighScore, ScoreStats], core = bonsaidb_core)]
+    struct Score {
+        player: String,
+        points: u32,
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Score, key = String, value = u64, name = "count", core = bonsaidb_core)]
+    #[view_schema(reduce = Count, core = bonsaidb_core)]
+    struct ScoreCount;
+
+    impl CollectionMapReduce for ScoreCount {
+        fn map<'doc>(&self, document: CollectionDocument<Score>) -> ViewMapResult<'doc, Self> {
+            document
+                .header
+                .emit_key_and_value(document.contents.player, 0)
+        }
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Score, key = String, value = u64, name = "total", core = bonsaidb_core)]
+    #[view_schema(reduce = Sum, core = bonsaidb_core)]
+    struct ScoreTotal;
+
+    impl CollectionMapReduce for ScoreTotal {
+        fn map<'doc>(&self, document: CollectionDocument<Score>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key_and_value(
+                document.contents.player,
+                u64::from(document.contents.points),
+            )
+        }
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Score, key = String, value = u32, name = "high", core = bonsaidb_core)]
+    #[view_schema(reduce = Max, core = bonsaidb_core)]
+    struct HighScore;
+
+    impl CollectionMapReduce for HighScore {
+        fn map<'doc>(&self, document: CollectionDocument<Score>) -> ViewMapResult<'doc, Self> {
+            document
+                .header
+                .emit_key_and_value(document.contents.player, document.contents.points)
+        }
+    }
+
+    #[derive(View, ViewSchema, Debug, Clone)]
+    #[view(collection = Score, key = String, value = Statistics<u32>, name = "stats", core = bonsaidb_core)]
+    #[view_schema(reduce = Stats, core = bonsaidb_core)]
+    struct ScoreStats;
+
+    impl CollectionMapReduce for ScoreStats {
+        fn map<'doc>(&self, document: CollectionDocument<Score>) -> ViewMapResult<'doc, Self> {
+            document.header.emit_key_and_value(
+                document.contents.player,
+                Statistics::new(document.contents.points),
+            )
+        }
+    }
+
+    let path = TestDirectory::new("built-in-reducers");
+    let db = Database::open::<Score>(StorageConfiguration::new(&path))?;
+    for (player, points) in [("a", 10), ("a", 30), ("b", 5), ("b", 20), ("b", 35)] {
+        Score {
+            player: String::from(player),
+            points,
+        }
+        .push_into(&db)?;
+    }
+
+    assert_eq!(db.view::<ScoreCount>().reduce()?, 5);
+    assert_eq!(db.view::<ScoreCount>().with_key("b").reduce()?, 3);
+    assert_eq!(db.view::<ScoreTotal>().reduce()?, 100);
+    assert_eq!(db.view::<HighScore>().reduce()?, 35);
+    assert_eq!(db.view::<HighScore>().with_key("a").reduce()?, 30);
+    let grouped = db
+        .view::<ScoreTotal>()
+        .reduce_grouped()?
+        .into_iter()
+        .map(|mapping| (mapping.key, mapping.value))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        grouped,
+        vec![(String::from("a"), 40), (String::from("b"), 60)]
+    );
+
+    let stats = db.view::<ScoreStats>().reduce()?;
+    assert_eq!(
+        stats,
+        Statistics {
+            count: 5,
+            sum: 100,
+            min: 5,
+            max: 35,
+        }
+    );
+    assert!((stats.mean() - 20.).abs() < f64::EPSILON);
+
+    // Reducing an empty range with a reducer that requires values fails.
+    assert!(matches!(
+        db.view::<HighScore>().with_key("c").reduce(),
+        Err(bonsaidb_core::Error::NothingToReduce)
+    ));
+    assert_eq!(db.view::<ScoreCount>().with_key("c").reduce()?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn batched_iteration() -> anyhow::Result<()> {
+    use bonsaidb_core::schema::SerializedCollection;
+
+    fn mappings(
+        view: bonsaidb_core::connection::View<'_, Database, BasicByParentId, Option<u64>>,
+    ) -> Result<Vec<(Option<u64>, u64)>, bonsaidb_core::Error> {
+        view.query().map(|mappings| {
+            mappings
+                .into_iter()
+                .map(|mapping| (mapping.key, mapping.source.id))
+                .collect()
+        })
+    }
+
+    let path = TestDirectory::new("batched-iteration");
+    let db = Database::open::<BasicSchema>(StorageConfiguration::new(&path))?;
+    for index in 0..7 {
+        Basic::new(format!("{index}"))
+            .with_parent_id(index % 3)
+            .push_into(&db)?;
+    }
+
+    let ids = |docs: Vec<bonsaidb_core::document::OwnedDocument>| {
+        docs.into_iter()
+            .map(|doc| doc.header.id)
+            .collect::<Vec<_>>()
+    };
+
+    // Documents are returned in the same order as query(), regardless of how
+    // many requests are needed to retrieve them.
+    let expected = ids(db.collection::<Basic>().all().query()?);
+    assert_eq!(expected.len(), 7);
+    let iterated = db
+        .collection::<Basic>()
+        .all()
+        .iter()?
+        .batch_size(2)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(ids(iterated), expected);
+
+    let expected = ids(db
+        .collection::<Basic>()
+        .all()
+        .descending()
+        .limit(5)
+        .query()?);
+    let iterated = db
+        .collection::<Basic>()
+        .all()
+        .descending()
+        .limit(5)
+        .iter()?
+        .batch_size(2)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(ids(iterated), expected);
+
+    let expected = Basic::list(3.., &db)
+        .query()?
+        .into_iter()
+        .map(|doc| doc.contents.value)
+        .collect::<Vec<_>>();
+    assert_eq!(expected.len(), 5);
+    let iterated = Basic::list(3.., &db)
+        .iter()?
+        .batch_size(3)
+        .map(|doc| doc.map(|doc| doc.contents.value))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(iterated, expected);
+
+    // View results are batched by key, and every mapping for a key is
+    // returned in the same batch.
+    let expected = mappings(db.view::<BasicByParentId>())?;
+    assert_eq!(expected.len(), 7);
+    let iterated = db
+        .view::<BasicByParentId>()
+        .iter_with_docs()?
+        .batch_size(1)
+        .map(|mapped| {
+            mapped.map(|mapped| {
+                assert_eq!(mapped.mapping.source.id, mapped.document.header.id);
+                (mapped.mapping.key, mapped.mapping.source.id)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(iterated, expected);
+
+    let expected = mappings(db.view::<BasicByParentId>().descending().limit(2))?;
+    assert_eq!(expected.len(), 5);
+    let iterated = db
+        .view::<BasicByParentId>()
+        .descending()
+        .limit(2)
+        .iter_with_docs()?
+        .batch_size(1)
+        .map(|mapped| mapped.map(|mapped| (mapped.mapping.key, mapped.mapping.source.id)))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(iterated, expected);
+
+    let iterated = db
+        .view::<BasicByParentId>()
+        .with_key(&Some(1))
+        .iter_with_docs()?
+        .batch_size(1)
+        .count();
+    assert_eq!(iterated, 2);
+
+    Ok(())
+}
+
+#[test]
+fn bulk_import() -> anyhow::Result<()> {
+    use bonsaidb_core::schema::{SerializedCollection, View};
+    use bonsaidb_core::test_util::{BasicByParentIdEager, Unique, UniqueValue};
+
+    let path = TestDirectory::new("bulk-import");
+    let db = Database::open::<BasicSchema>(StorageConfiguration::new(&path))?;
+    let executed_before = db.list_executed_transactions(None, None)?.len();
+
+    let mut import = db.bulk_import::<Basic>()?.batch_size(10);
+    let mut headers = Vec::new();
+    for index in 0..25 {
+        headers.push(import.push(&Basic::new(format!("{index}")).with_parent_id(index % 3))?);
+    }
+    assert_eq!(import.finish()?, 25);
+
+    // Each batch was recorded as a single transaction.
+    assert_eq!(db.collection::<Basic>().all().count()?, 25);
+    let batch_sizes = db.list_executed_transactions(None, None)?[executed_before..]
+        .iter()
+        .map(|executed| executed.changes.documents().map(|changes| changes.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(batch_sizes, [Some(10), Some(10), Some(5)]);
+    let imported = Basic::get(&headers[24].id, &db)?.expect("document not imported");
+    assert_eq!(imported.contents.value, "24");
+
+    // Eager and lazy views include the imported documents.
+    assert_eq!(
+        db.view::<BasicByParentIdEager>()
+            .with_key(&Some(1))
+            .query()?
+            .len(),
+        8
+    );
+    assert_eq!(db.view::<BasicByParentId>().reduce()?, 25);
+
+    // Ids continue after the imported documents.
+    let pushed = Basic::new("pushed").push_into(&db)?;
+    assert!(headers.iter().all(|header| header.id != pushed.header.id));
+
+    // Existing documents are not overwritten.
+    let mut import = db.bulk_import::<Basic>()?;
+    import.insert(&headers[0].id, &Basic::new("conflict"))?;
+    assert!(matches!(
+        import.finish(),
+        Err(crate::Error::Core(bonsaidb_core::Error::DocumentConflict(
+            ..
+        )))
+    ));
+    assert_eq!(db.collection::<Basic>().all().count()?, 26);
+
+    // Unique constraints are checked once the documents are imported.
+    let mut import = db.bulk_import::<Unique>()?;
+    for value in ["a", "b", "a"] {
+        import.push(&Unique::new(value))?;
+    }
+    match import.finish() {
+        Err(crate::Error::Core(bonsaidb_core::Error::UniqueKeyViolation { view, .. })) => {
+            assert_eq!(view, UniqueValue.view_name());
+        }
+        other => unreachable!("unexpected result: {other:?}"),
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn async_bulk_import() -> anyhow::Result<()> {
+    use bonsaidb_core::connection::AsyncConnection;
+    use bonsaidb_core::schema::SerializedCollection;
+
+    use crate::AsyncDatabase;
+
+    let path = TestDirectory::new("async-bulk-import");
+    let db = AsyncDatabase::open::<BasicSchema>(StorageConfiguration::new(&path)).await?;
+
+    let mut import = db.bulk_import::<Basic>().await?.batch_size(10);
+    let mut headers = Vec::new();
+    for index in 0..25 {
+        headers.push(
+            import
+                .push(&Basic::new(format!("{index}")).with_parent_id(index % 3))
+                .await?,
+        );
+    }
+    assert_eq!(import.finish().await?, 25);
+
+    assert_eq!(db.collection::<Basic>().all().count().await?, 25);
+    assert_eq!(db.list_executed_transactions(None, None).await?.len(), 3);
+    let imported = Basic::get_async(&headers[24].id, &db)
+        .await?
+        .expect("document not imported");
+    assert_eq!(imported.contents.value, "24");
+    assert_eq!(db.view::<BasicByParentId>().reduce().await?, 25);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn dynamic_collections() -> anyhow::Result<()> {
+    use bonsaidb_core::arc_bytes::serde::Bytes;
+    use bonsaidb_core::connection::{
+        HasSchema, LowLevelConnection, Range, SerializedQueryKey, Sort, StorageConnection,
+    };
+    use bonsaidb_core::key::KeyEncoding;
+    use bonsaidb_core::schema::dynamic::{
+        DocumentFormat, DynamicCollection, DynamicIndex, FieldKind,
+    };
+    use bonsaidb_core::schema::{Collection, CollectionName, Qualified};
+    use bonsaidb_core::transaction::{Operation, OperationResult, Transaction};
+
+    fn person(email: &str, age: u64) -> Vec<u8> {
+        format!(r#"{{"email":"{email}","age":{age}}}"#).into_bytes()
+    }
+
+    fn matches(key: &[u8]) -> Option<SerializedQueryKey> {
+        Some(SerializedQueryKey::Matches(Bytes::from(key.to_vec())))
+    }
+
+    let path = TestDirectory::new("dynamic-collections");
+    let name = CollectionName::private("people");
+    let people = DynamicCollection::new(name.clone(), DocumentFormat::Json)
+        .with_index(
+            DynamicIndex::new("by-email")
+                .field("email", FieldKind::String)
+                .unique(),
+        )
+        .with_index(DynamicIndex::new("by-age").field("age", FieldKind::Unsigned));
+    let by_email = people.index_view_name("by-email").unwrap();
+    let by_age = people.index_view_name("by-age").unwrap();
+
+    {
+        let storage =
+            Storage::open(StorageConfiguration::new(&path).with_schema::<BasicSchema>()?)?;
+        let db = storage.create_database::<BasicSchema>("tests", false)?;
+        db.define_dynamic_collection(people.clone())?;
+        // Collections in the schema can't be redefined.
+        assert!(matches!(
+            db.define_dynamic_collection(DynamicCollection::new(
+                Basic::collection_name(),
+                DocumentFormat::Json
+            )),
+            Err(bonsaidb_core::Error::CollectionAlreadyDefined)
+        ));
+
+        // Existing connections aren't updated.
+        assert!(db.schematic().dynamic_collection(&name).is_none());
+        let db = storage.database::<BasicSchema>("tests")?;
+        assert_eq!(db.schematic().dynamic_collection(&name), Some(&people));
+
+        let mut transaction = Transaction::new();
+        for (email, age) in [("a@example.com", 30), ("b@example.com", 42)] {
+            transaction.push(Operation::insert(name.clone(), None, person(email, age)));
+        }
+        let results = db.apply_transaction(transaction)?;
+        assert!(matches!(
+            db.apply_transaction(Transaction::insert(
+                name.clone(),
+                None,
+                person("a@example.com", 18)
+            )),
+            Err(bonsaidb_core::Error::UniqueKeyViolation { .. })
+        ));
+
+        // Documents must be valid for the collection's format.
+        assert!(db
+            .apply_transaction(Transaction::insert(
+                name.clone(),
+                None,
+                b"not json".to_vec()
+            ))
+            .is_err());
+        let OperationResult::DocumentUpdated { header, .. } = &results[0] else {
+            unreachable!("insert returned {:?}", results[0])
+        };
+        assert!(db
+            .apply_transaction(Transaction::update(
+                name.clone(),
+                header.clone(),
+                b"{\"email\":".to_vec()
+            ))
+            .is_err());
+        assert_eq!(
+            db.list_from_collection(Range::from(..), Sort::Ascending, None, &name)?
+                .len(),
+            2
+        );
+    }
+
+    // Definitions are loaded when the storage is reopened.
+    let storage = Storage::open(StorageConfiguration::new(&path).with_schema::<BasicSchema>()?)?;
+    let db = storage.database::<BasicSchema>("tests")?;
+    db.apply_transaction(Transaction::insert(
+        name.clone(),
+        None,
+        person("c@example.com", 30),
+    ))?;
+    assert_eq!(
+        db.list_from_collection(Range::from(..), Sort::Ascending, None, &name)?
+            .len(),
+        3
+    );
+
+    let mappings = db.query_by_name_with_docs(
+        &by_email,
+        matches(&KeyEncoding::<String>::as_ord_bytes("b@example.com")?),
+        Sort::Ascending,
+        None,
+        AccessPolicy::UpdateBefore,
+    )?;
+    assert_eq!(mappings.mappings.len(), 1);
+    assert_eq!(
+        &mappings.documents[&mappings.mappings[0].source.id].contents[..],
+        &person("b@example.com", 42)[..]
+    );
+
+    let thirty = matches(&KeyEncoding::<u64>::as_ord_bytes(&30_u64)?);
+    assert_eq!(
+        db.query_by_name(
+            &by_age,
+            thirty.clone(),
+            Sort::Ascending,
+            None,
+            AccessPolicy::UpdateBefore
+        )?
+        .len(),
+        2
+    );
+    let count = db.reduce_by_name(&by_age, thirty, AccessPolicy::UpdateBefore)?;
+    assert_eq!(pot::from_slice::<u64>(&count)?, 2);
+
+    Ok(())
+}
@@ -17,25 +17,74 @@ pub struct ViewEntry {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EntryMapping {
     pub source: Header,
+    /// The collection of the source document, if it belongs to one of the
+    /// view's linked collections.
+    pub collection: Option<CollectionName>,
     pub value: Bytes,
+    /// The stored projection of the source document, if the view stores
+    /// projections.
+    pub projection: Option<Bytes>,
 }
 
 pub mod integrity_scanner;
 pub mod mapper;
+pub mod rebuilder;
+pub mod reductions;
 
-pub fn view_entries_tree_name(view_name: &impl Display) -> String {
-    format!("view.{view_name:#}")
+pub fn view_entries_tree_name(view_name: &impl Display, generation: u64) -> String {
+    view_generation_tree_name(format!("view.{view_name:#}"), generation)
 }
 
 /// Used to store Document ID -> Key mappings, so that when a document is updated, we can remove the old entry.
-pub fn view_document_map_tree_name(view_name: &impl Display) -> String {
-    format!("view.{view_name:#}.document-map")
+pub fn view_document_map_tree_name(view_name: &impl Display, generation: u64) -> String {
+    view_generation_tree_name(format!("view.{view_name:#}.document-map"), generation)
+}
+
+/// Used to store partially reduced values of ranges of keys, so that reducing
+/// a range of keys doesn't require reducing every entry in the range.
+pub fn view_reductions_tree_name(view_name: &impl Display, generation: u64) -> String {
+    view_generation_tree_name(format!("view.{view_name:#}.reductions"), generation)
 }
 
 pub fn view_invalidated_docs_tree_name(view_name: &impl Display) -> String {
     format!("view.{view_name:#}.invalidated")
 }
 
+/// Used to store Document ID -> Key mappings for documents from one of a view's
+/// linked collections.
+pub fn view_linked_document_map_tree_name(
+    view_name: &impl Display,
+    collection: &CollectionName,
+) -> String {
+    format!("view.{view_name:#}.document-map.{collection:#}")
+}
+
+pub fn view_linked_invalidated_docs_tree_name(
+    view_name: &impl Display,
+    collection: &CollectionName,
+) -> String {
+    format!("view.{view_name:#}.invalidated.{collection:#}")
+}
+
+/// A view's entries, document map, and reductions are stored in trees named
+/// for the generation of the view's index. Rebuilding a view in the background
+/// builds the next generation's trees while the existing trees continue to be
+/// queried, and the view's version is then updated to point to the new
+/// generation.
+fn view_generation_tree_name(tree_name: String, generation: u64) -> String {
+    if generation == 0 {
+        tree_name
+    } else {
+        format!("{tree_name}.generation-{generation}")
+    }
+}
+
+/// Used to track the documents remaining to be mapped by a background rebuild
+/// of a view.
+pub fn view_rebuild_invalidated_docs_tree_name(view_name: &impl Display) -> String {
+    format!("view.{view_name:#}.invalidated.rebuild")
+}
+
 pub fn view_versions_tree_name(collection: &CollectionName) -> String {
     format!("view-versions.{collection:#}")
 }
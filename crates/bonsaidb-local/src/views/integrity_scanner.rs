@@ -13,11 +13,16 @@ use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
 use super::mapper::{Map, Mapper};
+use super::rebuilder::{Rebuild, Rebuilder};
 use super::{view_invalidated_docs_tree_name, view_versions_tree_name};
 use crate::database::{document_tree_name, Database};
 use crate::tasks::handle::Handle;
 use crate::tasks::{Job, Keyed, Task};
-use crate::views::{view_document_map_tree_name, view_entries_tree_name};
+use crate::views::{
+    view_document_map_tree_name, view_entries_tree_name, view_linked_document_map_tree_name,
+    view_linked_invalidated_docs_tree_name, view_rebuild_invalidated_docs_tree_name,
+    view_reductions_tree_name,
+};
 use crate::Error;
 
 #[derive(Debug)]
@@ -68,8 +73,32 @@ impl Job for IntegrityScanner {
         // Remove any old files that are no longer used.
         version.cleanup(&roots, &view_name)?;
 
+        let view = self.database.data.schema.view_by_name(&view_name)?;
+        let linked_collections = view.linked_collections();
         let task = if version.is_current(view_version) {
             None
+        } else if version.internal_version == ViewVersion::CURRENT_VERSION
+            && !view.update_policy().is_eager()
+            && linked_collections.is_empty()
+            && view.compatible_with_version(version.schema_version)
+        {
+            // The existing index can continue to be queried while the view is
+            // rebuilt in the background.
+            self.database
+                .storage
+                .instance
+                .tasks()
+                .jobs
+                .lookup_or_enqueue(Rebuilder {
+                    database: self.database.clone(),
+                    rebuild: Rebuild {
+                        view_version,
+                        database: self.database.data.name.clone(),
+                        collection: self.scan.collection.clone(),
+                        view_name: self.scan.view_name.clone(),
+                    },
+                });
+            None
         } else {
             // The view isn't the current version, queue up all documents.
             let missing_entries = tree_keys::<Versioned>(&documents)?;
@@ -77,8 +106,29 @@ impl Job for IntegrityScanner {
             // existing keys. The best we can do is delete the existing files so
             // that the view starts fresh.
             roots.delete_tree(view_invalidated_docs_tree_name(&self.scan.view_name))?;
-            roots.delete_tree(view_entries_tree_name(&self.scan.view_name))?;
-            roots.delete_tree(view_document_map_tree_name(&self.scan.view_name))?;
+            roots.delete_tree(view_rebuild_invalidated_docs_tree_name(
+                &self.scan.view_name,
+            ))?;
+            // Also remove any trees left behind by an interrupted background
+            // rebuild, which are the next generation's trees.
+            for generation in [version.generation(), version.generation() + 1] {
+                roots.delete_tree(view_entries_tree_name(&self.scan.view_name, generation))?;
+                roots.delete_tree(view_document_map_tree_name(
+                    &self.scan.view_name,
+                    generation,
+                ))?;
+                roots.delete_tree(view_reductions_tree_name(&self.scan.view_name, generation))?;
+            }
+            for linked_collection in &linked_collections {
+                roots.delete_tree(view_linked_invalidated_docs_tree_name(
+                    &self.scan.view_name,
+                    linked_collection,
+                ))?;
+                roots.delete_tree(view_linked_document_map_tree_name(
+                    &self.scan.view_name,
+                    linked_collection,
+                ))?;
+            }
             // Add all missing entries to the invalidated list. The view
             // mapping job will update them on the next pass.
             let invalidated_entries_tree = self.database.collection_tree::<Unversioned, _>(
@@ -86,12 +136,31 @@ impl Job for IntegrityScanner {
                 view_invalidated_docs_tree_name(&self.scan.view_name),
             )?;
 
-            let transaction = roots.transaction(&[invalidated_entries_tree, view_versions_tree])?;
+            let mut linked_trees = Vec::with_capacity(linked_collections.len());
+            let mut linked_missing_entries = Vec::with_capacity(linked_collections.len());
+            for linked_collection in &linked_collections {
+                let documents = roots.tree(self.database.collection_tree::<Versioned, _>(
+                    linked_collection,
+                    document_tree_name(linked_collection),
+                )?)?;
+                linked_missing_entries.push(tree_keys::<Versioned>(&documents)?);
+                linked_trees.push(self.database.collection_tree::<Unversioned, _>(
+                    linked_collection,
+                    view_linked_invalidated_docs_tree_name(&self.scan.view_name, linked_collection),
+                )?);
+            }
+
+            let transaction = roots.transaction(
+                &[invalidated_entries_tree, view_versions_tree]
+                    .into_iter()
+                    .chain(linked_trees)
+                    .collect::<Vec<_>>(),
+            )?;
             {
                 let mut view_versions = transaction.tree::<Unversioned>(1).unwrap();
                 view_versions.set(
                     view_name.to_string().as_bytes().to_vec(),
-                    ViewVersion::current_for(view_version).to_vec()?,
+                    ViewVersion::current_for(view_version, version.generation()).to_vec()?,
                 )?;
                 let mut invalidated_entries = transaction.tree::<Unversioned>(0).unwrap();
                 let mut missing_entries = missing_entries
@@ -100,6 +169,18 @@ impl Job for IntegrityScanner {
                     .collect::<Vec<_>>();
                 missing_entries.sort();
                 invalidated_entries.modify(missing_entries, Operation::Set(ArcBytes::default()))?;
+
+                for (index, missing_entries) in linked_missing_entries.into_iter().enumerate() {
+                    let mut invalidated_entries =
+                        transaction.tree::<Unversioned>(index + 2).unwrap();
+                    let mut missing_entries = missing_entries
+                        .into_iter()
+                        .map(|id| ArcBytes::from(id.to_vec()))
+                        .collect::<Vec<_>>();
+                    missing_entries.sort();
+                    invalidated_entries
+                        .modify(missing_entries, Operation::Set(ArcBytes::default()))?;
+                }
             }
             transaction.commit()?;
 
@@ -138,10 +219,14 @@ impl Job for IntegrityScanner {
 pub struct ViewVersion {
     internal_version: u8,
     schema_version: u64,
+    /// The generation of the view's trees, which is incremented each time the
+    /// view is rebuilt in the background.
+    #[serde(default)]
+    generation: u64,
 }
 
 impl ViewVersion {
-    const CURRENT_VERSION: u8 = 3;
+    const CURRENT_VERSION: u8 = 4;
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, crate::Error> {
         match pot::from_slice(bytes) {
@@ -153,6 +238,7 @@ impl ViewVersion {
                 Ok(Self {
                     internal_version: 0,
                     schema_version,
+                    generation: 0,
                 })
             }
             Err(err) => Err(crate::Error::from(err)),
@@ -163,13 +249,18 @@ impl ViewVersion {
         pot::to_vec(self).map_err(crate::Error::from)
     }
 
-    pub fn current_for(schema_version: u64) -> Self {
+    pub fn current_for(schema_version: u64, generation: u64) -> Self {
         Self {
             internal_version: Self::CURRENT_VERSION,
             schema_version,
+            generation,
         }
     }
 
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
     pub fn is_current(&self, schema_version: u64) -> bool {
         self.internal_version == Self::CURRENT_VERSION && self.schema_version == schema_version
     }
@@ -6,18 +6,21 @@ use std::sync::Arc;
 use bonsaidb_core::arc_bytes::serde::Bytes;
 use bonsaidb_core::arc_bytes::{ArcBytes, OwnedBytes};
 use bonsaidb_core::connection::Connection;
-use bonsaidb_core::schema::view::{self, map, Serialized, ViewUpdatePolicy};
+use bonsaidb_core::document::BorrowedDocument;
+use bonsaidb_core::schema::view::{self, map, Serialized, ViewProjection, ViewUpdatePolicy};
 use bonsaidb_core::schema::{CollectionName, ViewName};
 use easy_parallel::Parallel;
 use nebari::io::any::AnyFile;
 use nebari::tree::{AnyTreeRoot, CompareSwap, KeyOperation, Operation, Unversioned, Versioned};
-use nebari::{LockedTransactionTree, Tree, UnlockedTransactionTree};
+use nebari::{LockedTransactionTree, TransactionTree, Tree, UnlockedTransactionTree};
 
 use crate::database::{deserialize_document, document_tree_name, Database};
 use crate::tasks::{Job, Keyed, Task};
+use crate::views::reductions::PartialReductions;
 use crate::views::{
     view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
-    EntryMapping, ViewEntry,
+    view_linked_document_map_tree_name, view_linked_invalidated_docs_tree_name,
+    view_reductions_tree_name, EntryMapping, ViewEntry,
 };
 use crate::Error;
 
@@ -41,6 +44,16 @@ impl Job for Mapper {
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
     #[allow(clippy::too_many_lines)]
     fn execute(&mut self) -> Result<Self::Output, Error> {
+        let view = self
+            .database
+            .data
+            .schema
+            .view_by_name(&self.map.view_name)?;
+        // Holding the generation prevents a background rebuild from replacing
+        // the view's trees while they are being updated.
+        let generation = self.database.view_generation(view)?;
+        let generation = generation.read();
+
         let documents =
             self.database
                 .roots()
@@ -54,7 +67,7 @@ impl Job for Mapper {
                 .roots()
                 .tree(self.database.collection_tree::<Unversioned, _>(
                     &self.map.collection,
-                    view_entries_tree_name(&self.map.view_name),
+                    view_entries_tree_name(&self.map.view_name, *generation),
                 )?)?;
 
         let document_map =
@@ -62,7 +75,7 @@ impl Job for Mapper {
                 .roots()
                 .tree(self.database.collection_tree::<Unversioned, _>(
                     &self.map.collection,
-                    view_document_map_tree_name(&self.map.view_name),
+                    view_document_map_tree_name(&self.map.view_name, *generation),
                 )?)?;
 
         let invalidated_entries =
@@ -73,6 +86,14 @@ impl Job for Mapper {
                     view_invalidated_docs_tree_name(&self.map.view_name),
                 )?)?;
 
+        let view_reductions =
+            self.database
+                .roots()
+                .tree(self.database.collection_tree::<Unversioned, _>(
+                    &self.map.collection,
+                    view_reductions_tree_name(&self.map.view_name, *generation),
+                )?)?;
+
         let transaction_id = self
             .database
             .last_transaction_id()?
@@ -86,10 +107,50 @@ impl Job for Mapper {
             &document_map,
             &documents,
             &view_entries,
+            &view_reductions,
             &storage,
             &map_request,
+            None,
         )?;
 
+        for linked_collection in view.linked_collections() {
+            let documents =
+                self.database
+                    .roots()
+                    .tree(self.database.collection_tree::<Versioned, _>(
+                        &linked_collection,
+                        document_tree_name(&linked_collection),
+                    )?)?;
+            let document_map =
+                self.database
+                    .roots()
+                    .tree(self.database.collection_tree::<Unversioned, _>(
+                        &linked_collection,
+                        view_linked_document_map_tree_name(&self.map.view_name, &linked_collection),
+                    )?)?;
+            let invalidated_entries =
+                self.database
+                    .roots()
+                    .tree(self.database.collection_tree::<Unversioned, _>(
+                        &linked_collection,
+                        view_linked_invalidated_docs_tree_name(
+                            &self.map.view_name,
+                            &linked_collection,
+                        ),
+                    )?)?;
+
+            map_view(
+                &invalidated_entries,
+                &document_map,
+                &documents,
+                &view_entries,
+                &view_reductions,
+                &storage,
+                &map_request,
+                Some(&linked_collection),
+            )?;
+        }
+
         self.database.storage.instance.tasks().mark_view_updated(
             self.map.database.clone(),
             self.map.collection.clone(),
@@ -101,13 +162,19 @@ impl Job for Mapper {
     }
 }
 
-fn map_view(
+/// Maps the documents in `invalidated_entries`. `linked_collection` is the
+/// collection `documents` belongs to if it is one of the view's linked
+/// collections rather than the view's own collection.
+#[allow(clippy::too_many_arguments)]
+pub fn map_view(
     invalidated_entries: &Tree<Unversioned, AnyFile>,
     document_map: &Tree<Unversioned, AnyFile>,
     documents: &Tree<Versioned, AnyFile>,
     view_entries: &Tree<Unversioned, AnyFile>,
+    view_reductions: &Tree<Unversioned, AnyFile>,
     database: &Database,
     map_request: &Map,
+    linked_collection: Option<&CollectionName>,
 ) -> Result<(), Error> {
     const CHUNK_SIZE: usize = 100_000;
     // Only do any work if there are invalidated documents to process
@@ -124,6 +191,7 @@ fn map_view(
                 Box::new(document_map.clone()),
                 Box::new(documents.clone()),
                 Box::new(view_entries.clone()),
+                Box::new(view_reductions.clone()),
             ])?;
         {
             let view = database
@@ -138,6 +206,7 @@ fn map_view(
             let document_map = transaction.unlocked_tree(1).unwrap();
             let documents = transaction.unlocked_tree(2).unwrap();
             let view_entries = transaction.unlocked_tree(3).unwrap();
+            let view_reductions = transaction.unlocked_tree(4).unwrap();
             DocumentRequest {
                 document_ids: document_ids.clone(),
                 map_request,
@@ -145,7 +214,9 @@ fn map_view(
                 document_map,
                 documents,
                 view_entries,
+                view_reductions,
                 view,
+                linked_collection,
             }
             .map()?;
 
@@ -166,7 +237,9 @@ pub struct DocumentRequest<'a> {
     pub document_map: &'a UnlockedTransactionTree<AnyFile>,
     pub documents: &'a UnlockedTransactionTree<AnyFile>,
     pub view_entries: &'a UnlockedTransactionTree<AnyFile>,
+    pub view_reductions: &'a UnlockedTransactionTree<AnyFile>,
     pub view: &'a dyn Serialized,
+    pub linked_collection: Option<&'a CollectionName>,
 }
 
 type DocumentIdPayload = (ArcBytes<'static>, Option<ArcBytes<'static>>);
@@ -209,6 +282,7 @@ impl<'a> DocumentRequest<'a> {
         batch_receiver: &flume::Receiver<BatchPayload>,
         mapped_sender: flume::Sender<Batch>,
         view: &dyn Serialized,
+        linked_collection: Option<&CollectionName>,
         parallelization: usize,
     ) -> Result<(), Error> {
         // Process batches
@@ -221,14 +295,24 @@ impl<'a> DocumentRequest<'a> {
                 .each(1..=parallelization, |_| -> Result<_, Error> {
                     let mut results = Vec::new();
                     while let Ok((document_id, document)) = document_id_receiver.recv() {
-                        let map_result = if let Some(document) = document {
+                        let (map_result, projection) = if let Some(document) = document {
                             let document = deserialize_document(&document)?;
 
                             // Call the schema map function
-                            view.map(&document).map_err(bonsaidb_core::Error::from)?
+                            let map_result = match linked_collection {
+                                Some(collection) => view.map_linked(collection, &document),
+                                None => view.map(&document),
+                            }
+                            .map_err(bonsaidb_core::Error::from)?;
+                            let projection = if map_result.is_empty() {
+                                None
+                            } else {
+                                project_document(view, linked_collection, &document)?
+                            };
+                            (map_result, projection)
                         } else {
                             // Get multiple didn't return this document ID.
-                            Vec::new()
+                            (Vec::new(), None)
                         };
                         let keys: HashSet<OwnedBytes> = map_result
                             .iter()
@@ -236,17 +320,20 @@ impl<'a> DocumentRequest<'a> {
                             .collect();
                         let new_keys = ArcBytes::from(bincode::serialize(&keys)?);
 
-                        results.push((document_id, new_keys, keys, map_result));
+                        results.push((document_id, new_keys, keys, map_result, projection));
                     }
 
                     Ok(results)
                 })
                 .run()
             {
-                for (document_id, new_keys, keys, map_result) in result? {
+                for (document_id, new_keys, keys, map_result, projection) in result? {
                     for key in &keys {
                         batch.all_keys.insert(key.0.clone());
                     }
+                    if let Some(projection) = projection {
+                        batch.projections.insert(document_id.clone(), projection);
+                    }
                     batch.document_maps.insert(document_id.clone(), new_keys);
                     batch.document_keys.insert(document_id.clone(), keys);
                     for mapping in map_result {
@@ -298,25 +385,30 @@ impl<'a> DocumentRequest<'a> {
         Ok(view_entries_to_clean)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn update_view_entries(
         view: &dyn Serialized,
         map_request: &Map,
+        linked_collection: Option<&CollectionName>,
         view_entries: &mut LockedTransactionTree<'_, Unversioned, AnyFile>,
-        all_keys: BTreeSet<ArcBytes<'static>>,
+        all_keys: &BTreeSet<ArcBytes<'static>>,
         view_entries_to_clean: BTreeMap<ArcBytes<'static>, HashSet<ArcBytes<'static>>>,
         new_mappings: BTreeMap<ArcBytes<'static>, Vec<map::Serialized>>,
+        projections: BTreeMap<ArcBytes<'static>, Bytes>,
     ) -> Result<(), Error> {
         let mut updater = ViewEntryUpdater {
             view,
             map_request,
+            linked_collection,
             view_entries_to_clean,
             new_mappings,
+            projections,
             result: Ok(()),
-            has_reduce: true,
+            has_reduce: view.implements_reduce(),
         };
         view_entries
             .modify(
-                all_keys.into_iter().collect(),
+                all_keys.iter().cloned().collect(),
                 Operation::CompareSwap(CompareSwap::new(&mut |key, view_entries| {
                     updater.compare_swap_view_entry(key, view_entries)
                 })),
@@ -329,14 +421,17 @@ impl<'a> DocumentRequest<'a> {
         mapped_receiver: &flume::Receiver<Batch>,
         view: &dyn Serialized,
         map_request: &Map,
+        linked_collection: Option<&CollectionName>,
         document_map: &mut LockedTransactionTree<'_, Unversioned, AnyFile>,
         view_entries: &mut LockedTransactionTree<'_, Unversioned, AnyFile>,
+        view_reductions: &mut LockedTransactionTree<'_, Unversioned, AnyFile>,
     ) -> Result<(), Error> {
         while let Ok(Batch {
             document_ids,
             document_maps,
             document_keys,
             new_mappings,
+            projections,
             mut all_keys,
         }) = mapped_receiver.recv()
         {
@@ -351,11 +446,22 @@ impl<'a> DocumentRequest<'a> {
             Self::update_view_entries(
                 view,
                 map_request,
+                linked_collection,
                 view_entries,
-                all_keys,
+                &all_keys,
                 view_entries_to_clean,
                 new_mappings,
+                projections,
             )?;
+
+            if view.implements_reduce() {
+                PartialReductions::<TransactionTree<Unversioned, AnyFile>> {
+                    view,
+                    view_entries,
+                    reductions: view_reductions,
+                }
+                .update(&all_keys)?;
+            }
         }
         Ok(())
     }
@@ -371,18 +477,22 @@ impl<'a> DocumentRequest<'a> {
                     &batch_receiver,
                     mapped_sender,
                     self.view,
+                    self.linked_collection,
                     self.database.storage().parallelization(),
                 )
             })
             .add(|| {
                 let mut document_map = self.document_map.lock();
                 let mut view_entries = self.view_entries.lock();
+                let mut view_reductions = self.view_reductions.lock();
                 Self::save_mappings(
                     &mapped_receiver,
                     self.view,
                     self.map_request,
+                    self.linked_collection,
                     &mut document_map,
                     &mut view_entries,
+                    &mut view_reductions,
                 )
             })
             .run()
@@ -400,6 +510,7 @@ struct Batch {
     document_maps: BTreeMap<ArcBytes<'static>, ArcBytes<'static>>,
     document_keys: BTreeMap<ArcBytes<'static>, HashSet<OwnedBytes>>,
     new_mappings: BTreeMap<ArcBytes<'static>, Vec<map::Serialized>>,
+    projections: BTreeMap<ArcBytes<'static>, Bytes>,
     all_keys: BTreeSet<ArcBytes<'static>>,
 }
 
@@ -412,8 +523,10 @@ impl Keyed<Task> for Mapper {
 struct ViewEntryUpdater<'a> {
     view: &'a dyn Serialized,
     map_request: &'a Map,
+    linked_collection: Option<&'a CollectionName>,
     view_entries_to_clean: BTreeMap<ArcBytes<'static>, HashSet<ArcBytes<'static>>>,
     new_mappings: BTreeMap<ArcBytes<'static>, Vec<map::Serialized>>,
+    projections: BTreeMap<ArcBytes<'static>, Bytes>,
     result: Result<(), Error>,
     has_reduce: bool,
 }
@@ -434,12 +547,16 @@ impl<'a> ViewEntryUpdater<'a> {
             });
         let key = key.to_owned();
         if let Some(document_ids) = self.view_entries_to_clean.remove(&key) {
-            view_entry
-                .mappings
-                .retain(|m| !document_ids.contains(m.source.id.as_ref()));
+            view_entry.mappings.retain(|m| {
+                m.collection.as_ref() != self.linked_collection
+                    || !document_ids.contains(m.source.id.as_ref())
+            });
 
-            if view_entry.mappings.is_empty() && !self.new_mappings.contains_key(&key[..]) {
-                return KeyOperation::Remove;
+            if view_entry.mappings.is_empty() {
+                // The new mappings for this key are reduced below.
+                if !self.new_mappings.contains_key(&key[..]) {
+                    return KeyOperation::Remove;
+                }
             } else if self.has_reduce {
                 let mappings = view_entry
                     .mappings
@@ -476,17 +593,26 @@ impl<'a> ViewEntryUpdater<'a> {
                     }));
                     return KeyOperation::Skip;
                 }
-                let entry_mapping = EntryMapping { source, value };
+                let projection = self.projections.get(source.id.as_ref()).cloned();
+                let entry_mapping = EntryMapping {
+                    source,
+                    collection: self.linked_collection.cloned(),
+                    value,
+                    projection,
+                };
 
                 // attempt to update an existing
                 // entry for this document, if
                 // present
                 let mut found = false;
                 for mapping in &mut view_entry.mappings {
-                    if mapping.source.id == entry_mapping.source.id {
+                    if mapping.collection == entry_mapping.collection
+                        && mapping.source.id == entry_mapping.source.id
+                    {
                         found = true;
                         mapping.source.revision = entry_mapping.source.revision;
                         mapping.value = entry_mapping.value.clone();
+                        mapping.projection = entry_mapping.projection.clone();
                         break;
                     }
                 }
@@ -535,3 +661,23 @@ impl<'a> ViewEntryUpdater<'a> {
         KeyOperation::Set(ArcBytes::from(value))
     }
 }
+
+/// Returns the projection of `document` that should be stored alongside its
+/// view entries, if any.
+fn project_document(
+    view: &dyn Serialized,
+    linked_collection: Option<&CollectionName>,
+    document: &BorrowedDocument<'_>,
+) -> Result<Option<Bytes>, Error> {
+    match (view.projection(), linked_collection) {
+        (ViewProjection::None, _) => Ok(None),
+        (ViewProjection::Document, _) => Ok(Some(Bytes::from(document.contents.to_vec()))),
+        // Custom projections can only be computed for the view's own
+        // collection. Linked documents are loaded from their collection.
+        (ViewProjection::Custom, Some(_)) => Ok(None),
+        (ViewProjection::Custom, None) => view
+            .project(document)
+            .map(|projection| Some(Bytes::from(projection)))
+            .map_err(Error::from),
+    }
+}
@@ -0,0 +1,247 @@
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use bonsaidb_core::connection::Range;
+use bonsaidb_core::schema::view::ViewRebuildProgress;
+use bonsaidb_core::schema::{CollectionName, ViewName};
+use bonsaidb_core::transaction::Changes;
+use nebari::tree::{Operation, ScanEvaluation, Unversioned, Versioned};
+use nebari::ArcBytes;
+
+use super::integrity_scanner::ViewVersion;
+use super::mapper::{map_view, Map};
+use super::{
+    view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
+    view_rebuild_invalidated_docs_tree_name, view_reductions_tree_name, view_versions_tree_name,
+};
+use crate::database::{compat, document_tree_name, Database};
+use crate::tasks::{Job, Keyed, Task};
+use crate::Error;
+
+/// Rebuilds a view into the next generation of its trees while queries
+/// continue to use the view's existing trees. Once every document has been
+/// mapped, the view's version is updated to point to the new generation and
+/// the previous generation's trees are deleted.
+#[derive(Debug)]
+pub struct Rebuilder {
+    pub database: Database,
+    pub rebuild: Rebuild,
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+pub struct Rebuild {
+    pub view_version: u64,
+    pub database: Arc<Cow<'static, str>>,
+    pub collection: CollectionName,
+    pub view_name: ViewName,
+}
+
+impl Job for Rebuilder {
+    type Error = Error;
+    type Output = ();
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+    fn execute(&mut self) -> Result<Self::Output, Self::Error> {
+        const CHUNK_SIZE: usize = 10_000;
+
+        let roots = self.database.roots().clone();
+        let collection = &self.rebuild.collection;
+        let view_name = &self.rebuild.view_name;
+        let view = self.database.data.schema.view_by_name(view_name)?;
+        let generation = self.database.view_generation(view)?;
+        // Only this job changes the view's generation, so it can't change
+        // while the new generation is being built.
+        let previous_generation = *generation.read();
+        let next_generation = previous_generation + 1;
+        let next_tree_names = generation_tree_names(view_name, next_generation);
+        let rebuild_invalidated_name = view_rebuild_invalidated_docs_tree_name(view_name);
+
+        // Start from scratch in case a previous rebuild was interrupted.
+        for name in next_tree_names.iter().chain([&rebuild_invalidated_name]) {
+            roots.delete_tree(name.clone())?;
+        }
+
+        let documents = roots.tree(
+            self.database
+                .collection_tree::<Versioned, _>(collection, document_tree_name(collection))?,
+        )?;
+        let next_trees = next_tree_names
+            .iter()
+            .map(|name| {
+                self.database
+                    .collection_tree::<Unversioned, _>(collection, name.clone())
+                    .and_then(|tree| roots.tree(tree).map_err(Error::from))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let rebuild_invalidated = roots.tree(
+            self.database
+                .collection_tree::<Unversioned, _>(collection, rebuild_invalidated_name.clone())?,
+        )?;
+
+        // Any changes made after this transaction will be found in the
+        // transaction log when the new generation replaces the previous one.
+        let mapped_through = self.database.last_transaction_id()?;
+        let mut document_ids = Vec::new();
+        documents.scan::<Infallible, _, _, _, _>(
+            &(..),
+            true,
+            |_, _, _| ScanEvaluation::ReadData,
+            |key, _| {
+                document_ids.push(key.clone());
+                ScanEvaluation::Skip
+            },
+            |_, _, _| unreachable!(),
+        )?;
+
+        let tasks = self.database.storage.instance.tasks();
+        let mut progress = ViewRebuildProgress {
+            documents_mapped: 0,
+            total_documents: document_ids.len() as u64,
+        };
+        tasks.update_view_rebuild_progress(
+            self.rebuild.database.clone(),
+            collection.clone(),
+            view_name.clone(),
+            progress,
+        );
+
+        let map = Map {
+            database: self.rebuild.database.clone(),
+            collection: collection.clone(),
+            view_name: view_name.clone(),
+        };
+        for chunk in document_ids.chunks(CHUNK_SIZE) {
+            rebuild_invalidated.modify(chunk.to_vec(), Operation::Set(ArcBytes::default()))?;
+            map_view(
+                &rebuild_invalidated,
+                &next_trees[1],
+                &documents,
+                &next_trees[0],
+                &next_trees[2],
+                &self.database,
+                &map,
+                None,
+            )?;
+
+            progress.documents_mapped += chunk.len() as u64;
+            tasks.update_view_rebuild_progress(
+                self.rebuild.database.clone(),
+                collection.clone(),
+                view_name.clone(),
+                progress,
+            );
+        }
+        roots.delete_tree(rebuild_invalidated_name)?;
+
+        {
+            // Waiting for exclusive access ensures no queries or updates are
+            // using the previous generation's trees once the view points to
+            // the new generation.
+            let mut generation = generation.write();
+            self.replace_generation(next_generation, mapped_through)?;
+            *generation = next_generation;
+        }
+
+        for name in generation_tree_names(view_name, previous_generation) {
+            roots.delete_tree(name)?;
+        }
+
+        tasks.mark_view_rebuilt(
+            self.rebuild.database.clone(),
+            collection.clone(),
+            view_name.clone(),
+        );
+
+        Ok(())
+    }
+}
+
+/// Returns the names of the view's entries, document map, and reductions trees
+/// for `generation`.
+fn generation_tree_names(view_name: &ViewName, generation: u64) -> [String; 3] {
+    [
+        view_entries_tree_name(view_name, generation),
+        view_document_map_tree_name(view_name, generation),
+        view_reductions_tree_name(view_name, generation),
+    ]
+}
+
+impl Rebuilder {
+    /// Points the view's version to `generation` in a single transaction.
+    /// Documents changed after `mapped_through` are invalidated so that they
+    /// are mapped into the new generation on the view's next update.
+    fn replace_generation(
+        &self,
+        generation: u64,
+        mapped_through: Option<u64>,
+    ) -> Result<(), Error> {
+        let collection = &self.rebuild.collection;
+        // Locking the invalidated documents prevents other transactions from
+        // invalidating documents until the view points to the new generation.
+        let transaction = self.database.roots().transaction(&[
+            self.database.collection_tree::<Unversioned, _>(
+                collection,
+                view_invalidated_docs_tree_name(&self.rebuild.view_name),
+            )?,
+            self.database.collection_tree::<Unversioned, _>(
+                collection,
+                view_versions_tree_name(collection),
+            )?,
+        ])?;
+        {
+            let mut invalidated = transaction.tree::<Unversioned>(0).unwrap();
+            for document_id in self.changed_documents_since(mapped_through)? {
+                invalidated.set(document_id, b"")?;
+            }
+
+            let mut view_versions = transaction.tree::<Unversioned>(1).unwrap();
+            view_versions.set(
+                self.rebuild.view_name.to_string().as_bytes().to_vec(),
+                ViewVersion::current_for(self.rebuild.view_version, generation).to_vec()?,
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    /// Returns the ids of the documents in the view's collection that were
+    /// changed in transactions after `transaction_id`.
+    fn changed_documents_since(&self, transaction_id: Option<u64>) -> Result<Vec<Vec<u8>>, Error> {
+        let range = if let Some(transaction_id) = transaction_id {
+            Range::from(transaction_id + 1..)
+        } else {
+            Range::from(..)
+        };
+
+        let mut changes = Vec::new();
+        self.database.roots().transactions().scan(range, |entry| {
+            if let Some(data) = entry.data() {
+                changes.push(data.to_vec());
+            }
+            true
+        })?;
+
+        let mut document_ids = Vec::new();
+        for data in changes {
+            if let Changes::Documents(changes) =
+                compat::deserialize_executed_transaction_changes(&data)?
+            {
+                document_ids.extend(
+                    changes
+                        .iter()
+                        .filter(|(collection, _)| *collection == &self.rebuild.collection)
+                        .map(|(_, document)| document.id.as_ref().to_vec()),
+                );
+            }
+        }
+        Ok(document_ids)
+    }
+}
+
+impl Keyed<Task> for Rebuilder {
+    fn key(&self) -> Task {
+        Task::ViewRebuild(self.rebuild.clone())
+    }
+}
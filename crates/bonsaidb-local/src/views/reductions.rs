@@ -0,0 +1,408 @@
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::ops::Bound;
+
+use bonsaidb_core::arc_bytes::serde::Bytes;
+use bonsaidb_core::arc_bytes::ArcBytes;
+use bonsaidb_core::schema::view::{self, Serialized};
+use nebari::io::any::AnyFile;
+use nebari::tree::{ScanEvaluation, Unversioned};
+use nebari::{TransactionTree, Tree};
+
+use crate::views::ViewEntry;
+use crate::Error;
+
+/// Each level contains roughly `2^LEVEL_BITS` fewer nodes than the level below
+/// it.
+const LEVEL_BITS: u32 = 4;
+/// The highest level a key can be promoted to: `u64::BITS / LEVEL_BITS`.
+const MAX_LEVEL: u8 = 16;
+
+/// Partially reduced values of a view, stored in a tree alongside the view's
+/// entries.
+///
+/// The view's entries make up level 0. Each entry's key is deterministically
+/// assigned a level based on its hash, and every key with a level greater than
+/// or equal to `n` starts a node on level `n`. A node stores the re-reduced
+/// value of the nodes on the level below it, from its own key up to the next
+/// node on its level. Because each level contains a fraction of the nodes of
+/// the level below it, any range of keys can be reduced by combining a
+/// logarithmic number of nodes.
+///
+/// Nodes are stored in the reductions tree using their level followed by
+/// their key.
+///
+/// Reducing only requires reading the trees, while updating the nodes requires
+/// the trees to be part of a transaction.
+pub struct PartialReductions<'a, T> {
+    pub view: &'a dyn Serialized,
+    pub view_entries: &'a mut T,
+    pub reductions: &'a mut T,
+}
+
+/// A tree that [`PartialReductions`] can read nodes from.
+pub trait NodeTree {
+    /// Scans the keys in `range`, invoking `callback` with each key and value
+    /// that `key_evaluator` chooses to read.
+    fn scan_nodes(
+        &mut self,
+        range: (Bound<&[u8]>, Bound<&[u8]>),
+        forwards: bool,
+        key_evaluator: &mut dyn FnMut(&[u8]) -> ScanEvaluation,
+        callback: &mut dyn FnMut(&[u8], ArcBytes<'static>),
+    ) -> Result<(), Error>;
+}
+
+impl NodeTree for TransactionTree<Unversioned, AnyFile> {
+    fn scan_nodes(
+        &mut self,
+        range: (Bound<&[u8]>, Bound<&[u8]>),
+        forwards: bool,
+        key_evaluator: &mut dyn FnMut(&[u8]) -> ScanEvaluation,
+        callback: &mut dyn FnMut(&[u8], ArcBytes<'static>),
+    ) -> Result<(), Error> {
+        self.scan::<Infallible, _, _, _, _>(
+            &range,
+            forwards,
+            |_, _, _| ScanEvaluation::ReadData,
+            |key, _| key_evaluator(key.as_slice()),
+            |key, _, value| {
+                callback(key.as_slice(), value);
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+}
+
+impl NodeTree for Tree<Unversioned, AnyFile> {
+    fn scan_nodes(
+        &mut self,
+        range: (Bound<&[u8]>, Bound<&[u8]>),
+        forwards: bool,
+        key_evaluator: &mut dyn FnMut(&[u8]) -> ScanEvaluation,
+        callback: &mut dyn FnMut(&[u8], ArcBytes<'static>),
+    ) -> Result<(), Error> {
+        self.scan::<Infallible, _, _, _, _>(
+            &range,
+            forwards,
+            |_, _, _| ScanEvaluation::ReadData,
+            |key, _| key_evaluator(key.as_slice()),
+            |key, _, value| {
+                callback(key.as_slice(), value);
+                Ok(())
+            },
+        )?;
+        Ok(())
+    }
+}
+
+impl<'a> PartialReductions<'a, TransactionTree<Unversioned, AnyFile>> {
+    /// Updates the nodes affected by changes to the view entries with `keys`.
+    /// This must be called after the view entries have been updated, and only
+    /// for views whose [`Serialized::implements_reduce()`] returns true.
+    pub fn update(&mut self, keys: &BTreeSet<ArcBytes<'static>>) -> Result<(), Error> {
+        let mut changed = keys.iter().map(|key| key.to_vec()).collect::<BTreeSet<_>>();
+        let mut level = 1;
+        while !changed.is_empty() && level <= MAX_LEVEL {
+            // Gather the nodes that need to be recomputed before modifying
+            // this level, so that every lookup sees the same nodes. A changed
+            // key may have split or merged the node before it, and if it
+            // starts a node on this level, that node changed too.
+            let mut dirty = BTreeSet::new();
+            for key in &changed {
+                if let Some(previous) = self.previous_node(level, key)? {
+                    dirty.insert(previous);
+                }
+                if node_level(key) >= level {
+                    dirty.insert(key.clone());
+                }
+            }
+
+            for node in &dirty {
+                match self.recompute(level, node) {
+                    Ok(()) => {}
+                    // The view's reduce function returned that it isn't
+                    // implemented, so there are no values to store.
+                    Err(Error::View(view::Error::Core(
+                        bonsaidb_core::Error::ReduceUnimplemented,
+                    ))) => return Ok(()),
+                    Err(err) => return Err(err),
+                }
+            }
+
+            changed = dirty;
+            level += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Re-reduces the node starting at `key` on `level` from the nodes on the
+    /// level below it, removing it if `key` no longer exists.
+    fn recompute(&mut self, level: u8, key: &[u8]) -> Result<(), Error> {
+        let (mut children, _) = self.read_level(level - 1, Bound::Included(key), |child| {
+            if child == key {
+                true
+            } else {
+                node_level(child) < level
+            }
+        })?;
+        // The first key read will be a different key if `key` was removed.
+        if children.first().map_or(true, |(child, _)| child != key) {
+            self.reductions.remove(&node_key(level, key))?;
+            return Ok(());
+        }
+
+        let value = if children.len() == 1 {
+            children.pop().unwrap().1.into_vec()
+        } else {
+            let mappings = children
+                .iter()
+                .map(|(key, value)| (key.as_slice(), value.as_slice()))
+                .collect::<Vec<_>>();
+            self.view.reduce(&mappings, true)?
+        };
+        self.reductions.set(node_key(level, key), value)?;
+
+        Ok(())
+    }
+}
+
+impl<'a, T> PartialReductions<'a, T>
+where
+    T: NodeTree,
+{
+    /// Reduces the entries with keys contained in `start` and `end`.
+    pub fn reduce(&mut self, start: Bound<&[u8]>, end: Bound<&[u8]>) -> Result<Vec<u8>, Error> {
+        let mut pieces = Vec::new();
+
+        // Reduce the entries until the first key that starts a node on a
+        // higher level.
+        let mut found_node = false;
+        let (entries, _) = self.read_level(0, start, |key| {
+            if found_node || !contains_end(key, end) {
+                false
+            } else {
+                found_node = node_level(key) > 0;
+                true
+            }
+        })?;
+        let mut cursor = None;
+        for (key, value) in entries {
+            if node_level(&key) > 0 {
+                cursor = Some(key);
+            } else {
+                pieces.push((key, value));
+            }
+        }
+
+        // Climb as high as possible, taking each node that ends before the end
+        // of the range.
+        let mut descend_from = None;
+        while let Some(node) = cursor.take() {
+            let level = node_level(&node);
+            let mut ascend = false;
+            let (mut nodes, next) =
+                self.read_level(level, Bound::Included(node.as_slice()), |key| {
+                    if ascend {
+                        false
+                    } else if key == node.as_slice() {
+                        true
+                    } else if node_ends_before(Some(key), end) {
+                        ascend = node_level(key) > level;
+                        true
+                    } else {
+                        false
+                    }
+                })?;
+            let (last_key, last_value) = nodes.pop().ok_or_else(missing_node)?;
+            pieces.extend(nodes);
+            if ascend {
+                cursor = Some(last_key);
+            } else if next.is_none() && matches!(end, Bound::Unbounded) {
+                pieces.push((last_key, last_value));
+            } else {
+                descend_from = Some((level, last_key));
+            }
+        }
+
+        // The last node extends beyond the end of the range. Take the nodes
+        // on the levels below it that are contained within the range.
+        while let Some((level, node)) = descend_from.take() {
+            let level = level - 1;
+            if level == 0 {
+                let (entries, _) = self.read_level(0, Bound::Included(node.as_slice()), |key| {
+                    contains_end(key, end)
+                })?;
+                pieces.extend(entries);
+            } else {
+                let (mut nodes, _) =
+                    self.read_level(level, Bound::Included(node.as_slice()), |key| {
+                        key == node.as_slice() || node_ends_before(Some(key), end)
+                    })?;
+                let (last_key, _) = nodes.pop().ok_or_else(missing_node)?;
+                pieces.extend(nodes);
+                descend_from = Some((level, last_key));
+            }
+        }
+
+        if pieces.len() == 1 {
+            Ok(pieces.pop().unwrap().1.into_vec())
+        } else {
+            let mappings = pieces
+                .iter()
+                .map(|(key, value)| (key.as_slice(), value.as_slice()))
+                .collect::<Vec<_>>();
+            self.view.reduce(&mappings, true).map_err(Error::from)
+        }
+    }
+
+    /// Returns the key of the last node on `level` before `key`.
+    fn previous_node(&mut self, level: u8, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let start = node_key(level, &[]);
+        let end = node_key(level, key);
+        let mut previous = None;
+        self.reductions.scan_nodes(
+            (
+                Bound::Included(start.as_slice()),
+                Bound::Excluded(end.as_slice()),
+            ),
+            false,
+            &mut |node| {
+                if previous.is_none() {
+                    previous = Some(node[1..].to_vec());
+                }
+                ScanEvaluation::Stop
+            },
+            &mut |_, _| unreachable!(),
+        )?;
+        Ok(previous)
+    }
+
+    /// Reads the nodes on `level`, starting at `start`, in ascending order.
+    /// Nodes are read until `accept` returns false, and the key that was
+    /// rejected is returned alongside the nodes that were read.
+    #[allow(clippy::type_complexity)]
+    fn read_level(
+        &mut self,
+        level: u8,
+        start: Bound<&[u8]>,
+        mut accept: impl FnMut(&[u8]) -> bool,
+    ) -> Result<(Vec<(Vec<u8>, Bytes)>, Option<Vec<u8>>), Error> {
+        let mut rejected = None;
+        if level == 0 {
+            let mut entries = Vec::new();
+            self.view_entries.scan_nodes(
+                (start, Bound::Unbounded),
+                true,
+                &mut |key| {
+                    if accept(key) {
+                        ScanEvaluation::ReadData
+                    } else {
+                        rejected = Some(key.to_vec());
+                        ScanEvaluation::Stop
+                    }
+                },
+                &mut |key, value| entries.push((key.to_vec(), value)),
+            )?;
+            return entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let entry = bincode::deserialize::<ViewEntry>(&value)?;
+                    Ok((key, entry.reduced_value))
+                })
+                .collect::<Result<Vec<_>, Error>>()
+                .map(|nodes| (nodes, rejected));
+        }
+
+        let (start, inclusive) = match start {
+            Bound::Included(key) => (node_key(level, key), true),
+            Bound::Excluded(key) => (node_key(level, key), false),
+            Bound::Unbounded => (node_key(level, &[]), true),
+        };
+        let start = if inclusive {
+            Bound::Included(start.as_slice())
+        } else {
+            Bound::Excluded(start.as_slice())
+        };
+        let end = node_key(level + 1, &[]);
+        let mut nodes = Vec::new();
+        self.reductions.scan_nodes(
+            (start, Bound::Excluded(end.as_slice())),
+            true,
+            &mut |key| {
+                if accept(&key[1..]) {
+                    ScanEvaluation::ReadData
+                } else {
+                    rejected = Some(key[1..].to_vec());
+                    ScanEvaluation::Stop
+                }
+            },
+            &mut |key, value| nodes.push((key[1..].to_vec(), Bytes::from(value.to_vec()))),
+        )?;
+
+        Ok((nodes, rejected))
+    }
+}
+
+fn missing_node() -> Error {
+    Error::Core(bonsaidb_core::Error::other(
+        "view reductions",
+        "node missing from its level",
+    ))
+}
+
+/// Returns the highest level that `key` starts a node on.
+#[allow(clippy::cast_possible_truncation)] // at most MAX_LEVEL
+fn node_level(key: &[u8]) -> u8 {
+    // FNV-1a, followed by a finalizer to mix the low bits.
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in key {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+
+    (hash.trailing_zeros() / LEVEL_BITS) as u8
+}
+
+fn node_key(level: u8, key: &[u8]) -> Vec<u8> {
+    let mut node_key = Vec::with_capacity(key.len() + 1);
+    node_key.push(level);
+    node_key.extend_from_slice(key);
+    node_key
+}
+
+/// Returns true if `key` is contained by `end`.
+fn contains_end(key: &[u8], end: Bound<&[u8]>) -> bool {
+    match end {
+        Bound::Included(end) => key <= end,
+        Bound::Excluded(end) => key < end,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Returns true if every key before `next` is contained by `end`. `next` is
+/// the key of the node after the node being checked, if there is one.
+fn node_ends_before(next: Option<&[u8]>, end: Bound<&[u8]>) -> bool {
+    match (next, end) {
+        (_, Bound::Unbounded) => true,
+        (Some(next), Bound::Included(end) | Bound::Excluded(end)) => next <= end,
+        (None, _) => false,
+    }
+}
+
+#[test]
+fn node_levels_are_distributed() {
+    let mut counts = [0_usize; MAX_LEVEL as usize + 1];
+    for i in 0_u32..65_536 {
+        counts[usize::from(node_level(&i.to_be_bytes()))] += 1;
+    }
+    // Each level should contain roughly 1/16th of the keys of the level below.
+    assert!((60_000..62_000).contains(&counts[0]));
+    assert!((3_500..4_200).contains(&counts[1]));
+    assert!((150..350).contains(&counts[2]));
+}
@@ -70,6 +70,12 @@ struct CollectionAttribute {
     name: String,
     #[attribute(optional, example = "[SomeView, AnotherView]")]
     views: Vec<Type>,
+    #[attribute(optional, example = "[SomeReference, AnotherReference]")]
+    references: Vec<Type>,
+    #[attribute(optional, example = "[SomeTextIndex, AnotherTextIndex]")]
+    text_indexes: Vec<Type>,
+    #[attribute(optional, example = "[SomeVectorIndex, AnotherVectorIndex]")]
+    vector_indexes: Vec<Type>,
     #[attribute(example = "Format or None")]
     serialization: Option<Path>,
     #[attribute(example = "Some(KeyId::Master)")]
@@ -80,6 +86,8 @@ struct CollectionAttribute {
     primary_key: Option<Type>,
     #[attribute(example = "self.0 or something(self)")]
     natural_id: Option<Expr>,
+    #[attribute(example = "SnowflakeGenerator")]
+    id_generator: Option<Expr>,
     #[attribute(example = "bosaidb::core")]
     core: Option<Path>,
 }
@@ -101,9 +109,13 @@ pub fn collection_derive(input: proc_macro::TokenStream) -> Result {
         authority,
         name,
         views,
+        references,
+        text_indexes,
+        vector_indexes,
         serialization,
         mut primary_key,
         mut natural_id,
+        id_generator,
         core,
         encryption_key,
         encryption_required,
@@ -231,6 +243,23 @@ pub fn collection_derive(input: proc_macro::TokenStream) -> Result {
         }
     });
 
+    let id_generator = id_generator.map(|id_generator| {
+        quote! {
+            fn id_generator() -> Box<dyn #core::schema::IdGenerator> {
+                fn generator_for<PrimaryKey, Generator>(
+                    generator: Generator,
+                ) -> Box<dyn #core::schema::IdGenerator>
+                where
+                    Generator: #core::schema::IdGeneratorFor<PrimaryKey> + 'static,
+                {
+                    Box::new(generator)
+                }
+
+                generator_for::<Self::PrimaryKey, _>(#id_generator)
+            }
+        }
+    });
+
     Ok(quote! {
         impl #impl_generics #core::schema::Collection for #ident #ty_generics #where_clause {
             type PrimaryKey = #primary_key;
@@ -240,9 +269,13 @@ pub fn collection_derive(input: proc_macro::TokenStream) -> Result {
             }
             fn define_views(schema: &mut #core::schema::Schematic) -> Result<(), #core::Error> {
                 #( schema.define_view(#views)?; )*
+                #( schema.define_reference(#references)?; )*
+                #( schema.define_text_index(#text_indexes)?; )*
+                #( schema.define_vector_index(#vector_indexes)?; )*
                 Ok(())
             }
             #encryption
+            #id_generator
         }
         #serialization
     })
@@ -258,7 +291,7 @@ pub fn view_derive(input: proc_macro::TokenStream) -> Result {
 }
 /// Derives the `bonsaidb::core::schema::ViewSchema` trait.
 #[manyhow]
-/// `#[view_schema(version = 1, policy = Unique, view=ViewType, mapped_key=KeyType<'doc>)]`
+/// `#[view_schema(version = 1, policy = Unique, projection = Document, reduce = Sum, view=ViewType, mapped_key=KeyType<'doc>)]`
 ///
 /// All attributes are optional.
 #[proc_macro_derive(ViewSchema, attributes(view_schema))]
@@ -668,6 +701,13 @@ pub fn key_derive(input: proc_macro::TokenStream) -> Result {
                                             )
                                         })
                                         .unzip();
+                                    let describe = describe_variant(
+                                        &core,
+                                        &ident,
+                                        &const_ident,
+                                        idents.len(),
+                                        &describe,
+                                    );
                                     (
                                         quote! {
                                             Self::#ident{#idents} => {
@@ -705,6 +745,13 @@ pub fn key_derive(input: proc_macro::TokenStream) -> Result {
                                             )
                                         })
                                         .unzip();
+                                    let describe = describe_variant(
+                                        &core,
+                                        &ident,
+                                        &const_ident,
+                                        idents.len(),
+                                        &describe,
+                                    );
                                     (
                                         quote! {
                                             Self::#ident(#idents) => {
@@ -726,13 +773,14 @@ pub fn key_derive(input: proc_macro::TokenStream) -> Result {
                                     } else {
                                         quote!(Self::#ident => $encoder.encode(&#const_ident)?,)
                                     };
-                                    (
-                                        encode,
-                                        (
-                                            quote!(#const_ident => Self::#ident,),
-                                            quote!(visitor.visit_type(#core::key::KeyKind::Unit);),
-                                        ),
-                                    )
+                                    let describe = describe_variant(
+                                        &core,
+                                        &ident,
+                                        &const_ident,
+                                        1,
+                                        &quote!(visitor.visit_type(#core::key::KeyKind::Unit);),
+                                    );
+                                    (encode, (quote!(#const_ident => Self::#ident,), describe))
                                 }
                             },
                         );
@@ -804,9 +852,14 @@ pub fn key_derive(input: proc_macro::TokenStream) -> Result {
                         )))
                     };
                 },
-                describe,
-                quote!(#core::key::CompositeKind::Tuple),
-                field_count,
+                quote! {
+                    #consts
+                    <#repr>::describe(visitor);
+                    #describe
+                },
+                quote!(#core::key::CompositeKind::Enum(std::borrow::Cow::Borrowed(#name))),
+                // The variant tag is described before the variants.
+                field_count + 1,
             )
         }
         Data::Union(_) => bail!("unions are not supported"),
@@ -856,6 +909,26 @@ pub fn key_derive(input: proc_macro::TokenStream) -> Result {
     })
 }
 
+/// Returns the `describe` implementation for an enum variant containing
+/// `field_count` fields, which are described by `describe_fields`.
+fn describe_variant(
+    core: &Path,
+    ident: &Ident,
+    discriminant: &Ident,
+    field_count: usize,
+    describe_fields: &TokenStream,
+) -> TokenStream {
+    let variant_name = ident.to_string();
+    quote! {
+        visitor.visit_composite(
+            #core::key::CompositeKind::Variant(std::borrow::Cow::Borrowed(#variant_name)),
+            #field_count,
+        );
+        visitor.visit_composite_attribute("discriminant", #discriminant);
+        #describe_fields
+    }
+}
+
 #[derive(FromAttr)]
 #[attribute(ident = api)]
 struct ApiAttribute {
@@ -87,6 +87,8 @@ pub fn derive(
     })
 }
 
+const BUILT_IN_REDUCERS: [&str; 5] = ["Count", "Sum", "Min", "Max", "Stats"];
+
 #[derive(FromAttr)]
 #[attribute(ident = view_schema)]
 struct ViewSchemaAttribute {
@@ -98,6 +100,10 @@ struct ViewSchemaAttribute {
     version: Option<u64>,
     #[attribute(example = "Lazy")]
     policy: Option<Ident>,
+    #[attribute(example = "Document")]
+    projection: Option<Ident>,
+    #[attribute(example = "Sum")]
+    reduce: Option<Path>,
     #[attribute(example = "bosaidb::core")]
     core: Option<Path>,
 }
@@ -115,6 +121,8 @@ pub fn derive_schema(
         mapped_key,
         version,
         policy,
+        projection,
+        reduce,
         core,
     } = ViewSchemaAttribute::from_attributes(&attrs)?;
 
@@ -139,6 +147,31 @@ pub fn derive_schema(
         })
     });
 
+    let projection = projection.map(|projection| {
+        quote!(fn projection(&self) -> #core::schema::view::ViewProjection {
+            #core::schema::view::ViewProjection::#projection
+        })
+    });
+
+    let reduce = reduce.map(|reducer| {
+        // The built-in reducers can be referred to by name.
+        let reducer = match reducer.get_ident() {
+            Some(name) if BUILT_IN_REDUCERS.iter().any(|builtin| name == builtin) => {
+                quote!(#core::schema::view::reducers::#name)
+            }
+            _ => quote!(#reducer),
+        };
+        quote!(fn default_reduce(
+            &self,
+            mappings: &[#core::schema::view::map::ViewMappedValue<'_, Self>],
+            rereduce: bool,
+        ) -> #core::schema::view::ReduceResult<Self::View> {
+            <#reducer as #core::schema::view::reducers::Reducer<
+                <Self::View as #core::schema::View>::Value,
+            >>::reduce(mappings, rereduce)
+        })
+    });
+
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     Ok(quote! {
@@ -148,6 +181,8 @@ pub fn derive_schema(
 
             #version
             #policy
+            #projection
+            #reduce
         }
     })
 }
@@ -178,3 +178,22 @@ fn natural_id_attr() {
         named: String,
     }
 }
+
+#[test]
+fn id_generator() {
+    use bonsaidb::core::schema::{IdGeneratorContext, SnowflakeGenerator};
+
+    #[derive(Collection, Debug, Deserialize, Serialize)]
+    #[collection(name = "Name", id_generator = SnowflakeGenerator)]
+    struct Test;
+
+    let collection = Test::collection_name();
+    let id = Test::id_generator()
+        .next_id(IdGeneratorContext {
+            collection: &collection,
+            last_id: None,
+            storage_id: 1,
+        })
+        .unwrap();
+    assert_eq!((id.deserialize::<u64>().unwrap() >> 12) & 0x3FF, 1);
+}
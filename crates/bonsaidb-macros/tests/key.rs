@@ -48,6 +48,46 @@ fn struct_struct() {
     )
 }
 
+#[test]
+fn float_fields() {
+    #[derive(Clone, Debug, Key)]
+    struct Test {
+        price: f64,
+        weight: f32,
+    }
+    assert_eq!(
+        &[191, 248, 0, 0, 0, 0, 0, 0, 63, 255, 255, 255],
+        Test {
+            price: 1.5,
+            weight: -2.0
+        }
+        .as_ord_bytes()
+        .unwrap()
+        .as_ref()
+    )
+}
+
+#[test]
+fn descending_fields() {
+    use bonsaidb::core::key::Descending;
+
+    #[derive(Clone, Debug, Key)]
+    struct Test {
+        category: String,
+        score: Descending<u16>,
+    }
+    assert_eq!(
+        &[97, 0, 255, 253, 1],
+        Test {
+            category: "a".into(),
+            score: Descending(2),
+        }
+        .as_ord_bytes()
+        .unwrap()
+        .as_ref()
+    )
+}
+
 #[test]
 fn unit_struct() {
     #[derive(Clone, Debug, Key)]
@@ -84,6 +124,77 @@ fn r#enum() {
     )
 }
 
+#[test]
+fn enum_fields() {
+    use std::collections::HashMap;
+
+    use bonsaidb::core::key::{
+        ByteSource, CompositeKeyDescription, CompositeKind, KeyAttibuteValue, KeyDescription,
+        KeyKind,
+    };
+
+    #[derive(Clone, Debug, PartialEq, Key)]
+    enum Event {
+        Login { user: u64 },
+        Message(u64, String),
+        Shutdown,
+    }
+
+    let events = [
+        Event::Login { user: 1 },
+        Event::Login { user: 2 },
+        Event::Message(1, String::from("b")),
+        Event::Message(2, String::from("a")),
+        Event::Shutdown,
+    ];
+    let encoded = events
+        .iter()
+        .map(|event| event.as_ord_bytes().unwrap().to_vec())
+        .collect::<Vec<_>>();
+    for (event, bytes) in events.iter().zip(&encoded) {
+        assert_eq!(
+            &Event::from_ord_bytes(ByteSource::Borrowed(bytes)).unwrap(),
+            event
+        );
+    }
+    for pair in encoded.windows(2) {
+        assert!(pair[0] < pair[1]);
+    }
+
+    let variant = |name: &'static str, discriminant: isize, fields: Vec<KeyDescription>| {
+        KeyDescription::Composite(CompositeKeyDescription {
+            kind: CompositeKind::Variant(Cow::Borrowed(name)),
+            fields,
+            attributes: [(
+                Cow::Borrowed("discriminant"),
+                KeyAttibuteValue::Isize(discriminant),
+            )]
+            .into_iter()
+            .collect(),
+        })
+    };
+    assert_eq!(
+        KeyDescription::for_key::<Event>(),
+        KeyDescription::Composite(CompositeKeyDescription {
+            kind: CompositeKind::Enum(Cow::Borrowed(std::any::type_name::<Event>())),
+            fields: vec![
+                KeyDescription::Basic(KeyKind::Signed),
+                variant("Login", 0, vec![KeyDescription::Basic(KeyKind::U64)]),
+                variant(
+                    "Message",
+                    1,
+                    vec![
+                        KeyDescription::Basic(KeyKind::U64),
+                        KeyDescription::Basic(KeyKind::String)
+                    ]
+                ),
+                variant("Shutdown", 2, vec![KeyDescription::Basic(KeyKind::Unit)]),
+            ],
+            attributes: HashMap::new(),
+        })
+    );
+}
+
 #[test]
 fn enum_repr() {
     #[repr(u8)]
@@ -60,13 +60,14 @@ where
     A: Api,
 {
     async fn handle(&self, client: HandlerSession<'_, B>, request: &[u8]) -> Result<Bytes, Error> {
-        let request = pot::from_slice(request)?;
+        let encoding = client.client.value_encoding();
+        let request = encoding.deserialize(request)?;
         let response = match T::handle(client, request).await {
             Ok(response) => Ok(response),
             Err(HandlerError::Api(err)) => Err(err),
             Err(HandlerError::Server(err)) => return Err(err),
         };
-        Ok(Bytes::from(pot::to_vec(&response)?))
+        Ok(encoding.serialize(&response)?)
     }
 }
 
@@ -17,6 +17,7 @@ use bonsaidb_local::config::{Builder, KeyValuePersistence, StorageConfiguration}
 use bonsaidb_local::vault::AnyVaultKeyStorage;
 
 use crate::api::{AnyHandler, AnyWrapper, Handler};
+use crate::trigger::{RegisteredTrigger, Trigger};
 use crate::{Backend, Error, NoBackend};
 
 /// Configuration options for [`Server`](crate::Server)
@@ -42,8 +43,14 @@ pub struct ServerConfiguration<B: Backend = NoBackend> {
     /// The ACME settings for automatic TLS certificate management.
     #[cfg(feature = "acme")]
     pub acme: AcmeConfiguration,
+    /// The maximum length, in bytes, of a request body accepted by the HTTP
+    /// API. Larger requests are rejected with `413 Payload Too Large`. Default
+    /// value is 8 MiB.
+    #[cfg(feature = "http-api")]
+    pub http_api_max_body_length: usize,
 
     pub(crate) custom_apis: HashMap<ApiName, Arc<dyn AnyHandler<B>>>,
+    pub(crate) triggers: Vec<RegisteredTrigger<B>>,
 }
 
 impl<B: Backend> ServerConfiguration<B> {
@@ -59,8 +66,11 @@ impl<B: Backend> ServerConfiguration<B> {
             storage: bonsaidb_local::config::StorageConfiguration::default(),
             default_permissions: DefaultPermissions::Permissions(Permissions::default()),
             custom_apis: HashMap::default(),
+            triggers: Vec::new(),
             #[cfg(feature = "acme")]
             acme: AcmeConfiguration::default(),
+            #[cfg(feature = "http-api")]
+            http_api_max_body_length: 8 * 1024 * 1024,
         }
     }
 
@@ -110,6 +120,13 @@ impl<B: Backend> ServerConfiguration<B> {
         self
     }
 
+    /// Sets [`Self::http_api_max_body_length`](Self#structfield.http_api_max_body_length) to `max_length` and returns self.
+    #[cfg(feature = "http-api")]
+    pub const fn http_api_max_body_length(mut self, max_length: usize) -> Self {
+        self.http_api_max_body_length = max_length;
+        self
+    }
+
     /// Registers a `handler` for a [`Api`][api::Api]. When an [`Api`][api::Api] is
     /// received by the server, the handler will be invoked
     pub fn register_custom_api<Dispatcher: Handler<Api, B> + 'static, Api: api::Api>(
@@ -130,6 +147,47 @@ impl<B: Backend> ServerConfiguration<B> {
         self.register_custom_api::<Dispatcher, Api>()?;
         Ok(self)
     }
+
+    /// Registers `trigger` to be executed for each transaction committed to
+    /// the database named `database`.
+    ///
+    /// Returns an error if a trigger with the same name has already been
+    /// registered for `database`.
+    pub fn register_trigger<T: Trigger<B>>(
+        &mut self,
+        database: impl Into<String>,
+        trigger: T,
+    ) -> Result<(), Error> {
+        let database = database.into();
+        let name = trigger.name();
+        if self
+            .triggers
+            .iter()
+            .any(|registered| registered.database == database && registered.trigger.name() == name)
+        {
+            return Err(Error::Core(bonsaidb_core::Error::other(
+                "bonsaidb-server config",
+                format!("trigger {name} is already registered for database {database}"),
+            )));
+        }
+
+        self.triggers.push(RegisteredTrigger {
+            database,
+            trigger: Arc::new(trigger),
+        });
+        Ok(())
+    }
+
+    /// Registers `trigger` to be executed for each transaction committed to
+    /// the database named `database`, and returns self.
+    pub fn with_trigger<T: Trigger<B>>(
+        mut self,
+        database: impl Into<String>,
+        trigger: T,
+    ) -> Result<Self, Error> {
+        self.register_trigger(database, trigger)?;
+        Ok(self)
+    }
 }
 
 impl<B> Default for ServerConfiguration<B>
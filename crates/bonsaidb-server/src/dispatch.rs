@@ -8,7 +8,8 @@ use bonsaidb_core::keyvalue::AsyncKeyValue;
 use bonsaidb_core::networking::{
     AlterUserPermissionGroupMembership, AlterUserRoleMembership, ApplyTransaction, AssumeIdentity,
     Compact, CompactCollection, CompactKeyValueStore, Count, CreateDatabase, CreateSubscriber,
-    CreateUser, DeleteDatabase, DeleteDocs, DeleteUser, ExecuteKeyOperation, Get, GetMultiple,
+    CreateUser, DefineDynamicCollection, DeleteDatabase, DeleteDocs, DeleteUser,
+    ExecuteKeyOperation, FilterDocuments, FilterHeaders, Get, GetMultiple, GetViewStatus,
     LastTransactionId, List, ListAvailableSchemas, ListDatabases, ListExecutedTransactions,
     ListHeaders, LogOutSession, Publish, PublishToAll, Query, QueryWithDocs, Reduce, ReduceGrouped,
     SubscribeTo, UnregisterSubscriber, UnsubscribeFrom,
@@ -36,12 +37,16 @@ pub fn register_api_handlers<B: Backend>(
         .with_api::<ServerDispatcher, CreateDatabase>()?
         .with_api::<ServerDispatcher, CreateSubscriber>()?
         .with_api::<ServerDispatcher, CreateUser>()?
+        .with_api::<ServerDispatcher, DefineDynamicCollection>()?
         .with_api::<ServerDispatcher, DeleteDatabase>()?
         .with_api::<ServerDispatcher, DeleteDocs>()?
         .with_api::<ServerDispatcher, DeleteUser>()?
         .with_api::<ServerDispatcher, ExecuteKeyOperation>()?
+        .with_api::<ServerDispatcher, FilterDocuments>()?
+        .with_api::<ServerDispatcher, FilterHeaders>()?
         .with_api::<ServerDispatcher, Get>()?
         .with_api::<ServerDispatcher, GetMultiple>()?
+        .with_api::<ServerDispatcher, GetViewStatus>()?
         .with_api::<ServerDispatcher, LastTransactionId>()?
         .with_api::<ServerDispatcher, List>()?
         .with_api::<ServerDispatcher, ListHeaders>()?
@@ -103,6 +108,23 @@ impl<B: Backend> Handler<CreateDatabase, B> for ServerDispatcher {
     }
 }
 
+#[async_trait]
+impl<B: Backend> Handler<DefineDynamicCollection, B> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: DefineDynamicCollection,
+    ) -> HandlerResult<DefineDynamicCollection> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.database)
+            .await?;
+        database
+            .define_dynamic_collection(command.collection)
+            .await
+            .map_err(HandlerError::from)
+    }
+}
+
 #[async_trait]
 impl<B: Backend> Handler<DeleteDatabase, B> for ServerDispatcher {
     async fn handle(
@@ -333,6 +355,23 @@ impl<B: Backend> Handler<GetMultiple, B> for ServerDispatcher {
     }
 }
 
+#[async_trait]
+impl<B: Backend> Handler<GetViewStatus, B> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: GetViewStatus,
+    ) -> HandlerResult<GetViewStatus> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.database)
+            .await?;
+        database
+            .view_status_by_name(&command.view)
+            .await
+            .map_err(HandlerError::from)
+    }
+}
+
 #[async_trait]
 impl<B: Backend> Handler<List, B> for ServerDispatcher {
     async fn handle(session: HandlerSession<'_, B>, command: List) -> HandlerResult<List> {
@@ -454,10 +493,15 @@ impl<B: Backend> Handler<ReduceGrouped, B> for ServerDispatcher {
     ) -> HandlerResult<ReduceGrouped> {
         let database = session
             .as_client
-            .database_without_schema(&command.0.database)
+            .database_without_schema(&command.reduce.database)
             .await?;
         database
-            .reduce_grouped_by_name(&command.0.view, command.0.key, command.0.access_policy)
+            .reduce_grouped_by_name(
+                &command.reduce.view,
+                command.reduce.key,
+                command.group_level,
+                command.reduce.access_policy,
+            )
             .await
             .map_err(HandlerError::from)
     }
@@ -497,6 +541,52 @@ impl<B: Backend> Handler<DeleteDocs, B> for ServerDispatcher {
     }
 }
 
+#[async_trait]
+impl<B: Backend> Handler<FilterDocuments, B> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: FilterDocuments,
+    ) -> HandlerResult<FilterDocuments> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.database)
+            .await?;
+        database
+            .filter_documents_by_name(
+                &command.collection,
+                command.filter,
+                command.order,
+                command.limit,
+                command.access_policy,
+            )
+            .await
+            .map_err(HandlerError::from)
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Handler<FilterHeaders, B> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: FilterHeaders,
+    ) -> HandlerResult<FilterHeaders> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.0.database)
+            .await?;
+        database
+            .filter_headers_by_name(
+                &command.0.collection,
+                command.0.filter,
+                command.0.order,
+                command.0.limit,
+                command.0.access_policy,
+            )
+            .await
+            .map_err(HandlerError::from)
+    }
+}
+
 #[async_trait]
 impl<B: Backend> Handler<ListExecutedTransactions, B> for ServerDispatcher {
     async fn handle(
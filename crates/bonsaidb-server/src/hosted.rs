@@ -6,7 +6,7 @@ use serde::de::Visitor;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Schema)]
-#[schema(name = "hosted", authority = "khonsulabs", collections = [TlsCertificate], core = bonsaidb_core)]
+#[schema(name = "hosted", authority = "khonsulabs", collections = [TlsCertificate, crate::trigger::TriggerState], core = bonsaidb_core)]
 #[cfg_attr(feature = "acme", schema(collections = [crate::server::acme::AcmeAccount]))]
 pub struct Hosted;
 
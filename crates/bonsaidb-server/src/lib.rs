@@ -27,6 +27,8 @@ mod dispatch;
 mod error;
 pub(crate) mod hosted;
 mod server;
+/// Types for defining triggers that execute when documents change.
+pub mod trigger;
 
 #[cfg(feature = "acme")]
 pub use config::{
@@ -36,6 +38,8 @@ pub use config::{
 pub use self::backend::{Backend, BackendError, ConnectionHandling, NoBackend};
 pub use self::config::{BonsaiListenConfig, DefaultPermissions, ServerConfiguration};
 pub use self::error::Error;
+#[cfg(feature = "http-api")]
+pub use self::server::HttpApiService;
 pub use self::server::{
     ApplicationProtocols, ConnectedClient, CustomServer, HttpService, LockedClientDataGuard, Peer,
     Server, ServerDatabase, StandardTcpProtocols, TcpService, Transport,
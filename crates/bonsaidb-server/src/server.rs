@@ -16,7 +16,7 @@ use bonsaidb_core::connection::{
     self, AsyncConnection, AsyncStorageConnection, HasSession, IdentityReference, Session,
     SessionId,
 };
-use bonsaidb_core::networking::{self, Payload, CURRENT_PROTOCOL_VERSION};
+use bonsaidb_core::networking::{self, Payload, ValueEncoding, CURRENT_PROTOCOL_VERSION};
 use bonsaidb_core::permissions::bonsai::{bonsaidb_resource_name, BonsaiAction, ServerAction};
 use bonsaidb_core::permissions::Permissions;
 use bonsaidb_core::schema::{self, Nameable, NamedCollection, Schema, SchemaSummary};
@@ -43,12 +43,15 @@ use crate::dispatch::{register_api_handlers, ServerDispatcher};
 use crate::error::Error;
 use crate::hosted::{Hosted, SerializablePrivateKey, TlsCertificate, TlsCertificatesByDomain};
 use crate::server::shutdown::{Shutdown, ShutdownState, ShutdownStateWatcher};
+use crate::trigger::RegisteredTrigger;
 use crate::{Backend, BackendError, BonsaiListenConfig, NoBackend, ServerConfiguration};
 
 #[cfg(feature = "acme")]
 pub mod acme;
 mod connected_client;
 mod database;
+#[cfg(feature = "http-api")]
+mod http_api;
 
 mod shutdown;
 mod tcp;
@@ -58,6 +61,8 @@ mod websockets;
 use self::connected_client::OwnedClient;
 pub use self::connected_client::{ConnectedClient, LockedClientDataGuard, Transport};
 pub use self::database::ServerDatabase;
+#[cfg(feature = "http-api")]
+pub use self::http_api::HttpApiService;
 pub use self::tcp::{ApplicationProtocols, HttpService, Peer, StandardTcpProtocols, TcpService};
 
 static CONNECTED_CLIENT_ID_COUNTER: AtomicU32 = AtomicU32::new(0);
@@ -99,6 +104,8 @@ struct Data<B: Backend = NoBackend> {
     acme: AcmeConfiguration,
     #[cfg(feature = "acme")]
     alpn_keys: AlpnKeys,
+    #[cfg(feature = "http-api")]
+    http_api_max_body_length: usize,
     shutdown: Shutdown,
 }
 
@@ -164,6 +171,7 @@ impl<B: Backend> CustomServer<B> {
         storage.create_database::<Hosted>("_hosted", true).await?;
 
         let default_permissions = Permissions::from(configuration.default_permissions);
+        let triggers = configuration.triggers;
 
         let server = Self {
             storage,
@@ -183,14 +191,34 @@ impl<B: Backend> CustomServer<B> {
                 acme: configuration.acme,
                 #[cfg(feature = "acme")]
                 alpn_keys: AlpnKeys::default(),
+                #[cfg(feature = "http-api")]
+                http_api_max_body_length: configuration.http_api_max_body_length,
                 shutdown: Shutdown::new(),
             }),
         };
 
         server.data.backend.initialize(&server).await?;
+
+        for trigger in triggers {
+            server.spawn_trigger(trigger).await;
+        }
+
         Ok(server)
     }
 
+    async fn spawn_trigger(&self, trigger: RegisteredTrigger<B>) {
+        let Some(mut shutdown) = self.data.shutdown.watcher().await else {
+            return;
+        };
+        let server = self.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown.wait_for_shutdown() => {}
+                () = trigger.run(&server) => {}
+            }
+        });
+    }
+
     /// Returns the path to the public pinned certificate, if this server has
     /// one. Note: this function will always succeed, but the file may not
     /// exist.
@@ -445,6 +473,7 @@ impl<B: Backend> CustomServer<B> {
     async fn initialize_client(
         &self,
         transport: Transport,
+        value_encoding: ValueEncoding,
         address: SocketAddr,
         sender: Sender<(Option<SessionId>, ApiName, Bytes)>,
     ) -> Option<OwnedClient<B>> {
@@ -463,6 +492,7 @@ impl<B: Backend> CustomServer<B> {
                     next_id,
                     address,
                     transport,
+                    value_encoding,
                     sender,
                     self.clone(),
                     self.data.default_session.clone(),
@@ -531,6 +561,7 @@ impl<B: Backend> CustomServer<B> {
                     if let Some(disconnector) = self
                         .initialize_client(
                             Transport::Bonsai,
+                            ValueEncoding::Pot,
                             connection.remote_address(),
                             api_response_sender,
                         )
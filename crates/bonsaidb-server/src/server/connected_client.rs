@@ -9,7 +9,7 @@ use bonsaidb_core::api;
 use bonsaidb_core::api::ApiName;
 use bonsaidb_core::arc_bytes::serde::Bytes;
 use bonsaidb_core::connection::{Session, SessionId};
-use bonsaidb_core::networking::MessageReceived;
+use bonsaidb_core::networking::{MessageReceived, ValueEncoding};
 use bonsaidb_core::pubsub::{Receiver, Subscriber as _};
 use bonsaidb_local::Subscriber;
 use bonsaidb_utils::fast_async_lock;
@@ -42,6 +42,7 @@ struct ConnectedClientData<B: Backend = NoBackend> {
     sessions: RwLock<HashMap<Option<SessionId>, ClientSession>>,
     address: SocketAddr,
     transport: Transport,
+    value_encoding: ValueEncoding,
     response_sender: Sender<(Option<SessionId>, ApiName, Bytes)>,
     client_data: Mutex<Option<B::ClientData>>,
     connected: AtomicBool,
@@ -66,6 +67,13 @@ impl<B: Backend> ConnectedClient<B> {
         &self.data.transport
     }
 
+    /// Returns the encoding of request and response values sent to and from
+    /// this client.
+    #[must_use]
+    pub fn value_encoding(&self) -> ValueEncoding {
+        self.data.value_encoding
+    }
+
     /// Returns true if the server still believes the client is connected.
     #[must_use]
     pub fn connected(&self) -> bool {
@@ -98,11 +106,14 @@ impl<B: Backend> ConnectedClient<B> {
         session: Option<&Session>,
         response: &Api::Response,
     ) -> Result<(), Error> {
-        let encoded = pot::to_vec(&Result::<&Api::Response, Api::Error>::Ok(response))?;
+        let encoded = self
+            .data
+            .value_encoding
+            .serialize(&Result::<&Api::Response, Api::Error>::Ok(response))?;
         self.data.response_sender.send((
             session.and_then(|session| session.id),
             Api::name(),
-            Bytes::from(encoded),
+            encoded,
         ))?;
         Ok(())
     }
@@ -278,6 +289,7 @@ impl<B: Backend> OwnedClient<B> {
         id: u32,
         address: SocketAddr,
         transport: Transport,
+        value_encoding: ValueEncoding,
         response_sender: Sender<(Option<SessionId>, ApiName, Bytes)>,
         server: CustomServer<B>,
         default_session: Session,
@@ -296,6 +308,7 @@ impl<B: Backend> OwnedClient<B> {
                     id,
                     address,
                     transport,
+                    value_encoding,
                     response_sender,
                     sessions: RwLock::new(session),
                     client_data: Mutex::default(),
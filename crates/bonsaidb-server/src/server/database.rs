@@ -2,13 +2,16 @@ use std::ops::Deref;
 
 use async_trait::async_trait;
 use bonsaidb_core::connection::{
-    AccessPolicy, AsyncLowLevelConnection, HasSchema, HasSession, Range, SerializedQueryKey, Sort,
+    AccessPolicy, AsyncLowLevelConnection, HasSchema, HasSession, Range, SerializedDocumentFilter,
+    SerializedQueryKey, Sort,
 };
 use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
 use bonsaidb_core::keyvalue::AsyncKeyValue;
 use bonsaidb_core::permissions::Permissions;
 use bonsaidb_core::pubsub::AsyncPubSub;
+use bonsaidb_core::schema::dynamic::DynamicCollection;
 use bonsaidb_core::schema::view::map::MappedSerializedValue;
+use bonsaidb_core::schema::view::ViewStatus;
 use bonsaidb_core::schema::{self, CollectionName, Schematic, ViewName};
 use bonsaidb_core::transaction::{OperationResult, Transaction};
 use bonsaidb_local::{AsyncDatabase, Database};
@@ -233,10 +236,11 @@ impl<B: Backend> AsyncLowLevelConnection for ServerDatabase<B> {
         &self,
         view: &ViewName,
         key: Option<SerializedQueryKey>,
+        group_level: Option<usize>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<MappedSerializedValue>, bonsaidb_core::Error> {
         self.db
-            .reduce_grouped_by_name(view, key, access_policy)
+            .reduce_grouped_by_name(view, key, group_level, access_policy)
             .await
     }
 
@@ -249,12 +253,52 @@ impl<B: Backend> AsyncLowLevelConnection for ServerDatabase<B> {
         self.db.delete_docs_by_name(view, key, access_policy).await
     }
 
+    async fn filter_documents_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        self.db
+            .filter_documents_by_name(collection, filter, order, limit, access_policy)
+            .await
+    }
+
+    async fn filter_headers_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, bonsaidb_core::Error> {
+        self.db
+            .filter_headers_by_name(collection, filter, order, limit, access_policy)
+            .await
+    }
+
     async fn apply_transaction(
         &self,
         transaction: Transaction,
     ) -> Result<Vec<OperationResult>, bonsaidb_core::Error> {
         self.db.apply_transaction(transaction).await
     }
+
+    async fn view_status_by_name(
+        &self,
+        view: &ViewName,
+    ) -> Result<ViewStatus, bonsaidb_core::Error> {
+        self.db.view_status_by_name(view).await
+    }
+
+    async fn define_dynamic_collection(
+        &self,
+        collection: DynamicCollection,
+    ) -> Result<(), bonsaidb_core::Error> {
+        self.db.define_dynamic_collection(collection).await
+    }
 }
 
 impl<B: Backend> HasSchema for ServerDatabase<B> {
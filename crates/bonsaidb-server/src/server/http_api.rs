@@ -0,0 +1,1302 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use bonsaidb_core::arc_bytes::serde::Bytes;
+use bonsaidb_core::connection::{
+    self, AsyncLowLevelConnection, AsyncStorageConnection, Bound, HasSchema, Range,
+    SerializedQueryKey,
+};
+use bonsaidb_core::document::{DocumentId, Header, OwnedDocument, Revision};
+use bonsaidb_core::key::{
+    ByteSource, CompositeKeyDecoder, CompositeKeyEncoder, CompositeKind, Key, KeyDescription,
+    KeyKind,
+};
+use bonsaidb_core::keyvalue::{
+    AsyncKeyValue, Command, KeyOperation, KeyStatus, Numeric, Output, SetCommand, Value,
+};
+use bonsaidb_core::permissions::bonsai::{bonsaidb_resource_name, BonsaiAction, ServerAction};
+use bonsaidb_core::schema::dynamic::DocumentFormat;
+use bonsaidb_core::schema::{CollectionName, SchemaName, Schematic, ViewName};
+use bonsaidb_core::transaction::{Operation, OperationResult};
+use bonsaidb_local::AsyncDatabase;
+use derive_where::derive_where;
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, IF_MATCH};
+use hyper::server::conn::Http;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::{Backend, CustomServer, HttpService, NoBackend, Peer};
+
+impl<B: Backend> CustomServer<B> {
+    /// Handles a request to BonsaiDb's HTTP API. The request's path must be
+    /// relative to where the API is mounted. See [`HttpApiService`] for a
+    /// description of the API. Requires feature `http-api` to be enabled.
+    ///
+    /// This can be used to serve the API from within any hyper-compatible
+    /// framework. To serve the API on its own, use [`HttpApiService`].
+    pub async fn handle_http_api_request(&self, request: Request<Body>) -> Response<Body> {
+        match self.dispatch_http_api_request(request).await {
+            Ok(response) => response,
+            Err(err) => err.into_response(),
+        }
+    }
+
+    async fn dispatch_http_api_request(
+        &self,
+        request: Request<Body>,
+    ) -> Result<Response<Body>, HttpError> {
+        let as_client = self.http_api_session(&request).await?;
+        let segments = request
+            .uri()
+            .path()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect::<Result<Vec<_>, _>>()?;
+        let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+        let parameters = query_parameters(request.uri().query());
+        let method = request.method().clone();
+        let max_body_length = self.data.http_api_max_body_length;
+
+        match (&method, segments.as_slice()) {
+            (&Method::GET, ["schemas"]) => {
+                let schemas = as_client
+                    .list_available_schemas()
+                    .await?
+                    .into_iter()
+                    .map(|summary| format!("{:#}", summary.name))
+                    .collect::<Vec<_>>();
+                json_response(StatusCode::OK, &schemas)
+            }
+            (&Method::GET, ["databases"]) => {
+                let databases = as_client
+                    .list_databases()
+                    .await?
+                    .into_iter()
+                    .map(|database| {
+                        json!({
+                            "name": database.name,
+                            "schema": format!("{:#}", database.schema),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json_response(StatusCode::OK, &databases)
+            }
+            (&Method::PUT, ["databases", name]) => {
+                let create = read_json::<CreateDatabase>(request, max_body_length).await?;
+                let schema = create
+                    .schema
+                    .parse::<SchemaName>()
+                    .map_err(HttpError::bad_request)?;
+                as_client
+                    .create_database_with_schema(name, schema, create.only_if_needed)
+                    .await?;
+                Ok(empty_response(StatusCode::CREATED))
+            }
+            (&Method::DELETE, ["databases", name]) => {
+                as_client.delete_database(name).await?;
+                Ok(empty_response(StatusCode::NO_CONTENT))
+            }
+            (_, ["databases", database, "collections", collection, "documents", rest @ ..]) => {
+                let database = as_client.database_without_schema(database).await?;
+                let collection = DocumentContext::new(
+                    database.schematic(),
+                    collection
+                        .parse::<CollectionName>()
+                        .map_err(HttpError::bad_request)?,
+                    parameters.get("contents").map(String::as_str),
+                )?;
+                match (&method, rest) {
+                    (&Method::GET, []) => list_documents(&database, &collection, &parameters).await,
+                    (&Method::POST, []) => {
+                        insert_document(&database, &collection, request, max_body_length).await
+                    }
+                    (&Method::GET, [id]) => get_document(&database, &collection, id).await,
+                    (&Method::PUT, [id]) => {
+                        put_document(&database, &collection, id, request, max_body_length).await
+                    }
+                    (&Method::DELETE, [id]) => {
+                        delete_document(&database, &collection, id, &request).await
+                    }
+                    (_, [] | [_]) => Err(HttpError::method_not_allowed()),
+                    _ => Err(HttpError::not_found()),
+                }
+            }
+            (&Method::POST, ["databases", database, "views", view, action]) => {
+                let database = as_client.database_without_schema(database).await?;
+                let view = view.parse::<ViewName>().map_err(HttpError::bad_request)?;
+                match *action {
+                    "query" => query_view(&database, &view, request, max_body_length).await,
+                    "reduce" => reduce_view(&database, &view, request, max_body_length).await,
+                    _ => Err(HttpError::not_found()),
+                }
+            }
+            (_, ["databases", database, "kv", key, rest @ ..]) => {
+                let database = as_client.database_without_schema(database).await?;
+                let operation = |command| KeyOperation {
+                    namespace: parameters.get("namespace").cloned(),
+                    key: (*key).to_string(),
+                    command,
+                };
+                match (&method, rest) {
+                    (&Method::GET, []) => get_key(&database, operation).await,
+                    (&Method::PUT, []) => {
+                        set_key(&database, operation, request, max_body_length).await
+                    }
+                    (&Method::DELETE, []) => delete_key(&database, operation).await,
+                    (&Method::POST, ["increment"]) => {
+                        let amount = read_json::<Increment>(request, max_body_length).await?;
+                        numeric_key_operation(
+                            &database,
+                            operation(Command::Increment {
+                                amount: numeric_from_json(&amount.amount),
+                                saturating: amount.saturating,
+                            }),
+                        )
+                        .await
+                    }
+                    (&Method::POST, ["decrement"]) => {
+                        let amount = read_json::<Increment>(request, max_body_length).await?;
+                        numeric_key_operation(
+                            &database,
+                            operation(Command::Decrement {
+                                amount: numeric_from_json(&amount.amount),
+                                saturating: amount.saturating,
+                            }),
+                        )
+                        .await
+                    }
+                    (_, [] | ["increment" | "decrement"]) => Err(HttpError::method_not_allowed()),
+                    _ => Err(HttpError::not_found()),
+                }
+            }
+            (_, ["schemas" | "databases"] | ["databases", _]) => {
+                Err(HttpError::method_not_allowed())
+            }
+            _ => Err(HttpError::not_found()),
+        }
+    }
+
+    /// Returns an instance of this server limited to the permissions of the
+    /// request's credentials, or the default permissions if no credentials
+    /// were provided.
+    async fn http_api_session(&self, request: &Request<Body>) -> Result<Self, HttpError> {
+        if !self.data.default_session.allowed_to(
+            bonsaidb_resource_name(),
+            &BonsaiAction::Server(ServerAction::Connect),
+        ) {
+            return Err(HttpError::new(
+                StatusCode::FORBIDDEN,
+                "connecting is not permitted",
+            ));
+        }
+
+        let as_client = Self {
+            data: self.data.clone(),
+            storage: self
+                .storage
+                .assume_session(self.data.default_session.clone())?,
+        };
+
+        let Some(authorization) = request.headers().get(AUTHORIZATION) else {
+            return Ok(as_client);
+        };
+        let authorization = authorization
+            .to_str()
+            .map_err(|_| HttpError::unauthorized())?;
+        let (scheme, credentials) = authorization
+            .split_once(' ')
+            .ok_or_else(HttpError::unauthorized)?;
+        let credentials = credentials.trim();
+
+        #[cfg(feature = "password-hashing")]
+        if scheme.eq_ignore_ascii_case("basic") {
+            let credentials = BASE64
+                .decode(credentials)
+                .ok()
+                .and_then(|credentials| String::from_utf8(credentials).ok())
+                .ok_or_else(HttpError::unauthorized)?;
+            let (user, password) = credentials
+                .split_once(':')
+                .ok_or_else(HttpError::unauthorized)?;
+            return Ok(as_client
+                .authenticate_with_password(user, connection::SensitiveString(password.to_string()))
+                .await?);
+        }
+
+        #[cfg(feature = "token-authentication")]
+        if scheme.eq_ignore_ascii_case("bearer") {
+            let (id, token) = credentials
+                .split_once(':')
+                .ok_or_else(HttpError::unauthorized)?;
+            let id = id.parse::<u64>().map_err(|_| HttpError::unauthorized())?;
+            return Ok(as_client
+                .authenticate_with_token(id, &connection::SensitiveString(token.to_string()))
+                .await?);
+        }
+
+        let _ = (scheme, credentials);
+        Err(HttpError::new(
+            StatusCode::UNAUTHORIZED,
+            "unsupported authorization scheme",
+        ))
+    }
+}
+
+/// An [`HttpService`] that serves BonsaiDb's HTTP API, which exposes
+/// databases using JSON over HTTP. Requires feature `http-api` to be enabled.
+///
+/// If feature `websockets` is enabled, requests containing an `Upgrade`
+/// header are upgraded to BonsaiDb's `WebSocket` protocol. To serve the API
+/// from another hyper-compatible framework, use
+/// [`CustomServer::handle_http_api_request()`].
+///
+/// All paths are relative to where the API is mounted, and all request and
+/// response bodies are JSON. Errors are returned as an object containing an
+/// `error` field. Request bodies longer than
+/// [`ServerConfiguration::http_api_max_body_length`](crate::ServerConfiguration#structfield.http_api_max_body_length)
+/// are rejected with `413 Payload Too Large`.
+///
+/// | Method   | Path                                    | Description                    |
+/// |----------|-----------------------------------------|--------------------------------|
+/// | `GET`    | `/schemas`                              | Lists the available schemas.   |
+/// | `GET`    | `/databases`                            | Lists all databases.           |
+/// | `PUT`    | `/databases/{db}`                       | Creates a database.            |
+/// | `DELETE` | `/databases/{db}`                       | Deletes a database.            |
+/// | `GET`    | `/databases/{db}/collections/{c}/documents`      | Lists documents.      |
+/// | `POST`   | `/databases/{db}/collections/{c}/documents`      | Inserts a document.   |
+/// | `GET`    | `/databases/{db}/collections/{c}/documents/{id}` | Retrieves a document. |
+/// | `PUT`    | `/databases/{db}/collections/{c}/documents/{id}` | Updates a document.   |
+/// | `DELETE` | `/databases/{db}/collections/{c}/documents/{id}` | Deletes a document.   |
+/// | `POST`   | `/databases/{db}/views/{view}/query`    | Queries a view.                |
+/// | `POST`   | `/databases/{db}/views/{view}/reduce`   | Reduces a view.                |
+/// | `GET`    | `/databases/{db}/kv/{key}`              | Retrieves a key's value.       |
+/// | `PUT`    | `/databases/{db}/kv/{key}`              | Sets a key's value.            |
+/// | `DELETE` | `/databases/{db}/kv/{key}`              | Deletes a key.                 |
+/// | `POST`   | `/databases/{db}/kv/{key}/increment`    | Increments a numeric key.      |
+/// | `POST`   | `/databases/{db}/kv/{key}/decrement`    | Decrements a numeric key.      |
+///
+/// Collection and view names use their encoded form, e.g.,
+/// `khonsulabs.basic.by-category`. Creating a database expects an object
+/// containing the `schema` name and optionally `only_if_needed`.
+///
+/// ## Authentication
+///
+/// Requests without an `Authorization` header are performed using the
+/// server's default permissions. Each request may authenticate using:
+///
+/// - `Basic <base64 of user:password>` to authenticate a user using a
+///   password. Requires feature `password-hashing`. Because the password is
+///   verified on every request, authentication tokens are better suited for
+///   frequent requests.
+/// - `Bearer <token id>:<token>` to authenticate using an
+///   [`AuthenticationToken`](bonsaidb_core::admin::AuthenticationToken).
+///   Requires feature `token-authentication`.
+///
+/// Each operation is checked against the authenticated permissions in the
+/// same way as requests made using BonsaiDb's native protocols.
+///
+/// ## Documents
+///
+/// Documents are objects containing `id`, `revision`, and `contents` fields.
+/// The contents of documents in [dynamic
+/// collections](bonsaidb_core::schema::dynamic::DynamicCollection) are
+/// converted to and from JSON using the collection's [`DocumentFormat`]. The
+/// server doesn't know the format of other collections, so their contents are
+/// sent and received as a base64-encoded string of the stored bytes. Adding
+/// `?contents=json` to a request converts their contents to and from JSON
+/// using Pot, the default format of
+/// [`SerializedCollection`](bonsaidb_core::schema::SerializedCollection), and
+/// adding `?contents=base64` always uses base64.
+///
+/// Listing documents accepts the `start` (inclusive) and `end` (exclusive)
+/// ids, the `order` (`ascending` or `descending`), and the `limit` as query
+/// parameters. `PUT` and `DELETE` requests containing an `If-Match` header
+/// with the document's current revision fail with `409 Conflict` if the
+/// document has been changed. Without the header, `PUT` overwrites the
+/// document.
+///
+/// ## Views
+///
+/// Queries are objects containing the optional fields `key`, `order`,
+/// `limit`, `access_policy` (`update_before`, `update_after`, or
+/// `no_update`), and `with_docs`. Reductions accept `key`, `access_policy`,
+/// `grouped`, and `group_level`. Keys are specified as one of
+/// `{"matches": key}`, `{"range": {"start": key, "end": key}}`, or
+/// `{"multiple": [key, ...]}`. Values are converted to JSON from Pot.
+///
+/// ## Keys
+///
+/// Document ids and view keys are converted based on the [`KeyDescription`]
+/// of the collection's primary key or the view's key. Numbers, booleans,
+/// strings, and byte arrays use their JSON equivalents, and tuples are
+/// represented as arrays. All other keys are represented as base64-encoded
+/// strings of their encoded bytes. In paths and query parameters, ids are
+/// parsed as JSON unless the primary key is a string.
+///
+/// ## Key-Value Store
+///
+/// Numbers are stored as numeric values, allowing them to be incremented and
+/// decremented by an `amount`, optionally `saturating`. All other values are
+/// stored using Pot. The `namespace` query parameter selects the key's
+/// namespace.
+///
+/// ```rust,no_run
+/// # use bonsaidb_server::{HttpApiService, Server};
+/// # async fn test(server: Server) -> Result<(), bonsaidb_server::Error> {
+/// server
+///     .listen_for_tcp_on("localhost:8080", HttpApiService::new(&server))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive_where(Clone, Debug)]
+pub struct HttpApiService<B: Backend = NoBackend> {
+    server: CustomServer<B>,
+}
+
+impl<B: Backend> HttpApiService<B> {
+    /// Returns a service that serves the HTTP API of `server`.
+    #[must_use]
+    pub fn new(server: &CustomServer<B>) -> Self {
+        Self {
+            server: server.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl<B: Backend> HttpService for HttpApiService<B> {
+    async fn handle_connection<
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+    >(
+        &self,
+        connection: S,
+        peer: &Peer,
+    ) -> Result<(), S> {
+        let server = self.server.clone();
+        #[cfg_attr(not(feature = "websockets"), allow(unused_variables))]
+        let peer_address = peer.address;
+        let service = hyper::service::service_fn(move |request: Request<Body>| {
+            let server = server.clone();
+            async move {
+                #[cfg(feature = "websockets")]
+                if request.headers().contains_key(hyper::header::UPGRADE) {
+                    return Ok::<_, Infallible>(server.upgrade_websocket(peer_address, request));
+                }
+
+                Ok::<_, Infallible>(server.handle_http_api_request(request).await)
+            }
+        });
+
+        if let Err(err) = Http::new()
+            .serve_connection(connection, service)
+            .with_upgrades()
+            .await
+        {
+            log::error!("[http] error serving {}: {:?}", peer.address, err);
+        }
+
+        Ok(())
+    }
+}
+
+async fn list_documents(
+    database: &AsyncDatabase,
+    collection: &DocumentContext,
+    parameters: &HashMap<String, String>,
+) -> Result<Response<Body>, HttpError> {
+    let bound = |parameter: &str, to_bound: fn(DocumentId) -> Bound<DocumentId>| {
+        parameters
+            .get(parameter)
+            .map(|id| collection.parse_id(id).map(to_bound))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    };
+    let ids = Range {
+        start: bound("start", Bound::Included)?,
+        end: bound("end", Bound::Excluded)?,
+    };
+    let order = parameters
+        .get("order")
+        .map(|order| {
+            serde_json::from_value::<Order>(JsonValue::String(order.clone()))
+                .map_err(HttpError::bad_request)
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let limit = parameters
+        .get("limit")
+        .map(|limit| limit.parse::<u32>().map_err(HttpError::bad_request))
+        .transpose()?;
+
+    let documents = database
+        .list_from_collection(ids, order.into(), limit, &collection.name)
+        .await?
+        .iter()
+        .map(|document| collection.document_json(document))
+        .collect::<Result<Vec<_>, _>>()?;
+    json_response(StatusCode::OK, &documents)
+}
+
+async fn insert_document(
+    database: &AsyncDatabase,
+    collection: &DocumentContext,
+    request: Request<Body>,
+    max_body_length: usize,
+) -> Result<Response<Body>, HttpError> {
+    let contents = collection.contents_from_json(read_json(request, max_body_length).await?)?;
+    let header = apply_operation(
+        database,
+        Operation::insert(collection.name.clone(), None, contents),
+    )
+    .await?;
+    json_response(StatusCode::CREATED, &collection.header_json(&header)?)
+}
+
+async fn get_document(
+    database: &AsyncDatabase,
+    collection: &DocumentContext,
+    id: &str,
+) -> Result<Response<Body>, HttpError> {
+    let id = collection.parse_id(id)?;
+    match database.get_from_collection(id, &collection.name).await? {
+        Some(document) => json_response(StatusCode::OK, &collection.document_json(&document)?),
+        None => Err(HttpError::not_found()),
+    }
+}
+
+async fn put_document(
+    database: &AsyncDatabase,
+    collection: &DocumentContext,
+    id: &str,
+    request: Request<Body>,
+    max_body_length: usize,
+) -> Result<Response<Body>, HttpError> {
+    let id = collection.parse_id(id)?;
+    let revision = if_match_revision(&request)?;
+    let contents = collection.contents_from_json(read_json(request, max_body_length).await?)?;
+    let operation = match revision {
+        Some(revision) => {
+            Operation::update(collection.name.clone(), Header { id, revision }, contents)
+        }
+        None => Operation::overwrite(collection.name.clone(), id, contents),
+    };
+    let header = apply_operation(database, operation).await?;
+    json_response(StatusCode::OK, &collection.header_json(&header)?)
+}
+
+async fn delete_document(
+    database: &AsyncDatabase,
+    collection: &DocumentContext,
+    id: &str,
+    request: &Request<Body>,
+) -> Result<Response<Body>, HttpError> {
+    let id = collection.parse_id(id)?;
+    let header = match if_match_revision(request)? {
+        Some(revision) => Header { id, revision },
+        None => {
+            database
+                .get_from_collection(id, &collection.name)
+                .await?
+                .ok_or_else(HttpError::not_found)?
+                .header
+        }
+    };
+    database
+        .apply_transaction(Operation::delete(collection.name.clone(), header).into())
+        .await?;
+    Ok(empty_response(StatusCode::NO_CONTENT))
+}
+
+/// Applies a transaction containing `operation`, returning the header of the
+/// inserted or updated document.
+async fn apply_operation(
+    database: &AsyncDatabase,
+    operation: Operation,
+) -> Result<Header, HttpError> {
+    let results = database.apply_transaction(operation.into()).await?;
+    match results.into_iter().next() {
+        Some(OperationResult::DocumentUpdated { header, .. }) => Ok(header),
+        _ => Err(HttpError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unexpected transaction result",
+        )),
+    }
+}
+
+fn if_match_revision(request: &Request<Body>) -> Result<Option<Revision>, HttpError> {
+    request
+        .headers()
+        .get(IF_MATCH)
+        .map(|revision| {
+            revision
+                .to_str()
+                .ok()
+                .and_then(|revision| parse_revision(revision.trim_matches('"')))
+                .ok_or_else(|| HttpError::new(StatusCode::BAD_REQUEST, "invalid revision"))
+        })
+        .transpose()
+}
+
+/// Parses a [`Revision`] from its `Display` representation: the revision id
+/// followed by a dash and the hexadecimal SHA-256 digest.
+fn parse_revision(revision: &str) -> Option<Revision> {
+    let (id, digest) = revision.split_once('-')?;
+    if digest.len() != 64 {
+        return None;
+    }
+    let mut sha256 = [0; 32];
+    for (index, byte) in sha256.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(digest.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(Revision {
+        id: id.parse().ok()?,
+        sha256,
+    })
+}
+
+async fn query_view(
+    database: &AsyncDatabase,
+    view_name: &ViewName,
+    request: Request<Body>,
+    max_body_length: usize,
+) -> Result<Response<Body>, HttpError> {
+    let query = read_json::<ViewQuery>(request, max_body_length).await?;
+    let schematic = database.schematic();
+    let view = schematic.view_by_name(view_name)?;
+    let key_description = view.key_description();
+    let key = query
+        .key
+        .map(|key| key.serialize(&key_description))
+        .transpose()?;
+    let order = query.order.unwrap_or_default().into();
+    let access_policy = query.access_policy.unwrap_or_default().into();
+
+    let mapping_json = |mapping: &bonsaidb_core::schema::view::map::Serialized| {
+        let collection = mapping
+            .collection
+            .clone()
+            .unwrap_or_else(|| view_name.collection.clone());
+        let source = DocumentContext::new(schematic, collection.clone(), None)?;
+        Ok::<_, HttpError>(json!({
+            "key": key_to_json(&key_description, &mapping.key, None)?,
+            "value": value_to_json(&mapping.value)?,
+            "source": source.header_json(&mapping.source)?,
+            "collection": format!("{collection:#}"),
+        }))
+    };
+
+    if query.with_docs {
+        let mapped = database
+            .query_by_name_with_docs(view_name, key, order, query.limit, access_policy)
+            .await?;
+        let collection =
+            DocumentContext::new(schematic, view.collection(), query.contents.as_deref())?;
+        let mut linked_documents = serde_json::Map::new();
+        for (linked_collection, documents) in &mapped.linked_documents {
+            let linked = DocumentContext::new(
+                schematic,
+                linked_collection.clone(),
+                query.contents.as_deref(),
+            )?;
+            linked_documents.insert(
+                format!("{linked_collection:#}"),
+                JsonValue::Array(
+                    documents
+                        .values()
+                        .map(|document| linked.document_json(document))
+                        .collect::<Result<_, _>>()?,
+                ),
+            );
+        }
+        json_response(
+            StatusCode::OK,
+            &json!({
+                "mappings": mapped
+                    .mappings
+                    .iter()
+                    .map(mapping_json)
+                    .collect::<Result<Vec<_>, _>>()?,
+                "documents": mapped
+                    .documents
+                    .values()
+                    .map(|document| collection.document_json(document))
+                    .collect::<Result<Vec<_>, _>>()?,
+                "linked_documents": linked_documents,
+            }),
+        )
+    } else {
+        let mappings = database
+            .query_by_name(view_name, key, order, query.limit, access_policy)
+            .await?
+            .iter()
+            .map(mapping_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        json_response(StatusCode::OK, &mappings)
+    }
+}
+
+async fn reduce_view(
+    database: &AsyncDatabase,
+    view_name: &ViewName,
+    request: Request<Body>,
+    max_body_length: usize,
+) -> Result<Response<Body>, HttpError> {
+    let reduce = read_json::<ViewReduce>(request, max_body_length).await?;
+    let key_description = database
+        .schematic()
+        .view_by_name(view_name)?
+        .key_description();
+    let key = reduce
+        .key
+        .map(|key| key.serialize(&key_description))
+        .transpose()?;
+    let access_policy = reduce.access_policy.unwrap_or_default().into();
+
+    if reduce.grouped || reduce.group_level.is_some() {
+        let reductions = database
+            .reduce_grouped_by_name(view_name, key, reduce.group_level, access_policy)
+            .await?
+            .iter()
+            .map(|reduction| {
+                Ok(json!({
+                    "key": key_to_json(&key_description, &reduction.key, reduce.group_level)?,
+                    "value": value_to_json(&reduction.value)?,
+                }))
+            })
+            .collect::<Result<Vec<_>, HttpError>>()?;
+        json_response(StatusCode::OK, &reductions)
+    } else {
+        let value = database
+            .reduce_by_name(view_name, key, access_policy)
+            .await?;
+        json_response(StatusCode::OK, &value_to_json(&value)?)
+    }
+}
+
+async fn get_key(
+    database: &AsyncDatabase,
+    operation: impl FnOnce(Command) -> KeyOperation,
+) -> Result<Response<Body>, HttpError> {
+    match database
+        .execute_key_operation(operation(Command::Get { delete: false }))
+        .await?
+    {
+        Output::Value(Some(value)) => json_response(StatusCode::OK, &key_value_to_json(&value)?),
+        Output::Value(None) | Output::Status(_) => Err(HttpError::not_found()),
+    }
+}
+
+async fn set_key(
+    database: &AsyncDatabase,
+    operation: impl FnOnce(Command) -> KeyOperation,
+    request: Request<Body>,
+    max_body_length: usize,
+) -> Result<Response<Body>, HttpError> {
+    let value = match read_json::<JsonValue>(request, max_body_length).await? {
+        JsonValue::Number(number) => Value::Numeric(numeric_from_json(&number)),
+        other => Value::Bytes(Bytes::from(
+            pot::to_vec(&other).map_err(HttpError::bad_request)?,
+        )),
+    };
+    let status = database
+        .execute_key_operation(operation(Command::Set(SetCommand {
+            value,
+            expiration: None,
+            keep_existing_expiration: false,
+            check: None,
+            return_previous_value: false,
+        })))
+        .await?;
+    match status {
+        Output::Status(KeyStatus::Inserted) => Ok(empty_response(StatusCode::CREATED)),
+        _ => Ok(empty_response(StatusCode::NO_CONTENT)),
+    }
+}
+
+async fn delete_key(
+    database: &AsyncDatabase,
+    operation: impl FnOnce(Command) -> KeyOperation,
+) -> Result<Response<Body>, HttpError> {
+    match database
+        .execute_key_operation(operation(Command::Delete))
+        .await?
+    {
+        Output::Status(KeyStatus::Deleted) => Ok(empty_response(StatusCode::NO_CONTENT)),
+        _ => Err(HttpError::not_found()),
+    }
+}
+
+async fn numeric_key_operation(
+    database: &AsyncDatabase,
+    operation: KeyOperation,
+) -> Result<Response<Body>, HttpError> {
+    match database.execute_key_operation(operation).await? {
+        Output::Value(Some(value)) => json_response(StatusCode::OK, &key_value_to_json(&value)?),
+        Output::Value(None) | Output::Status(_) => Err(HttpError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unexpected key-value result",
+        )),
+    }
+}
+
+fn numeric_from_json(number: &serde_json::Number) -> Numeric {
+    if let Some(value) = number.as_u64() {
+        Numeric::UnsignedInteger(value)
+    } else if let Some(value) = number.as_i64() {
+        Numeric::Integer(value)
+    } else {
+        Numeric::Float(number.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+fn key_value_to_json(value: &Value) -> Result<JsonValue, HttpError> {
+    match value {
+        Value::Numeric(Numeric::Integer(value)) => Ok(JsonValue::from(*value)),
+        Value::Numeric(Numeric::UnsignedInteger(value)) => Ok(JsonValue::from(*value)),
+        Value::Numeric(Numeric::Float(value)) => serde_json::Number::from_f64(*value)
+            .map(JsonValue::Number)
+            .ok_or_else(|| {
+                HttpError::new(StatusCode::INTERNAL_SERVER_ERROR, "value is not a number")
+            }),
+        Value::Bytes(bytes) => value_to_json(bytes),
+    }
+}
+
+/// Converts a Pot-encoded value, such as a view's value, to JSON.
+fn value_to_json(bytes: &[u8]) -> Result<JsonValue, HttpError> {
+    pot::from_slice(bytes).map_err(HttpError::undecodable)
+}
+
+/// Information needed to convert documents in a collection to and from JSON.
+struct DocumentContext<'a> {
+    name: CollectionName,
+    key: &'a KeyDescription,
+    contents: ContentsEncoding,
+}
+
+#[derive(Clone, Copy)]
+enum ContentsEncoding {
+    Format(DocumentFormat),
+    Base64,
+}
+
+impl<'a> DocumentContext<'a> {
+    fn new(
+        schematic: &'a Schematic,
+        name: CollectionName,
+        contents: Option<&str>,
+    ) -> Result<Self, HttpError> {
+        let key = schematic
+            .collection_primary_key_description(&name)
+            .ok_or(bonsaidb_core::Error::CollectionNotFound)?;
+        let format = schematic
+            .dynamic_collection(&name)
+            .map(|collection| collection.format);
+        let contents = match (contents, format) {
+            (Some("base64"), _) | (None, None) => ContentsEncoding::Base64,
+            (Some("json") | None, Some(format)) => ContentsEncoding::Format(format),
+            (Some("json"), None) => ContentsEncoding::Format(DocumentFormat::Pot),
+            (Some(_), _) => {
+                return Err(HttpError::new(
+                    StatusCode::BAD_REQUEST,
+                    "contents must be either json or base64",
+                ))
+            }
+        };
+        Ok(Self {
+            name,
+            key,
+            contents,
+        })
+    }
+
+    fn parse_id(&self, id: &str) -> Result<DocumentId, HttpError> {
+        let id = if matches!(self.key, KeyDescription::Basic(KeyKind::String)) {
+            JsonValue::String(id.to_string())
+        } else {
+            serde_json::from_str(id).unwrap_or_else(|_| JsonValue::String(id.to_string()))
+        };
+        DocumentId::try_from(key_from_json(self.key, &id)?.as_slice()).map_err(HttpError::from_core)
+    }
+
+    fn header_json(&self, header: &Header) -> Result<JsonValue, HttpError> {
+        Ok(json!({
+            "id": key_to_json(self.key, &header.id, None)?,
+            "revision": header.revision.to_string(),
+        }))
+    }
+
+    fn document_json(&self, document: &OwnedDocument) -> Result<JsonValue, HttpError> {
+        let mut json = self.header_json(&document.header)?;
+        json["contents"] = match self.contents {
+            ContentsEncoding::Format(format) => format
+                .deserialize::<JsonValue>(&document.contents)
+                .map_err(HttpError::undecodable)?,
+            ContentsEncoding::Base64 => JsonValue::String(BASE64.encode(&document.contents)),
+        };
+        Ok(json)
+    }
+
+    fn contents_from_json(&self, contents: JsonValue) -> Result<Vec<u8>, HttpError> {
+        match (self.contents, contents) {
+            (ContentsEncoding::Format(format), contents) => {
+                format.serialize(&contents).map_err(HttpError::bad_request)
+            }
+            (ContentsEncoding::Base64, JsonValue::String(contents)) => {
+                BASE64.decode(contents).map_err(HttpError::bad_request)
+            }
+            (ContentsEncoding::Base64, _) => Err(HttpError::new(
+                StatusCode::BAD_REQUEST,
+                "contents must be a base64-encoded string",
+            )),
+        }
+    }
+}
+
+/// How keys with a given [`KeyDescription`] are represented in JSON.
+enum KeyFormat<'a> {
+    Single(&'a KeyKind),
+    Tuple(Vec<&'a KeyKind>),
+    Base64,
+}
+
+impl<'a> KeyFormat<'a> {
+    fn for_description(description: &'a KeyDescription) -> Self {
+        const fn supported(kind: &KeyKind) -> bool {
+            !matches!(kind, KeyKind::Signed | KeyKind::Unsigned)
+        }
+
+        match description {
+            KeyDescription::Basic(kind) if supported(kind) => Self::Single(kind),
+            KeyDescription::Composite(composite) if composite.kind == CompositeKind::Tuple => {
+                composite
+                    .fields
+                    .iter()
+                    .map(|field| match field {
+                        KeyDescription::Basic(kind) if supported(kind) => Some(kind),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .map_or(Self::Base64, Self::Tuple)
+            }
+            _ => Self::Base64,
+        }
+    }
+}
+
+macro_rules! with_key_type {
+    ($kind:expr, $function:ident($($arg:expr),*)) => {
+        match $kind {
+            KeyKind::Unit => $function::<()>($($arg),*),
+            KeyKind::U8 => $function::<u8>($($arg),*),
+            KeyKind::U16 => $function::<u16>($($arg),*),
+            KeyKind::U32 => $function::<u32>($($arg),*),
+            KeyKind::U64 => $function::<u64>($($arg),*),
+            KeyKind::U128 => $function::<u128>($($arg),*),
+            KeyKind::Usize => $function::<usize>($($arg),*),
+            KeyKind::I8 => $function::<i8>($($arg),*),
+            KeyKind::I16 => $function::<i16>($($arg),*),
+            KeyKind::I32 => $function::<i32>($($arg),*),
+            KeyKind::I64 => $function::<i64>($($arg),*),
+            KeyKind::I128 => $function::<i128>($($arg),*),
+            KeyKind::Isize => $function::<isize>($($arg),*),
+            KeyKind::F32 => $function::<f32>($($arg),*),
+            KeyKind::F64 => $function::<f64>($($arg),*),
+            KeyKind::Bool => $function::<bool>($($arg),*),
+            KeyKind::String => $function::<String>($($arg),*),
+            KeyKind::Bytes => $function::<Vec<u8>>($($arg),*),
+            KeyKind::Signed | KeyKind::Unsigned => unreachable!("unsupported by KeyFormat"),
+        }
+    };
+}
+
+/// Encodes a JSON `value` into the key bytes described by `description`.
+fn key_from_json(description: &KeyDescription, value: &JsonValue) -> Result<Vec<u8>, HttpError> {
+    fn encode<T: for<'k> Key<'k> + DeserializeOwned>(
+        value: &JsonValue,
+    ) -> Result<Vec<u8>, HttpError> {
+        let value = T::deserialize(value).map_err(HttpError::bad_request)?;
+        let bytes = value.as_ord_bytes().map_err(HttpError::bad_request)?;
+        Ok(bytes.into_owned())
+    }
+
+    fn encode_field<T: for<'k> Key<'k> + DeserializeOwned>(
+        value: &JsonValue,
+        encoder: &mut CompositeKeyEncoder,
+    ) -> Result<(), HttpError> {
+        let value = T::deserialize(value).map_err(HttpError::bad_request)?;
+        encoder
+            .encode::<T, T>(&value)
+            .map_err(HttpError::bad_request)
+    }
+
+    match KeyFormat::for_description(description) {
+        KeyFormat::Single(kind) => with_key_type!(kind, encode(value)),
+        KeyFormat::Tuple(kinds) => match value {
+            JsonValue::Array(values) if values.len() == kinds.len() => {
+                let mut encoder = CompositeKeyEncoder::default();
+                for (kind, value) in kinds.into_iter().zip(values) {
+                    with_key_type!(kind, encode_field(value, &mut encoder))?;
+                }
+                Ok(encoder.finish())
+            }
+            _ => Err(HttpError::new(
+                StatusCode::BAD_REQUEST,
+                format!("key must be an array of {} values", kinds.len()),
+            )),
+        },
+        KeyFormat::Base64 => match value {
+            JsonValue::String(encoded) => BASE64.decode(encoded).map_err(HttpError::bad_request),
+            _ => Err(HttpError::new(
+                StatusCode::BAD_REQUEST,
+                "key must be a base64-encoded string",
+            )),
+        },
+    }
+}
+
+/// Decodes key `bytes` described by `description` into JSON. If `fields` is
+/// provided, only that many fields of a tuple are decoded.
+fn key_to_json(
+    description: &KeyDescription,
+    bytes: &[u8],
+    fields: Option<usize>,
+) -> Result<JsonValue, HttpError> {
+    fn decode<T: for<'k> Key<'k> + Serialize>(bytes: &[u8]) -> Result<JsonValue, HttpError> {
+        let value =
+            T::from_ord_bytes(ByteSource::Borrowed(bytes)).map_err(HttpError::undecodable)?;
+        serde_json::to_value(value).map_err(HttpError::undecodable)
+    }
+
+    fn decode_field<T: for<'k> Key<'k> + Serialize>(
+        decoder: &mut CompositeKeyDecoder<'_, '_>,
+    ) -> Result<JsonValue, HttpError> {
+        let value = decoder.decode::<T>().map_err(HttpError::undecodable)?;
+        serde_json::to_value(value).map_err(HttpError::undecodable)
+    }
+
+    match KeyFormat::for_description(description) {
+        KeyFormat::Single(kind) => with_key_type!(kind, decode(bytes)),
+        KeyFormat::Tuple(kinds) => {
+            let fields = fields.unwrap_or(kinds.len()).min(kinds.len());
+            let mut decoder = CompositeKeyDecoder::default_for(ByteSource::Borrowed(bytes));
+            let values = kinds
+                .into_iter()
+                .take(fields)
+                .map(|kind| with_key_type!(kind, decode_field(&mut decoder)))
+                .collect::<Result<Vec<_>, _>>()?;
+            decoder.finish().map_err(HttpError::undecodable)?;
+            Ok(JsonValue::Array(values))
+        }
+        KeyFormat::Base64 => Ok(JsonValue::String(BASE64.encode(bytes))),
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateDatabase {
+    schema: String,
+    #[serde(default)]
+    only_if_needed: bool,
+}
+
+#[derive(Deserialize)]
+struct Increment {
+    amount: serde_json::Number,
+    #[serde(default)]
+    saturating: bool,
+}
+
+#[derive(Deserialize)]
+struct ViewQuery {
+    key: Option<QueryKey>,
+    order: Option<Order>,
+    limit: Option<u32>,
+    access_policy: Option<AccessPolicy>,
+    #[serde(default)]
+    with_docs: bool,
+    contents: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ViewReduce {
+    key: Option<QueryKey>,
+    access_policy: Option<AccessPolicy>,
+    #[serde(default)]
+    grouped: bool,
+    group_level: Option<usize>,
+}
+
+/// A JSON representation of
+/// [`SerializedQueryKey`](bonsaidb_core::connection::SerializedQueryKey).
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum QueryKey {
+    Matches(JsonValue),
+    Range {
+        start: Option<JsonValue>,
+        end: Option<JsonValue>,
+    },
+    Multiple(Vec<JsonValue>),
+}
+
+impl QueryKey {
+    fn serialize(self, description: &KeyDescription) -> Result<SerializedQueryKey, HttpError> {
+        let encode = |value: &JsonValue| key_from_json(description, value).map(Bytes::from);
+        Ok(match self {
+            Self::Matches(key) => SerializedQueryKey::Matches(encode(&key)?),
+            Self::Range { start, end } => SerializedQueryKey::Range(Range {
+                start: start
+                    .as_ref()
+                    .map(encode)
+                    .transpose()?
+                    .map_or(Bound::Unbounded, Bound::Included),
+                end: end
+                    .as_ref()
+                    .map(encode)
+                    .transpose()?
+                    .map_or(Bound::Unbounded, Bound::Excluded),
+            }),
+            Self::Multiple(keys) => {
+                SerializedQueryKey::Multiple(keys.iter().map(encode).collect::<Result<_, _>>()?)
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl From<Order> for connection::Sort {
+    fn from(order: Order) -> Self {
+        match order {
+            Order::Ascending => Self::Ascending,
+            Order::Descending => Self::Descending,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum AccessPolicy {
+    #[default]
+    UpdateBefore,
+    UpdateAfter,
+    NoUpdate,
+}
+
+impl From<AccessPolicy> for connection::AccessPolicy {
+    fn from(policy: AccessPolicy) -> Self {
+        match policy {
+            AccessPolicy::UpdateBefore => Self::UpdateBefore,
+            AccessPolicy::UpdateAfter => Self::UpdateAfter,
+            AccessPolicy::NoUpdate => Self::NoUpdate,
+        }
+    }
+}
+
+/// An error returned from the HTTP API as a JSON object containing an `error`
+/// field.
+#[derive(Debug)]
+struct HttpError {
+    status: StatusCode,
+    message: String,
+}
+
+impl HttpError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not found")
+    }
+
+    fn method_not_allowed() -> Self {
+        Self::new(StatusCode::METHOD_NOT_ALLOWED, "method not allowed")
+    }
+
+    fn unauthorized() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "invalid authorization header")
+    }
+
+    fn bad_request(err: impl std::fmt::Display) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, err.to_string())
+    }
+
+    fn undecodable(err: impl std::fmt::Display) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("stored data could not be converted to json: {err}"),
+        )
+    }
+
+    fn from_core(err: bonsaidb_core::Error) -> Self {
+        use bonsaidb_core::Error;
+        let status = match &err {
+            Error::DatabaseNotFound(_)
+            | Error::CollectionNotFound
+            | Error::ViewNotFound
+            | Error::DocumentNotFound(..)
+            | Error::UserNotFound => StatusCode::NOT_FOUND,
+            Error::DatabaseNameAlreadyTaken(_)
+            | Error::DocumentConflict(..)
+            | Error::UniqueKeyViolation { .. }
+            | Error::ReferencedDocumentNotFound { .. }
+            | Error::DocumentStillReferenced { .. } => StatusCode::CONFLICT,
+            Error::PermissionDenied(_) => StatusCode::FORBIDDEN,
+            Error::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            Error::SchemaNotRegistered(_)
+            | Error::InvalidDatabaseName(_)
+            | Error::InvalidName(_)
+            | Error::DocumentIdTooLong
+            | Error::ReduceUnimplemented
+            | Error::NotANumber => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, err.to_string())
+    }
+
+    fn into_response(self) -> Response<Body> {
+        let mut response = Response::new(Body::from(
+            serde_json::to_vec(&json!({ "error": self.message }))
+                .expect("json serialization of a string can't fail"),
+        ));
+        *response.status_mut() = self.status;
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    }
+}
+
+impl From<bonsaidb_core::Error> for HttpError {
+    fn from(err: bonsaidb_core::Error) -> Self {
+        Self::from_core(err)
+    }
+}
+
+impl From<bonsaidb_local::Error> for HttpError {
+    fn from(err: bonsaidb_local::Error) -> Self {
+        Self::from_core(bonsaidb_core::Error::from(err))
+    }
+}
+
+/// Reads the body of `request` as JSON, rejecting bodies longer than
+/// `max_length` bytes without buffering more than `max_length` bytes.
+async fn read_json<T: DeserializeOwned>(
+    request: Request<Body>,
+    max_length: usize,
+) -> Result<T, HttpError> {
+    let too_large = || {
+        HttpError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("request body exceeds {max_length} bytes"),
+        )
+    };
+    let content_length = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<usize>().ok());
+    if content_length.is_some_and(|length| length > max_length) {
+        return Err(too_large());
+    }
+
+    let mut body = request.into_body();
+    let mut bytes = Vec::with_capacity(content_length.unwrap_or_default());
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(HttpError::bad_request)?;
+        if bytes.len() + chunk.len() > max_length {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    serde_json::from_slice(&bytes).map_err(HttpError::bad_request)
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> Result<Response<Body>, HttpError> {
+    let body = serde_json::to_vec(value).map_err(HttpError::undecodable)?;
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Ok(response)
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+fn query_parameters(query: Option<&str>) -> HashMap<String, String> {
+    form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+        .into_owned()
+        .collect()
+}
+
+fn percent_decode(encoded: &str) -> Result<String, HttpError> {
+    let invalid = || HttpError::new(StatusCode::BAD_REQUEST, "invalid percent-encoding");
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut bytes = encoded.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let high = bytes.next().and_then(|byte| char::from(byte).to_digit(16));
+            let low = bytes.next().and_then(|byte| char::from(byte).to_digit(16));
+            match (high, low) {
+                (Some(high), Some(low)) => {
+                    decoded.push(u8::try_from(high << 4 | low).map_err(|_| invalid())?);
+                }
+                _ => return Err(invalid()),
+            }
+        } else {
+            decoded.push(byte);
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+#[test]
+fn key_json_round_trip() {
+    use bonsaidb_core::key::KeyEncoding;
+
+    let tuple = KeyDescription::for_key::<(String, u64)>();
+    let json = json!(["admin", 42]);
+    let encoded = key_from_json(&tuple, &json).unwrap();
+    assert_eq!(
+        encoded,
+        KeyEncoding::<(String, u64)>::as_ord_bytes(&(String::from("admin"), 42_u64)).unwrap()
+    );
+    assert_eq!(key_to_json(&tuple, &encoded, None).unwrap(), json);
+    assert_eq!(key_to_json(&tuple, &encoded, Some(5)).unwrap(), json);
+
+    let id = KeyDescription::for_key::<u64>();
+    let encoded = key_from_json(&id, &json!(1)).unwrap();
+    assert_eq!(key_to_json(&id, &encoded, None).unwrap(), json!(1));
+    assert!(key_from_json(&id, &json!("one")).is_err());
+
+    let revision = Revision::new(b"hello");
+    assert_eq!(parse_revision(&revision.to_string()), Some(revision));
+    assert_eq!(parse_revision("1-00"), None);
+    assert_eq!(percent_decode("a%20b%2Fc").unwrap(), "a b/c");
+    let parameters = query_parameters(Some("namespace=a+b%2Bc&limit=2"));
+    assert_eq!(parameters["namespace"], "a b+c");
+    assert_eq!(parameters["limit"], "2");
+}
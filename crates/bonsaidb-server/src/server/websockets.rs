@@ -1,4 +1,4 @@
-use bonsaidb_core::networking::{Payload, CURRENT_PROTOCOL_VERSION};
+use bonsaidb_core::networking::{Payload, WebSocketFormat};
 use futures::{SinkExt, StreamExt};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_tungstenite::tungstenite::Message;
@@ -28,13 +28,25 @@ impl<B: Backend> CustomServer<B> {
         connection: S,
         peer_address: std::net::SocketAddr,
     ) -> Result<(), Error> {
-        let stream = tokio_tungstenite::accept_hdr_async(connection, VersionChecker).await?;
-        self.handle_websocket(stream, peer_address).await;
+        let mut format = WebSocketFormat::default();
+        let stream = tokio_tungstenite::accept_hdr_async(
+            connection,
+            VersionChecker {
+                format: &mut format,
+            },
+        )
+        .await?;
+        self.handle_websocket_with_format(stream, peer_address, format)
+            .await;
         Ok(())
     }
 
     /// Handles upgrading an HTTP connection to the `WebSocket` protocol based
     /// on the upgrade `request`. Requires feature `hyper` to be enabled.
+    ///
+    /// The [`WebSocketFormat`] is negotiated using the
+    /// `Sec-WebSocket-Protocol` header. If the request does not request a
+    /// supported subprotocol, [`WebSocketFormat::Bincode`] is used.
     #[cfg(feature = "hyper")]
     pub fn upgrade_websocket(
         &self,
@@ -42,7 +54,8 @@ impl<B: Backend> CustomServer<B> {
         mut request: hyper::Request<hyper::Body>,
     ) -> hyper::Response<hyper::Body> {
         use hyper::header::{
-            HeaderValue, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, UPGRADE,
+            HeaderValue, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY,
+            SEC_WEBSOCKET_PROTOCOL, UPGRADE,
         };
         use hyper::StatusCode;
         use tokio_tungstenite::tungstenite::protocol::Role;
@@ -61,12 +74,30 @@ impl<B: Backend> CustomServer<B> {
             return response;
         };
 
+        let negotiated = request
+            .headers()
+            .get(SEC_WEBSOCKET_PROTOCOL)
+            .and_then(|protocols| protocols.to_str().ok())
+            .and_then(WebSocketFormat::negotiate)
+            .map(|(subprotocol, format)| (HeaderValue::from_str(subprotocol), format));
+        let format = match negotiated {
+            Some((Ok(subprotocol), format)) => {
+                response
+                    .headers_mut()
+                    .insert(SEC_WEBSOCKET_PROTOCOL, subprotocol);
+                format
+            }
+            _ => WebSocketFormat::default(),
+        };
+
         let task_self = self.clone();
         tokio::spawn(async move {
             match hyper::upgrade::on(&mut request).await {
                 Ok(upgraded) => {
                     let ws = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
-                    task_self.handle_websocket(ws, peer_address).await;
+                    task_self
+                        .handle_websocket_with_format(ws, peer_address, format)
+                        .await;
                 }
                 Err(err) => {
                     log::error!("Error upgrading websocket: {:?}", err);
@@ -89,7 +120,8 @@ impl<B: Backend> CustomServer<B> {
         response
     }
 
-    /// Handles an established `tokio-tungstenite` `WebSocket` stream.
+    /// Handles an established `tokio-tungstenite` `WebSocket` stream whose
+    /// payloads are encoded using [`WebSocketFormat::Bincode`].
     pub async fn handle_websocket<
         S: futures::Stream<Item = Result<tokio_tungstenite::tungstenite::Message, E>>
             + futures::Sink<tokio_tungstenite::tungstenite::Message>
@@ -100,6 +132,24 @@ impl<B: Backend> CustomServer<B> {
         &self,
         connection: S,
         peer_address: std::net::SocketAddr,
+    ) {
+        self.handle_websocket_with_format(connection, peer_address, WebSocketFormat::default())
+            .await;
+    }
+
+    /// Handles an established `tokio-tungstenite` `WebSocket` stream whose
+    /// payloads are encoded using `format`.
+    pub async fn handle_websocket_with_format<
+        S: futures::Stream<Item = Result<tokio_tungstenite::tungstenite::Message, E>>
+            + futures::Sink<tokio_tungstenite::tungstenite::Message>
+            + Send
+            + 'static,
+        E: std::fmt::Debug + Send,
+    >(
+        &self,
+        connection: S,
+        peer_address: std::net::SocketAddr,
+        format: WebSocketFormat,
     ) {
         let mut shutdown = self
             .data
@@ -114,7 +164,12 @@ impl<B: Backend> CustomServer<B> {
 
         let (api_response_sender, api_response_receiver) = flume::unbounded();
         let Some(client) = self
-            .initialize_client(Transport::WebSocket, peer_address, api_response_sender)
+            .initialize_client(
+                Transport::WebSocket,
+                format.value_encoding(),
+                peer_address,
+                api_response_sender,
+            )
             .await
         else {
             return;
@@ -150,7 +205,7 @@ impl<B: Backend> CustomServer<B> {
         tokio::spawn(async move {
             while let Ok(response) = response_receiver.recv_async().await {
                 if task_sender
-                    .send(Message::Binary(bincode::serialize(&response)?))
+                    .send(payload_message(format, &response)?)
                     .is_err()
                 {
                     break;
@@ -170,7 +225,7 @@ impl<B: Backend> CustomServer<B> {
                 payload = receiver.next() => {
                     if let Some(payload) = payload {
                         match payload {
-                            Ok(Message::Binary(binary)) => match bincode::deserialize::<Payload>(&binary) {
+                            Ok(message @ (Message::Binary(_) | Message::Text(_))) => match decode_payload(format, message) {
                                 Ok(payload) => drop(request_sender.send_async(payload).await),
                                 Err(err) => {
                                     log::error!("[server] error decoding message: {:?}", err);
@@ -223,6 +278,28 @@ impl<B: Backend> CustomServer<B> {
     }
 }
 
+/// Encodes `payload` into a message using `format`.
+fn payload_message(format: WebSocketFormat, payload: &Payload) -> Result<Message, Error> {
+    match format {
+        WebSocketFormat::Bincode => Ok(Message::Binary(bincode::serialize(payload)?)),
+        WebSocketFormat::Pot => Ok(Message::Binary(pot::to_vec(payload)?)),
+        WebSocketFormat::Json => Ok(Message::Text(payload.to_json()?)),
+    }
+}
+
+/// Decodes a payload from `message` using `format`.
+fn decode_payload(format: WebSocketFormat, message: Message) -> Result<Payload, Error> {
+    match (format, message) {
+        (WebSocketFormat::Bincode, Message::Binary(binary)) => Ok(bincode::deserialize(&binary)?),
+        (WebSocketFormat::Pot, Message::Binary(binary)) => Ok(pot::from_slice(&binary)?),
+        (WebSocketFormat::Json, Message::Text(text)) => Ok(Payload::from_json(&text)?),
+        (_, other) => Err(Error::other(
+            "websockets",
+            format!("unexpected message: {other:?}"),
+        )),
+    }
+}
+
 #[cfg(feature = "hyper")]
 fn compute_websocket_accept_header(key: &[u8]) -> hyper::header::HeaderValue {
     use base64::engine::general_purpose::STANDARD as BASE64;
@@ -236,9 +313,13 @@ fn compute_websocket_accept_header(key: &[u8]) -> hyper::header::HeaderValue {
     hyper::header::HeaderValue::from_str(&encoded).expect("base64 is a valid value")
 }
 
-struct VersionChecker;
+/// Negotiates the protocol version and [`WebSocketFormat`] during the
+/// `WebSocket` handshake.
+struct VersionChecker<'a> {
+    format: &'a mut WebSocketFormat,
+}
 
-impl tokio_tungstenite::tungstenite::handshake::server::Callback for VersionChecker {
+impl tokio_tungstenite::tungstenite::handshake::server::Callback for VersionChecker<'_> {
     fn on_request(
         self,
         request: &tokio_tungstenite::tungstenite::handshake::server::Request,
@@ -248,16 +329,14 @@ impl tokio_tungstenite::tungstenite::handshake::server::Callback for VersionChec
         tokio_tungstenite::tungstenite::handshake::server::ErrorResponse,
     > {
         if let Some(protocols) = request.headers().get("Sec-WebSocket-Protocol") {
-            if let Ok(protocols) = protocols.to_str() {
-                for protocol in protocols.split(',').map(str::trim) {
-                    if protocol == CURRENT_PROTOCOL_VERSION {
-                        response.headers_mut().insert(
-                            "Sec-WebSocket-Protocol",
-                            CURRENT_PROTOCOL_VERSION.try_into().unwrap(),
-                        );
-                        return Ok(response);
-                    }
-                }
+            if let Some((subprotocol, format)) =
+                protocols.to_str().ok().and_then(WebSocketFormat::negotiate)
+            {
+                response
+                    .headers_mut()
+                    .insert("Sec-WebSocket-Protocol", subprotocol.try_into().unwrap());
+                *self.format = format;
+                return Ok(response);
             }
         }
 
@@ -1,10 +1,20 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
+use async_trait::async_trait;
 use bonsaidb_core::actionable::{Permissions, Statement};
 use bonsaidb_core::connection::AsyncStorageConnection;
-use bonsaidb_core::test_util::{self, BasicSchema, HarnessTest, TestDirectory};
+use bonsaidb_core::schema::{Collection, CollectionName, SerializedCollection};
+use bonsaidb_core::test_util::{self, Basic, BasicSchema, HarnessTest, TestDirectory};
+use bonsaidb_core::transaction::ChangedDocument;
+use bonsaidb_local::config::Builder;
+use flume::Sender;
 
+use crate::config::DefaultPermissions;
 use crate::server::ServerDatabase;
 use crate::test_util::initialize_basic_server;
-use crate::Server;
+use crate::trigger::{Trigger, TriggerContext};
+use crate::{BackendError, Server, ServerConfiguration};
 
 #[tokio::test]
 async fn simple_test() -> anyhow::Result<()> {
@@ -35,6 +45,275 @@ async fn install_self_signed_certificate_tests() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[derive(Debug)]
+struct ChangedDocumentTrigger(Sender<ChangedDocument>);
+
+#[async_trait]
+impl Trigger for ChangedDocumentTrigger {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("changed-documents")
+    }
+
+    async fn document_changed(
+        &self,
+        collection: &CollectionName,
+        document: &ChangedDocument,
+        _context: &TriggerContext<'_>,
+    ) -> Result<(), BackendError> {
+        if collection == &Basic::collection_name() {
+            drop(self.0.send(document.clone()));
+        }
+        Ok(())
+    }
+}
+
+async fn open_trigger_server(
+    path: &std::path::Path,
+    sender: Sender<ChangedDocument>,
+) -> anyhow::Result<Server> {
+    let server = Server::open(
+        ServerConfiguration::new(path)
+            .default_permissions(DefaultPermissions::AllowAll)
+            .with_schema::<BasicSchema>()?
+            .with_trigger("tests", ChangedDocumentTrigger(sender))?,
+    )
+    .await?;
+    server.create_database::<BasicSchema>("tests", true).await?;
+    Ok(server)
+}
+
+#[tokio::test]
+async fn triggers_resume_after_restart() -> anyhow::Result<()> {
+    let test_dir = TestDirectory::new("triggers-resume");
+    let (sender, receiver) = flume::unbounded();
+    let server = open_trigger_server(test_dir.as_ref(), sender).await?;
+    let db = server.database::<BasicSchema>("tests").await?;
+    let first = Basic::new("first").push_into_async(&db).await?;
+    let changed = tokio::time::timeout(Duration::from_secs(10), receiver.recv_async()).await??;
+    assert_eq!(changed.id.deserialize::<u64>()?, first.header.id);
+    assert!(!changed.deleted);
+    server.shutdown(Some(Duration::from_secs(5))).await?;
+    drop(db);
+    drop(server);
+
+    // Reopening the server resumes delivery after the last handled
+    // transaction. Delivery is at-least-once, so the insert may be delivered
+    // again if the server shut down before recording it as handled.
+    let (sender, receiver) = flume::unbounded();
+    let server = open_trigger_server(test_dir.as_ref(), sender).await?;
+    let db = server.database::<BasicSchema>("tests").await?;
+    first.delete_async(&db).await?;
+    loop {
+        let changed =
+            tokio::time::timeout(Duration::from_secs(10), receiver.recv_async()).await??;
+        assert_eq!(changed.id.deserialize::<u64>()?, first.header.id);
+        if changed.deleted {
+            break;
+        }
+    }
+    assert!(receiver.is_empty());
+
+    Ok(())
+}
+
+#[cfg(feature = "http-api")]
+#[tokio::test]
+async fn http_api_tests() -> anyhow::Result<()> {
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+    use bonsaidb_core::schema::{Schema, View};
+    use bonsaidb_core::test_util::BasicByCategory;
+    use hyper::header::{AUTHORIZATION, CONTENT_LENGTH, IF_MATCH};
+    use hyper::{Body, Method, Request, StatusCode};
+    use serde_json::{json, Value};
+
+    fn request(method: Method, path: &str) -> hyper::http::request::Builder {
+        Request::builder().method(method).uri(path)
+    }
+
+    async fn send(
+        server: &Server,
+        request: hyper::http::request::Builder,
+        body: Option<Value>,
+    ) -> anyhow::Result<(StatusCode, Value)> {
+        let request =
+            request.body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))?;
+        let response = server.handle_http_api_request(request).await;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        if body.is_empty() {
+            Ok((status, Value::Null))
+        } else {
+            Ok((status, serde_json::from_slice(&body)?))
+        }
+    }
+
+    let test_dir = TestDirectory::new("http-api");
+    let server = initialize_basic_server(test_dir.as_ref()).await?;
+    let collection = format!("{:#}", Basic::collection_name());
+    let documents = format!("/databases/http/collections/{collection}/documents");
+
+    let (status, _) = send(
+        &server,
+        request(Method::PUT, "/databases/http"),
+        Some(json!({ "schema": format!("{:#}", BasicSchema::schema_name()) })),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, databases) = send(&server, request(Method::GET, "/databases"), None).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(databases
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|database| database["name"] == "http"));
+
+    let contents = json!({
+        "value": "first",
+        "category": "a",
+        "parent_id": null,
+        "tags": [],
+    });
+    let (status, header) = send(
+        &server,
+        request(Method::POST, &format!("{documents}?contents=json")),
+        Some(contents.clone()),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = header["id"].as_u64().unwrap();
+
+    // Documents inserted over HTTP are readable using the collection's type.
+    let db = server.database::<BasicSchema>("http").await?;
+    let document = Basic::get_async(&id, &db).await?.unwrap();
+    assert_eq!(document.contents.value, "first");
+
+    let (status, document) = send(
+        &server,
+        request(Method::GET, &format!("{documents}/{id}?contents=json")),
+        None,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(document["contents"], contents);
+    assert_eq!(document["revision"], header["revision"]);
+
+    // The format of collections defined at compile time isn't known, so their
+    // contents default to base64.
+    let (status, document) = send(
+        &server,
+        request(Method::GET, &format!("{documents}/{id}")),
+        None,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        document["contents"],
+        BASE64.encode(Basic::serialize(
+            &Basic::get_async(&id, &db).await?.unwrap().contents
+        )?)
+    );
+
+    let mut updated = contents.clone();
+    updated["value"] = json!("updated");
+    let (status, _) = send(
+        &server,
+        request(Method::PUT, &format!("{documents}/{id}?contents=json"))
+            .header(IF_MATCH, header["revision"].as_str().unwrap()),
+        Some(updated.clone()),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    // Updating using the original revision is a conflict.
+    let (status, _) = send(
+        &server,
+        request(Method::PUT, &format!("{documents}/{id}?contents=json"))
+            .header(IF_MATCH, header["revision"].as_str().unwrap()),
+        Some(updated),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let view = format!("/databases/http/views/{:#}", BasicByCategory.view_name());
+    let (status, mappings) = send(
+        &server,
+        request(Method::POST, &format!("{view}/query")),
+        Some(json!({ "key": { "matches": "a" } })),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(mappings[0]["key"], "a");
+    assert_eq!(mappings[0]["source"]["id"], id);
+    let (status, count) = send(
+        &server,
+        request(Method::POST, &format!("{view}/reduce")),
+        Some(json!({})),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(count, 1);
+
+    let (status, _) = send(
+        &server,
+        request(Method::DELETE, &format!("{documents}/{id}")),
+        None,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(
+        &server,
+        request(Method::GET, &format!("{documents}/{id}")),
+        None,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(
+        &server,
+        request(Method::PUT, "/databases/http/kv/counter"),
+        Some(json!(1)),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, value) = send(
+        &server,
+        request(Method::POST, "/databases/http/kv/counter/increment"),
+        Some(json!({ "amount": 2 })),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(value, 3);
+    let (_, value) = send(
+        &server,
+        request(Method::GET, "/databases/http/kv/counter"),
+        None,
+    )
+    .await?;
+    assert_eq!(value, 3);
+
+    let (status, _) = send(
+        &server,
+        request(Method::GET, "/databases").header(AUTHORIZATION, "Unknown credentials"),
+        None,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Bodies are rejected when their declared or actual length exceeds the
+    // configured limit.
+    let too_large = request(Method::PUT, "/databases/http/kv/large")
+        .header(CONTENT_LENGTH, 9 * 1024 * 1024)
+        .body(Body::from("1"))?;
+    let response = server.handle_http_api_request(too_large).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let too_large = request(Method::PUT, "/databases/http/kv/large")
+        .body(Body::from(vec![b' '; 9 * 1024 * 1024]))?;
+    let response = server.handle_http_api_request(too_large).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    Ok(())
+}
+
 struct TestHarness {
     _directory: TestDirectory,
     server: Server,
@@ -0,0 +1,188 @@
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bonsaidb_core::connection::{AsyncConnection, AsyncStorageConnection};
+use bonsaidb_core::schema::{Collection, CollectionName, SerializedCollection};
+use bonsaidb_core::transaction::{ChangedDocument, Executed};
+use derive_where::derive_where;
+use serde::{Deserialize, Serialize};
+
+use crate::{Backend, BackendError, CustomServer, NoBackend, ServerDatabase};
+
+/// The amount of time to wait before retrying a transaction after a
+/// [`Trigger`] returns an error.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Server-side logic that is executed after transactions are committed to a
+/// database.
+///
+/// Triggers are registered for a specific database using
+/// [`ServerConfiguration::register_trigger()`](crate::ServerConfiguration::register_trigger).
+/// Each trigger is executed in its own background task, and each committed
+/// transaction is delivered to the trigger in the order the transactions were
+/// executed.
+///
+/// ## Delivery guarantees
+///
+/// Triggers are delivered transactions at least once. After a trigger
+/// successfully handles a transaction, the transaction's id is recorded by the
+/// server. When the server is restarted, delivery resumes with the first
+/// transaction that has not been recorded as handled. If a trigger returns an
+/// error or the server shuts down while a transaction is being handled, the
+/// transaction will be delivered again.
+///
+/// Because the handled transaction id is tracked using [`Trigger::name()`],
+/// changing the name of a trigger will cause it to be executed for every
+/// transaction in the database's history.
+///
+/// If a trigger writes to the database it is watching, the trigger will be
+/// invoked again with the transaction it wrote.
+#[async_trait]
+pub trait Trigger<B: Backend = NoBackend>: Debug + Send + Sync + 'static {
+    /// Returns the unique name of this trigger. The name is used to track which
+    /// transactions have been handled by this trigger.
+    fn name(&self) -> Cow<'static, str>;
+
+    /// A transaction was executed. The default implementation invokes
+    /// [`Trigger::document_changed()`] for each document changed by the
+    /// transaction.
+    ///
+    /// Transactions that modify the key-value store are also delivered to this
+    /// function once the changed keys have been persisted to disk, which is
+    /// controlled by the database's
+    /// [`KeyValuePersistence`](bonsaidb_local::config::KeyValuePersistence).
+    async fn transaction_executed(
+        &self,
+        transaction: &Executed,
+        context: &TriggerContext<'_, B>,
+    ) -> Result<(), BackendError<B::Error>> {
+        if let Some(documents) = transaction.changes.documents() {
+            for (collection, document) in documents {
+                self.document_changed(collection, document, context).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A document in `collection` was inserted, updated, or deleted.
+    #[allow(unused_variables)]
+    async fn document_changed(
+        &self,
+        collection: &CollectionName,
+        document: &ChangedDocument,
+        context: &TriggerContext<'_, B>,
+    ) -> Result<(), BackendError<B::Error>> {
+        Ok(())
+    }
+}
+
+/// The context a [`Trigger`] is executed within.
+pub struct TriggerContext<'a, B: Backend = NoBackend> {
+    /// The server the trigger is executing on. This server instance is not
+    /// limited by any permissions.
+    pub server: &'a CustomServer<B>,
+    /// The database the transaction was executed on. This database instance is
+    /// not limited by any permissions.
+    pub database: &'a ServerDatabase<B>,
+}
+
+#[derive_where(Debug, Clone)]
+pub(crate) struct RegisteredTrigger<B: Backend> {
+    pub database: String,
+    pub trigger: Arc<dyn Trigger<B>>,
+}
+
+impl<B: Backend> RegisteredTrigger<B> {
+    /// Executes the trigger until the task is cancelled, retrying after
+    /// errors.
+    pub async fn run(&self, server: &CustomServer<B>) {
+        // Creating a database records it in the admin database, so its
+        // transactions are used to wake up triggers waiting for their database
+        // to exist.
+        let mut databases_changed = server.storage.admin().await.watch_transactions();
+        loop {
+            match self.execute(server).await {
+                Ok(()) => {
+                    if databases_changed
+                        .wait_for_transaction_async()
+                        .await
+                        .is_none()
+                    {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    log::error!(
+                        "[server] error executing trigger {} on database {}: {err:?}",
+                        self.trigger.name(),
+                        self.database
+                    );
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+
+    /// Delivers transactions to the trigger. Returns `Ok(())` if the
+    /// database does not exist yet or has been closed.
+    async fn execute(&self, server: &CustomServer<B>) -> Result<(), BackendError<B::Error>> {
+        let db = match server.storage.database_without_schema(&self.database).await {
+            Ok(db) => db,
+            Err(bonsaidb_local::Error::Core(bonsaidb_core::Error::DatabaseNotFound(_))) => {
+                return Ok(())
+            }
+            Err(err) => return Err(BackendError::from(err)),
+        };
+        let database = ServerDatabase {
+            server: server.clone(),
+            db,
+        };
+        let hosted = server.hosted().await;
+        let state_id = format!("{}/{}", self.database, self.trigger.name());
+        let mut last_transaction_id = TriggerState::get_async(&state_id, &hosted)
+            .await?
+            .map(|state| state.contents.last_transaction_id);
+        let context = TriggerContext {
+            server,
+            database: &database,
+        };
+
+        let mut watcher = database.watch_transactions();
+        loop {
+            let transactions = database
+                .list_executed_transactions(last_transaction_id.map(|id| id + 1), None)
+                .await?;
+            if transactions.is_empty() {
+                if watcher.wait_for_transaction_async().await.is_none() {
+                    return Ok(());
+                }
+                continue;
+            }
+
+            for transaction in &transactions {
+                self.trigger
+                    .transaction_executed(transaction, &context)
+                    .await?;
+
+                TriggerState {
+                    last_transaction_id: transaction.id,
+                }
+                .overwrite_into_async(&state_id, &hosted)
+                .await?;
+                last_transaction_id = Some(transaction.id);
+            }
+        }
+    }
+}
+
+/// The last transaction successfully handled by a [`Trigger`]. The id of each
+/// document is `{database}/{trigger name}`.
+#[derive(Clone, Debug, Serialize, Deserialize, Collection)]
+#[collection(name = "trigger-states", authority = "khonsulabs", primary_key = String, core = bonsaidb_core)]
+pub(crate) struct TriggerState {
+    pub last_transaction_id: u64,
+}
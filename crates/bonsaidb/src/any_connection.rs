@@ -2,10 +2,13 @@ use bonsaidb_client::{AsyncClient, AsyncRemoteDatabase};
 use bonsaidb_core::async_trait::async_trait;
 use bonsaidb_core::connection::{
     self, AccessPolicy, AsyncConnection, AsyncLowLevelConnection, AsyncStorageConnection,
-    HasSchema, HasSession, IdentityReference, Range, SerializedQueryKey, Session, Sort,
+    HasSchema, HasSession, IdentityReference, Range, SerializedDocumentFilter, SerializedQueryKey,
+    Session, Sort,
 };
 use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
+use bonsaidb_core::schema::dynamic::DynamicCollection;
 use bonsaidb_core::schema::view::map::MappedSerializedValue;
+use bonsaidb_core::schema::view::ViewStatus;
 use bonsaidb_core::schema::{
     self, Collection, CollectionName, Nameable, Schema, SchemaName, SchemaSummary, Schematic,
     ViewName,
@@ -467,17 +470,18 @@ impl<B: Backend> AsyncLowLevelConnection for AnyDatabase<B> {
         &self,
         view: &ViewName,
         key: Option<SerializedQueryKey>,
+        group_level: Option<usize>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<MappedSerializedValue>, bonsaidb_core::Error> {
         match self {
             Self::Local(server) => {
                 server
-                    .reduce_grouped_by_name(view, key, access_policy)
+                    .reduce_grouped_by_name(view, key, group_level, access_policy)
                     .await
             }
             Self::Networked(client) => {
                 client
-                    .reduce_grouped_by_name(view, key, access_policy)
+                    .reduce_grouped_by_name(view, key, group_level, access_policy)
                     .await
             }
         }
@@ -494,6 +498,70 @@ impl<B: Backend> AsyncLowLevelConnection for AnyDatabase<B> {
             Self::Networked(client) => client.delete_docs_by_name(view, key, access_policy).await,
         }
     }
+
+    async fn filter_documents_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        match self {
+            Self::Local(server) => {
+                server
+                    .filter_documents_by_name(collection, filter, order, limit, access_policy)
+                    .await
+            }
+            Self::Networked(client) => {
+                client
+                    .filter_documents_by_name(collection, filter, order, limit, access_policy)
+                    .await
+            }
+        }
+    }
+
+    async fn filter_headers_by_name(
+        &self,
+        collection: &CollectionName,
+        filter: SerializedDocumentFilter,
+        order: Sort,
+        limit: Option<u32>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<Header>, bonsaidb_core::Error> {
+        match self {
+            Self::Local(server) => {
+                server
+                    .filter_headers_by_name(collection, filter, order, limit, access_policy)
+                    .await
+            }
+            Self::Networked(client) => {
+                client
+                    .filter_headers_by_name(collection, filter, order, limit, access_policy)
+                    .await
+            }
+        }
+    }
+
+    async fn view_status_by_name(
+        &self,
+        view: &ViewName,
+    ) -> Result<ViewStatus, bonsaidb_core::Error> {
+        match self {
+            Self::Local(server) => server.view_status_by_name(view).await,
+            Self::Networked(client) => client.view_status_by_name(view).await,
+        }
+    }
+
+    async fn define_dynamic_collection(
+        &self,
+        collection: DynamicCollection,
+    ) -> Result<(), bonsaidb_core::Error> {
+        match self {
+            Self::Local(server) => server.define_dynamic_collection(collection).await,
+            Self::Networked(client) => client.define_dynamic_collection(collection).await,
+        }
+    }
 }
 
 impl<B: Backend> HasSchema for AnyDatabase<B> {
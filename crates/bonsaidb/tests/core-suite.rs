@@ -193,10 +193,91 @@ mod websockets {
         check_incompatible_client(client).await
     }
 
+    #[tokio::test]
+    async fn negotiated_formats() -> anyhow::Result<()> {
+        use bonsaidb_core::connection::AsyncStorageConnection;
+        use bonsaidb_core::networking::WebSocketFormat;
+        use bonsaidb_core::test_util::Basic;
+
+        initialize_shared_server().await;
+        let url = Url::parse("ws://localhost:6001")?;
+        for format in [WebSocketFormat::Pot, WebSocketFormat::Json] {
+            let client = AsyncClient::build(url.clone())
+                .with_websocket_format(format)
+                .build()?;
+
+            let dbname = format!("websockets-{}", format.suffix().unwrap());
+            client
+                .create_database::<BasicSchema>(&dbname, false)
+                .await?;
+            let db = client.database::<BasicSchema>(&dbname).await?;
+
+            let doc = Basic::new("negotiated").push_into_async(&db).await?;
+            let retrieved = Basic::get_async(&doc.header.id, &db)
+                .await?
+                .expect("document not found");
+            assert_eq!(retrieved.contents.value, "negotiated");
+            assert_eq!(db.increment_key_by("counter", 1_u64).await?, 1);
+        }
+
+        Ok(())
+    }
+
     bonsaidb_core::define_blocking_connection_test_suite!(BlockingWebsocketTestHarness);
 
     bonsaidb_core::define_blocking_pubsub_test_suite!(BlockingWebsocketTestHarness);
     bonsaidb_core::define_blocking_kv_test_suite!(BlockingWebsocketTestHarness);
+
+    /// Runs the connection test suite using JSON-encoded payloads, which
+    /// ensures every request and response can be represented as JSON.
+    mod json {
+        use bonsaidb_core::networking::WebSocketFormat;
+
+        use super::*;
+
+        struct JsonWebsocketTestHarness {
+            client: AsyncClient,
+            db: AsyncRemoteDatabase,
+        }
+
+        impl JsonWebsocketTestHarness {
+            pub async fn new(test: HarnessTest) -> anyhow::Result<Self> {
+                use bonsaidb_core::connection::AsyncStorageConnection;
+
+                initialize_shared_server().await;
+                let url = Url::parse("ws://localhost:6001")?;
+                let client = AsyncClient::build(url)
+                    .with_websocket_format(WebSocketFormat::Json)
+                    .build()?;
+
+                let dbname = format!("websockets-json-{test}");
+                client
+                    .create_database::<BasicSchema>(&dbname, false)
+                    .await?;
+                let db = client.database::<BasicSchema>(&dbname).await?;
+
+                Ok(Self { client, db })
+            }
+
+            pub const fn server_name() -> &'static str {
+                "websocket-json"
+            }
+
+            pub fn server(&self) -> &AsyncClient {
+                &self.client
+            }
+
+            pub async fn connect(&self) -> anyhow::Result<AsyncRemoteDatabase> {
+                Ok(self.db.clone())
+            }
+
+            pub async fn shutdown(&self) -> anyhow::Result<()> {
+                Ok(())
+            }
+        }
+
+        bonsaidb_core::define_async_connection_test_suite!(JsonWebsocketTestHarness);
+    }
 }
 
 mod bonsai {
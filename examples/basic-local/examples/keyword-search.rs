@@ -8,8 +8,9 @@
 //! keyword hit, which fields it matched upon, etc.
 //!
 //! While this approach can be powerful, it pales in comparsion to full text
-//! search capabilities. The tracking issue for adding full text indexes to
-//! BonsaiDb is here: <https://github.com/khonsulabs/bonsaidb/issues/149>.
+//! search capabilities. The `text-search` example shows how to use a
+//! `TextIndex`, which supports phrase queries, boolean queries, and ranking
+//! results by relevance.
 use std::str::Chars;
 use std::time::SystemTime;
 
@@ -0,0 +1,79 @@
+//! This example shows how to use a `TextIndex` to search the text contained in
+//! documents. Text is extracted from each document, split into tokens, and
+//! stored in an inverted index. Searches support phrases, boolean operators,
+//! and rank the matching documents by relevance.
+use std::borrow::Cow;
+use std::time::SystemTime;
+
+use bonsaidb::core::document::CollectionDocument;
+use bonsaidb::core::schema::text::{TextIndex, Tokenizer};
+use bonsaidb::core::schema::{Collection, Name, SerializedCollection};
+use bonsaidb::local::config::{Builder, StorageConfiguration};
+use bonsaidb::local::Database;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Collection)]
+#[collection(name = "messages", text_indexes = [MessageText])]
+struct Message {
+    pub timestamp: SystemTime,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Message {
+    /// Returns a new message with the current timestamp.
+    pub fn new(subject: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            subject: subject.into(),
+            body: body.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MessageText;
+
+impl TextIndex for MessageText {
+    type Collection = Message;
+
+    fn name(&self) -> Name {
+        Name::new("text")
+    }
+
+    fn tokenizer(&self) -> Tokenizer {
+        // Common words like "the" and "and" aren't useful when searching.
+        Tokenizer::new().with_english_stop_words()
+    }
+
+    fn text<'doc>(&self, document: &'doc CollectionDocument<Message>) -> Vec<Cow<'doc, str>> {
+        vec![
+            Cow::Borrowed(&document.contents.subject),
+            Cow::Borrowed(&document.contents.body),
+        ]
+    }
+}
+
+fn main() -> Result<(), bonsaidb::core::Error> {
+    let db = Database::open::<Message>(StorageConfiguration::new("text-search.bonsaidb"))?;
+
+    Message::new("Groceries", "Can you pick up some milk on the way home?").push_into(&db)?;
+    Message::new("Re: Groceries", "2% milk? How are our eggs?").push_into(&db)?;
+    Message::new("Re: Groceries", "Yes. We could use another dozen eggs.").push_into(&db)?;
+
+    for query in ["eggs", "milk -eggs", "\"dozen eggs\"", "home OR dozen"] {
+        println!("Results for {query}:");
+        // Results are ordered from most relevant to least relevant.
+        for result in MessageText.search(&db, query).query()? {
+            let message = Message::get(&result.id, &db)?.expect("message not found");
+            println!("{:.3}: {}", result.score, message.contents.body);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn runs() {
+    main().unwrap()
+}